@@ -1,3 +1,4 @@
+use bigneon_db::models::Impersonation;
 use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
@@ -8,12 +9,16 @@ const ACCESS_TOKEN_EXPIRATION_IN_SECONDS: u64 = 15 * 60;
 #[derive(RustcDecodable, RustcEncodable)]
 pub struct AccessToken {
     pub sub: String,
+    pub sid: String,
     pub iss: String,
     pub exp: u64,
+    pub two_factor: bool,
+    /// Impersonation id when an admin is acting as the user
+    pub imp: Option<String>,
 }
 
 impl AccessToken {
-    pub fn new(user_id: &Uuid, issuer: String) -> Self {
+    pub fn new(user_id: &Uuid, session_id: &Uuid, issuer: String, two_factor: bool) -> Self {
         let mut timer = SystemTime::now();
         timer += Duration::from_secs(ACCESS_TOKEN_EXPIRATION_IN_SECONDS);
         let exp = timer.duration_since(UNIX_EPOCH).unwrap().as_secs();
@@ -21,11 +26,39 @@ impl AccessToken {
         AccessToken {
             iss: issuer,
             sub: user_id.hyphenated().to_string(),
+            sid: session_id.hyphenated().to_string(),
             exp,
+            two_factor,
+            imp: None,
         }
     }
 
+    /// Token acting as the impersonated user, it expires with the impersonation
+    pub fn for_impersonation(
+        impersonation: &Impersonation,
+        issuer: String,
+        two_factor: bool,
+    ) -> Self {
+        let mut access_token = AccessToken::new(
+            &impersonation.user_id,
+            &impersonation.session_id,
+            issuer,
+            two_factor,
+        );
+        access_token.exp = impersonation.expires_at.timestamp() as u64;
+        access_token.imp = Some(impersonation.id.hyphenated().to_string());
+        access_token
+    }
+
     pub fn get_id(&self) -> Uuid {
         Uuid::parse_str(&self.sub).unwrap()
     }
+
+    pub fn get_session_id(&self) -> Uuid {
+        Uuid::parse_str(&self.sid).unwrap()
+    }
+
+    pub fn get_impersonation_id(&self) -> Option<Uuid> {
+        self.imp.as_ref().and_then(|imp| Uuid::parse_str(imp).ok())
+    }
 }
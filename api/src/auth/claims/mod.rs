@@ -1,5 +1,7 @@
 pub use self::access_token::AccessToken;
 pub use self::refresh_token::RefreshToken;
+pub use self::two_factor_token::TwoFactorToken;
 
 pub mod access_token;
 pub mod refresh_token;
+pub mod two_factor_token;
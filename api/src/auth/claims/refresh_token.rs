@@ -4,12 +4,14 @@ use uuid::Uuid;
 #[derive(RustcDecodable, RustcEncodable)]
 pub struct RefreshToken {
     pub sub: String,
+    pub jti: String,
     pub iss: String,
     pub issued: u64,
+    pub two_factor: bool,
 }
 
 impl RefreshToken {
-    pub fn new(user_id: &Uuid, issuer: String) -> Self {
+    pub fn new(user_id: &Uuid, session_id: &Uuid, issuer: String, two_factor: bool) -> Self {
         let issued = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -18,11 +20,17 @@ impl RefreshToken {
         RefreshToken {
             iss: issuer,
             sub: user_id.hyphenated().to_string(),
+            jti: session_id.hyphenated().to_string(),
             issued,
+            two_factor,
         }
     }
 
     pub fn get_id(&self) -> Uuid {
         Uuid::parse_str(&self.sub).unwrap()
     }
+
+    pub fn get_session_id(&self) -> Uuid {
+        Uuid::parse_str(&self.jti).unwrap()
+    }
 }
@@ -0,0 +1,44 @@
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use uuid::Uuid;
+
+const TWO_FACTOR_TOKEN_EXPIRATION_IN_SECONDS: u64 = 5 * 60;
+const TWO_FACTOR_TOKEN_PURPOSE: &str = "two_factor";
+
+/// Issued once the password has been checked for users with two factor authentication
+/// enabled. It is exchanged for access and refresh tokens along with a valid code.
+#[derive(RustcDecodable, RustcEncodable)]
+pub struct TwoFactorToken {
+    pub sub: String,
+    pub iss: String,
+    pub exp: u64,
+    pub purpose: String,
+}
+
+impl TwoFactorToken {
+    pub fn new(user_id: &Uuid, issuer: String) -> Self {
+        let mut timer = SystemTime::now();
+        timer += Duration::from_secs(TWO_FACTOR_TOKEN_EXPIRATION_IN_SECONDS);
+        let exp = timer.duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        TwoFactorToken {
+            iss: issuer,
+            sub: user_id.hyphenated().to_string(),
+            exp,
+            purpose: TWO_FACTOR_TOKEN_PURPOSE.to_string(),
+        }
+    }
+
+    pub fn get_id(&self) -> Uuid {
+        Uuid::parse_str(&self.sub).unwrap()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.exp >= now && self.purpose == TWO_FACTOR_TOKEN_PURPOSE
+    }
+}
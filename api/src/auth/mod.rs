@@ -1,5 +1,9 @@
-pub use self::token_response::TokenResponse;
+pub use self::token_response::{
+    ImpersonationTokenResponse, TokenResponse, TwoFactorChallengeResponse,
+};
 
 pub mod claims;
+pub mod oidc;
 pub mod token_response;
+pub mod totp;
 pub mod user;
@@ -0,0 +1,374 @@
+use chrono::Utc;
+use config::Config;
+use errors::*;
+use reqwest::{self, Url};
+use ring::signature::{self, primitive};
+use rustc_serialize::base64::FromBase64;
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use untrusted;
+
+/// Allowed difference between our clock and the identity provider's when checking token times
+const CLOCK_SKEW_SECONDS: i64 = 60;
+/// How long the provider's signing keys are reused before being fetched again
+const KEY_CACHE_SECONDS: u64 = 60 * 60;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DiscoveryDocument {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonWebKey {
+    pub kty: String,
+    pub kid: Option<String>,
+    pub alg: Option<String>,
+    pub n: Option<String>,
+    pub e: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonWebKeySet {
+    pub keys: Vec<JsonWebKey>,
+}
+
+/// Signing keys by JWKS URI, shared by every clone so keys are not fetched on every login
+#[derive(Clone, Default)]
+pub struct JwksCache {
+    keys: Arc<Mutex<HashMap<String, (Instant, JsonWebKeySet)>>>,
+}
+
+impl JwksCache {
+    /// Cached keys for the URI unless they are stale or do not include the requested key, in
+    /// which case the provider may have rotated its keys
+    fn get(&self, jwks_uri: &str, kid: Option<&str>) -> Option<JsonWebKeySet> {
+        let keys = self.keys.lock().ok()?;
+        let (fetched_at, key_set) = keys.get(jwks_uri)?;
+        let has_key = kid.is_none() || key_set
+            .keys
+            .iter()
+            .any(|k| k.kid.as_ref().map(|k| k.as_str()) == kid);
+        if fetched_at.elapsed() < Duration::from_secs(KEY_CACHE_SECONDS) && has_key {
+            Some(key_set.clone())
+        } else {
+            None
+        }
+    }
+
+    fn insert(&self, jwks_uri: &str, key_set: JsonWebKeySet) {
+        if let Ok(mut keys) = self.keys.lock() {
+            keys.insert(jwks_uri.to_string(), (Instant::now(), key_set));
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenEndpointResponse {
+    access_token: String,
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Audience::Single(aud) => aud == client_id,
+            Audience::Multiple(auds) => auds.iter().any(|aud| aud == client_id),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: Audience,
+    pub exp: i64,
+    pub iat: i64,
+    pub azp: Option<String>,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_email_verified")]
+    pub email_verified: bool,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub name: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// The email address, but only if the identity provider has verified it
+    pub fn verified_email(&self) -> Option<&str> {
+        match self.email {
+            Some(ref email) if self.email_verified => Some(email),
+            _ => None,
+        }
+    }
+
+    /// First and last name, falling back to splitting the full name
+    pub fn names(&self) -> (String, String) {
+        if self.given_name.is_some() || self.family_name.is_some() {
+            return (
+                self.given_name.clone().unwrap_or_default(),
+                self.family_name.clone().unwrap_or_default(),
+            );
+        }
+        let name = self.name.clone().unwrap_or_default();
+        let mut parts = name.trim().splitn(2, ' ');
+        (
+            parts.next().unwrap_or("").to_string(),
+            parts.next().unwrap_or("").trim().to_string(),
+        )
+    }
+}
+
+/// Some providers (Apple) send `email_verified` as a string
+fn deserialize_email_verified<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+
+    match BoolOrString::deserialize(deserializer)? {
+        BoolOrString::Bool(b) => Ok(b),
+        BoolOrString::String(s) => match s.as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(de::Error::custom("email_verified must be a boolean")),
+        },
+    }
+}
+
+pub struct OidcLogin {
+    pub claims: IdTokenClaims,
+    pub access_token: String,
+}
+
+/// A configured OpenID Connect identity provider, e.g. Google, Apple or an enterprise IdP
+pub struct OidcProvider {
+    pub discovery_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    key_cache: JwksCache,
+}
+
+impl OidcProvider {
+    pub fn new(discovery_url: String, client_id: String, client_secret: String) -> OidcProvider {
+        OidcProvider {
+            discovery_url,
+            client_id,
+            client_secret,
+            key_cache: JwksCache::default(),
+        }
+    }
+
+    /// Returns `None` when OpenID Connect login has not been configured
+    pub fn from_config(config: &Config) -> Option<OidcProvider> {
+        match (
+            &config.oidc_discovery_url,
+            &config.oidc_client_id,
+            &config.oidc_client_secret,
+        ) {
+            (Some(discovery_url), Some(client_id), Some(client_secret)) => Some(OidcProvider {
+                discovery_url: discovery_url.clone(),
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+                key_cache: config.oidc_key_cache.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Where to send the user to log in, the state and nonce are checked when they return
+    pub fn authorization_url(
+        &self,
+        discovery: &DiscoveryDocument,
+        redirect_uri: &str,
+        state: &str,
+        nonce: &str,
+    ) -> Result<String, BigNeonError> {
+        let url = Url::parse_with_params(
+            &discovery.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("scope", "openid email profile"),
+                ("state", state),
+                ("nonce", nonce),
+            ],
+        ).map_err(|_| ApplicationError::new("Invalid authorization endpoint".to_string()))?;
+        Ok(url.to_string())
+    }
+
+    /// Exchanges an authorization code for tokens and returns the validated ID token claims
+    pub fn login(
+        &self,
+        code: &str,
+        redirect_uri: &str,
+        nonce: &str,
+    ) -> Result<OidcLogin, BigNeonError> {
+        let discovery = self.discover()?;
+        let tokens = self.exchange_code(&discovery, code, redirect_uri)?;
+        let keys = self.keys(&discovery, &tokens.id_token)?;
+        let claims = self.validate_id_token(&tokens.id_token, &discovery, &keys, nonce)?;
+        Ok(OidcLogin {
+            claims,
+            access_token: tokens.access_token,
+        })
+    }
+
+    pub fn discover(&self) -> Result<DiscoveryDocument, BigNeonError> {
+        let response = reqwest::get(&self.discovery_url)?.text()?;
+        Ok(serde_json::from_str(&response)?)
+    }
+
+    pub fn fetch_keys(&self, discovery: &DiscoveryDocument) -> Result<JsonWebKeySet, BigNeonError> {
+        let response = reqwest::get(&discovery.jwks_uri)?.text()?;
+        Ok(serde_json::from_str(&response)?)
+    }
+
+    /// Keys to check the ID token against, fetched only when the cached keys are stale or do
+    /// not include the key the token was signed with
+    fn keys(
+        &self,
+        discovery: &DiscoveryDocument,
+        id_token: &str,
+    ) -> Result<JsonWebKeySet, BigNeonError> {
+        let kid = id_token_header(id_token).ok().and_then(|header| header.kid);
+        if let Some(keys) = self
+            .key_cache
+            .get(&discovery.jwks_uri, kid.as_ref().map(|k| k.as_str()))
+        {
+            return Ok(keys);
+        }
+        let keys = self.fetch_keys(discovery)?;
+        self.key_cache.insert(&discovery.jwks_uri, keys.clone());
+        Ok(keys)
+    }
+
+    fn exchange_code(
+        &self,
+        discovery: &DiscoveryDocument,
+        code: &str,
+        redirect_uri: &str,
+    ) -> Result<TokenEndpointResponse, BigNeonError> {
+        let client = reqwest::Client::new();
+        let mut response = client
+            .post(&discovery.token_endpoint)
+            .basic_auth(self.client_id.clone(), Some(self.client_secret.clone()))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+            ]).send()?;
+        if !response.status().is_success() {
+            return Err(AuthError::new(
+                "Authorization code was rejected by the identity provider".to_string(),
+            ).into());
+        }
+        Ok(serde_json::from_str(&response.text()?)?)
+    }
+
+    /// Checks the ID token signature against the provider's keys and validates its claims
+    pub fn validate_id_token(
+        &self,
+        id_token: &str,
+        discovery: &DiscoveryDocument,
+        keys: &JsonWebKeySet,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, BigNeonError> {
+        let parts: Vec<&str> = id_token.split('.').collect();
+        if parts.len() != 3 {
+            return Err(invalid_id_token("Malformed token"));
+        }
+
+        let header = id_token_header(id_token)?;
+        if header.alg != "RS256" {
+            return Err(invalid_id_token("Unsupported signing algorithm"));
+        }
+        let key = keys
+            .keys
+            .iter()
+            .filter(|k| k.kty == "RSA")
+            .find(|k| header.kid.is_none() || k.kid == header.kid)
+            .ok_or_else(|| invalid_id_token("Unknown signing key"))?;
+        let (n, e) = match (&key.n, &key.e) {
+            (Some(n), Some(e)) => (decode_segment(n)?, decode_segment(e)?),
+            _ => return Err(invalid_id_token("Unknown signing key")),
+        };
+        let message = format!("{}.{}", parts[0], parts[1]);
+        let signature = decode_segment(parts[2])?;
+        primitive::verify_rsa(
+            &signature::RSA_PKCS1_2048_8192_SHA256,
+            (untrusted::Input::from(&n), untrusted::Input::from(&e)),
+            untrusted::Input::from(message.as_bytes()),
+            untrusted::Input::from(&signature),
+        ).map_err(|_| invalid_id_token("Invalid signature"))?;
+
+        let claims: IdTokenClaims = serde_json::from_slice(&decode_segment(parts[1])?)
+            .map_err(|_| invalid_id_token("Malformed token"))?;
+        if claims.iss != discovery.issuer {
+            return Err(invalid_id_token("Invalid issuer"));
+        }
+        if !claims.aud.contains(&self.client_id) {
+            return Err(invalid_id_token("Invalid audience"));
+        }
+        if let Audience::Multiple(_) = claims.aud {
+            if claims.azp.as_ref() != Some(&self.client_id) {
+                return Err(invalid_id_token("Invalid authorized party"));
+            }
+        }
+        let now = Utc::now().timestamp();
+        if claims.exp < now - CLOCK_SKEW_SECONDS {
+            return Err(invalid_id_token("Token has expired"));
+        }
+        if claims.iat > now + CLOCK_SKEW_SECONDS {
+            return Err(invalid_id_token("Token was issued in the future"));
+        }
+        // The nonce ties the token to the login request it was issued for
+        if claims.nonce.as_ref().map(|n| n.as_str()) != Some(nonce) {
+            return Err(invalid_id_token("Invalid nonce"));
+        }
+
+        Ok(claims)
+    }
+}
+
+fn id_token_header(id_token: &str) -> Result<IdTokenHeader, BigNeonError> {
+    let header = id_token.split('.').next().unwrap_or("");
+    serde_json::from_slice(&decode_segment(header)?)
+        .map_err(|_| invalid_id_token("Malformed token"))
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, BigNeonError> {
+    segment
+        .from_base64()
+        .map_err(|_| invalid_id_token("Malformed token"))
+}
+
+fn invalid_id_token(reason: &str) -> BigNeonError {
+    AuthError::new(format!("Invalid ID token: {}", reason)).into()
+}
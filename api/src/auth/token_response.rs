@@ -2,8 +2,9 @@ use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use actix_web::Responder;
-use auth::{claims::AccessToken, claims::RefreshToken};
-use bigneon_db::models::User;
+use auth::{claims::AccessToken, claims::RefreshToken, claims::TwoFactorToken};
+use bigneon_db::models::{Impersonation, Session, User};
+use chrono::NaiveDateTime;
 use crypto::sha2::Sha256;
 use jwt::{Component, Header, Token};
 use serde_json;
@@ -15,6 +16,20 @@ pub struct TokenResponse {
     pub refresh_token: String,
 }
 
+/// Short lived access token for an admin acting as another user, no refresh token is issued
+#[derive(Serialize, Deserialize)]
+pub struct ImpersonationTokenResponse {
+    pub access_token: String,
+    pub impersonation_id: Uuid,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub two_factor_token: String,
+}
+
 impl Responder for TokenResponse {
     type Item = HttpResponse;
     type Error = Error;
@@ -35,11 +50,26 @@ impl TokenResponse {
         }
     }
 
-    pub fn create_from_user(token_secret: &str, token_issuer: &str, user: &User) -> Self {
-        let access_token_claims = AccessToken::new(&user.id, token_issuer.to_string());
+    pub fn create_from_session(
+        token_secret: &str,
+        token_issuer: &str,
+        session: &Session,
+        two_factor: bool,
+    ) -> Self {
+        let access_token_claims = AccessToken::new(
+            &session.user_id,
+            &session.id,
+            token_issuer.to_string(),
+            two_factor,
+        );
         let access_token = Token::new(Default::default(), access_token_claims);
 
-        let refresh_token_claims = RefreshToken::new(&user.id, token_issuer.to_string());
+        let refresh_token_claims = RefreshToken::new(
+            &session.user_id,
+            &session.id,
+            token_issuer.to_string(),
+            two_factor,
+        );
         let refresh_token = Token::new(Default::default(), refresh_token_claims);
 
         TokenResponse {
@@ -51,10 +81,16 @@ impl TokenResponse {
     pub fn create_from_refresh_token(
         token_secret: &str,
         token_issuer: &str,
-        user_id: &Uuid,
+        session: &Session,
+        two_factor: bool,
         signed_refresh_token: &str,
     ) -> Self {
-        let access_token_claims = AccessToken::new(&user_id, token_issuer.to_string());
+        let access_token_claims = AccessToken::new(
+            &session.user_id,
+            &session.id,
+            token_issuer.to_string(),
+            two_factor,
+        );
         let access_token = Token::new(Default::default(), access_token_claims);
 
         TokenResponse {
@@ -64,6 +100,37 @@ impl TokenResponse {
     }
 }
 
+impl ImpersonationTokenResponse {
+    pub fn create_for_impersonation(
+        token_secret: &str,
+        token_issuer: &str,
+        impersonation: &Impersonation,
+        two_factor: bool,
+    ) -> Self {
+        let access_token_claims =
+            AccessToken::for_impersonation(impersonation, token_issuer.to_string(), two_factor);
+        let access_token = Token::new(Default::default(), access_token_claims);
+
+        ImpersonationTokenResponse {
+            access_token: sign_token(token_secret, &access_token),
+            impersonation_id: impersonation.id,
+            expires_at: impersonation.expires_at,
+        }
+    }
+}
+
+impl TwoFactorChallengeResponse {
+    pub fn create_for_user(token_secret: &str, token_issuer: &str, user: &User) -> Self {
+        let two_factor_token_claims = TwoFactorToken::new(&user.id, token_issuer.to_string());
+        let two_factor_token = Token::new(Default::default(), two_factor_token_claims);
+
+        TwoFactorChallengeResponse {
+            two_factor_required: true,
+            two_factor_token: sign_token(token_secret, &two_factor_token),
+        }
+    }
+}
+
 fn sign_token<T: Component>(token_secret: &str, token: &Token<Header, T>) -> String {
     token
         .signed(token_secret.as_bytes(), Sha256::new())
@@ -0,0 +1,88 @@
+use bigneon_db::utils::base32;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use crypto::util::fixed_time_eq;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STEP_IN_SECONDS: u64 = 30;
+const DIGITS: usize = 6;
+// Codes from the previous and next step are accepted to allow for clock drift
+const ALLOWED_DRIFT_IN_STEPS: u64 = 1;
+
+pub fn current_step() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        / STEP_IN_SECONDS
+}
+
+/// Generates the RFC 6238 code for a base32 encoded secret at the given time step
+pub fn code_for_step(secret: &str, step: u64) -> Option<String> {
+    let key = base32::decode(secret)?;
+    let mut message = [0u8; 8];
+    for (i, byte) in message.iter_mut().enumerate() {
+        *byte = (step >> (56 - i * 8)) as u8;
+    }
+
+    let mut hmac = Hmac::new(Sha1::new(), &key);
+    hmac.input(&message);
+    let result = hmac.result();
+    let hash = result.code();
+
+    let offset = (hash[hash.len() - 1] & 0xf) as usize;
+    let binary = (u32::from(hash[offset] & 0x7f) << 24)
+        | (u32::from(hash[offset + 1]) << 16)
+        | (u32::from(hash[offset + 2]) << 8)
+        | u32::from(hash[offset + 3]);
+    Some(format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS as u32),
+        width = DIGITS
+    ))
+}
+
+/// Returns the time step the code was generated for if it is valid near the given step
+pub fn verify(secret: &str, code: &str, step: u64) -> Option<u64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != DIGITS {
+        return None;
+    }
+
+    for candidate in step.saturating_sub(ALLOWED_DRIFT_IN_STEPS)..=step + ALLOWED_DRIFT_IN_STEPS {
+        if fixed_time_eq(
+            code_for_step(secret, candidate)?.as_bytes(),
+            code.as_bytes(),
+        ) {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// URI understood by authenticator apps, usually displayed to the user as a QR code
+pub fn provisioning_uri(secret: &str, account_name: &str, issuer: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account_name),
+        secret,
+        percent_encode(issuer),
+        DIGITS,
+        STEP_IN_SECONDS
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
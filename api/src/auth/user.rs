@@ -1,13 +1,18 @@
-use actix_web::{error, error::Error, FromRequest, HttpRequest, Result};
+use actix_web::{error, error::Error, http::Method, FromRequest, HttpRequest, Result};
 use auth::claims;
 use bigneon_db::models::User as DbUser;
-use bigneon_db::models::{Organization, Scopes};
+use bigneon_db::models::{
+    AuditActions, AuditEntry, Event, EventPromoter, Impersonation, NewAuditEntry, Organization,
+    OrganizationApiKey, Scopes, Session, Tables,
+};
+use bigneon_db::utils::errors::Optional;
+use controllers::auth::remote_ip_address;
 use crypto::sha2::Sha256;
 use diesel::PgConnection;
 use errors::*;
 use jwt::Header;
 use jwt::Token;
-use middleware::RequestConnection;
+use middleware::{RequestConnection, RequestId};
 use server::AppState;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
@@ -16,6 +21,14 @@ use uuid::Uuid;
 pub struct User {
     pub user: DbUser,
     pub global_scopes: Vec<String>,
+    pub session_id: Option<Uuid>,
+    pub two_factor_authenticated: bool,
+    /// Set when the request was authenticated with an organization API key, in which case
+    /// `user` is the key's service user
+    pub api_key: Option<OrganizationApiKey>,
+    /// Set when an admin is acting as `user`
+    pub impersonation: Option<Impersonation>,
+    pub request_id: Option<String>,
 }
 
 impl User {
@@ -24,9 +37,18 @@ impl User {
         User {
             user,
             global_scopes,
+            session_id: None,
+            two_factor_authenticated: false,
+            api_key: None,
+            impersonation: None,
+            request_id: None,
         }
     }
 
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonation.is_some()
+    }
+
     pub fn id(&self) -> Uuid {
         self.user.id
     }
@@ -35,20 +57,71 @@ impl User {
         self.user.email.clone()
     }
 
+    /// Starts an audit log entry for a write made by this user in the current request
+    pub fn audit(
+        &self,
+        action: AuditActions,
+        table_name: Tables,
+        target_id: Option<Uuid>,
+    ) -> NewAuditEntry {
+        AuditEntry::create(Some(self.id()), action, table_name, target_id)
+            .with_request_id(self.request_id.clone())
+            .impersonated_by(self.impersonation.as_ref().map(|i| i.admin_user_id))
+    }
+
     pub fn has_scope(
         &self,
         scope: Scopes,
         organization: Option<&Organization>,
         connection: &PgConnection,
     ) -> Result<bool, BigNeonError> {
+        // API keys are limited to their own scopes within their organization
+        if let Some(ref api_key) = self.api_key {
+            return Ok(
+                organization.map_or(false, |o| o.id == api_key.organization_id)
+                    && api_key.scopes.contains(&scope.to_string()),
+            );
+        }
+
         if self.global_scopes.contains(&scope.to_string()) {
             return Ok(true);
         }
 
         if let Some(organization) = organization {
-            return Ok(organization
+            if !organization
                 .get_scopes_for_user(&self.user, connection)?
-                .contains(&scope.to_string()));
+                .contains(&scope.to_string())
+            {
+                return Ok(false);
+            }
+
+            // Organization scopes are withheld until the user signs in with a second factor
+            return Ok(self.two_factor_authenticated
+                || !organization.requires_two_factor_for_scope(&self.user, scope, connection)?);
+        }
+
+        Ok(false)
+    }
+
+    /// Members of an event's co-promoters have the scopes their promoter permission allows
+    pub fn has_scope_for_event(
+        &self,
+        scope: Scopes,
+        event: &Event,
+        connection: &PgConnection,
+    ) -> Result<bool, BigNeonError> {
+        if self.has_scope(scope, Some(&event.organization(connection)?), connection)? {
+            return Ok(true);
+        }
+
+        for promoter in EventPromoter::find_for_event(event.id, connection)? {
+            if promoter.allows(scope) && self.has_scope(
+                scope,
+                Some(&Organization::find(promoter.organization_id, connection)?),
+                connection,
+            )? {
+                return Ok(true);
+            }
         }
 
         Ok(false)
@@ -74,6 +147,81 @@ impl User {
         }
         Err(AuthError::new("User does not have the required permissions".to_string()).into())
     }
+
+    pub fn requires_scope_for_event(
+        &self,
+        scope: Scopes,
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        if self.has_scope_for_event(scope, event, conn)? {
+            return Ok(());
+        }
+        Err(AuthError::new("User does not have the required permissions".to_string()).into())
+    }
+}
+
+impl User {
+    fn from_api_key(req: &HttpRequest<AppState>, key: &str) -> Result<User, Error> {
+        let connection = req.connection()?;
+        let api_key = match OrganizationApiKey::find_by_key(key, connection.get()) {
+            Ok(Some(api_key)) => api_key,
+            Ok(None) => return Err(error::ErrorUnauthorized("Invalid API key")),
+            Err(e) => return Err(error::ErrorInternalServerError(e)),
+        };
+
+        // Requests that fail or are refused are kept in the key's audit log too
+        let used_api_key = api_key.clone();
+        let method = req.method().as_str().to_string();
+        let path = req.path().to_string();
+        let ip_address = remote_ip_address(req);
+        if let Err(e) = connection.persist(move |conn| {
+            used_api_key.record_use(
+                &method,
+                &path,
+                ip_address.as_ref().map(|ip| ip.as_str()),
+                conn,
+            )
+        }) {
+            return Err(error::ErrorInternalServerError(e));
+        }
+
+        match DbUser::find(api_key.service_user_id, connection.get()) {
+            Ok(service_user) => {
+                let mut user = User::new(service_user);
+                user.api_key = Some(api_key);
+                Ok(user)
+            }
+            Err(e) => Err(error::ErrorInternalServerError(e)),
+        }
+    }
+
+    /// Loads an active impersonation and records the request against it if it is a write
+    fn find_impersonation(
+        req: &HttpRequest<AppState>,
+        impersonation_id: Uuid,
+    ) -> Result<Impersonation, Error> {
+        let connection = req.connection()?;
+        let impersonation = match Impersonation::find(impersonation_id, connection.get()) {
+            Ok(ref impersonation) if impersonation.is_active() => impersonation.clone(),
+            Ok(_) => return Err(error::ErrorUnauthorized("Impersonation has ended")),
+            Err(e) => return Err(error::ErrorInternalServerError(e)),
+        };
+
+        // Writes that fail or are refused are kept in the impersonation log too
+        if ![Method::GET, Method::HEAD, Method::OPTIONS].contains(req.method()) {
+            let logged_impersonation = impersonation.clone();
+            let method = req.method().as_str().to_string();
+            let path = req.path().to_string();
+            if let Err(e) = connection
+                .persist(move |conn| logged_impersonation.record_action(&method, &path, conn))
+            {
+                return Err(error::ErrorInternalServerError(e));
+            }
+        }
+
+        Ok(impersonation)
+    }
 }
 
 impl FromRequest<AppState> for User {
@@ -81,10 +229,22 @@ impl FromRequest<AppState> for User {
     type Result = Result<User, Error>;
 
     fn from_request(req: &HttpRequest<AppState>, _cfg: &Self::Config) -> Self::Result {
+        let mut user = User::authenticate(req)?;
+        user.request_id = RequestId::from_request(req);
+        Ok(user)
+    }
+}
+
+impl User {
+    fn authenticate(req: &HttpRequest<AppState>) -> Result<User, Error> {
         match req.headers().get("Authorization") {
             Some(auth_header) => {
                 let mut parts = auth_header.to_str().unwrap().split_whitespace();
-                if str::ne(parts.next().unwrap(), "Bearer") {
+                let scheme = parts.next().unwrap();
+                if scheme == "ApiKey" {
+                    return User::from_api_key(req, parts.next().unwrap_or(""));
+                }
+                if str::ne(scheme, "Bearer") {
                     return Err(error::ErrorUnauthorized(
                         "Authorization scheme not supported",
                     ));
@@ -105,8 +265,31 @@ impl FromRequest<AppState> for User {
                             }
 
                             let connection = req.connection()?;
+                            // Revoked sessions stop working immediately rather than once the
+                            // access token expires
+                            match Session::find(token.claims.get_session_id(), connection.get())
+                                .optional()
+                            {
+                                Ok(Some(ref session)) if session.is_active() => (),
+                                Ok(_) => return Err(error::ErrorUnauthorized("Invalid token")),
+                                Err(e) => return Err(error::ErrorInternalServerError(e)),
+                            }
+
+                            let impersonation = match token.claims.get_impersonation_id() {
+                                Some(impersonation_id) => {
+                                    Some(User::find_impersonation(req, impersonation_id)?)
+                                }
+                                None => None,
+                            };
+
                             match DbUser::find(token.claims.get_id(), connection.get()) {
-                                Ok(user) => Ok(User::new(user)),
+                                Ok(user) => {
+                                    let mut user = User::new(user);
+                                    user.session_id = Some(token.claims.get_session_id());
+                                    user.two_factor_authenticated = token.claims.two_factor;
+                                    user.impersonation = impersonation;
+                                    Ok(user)
+                                }
                                 Err(e) => Err(error::ErrorInternalServerError(e)),
                             }
                         } else {
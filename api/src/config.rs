@@ -1,6 +1,9 @@
+use auth::oidc::JwksCache;
 use dotenv::dotenv;
 use mail::transports::{SmtpTransport, TestTransport, Transport};
+use sms::transports::{SmsTransport, TestSmsTransport, TwilioSmsTransport};
 use std::env;
+use std::net::IpAddr;
 use tari_client::{HttpTariClient, TariClient, TariTestClient};
 
 #[derive(Clone, PartialEq)]
@@ -20,17 +23,32 @@ pub struct Config {
     pub database_url: String,
     pub domain: String,
     pub environment: Environment,
+    pub event_reminder_batch_size: i64,
+    pub event_reminder_interval: u64,
     pub facebook_app_id: Option<String>,
     pub facebook_app_secret: Option<String>,
     pub google_recaptcha_secret_key: Option<String>,
+    pub interest_notification_batch_size: i64,
+    pub interest_notification_interval: u64,
     pub mail_from_email: String,
     pub mail_from_name: String,
     pub mail_transport: Box<Transport + Send + Sync>,
+    pub oidc_client_id: Option<String>,
+    pub oidc_client_secret: Option<String>,
+    pub oidc_discovery_url: Option<String>,
+    pub oidc_key_cache: JwksCache,
     pub primary_currency: String,
+    pub sms_transport: Option<Box<SmsTransport + Send + Sync>>,
     pub stripe_secret_key: String,
     pub token_secret: String,
     pub token_issuer: String,
+    /// Proxies whose forwarding headers are trusted to give the client address
+    pub trusted_proxies: Vec<IpAddr>,
     pub tari_client: Box<TariClient + Send + Sync>,
+    pub webhook_batch_size: i64,
+    pub webhook_interval: u64,
+    /// Egress proxy webhook deliveries are sent through, it should refuse internal addresses
+    pub webhook_proxy: Option<String>,
 }
 
 const ALLOWED_ORIGINS: &str = "ALLOWED_ORIGINS";
@@ -39,15 +57,26 @@ const API_URL: &str = "API_URL";
 const API_PORT: &str = "API_PORT";
 const DATABASE_URL: &str = "DATABASE_URL";
 const DOMAIN: &str = "DOMAIN";
+const EVENT_REMINDER_BATCH_SIZE: &str = "EVENT_REMINDER_BATCH_SIZE";
+const EVENT_REMINDER_INTERVAL: &str = "EVENT_REMINDER_INTERVAL";
 const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
+const INTEREST_NOTIFICATION_BATCH_SIZE: &str = "INTEREST_NOTIFICATION_BATCH_SIZE";
+const INTEREST_NOTIFICATION_INTERVAL: &str = "INTEREST_NOTIFICATION_INTERVAL";
+const OIDC_CLIENT_ID: &str = "OIDC_CLIENT_ID";
+const OIDC_CLIENT_SECRET: &str = "OIDC_CLIENT_SECRET";
+const OIDC_DISCOVERY_URL: &str = "OIDC_DISCOVERY_URL";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
 const TOKEN_SECRET: &str = "TOKEN_SECRET";
 const TOKEN_ISSUER: &str = "TOKEN_ISSUER";
+const TRUSTED_PROXIES: &str = "TRUSTED_PROXIES";
+const WEBHOOK_BATCH_SIZE: &str = "WEBHOOK_BATCH_SIZE";
+const WEBHOOK_INTERVAL: &str = "WEBHOOK_INTERVAL";
+const WEBHOOK_PROXY: &str = "WEBHOOK_PROXY";
 
 // Mail settings
 const MAIL_FROM_EMAIL: &str = "MAIL_FROM_EMAIL";
@@ -57,6 +86,11 @@ const MAIL_SMTP_HOST: &str = "MAIL_SMTP_HOST";
 const MAIL_SMTP_PORT: &str = "MAIL_SMTP_PORT";
 const FRONT_END_URL: &str = "FRONT_END_URL";
 
+// SMS settings, SMS delivery is disabled when the account sid is not set
+const SMS_FROM_NUMBER: &str = "SMS_FROM_NUMBER";
+const TWILIO_ACCOUNT_SID: &str = "TWILIO_ACCOUNT_SID";
+const TWILIO_AUTH_TOKEN: &str = "TWILIO_AUTH_TOKEN";
+
 impl Config {
     pub fn new(environment: Environment) -> Self {
         dotenv().ok();
@@ -98,6 +132,30 @@ impl Config {
             }
         };
 
+        let sms_transport = match environment {
+            Environment::Test => {
+                Some(Box::new(TestSmsTransport::new()) as Box<SmsTransport + Send + Sync>)
+            }
+            _ => env::var(&TWILIO_ACCOUNT_SID).ok().map(|account_sid| {
+                if account_sid == "test" {
+                    Box::new(TestSmsTransport::new()) as Box<SmsTransport + Send + Sync>
+                } else {
+                    let auth_token = env::var(&TWILIO_AUTH_TOKEN)
+                        .unwrap_or_else(|_| panic!("{} must be defined.", TWILIO_AUTH_TOKEN));
+                    let from_number = env::var(&SMS_FROM_NUMBER)
+                        .unwrap_or_else(|_| panic!("{} must be defined.", SMS_FROM_NUMBER));
+
+                    info!("SMS configured from {}", from_number);
+
+                    Box::new(TwilioSmsTransport::new(
+                        &account_sid,
+                        &auth_token,
+                        &from_number,
+                    )) as Box<SmsTransport + Send + Sync>
+                }
+            }),
+        };
+
         let allowed_origins = env::var(&ALLOWED_ORIGINS).unwrap_or_else(|_| "*".to_string());
         let api_url = env::var(&API_URL).unwrap_or_else(|_| "127.0.0.1".to_string());
         let api_port = env::var(&API_PORT).unwrap_or_else(|_| "8088".to_string());
@@ -115,6 +173,11 @@ impl Config {
 
         let facebook_app_secret = env::var(&FACEBOOK_APP_SECRET).ok();
 
+        // OpenID Connect login is only enabled when the provider's discovery url is set
+        let oidc_discovery_url = env::var(&OIDC_DISCOVERY_URL).ok();
+        let oidc_client_id = env::var(&OIDC_CLIENT_ID).ok();
+        let oidc_client_secret = env::var(&OIDC_CLIENT_SECRET).ok();
+
         let front_end_url =
             env::var(&FRONT_END_URL).unwrap_or_else(|_| panic!("Front end url must be defined"));
 
@@ -134,6 +197,47 @@ impl Config {
 
         let google_recaptcha_secret_key = env::var(&GOOGLE_RECAPTCHA_SECRET_KEY).ok();
 
+        // Comma separated addresses of the load balancers in front of the API, forwarding
+        // headers from any other peer are ignored
+        let trusted_proxies = env::var(&TRUSTED_PROXIES)
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|s| s.trim().parse::<IpAddr>().ok())
+            .collect();
+
+        // Interested users are emailed in batches of this size, one batch per interval (seconds)
+        let interest_notification_batch_size = env::var(&INTEREST_NOTIFICATION_BATCH_SIZE)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(100);
+        let interest_notification_interval = env::var(&INTEREST_NOTIFICATION_INTERVAL)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(60);
+
+        // Ticket holders are reminded of upcoming events in batches of this size, one batch per
+        // interval (seconds)
+        let event_reminder_batch_size = env::var(&EVENT_REMINDER_BATCH_SIZE)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(100);
+        let event_reminder_interval = env::var(&EVENT_REMINDER_INTERVAL)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(300);
+
+        // Due webhook deliveries are attempted in batches of this size, one batch per interval
+        // (seconds)
+        let webhook_batch_size = env::var(&WEBHOOK_BATCH_SIZE)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(100);
+        let webhook_interval = env::var(&WEBHOOK_INTERVAL)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(10);
+        let webhook_proxy = env::var(&WEBHOOK_PROXY).ok();
+
         Config {
             allowed_origins,
             app_name,
@@ -142,18 +246,31 @@ impl Config {
             database_url,
             domain,
             environment,
+            event_reminder_batch_size,
+            event_reminder_interval,
             facebook_app_id,
             facebook_app_secret,
             google_recaptcha_secret_key,
+            interest_notification_batch_size,
+            interest_notification_interval,
             mail_from_name,
             mail_from_email,
             mail_transport,
+            oidc_client_id,
+            oidc_client_secret,
+            oidc_discovery_url,
+            oidc_key_cache: JwksCache::default(),
             primary_currency,
+            sms_transport,
             stripe_secret_key,
             token_secret,
             token_issuer,
+            trusted_proxies,
             front_end_url,
             tari_client,
+            webhook_batch_size,
+            webhook_interval,
+            webhook_proxy,
         }
     }
 }
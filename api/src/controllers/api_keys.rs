@@ -0,0 +1,120 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+const API_KEY_REQUESTS_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// The full key, only returned when it is created
+    pub key: String,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    if !can_manage_api_keys(&user, &organization, connection)? {
+        return application::unauthorized();
+    }
+
+    let api_keys = OrganizationApiKey::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&api_keys))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateApiKeyRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    // Keys are credentials so an admin acting as the user can not issue them
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let organization = Organization::find(parameters.id, connection)?;
+    if !can_manage_api_keys(&user, &organization, connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        &json.name,
+        json.scopes,
+        user.id(),
+        connection,
+    )?;
+    user.audit(AuditActions::Create, Tables::OrganizationApiKeys, Some(api_key.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&api_key))
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&CreateApiKeyResponse {
+        id: api_key.id,
+        name: api_key.name,
+        scopes: api_key.scopes,
+        key,
+    }))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let api_key = OrganizationApiKey::find(parameters.id, connection)?;
+    let organization = Organization::find(api_key.organization_id, connection)?;
+    if !can_manage_api_keys(&user, &organization, connection)? {
+        return application::unauthorized();
+    }
+
+    let revoked_api_key = api_key.revoke(connection)?;
+    user.audit(AuditActions::Delete, Tables::OrganizationApiKeys, Some(api_key.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&api_key), Some(&revoked_api_key))
+        .commit(connection)?;
+    application::no_content()
+}
+
+pub fn requests(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let api_key = OrganizationApiKey::find(parameters.id, connection)?;
+    let organization = Organization::find(api_key.organization_id, connection)?;
+    if !can_manage_api_keys(&user, &organization, connection)? {
+        return application::unauthorized();
+    }
+
+    let requests = api_key.requests(API_KEY_REQUESTS_LIMIT, connection)?;
+    Ok(HttpResponse::Ok().json(&requests))
+}
+
+/// API keys can only be managed by signed in users, never by another API key
+fn can_manage_api_keys(
+    user: &AuthUser,
+    organization: &Organization,
+    connection: &PgConnection,
+) -> Result<bool, BigNeonError> {
+    Ok(user.api_key.is_none()
+        && user.has_scope(Scopes::OrgWrite, Some(organization), connection)?)
+}
@@ -53,6 +53,10 @@ pub fn create(
     if artist.organization_id.is_some() {
         artist = artist.set_privacy(true, connection)?;
     }
+    user.audit(AuditActions::Create, Tables::Artists, Some(artist.id))
+        .for_organization(artist.organization_id)
+        .with_changes(None, Some(&artist))
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&artist))
 }
 
@@ -102,6 +106,10 @@ pub fn update(
     }
 
     let updated_artist = artist.update(&artist_parameters, connection)?;
+    user.audit(AuditActions::Update, Tables::Artists, Some(artist.id))
+        .for_organization(artist.organization_id)
+        .with_changes(Some(&artist), Some(&updated_artist))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(&updated_artist))
 }
 
@@ -114,5 +122,9 @@ pub fn toggle_privacy(
     }
     let artist = Artist::find(&parameters.id, connection)?;
     let updated_artist = artist.set_privacy(!artist.is_private, connection)?;
+    user.audit(AuditActions::Update, Tables::Artists, Some(artist.id))
+        .for_organization(artist.organization_id)
+        .with_changes(Some(&artist), Some(&updated_artist))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(updated_artist))
 }
@@ -0,0 +1,36 @@
+use actix_web::{HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::{Paging, PagingParameters, PathParameters, Payload};
+
+pub fn index(
+    (connection, path, paging_parameters, filter, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        Query<AuditEntryFilter>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let paging = Paging::new(&paging_parameters.into_inner());
+    let (entries, total) = AuditEntry::find_for_organization(
+        organization.id,
+        &filter,
+        paging.limit as i64,
+        (paging.page * paging.limit) as i64,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&Payload {
+        data: entries,
+        paging: Paging::clone_with_new_total(&paging, total as u64),
+    }))
+}
@@ -1,26 +1,47 @@
-use actix_web::{HttpRequest, HttpResponse, Json, State};
-use auth::{claims::RefreshToken, TokenResponse};
-use bigneon_db::models::User;
+use actix_web::{http::header, HttpRequest, HttpResponse, Json, State};
+use auth::{
+    claims::RefreshToken, claims::TwoFactorToken, TokenResponse, TwoFactorChallengeResponse,
+};
+use bigneon_db::models::{
+    LoginCode, LoginCodeChannel, LoginCodePurpose, LoginThrottle, Session, TwoFactorCredential,
+    User,
+};
+use bigneon_db::utils::errors::Optional;
+use controllers::two_factor::{self, TwoFactorCodeRequest};
 use crypto::sha2::Sha256;
 use db::Connection;
+use diesel::PgConnection;
 use errors::*;
 use helpers::application;
 use jwt::{Header, Token};
+use mail::mailers;
 use reqwest;
 use serde_json;
 use server::AppState;
+use sms::SmsMessage;
 use std::collections::HashMap;
+use std::net::IpAddr;
 
 const GOOGLE_RECAPTCHA_SITE_VERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+pub const LOGIN_THROTTLED_MESSAGE: &str = "Too many failed attempts, please try again later";
 
+/// Either an email and password, or an email or phone number and a one time login code
 #[derive(Deserialize)]
 pub struct LoginRequest {
-    email: String,
-    password: String,
+    email: Option<String>,
+    phone: Option<String>,
+    password: Option<String>,
+    code: Option<String>,
     #[serde(rename = "g-recaptcha-response")]
     captcha_response: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct LoginCodeRequest {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GoogleCaptchaResponse {
     success: bool,
@@ -33,11 +54,30 @@ pub struct RefreshRequest {
     refresh_token: String,
 }
 
+#[derive(Deserialize)]
+pub struct TwoFactorLoginRequest {
+    two_factor_token: String,
+    code: Option<String>,
+    recovery_code: Option<String>,
+}
+
 impl LoginRequest {
     pub fn new(email: &str, password: &str) -> Self {
         LoginRequest {
-            email: String::from(email),
-            password: String::from(password),
+            email: Some(String::from(email)),
+            phone: None,
+            password: Some(String::from(password)),
+            code: None,
+            captcha_response: None,
+        }
+    }
+
+    pub fn new_with_code(email: Option<&str>, phone: Option<&str>, code: &str) -> Self {
+        LoginRequest {
+            email: email.map(String::from),
+            phone: phone.map(String::from),
+            password: None,
+            code: Some(String::from(code)),
             captcha_response: None,
         }
     }
@@ -51,16 +91,30 @@ impl RefreshRequest {
     }
 }
 
+impl TwoFactorLoginRequest {
+    pub fn new(two_factor_token: &str, code: Option<&str>, recovery_code: Option<&str>) -> Self {
+        TwoFactorLoginRequest {
+            two_factor_token: String::from(two_factor_token),
+            code: code.map(String::from),
+            recovery_code: recovery_code.map(String::from),
+        }
+    }
+}
+
 pub fn token(
-    (http_request, connection, login_request): (
+    (http_request, request_connection, login_request): (
         HttpRequest<AppState>,
         Connection,
         Json<LoginRequest>,
     ),
 ) -> Result<HttpResponse, BigNeonError> {
     let state = http_request.state();
-    let connection_info = http_request.connection_info();
-    let remote_ip = connection_info.remote();
+    let connection = request_connection.get();
+    if is_login_throttled(&http_request, None, connection)? {
+        return application::too_many_requests(LOGIN_THROTTLED_MESSAGE);
+    }
+
+    let remote_ip = remote_ip_address(&http_request);
 
     if let Some(ref google_recaptcha_secret_key) = state.config.google_recaptcha_secret_key {
         match login_request.captcha_response {
@@ -68,7 +122,7 @@ pub fn token(
                 if !verify_google_captcha_response(
                     google_recaptcha_secret_key,
                     captcha_response,
-                    remote_ip,
+                    remote_ip.as_ref().map(|ip| ip.as_str()),
                 )? {
                     return application::unauthorized_with_message("Captcha value invalid");
                 }
@@ -79,22 +133,192 @@ pub fn token(
         }
     }
 
+    if let Some(ref code) = login_request.code {
+        return token_from_login_code(
+            &http_request,
+            login_request.email.as_ref().map(|e| e.as_str()),
+            login_request.phone.as_ref().map(|p| p.as_str()),
+            code,
+            &request_connection,
+        );
+    }
+
     // Generic messaging to prevent exposing user is member of system
     let login_failure_messaging = "Email or password incorrect";
 
-    let user = match User::find_by_email(&login_request.email, connection.get()) {
+    let (email, password) = match (&login_request.email, &login_request.password) {
+        (Some(ref email), Some(ref password)) => (email, password),
+        _ => return application::unauthorized_with_message(login_failure_messaging),
+    };
+
+    let user = match User::find_by_email(email, connection) {
         Ok(u) => u,
-        Err(_e) => return application::unauthorized_with_message(login_failure_messaging),
+        Err(_e) => {
+            record_failed_login(&http_request, None, &request_connection)?;
+            return application::unauthorized_with_message(login_failure_messaging);
+        }
     };
 
-    if !user.check_password(&login_request.password) {
+    if is_login_throttled(&http_request, Some(&user), connection)? {
+        return application::too_many_requests(LOGIN_THROTTLED_MESSAGE);
+    }
+
+    if !user.check_password(password) {
+        record_failed_login(&http_request, Some(&user), &request_connection)?;
         return application::unauthorized_with_message(login_failure_messaging);
     }
 
-    let response = TokenResponse::create_from_user(
+    LoginThrottle::clear_for_user(user.id, connection)?;
+    token_response_for_user(&http_request, &user, connection)
+}
+
+fn token_from_login_code(
+    http_request: &HttpRequest<AppState>,
+    email: Option<&str>,
+    phone: Option<&str>,
+    code: &str,
+    request_connection: &Connection,
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = request_connection.get();
+    // Generic messaging to prevent exposing user is member of system
+    let login_failure_messaging = "Login code incorrect or expired";
+
+    let user = match find_user_for_login_code(email, phone, connection)? {
+        Some(user) => user,
+        None => {
+            record_failed_login(http_request, None, request_connection)?;
+            return application::unauthorized_with_message(login_failure_messaging);
+        }
+    };
+
+    if is_login_throttled(http_request, Some(&user), connection)? {
+        return application::too_many_requests(LOGIN_THROTTLED_MESSAGE);
+    }
+
+    // Failed attempts are kept when the request transaction is rolled back so codes can not be
+    // guessed without limit
+    let user_id = user.id;
+    let code = code.to_string();
+    if !request_connection.persist(move |conn| LoginCode::redeem(user_id, &code, conn))? {
+        record_failed_login(http_request, Some(&user), request_connection)?;
+        return application::unauthorized_with_message(login_failure_messaging);
+    }
+
+    LoginThrottle::clear_for_user(user.id, connection)?;
+    token_response_for_user(http_request, &user, connection)
+}
+
+/// Sends a one time login code by email, or by SMS when a phone number is given. The response
+/// is the same whether or not a user was found to prevent exposing user is member of system
+pub fn request_login_code(
+    (http_request, connection, code_request): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<LoginCodeRequest>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let state = http_request.state();
+    let connection = connection.get();
+    if code_request.email.is_none() && code_request.phone.is_none() {
+        return application::unprocessable("Email or phone is required");
+    }
+    if code_request.phone.is_some() && state.config.sms_transport.is_none() {
+        return application::unprocessable("Login codes cannot be sent by SMS");
+    }
+    if is_login_throttled(&http_request, None, connection)? {
+        return application::too_many_requests(LOGIN_THROTTLED_MESSAGE);
+    }
+
+    let user = match find_user_for_login_code(
+        code_request.email.as_ref().map(|e| e.as_str()),
+        code_request.phone.as_ref().map(|p| p.as_str()),
+        connection,
+    )? {
+        Some(user) => user,
+        None => return application::no_content(),
+    };
+
+    if LoginCode::recently_sent(user.id, LoginCodePurpose::Login, connection)? {
+        return application::no_content();
+    }
+
+    match (&code_request.email, &user.phone) {
+        (Some(_), _) => {
+            let (_, code) =
+                LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection)?;
+            mailers::user::login_code_email(&state.config, &user, &code).deliver()?;
+        }
+        (None, Some(ref phone)) => {
+            let (_, code) = LoginCode::create_for_user(user.id, LoginCodeChannel::Sms, connection)?;
+            if let Some(ref sms_transport) = state.config.sms_transport {
+                sms_transport.clone().send(SmsMessage::new(
+                    phone,
+                    format!("Your {} login code is {}", state.config.app_name, code),
+                ))?;
+            }
+        }
+        (None, None) => (),
+    }
+
+    application::no_content()
+}
+
+/// Email takes precedence, phone numbers are only used once they have been verified
+fn find_user_for_login_code(
+    email: Option<&str>,
+    phone: Option<&str>,
+    connection: &PgConnection,
+) -> Result<Option<User>, BigNeonError> {
+    if let Some(email) = email {
+        return Ok(User::find_by_email(email, connection).optional()?);
+    }
+    if let Some(phone) = phone {
+        return Ok(User::find_by_verified_phone(phone, connection).optional()?);
+    }
+    Ok(None)
+}
+
+pub fn token_two_factor(
+    (http_request, request_connection, login_request): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<TwoFactorLoginRequest>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let state = http_request.state();
+    let token = match Token::<Header, TwoFactorToken>::parse(&login_request.two_factor_token) {
+        Ok(token) => token,
+        Err(_e) => return application::unauthorized_with_message("Invalid token"),
+    };
+    if !token.verify(state.config.token_secret.as_bytes(), Sha256::new())
+        || !token.claims.is_valid()
+    {
+        return application::unauthorized_with_message("Invalid token");
+    }
+
+    let connection = request_connection.get();
+    let user = User::find(token.claims.get_id(), connection)?;
+    if is_login_throttled(&http_request, Some(&user), connection)? {
+        return application::too_many_requests(LOGIN_THROTTLED_MESSAGE);
+    }
+
+    let code_request = TwoFactorCodeRequest {
+        code: login_request.code.clone(),
+        recovery_code: login_request.recovery_code.clone(),
+    };
+    if !two_factor::verify_code(user.id, &code_request, connection)? {
+        record_failed_login(&http_request, Some(&user), &request_connection)?;
+        return application::unauthorized_with_message("Two factor code incorrect");
+    }
+
+    LoginThrottle::clear_for_user(user.id, connection)?;
+
+    let session = create_session(&http_request, &user, connection)?;
+    let response = TokenResponse::create_from_session(
         &state.config.token_secret,
         &state.config.token_issuer,
-        &user,
+        &session,
+        true,
     );
     Ok(HttpResponse::Ok().json(response))
 }
@@ -108,15 +332,34 @@ pub fn token_refresh(
     };
 
     if token.verify(state.config.token_secret.as_bytes(), Sha256::new()) {
-        let user = User::find(token.claims.get_id(), connection.get())?;
+        let connection = connection.get();
+        let user = User::find(token.claims.get_id(), connection)?;
+
+        // Refresh tokens stop working once their session is revoked
+        let session = match Session::find(token.claims.get_session_id(), connection).optional()? {
+            Some(session) => session,
+            None => return application::unauthorized_with_message("Invalid token"),
+        };
+        if !session.is_active() || session.user_id != user.id {
+            return application::unauthorized_with_message("Invalid token");
+        }
+
+        // Refresh tokens issued before two factor authentication was enabled are invalidated
+        if !token.claims.two_factor
+            && TwoFactorCredential::is_enabled_for_user(user.id, connection)?
+        {
+            return application::unauthorized_with_message("Invalid token");
+        }
 
         // If the user changes their password invalidate all refresh tokens
         let password_modified_timestamp = user.password_modified_at.timestamp() as u64;
         if password_modified_timestamp <= token.claims.issued {
+            session.record_use(connection)?;
             let response = TokenResponse::create_from_refresh_token(
                 &state.config.token_secret,
                 &state.config.token_issuer,
-                &user.id,
+                &session,
+                token.claims.two_factor,
                 &refresh_request.refresh_token,
             );
             Ok(HttpResponse::Ok().json(response))
@@ -128,6 +371,144 @@ pub fn token_refresh(
     }
 }
 
+/// Starts a session for a user who has proven their identity, or returns a two factor
+/// challenge to be completed with `token_two_factor` if the user has two factor authentication
+/// enabled
+pub fn token_response_for_user(
+    http_request: &HttpRequest<AppState>,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<HttpResponse, BigNeonError> {
+    let config = &http_request.state().config;
+    if TwoFactorCredential::is_enabled_for_user(user.id, connection)? {
+        return Ok(
+            HttpResponse::Ok().json(TwoFactorChallengeResponse::create_for_user(
+                &config.token_secret,
+                &config.token_issuer,
+                user,
+            )),
+        );
+    }
+
+    let session = create_session(http_request, user, connection)?;
+    Ok(HttpResponse::Ok().json(TokenResponse::create_from_session(
+        &config.token_secret,
+        &config.token_issuer,
+        &session,
+        false,
+    )))
+}
+
+fn create_session(
+    http_request: &HttpRequest<AppState>,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<Session, BigNeonError> {
+    let user_agent = http_request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let ip_address = remote_ip_address(http_request);
+
+    Ok(Session::create(user.id, user_agent, ip_address).commit(connection)?)
+}
+
+/// True if further attempts from the requesting IP address, or against the user if given,
+/// are not allowed yet
+pub fn is_login_throttled(
+    http_request: &HttpRequest<AppState>,
+    user: Option<&User>,
+    connection: &PgConnection,
+) -> Result<bool, BigNeonError> {
+    if let Some(ip_address) = remote_ip_address(http_request) {
+        if let Some(throttle) =
+            LoginThrottle::find_for_ip_address(&ip_address, connection).optional()?
+        {
+            if throttle.is_throttled() {
+                return Ok(true);
+            }
+        }
+    }
+
+    if let Some(user) = user {
+        if let Some(throttle) = LoginThrottle::find_for_user(user.id, connection).optional()? {
+            return Ok(throttle.is_throttled());
+        }
+    }
+
+    Ok(false)
+}
+
+/// Records a failed attempt from the requesting IP address and against the user if given,
+/// emailing the user when the failure locks their account. The failure is kept when the
+/// request transaction is rolled back
+pub fn record_failed_login(
+    http_request: &HttpRequest<AppState>,
+    user: Option<&User>,
+    connection: &Connection,
+) -> Result<(), BigNeonError> {
+    if let Some(ip_address) = remote_ip_address(http_request) {
+        connection.persist(move |conn| {
+            LoginThrottle::record_failure_for_ip_address(&ip_address, conn)
+        })?;
+    }
+
+    if let Some(user) = user {
+        let user_id = user.id;
+        let throttle =
+            connection.persist(move |conn| LoginThrottle::record_failure_for_user(user_id, conn))?;
+        match throttle.locked_until {
+            Some(locked_until) if throttle.lockout_started() && user.email.is_some() => {
+                mailers::user::account_locked_email(
+                    &http_request.state().config,
+                    user,
+                    locked_until,
+                ).deliver()?;
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// The client address without the port. Forwarding headers are only honoured when the request
+/// came from a trusted proxy, otherwise any client could choose the address it is throttled by
+pub fn remote_ip_address(http_request: &HttpRequest<AppState>) -> Option<String> {
+    let forwarded_for = http_request
+        .headers()
+        .get("X-Forwarded-For")
+        .and_then(|value| value.to_str().ok());
+    client_ip_address(
+        http_request.peer_addr().map(|address| address.ip()),
+        forwarded_for,
+        &http_request.state().config.trusted_proxies,
+    ).map(|address| address.to_string())
+}
+
+/// Walks the X-Forwarded-For chain back from the peer, the first address not added by a trusted
+/// proxy is the client
+pub fn client_ip_address(
+    peer: Option<IpAddr>,
+    forwarded_for: Option<&str>,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    let mut client = peer?;
+    if let Some(forwarded_for) = forwarded_for {
+        for address in forwarded_for.rsplit(',') {
+            if !trusted_proxies.contains(&client) {
+                break;
+            }
+            match address.trim().parse::<IpAddr>() {
+                Ok(address) => client = address,
+                Err(_) => break,
+            }
+        }
+    }
+    Some(client)
+}
+
 fn verify_google_captcha_response(
     google_recaptcha_secret_key: &str,
     captcha_response: &str,
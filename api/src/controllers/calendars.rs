@@ -0,0 +1,147 @@
+use actix_web::{http::header, HttpResponse, Path, Query, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use server::AppState;
+use utils::calendar::Calendar;
+use uuid::Uuid;
+
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+#[derive(Deserialize)]
+pub struct CalendarFeedParameters {
+    pub token: Uuid,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CalendarSubscription {
+    pub token: Uuid,
+    pub path: String,
+}
+
+impl CalendarSubscription {
+    fn for_user(calendar_token: &CalendarToken) -> CalendarSubscription {
+        CalendarSubscription {
+            token: calendar_token.token,
+            path: format!("/users/me/calendar.ics?token={}", calendar_token.token),
+        }
+    }
+
+    fn for_organization(
+        organization_id: Uuid,
+        calendar_token: &CalendarToken,
+    ) -> CalendarSubscription {
+        CalendarSubscription {
+            token: calendar_token.token,
+            path: format!(
+                "/organizations/{}/events.ics?token={}",
+                organization_id, calendar_token.token
+            ),
+        }
+    }
+}
+
+pub fn show_user_subscription(
+    (connection, user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let calendar_token = CalendarToken::find_or_create_for_user(user.id(), connection.get())?;
+    Ok(HttpResponse::Ok().json(&CalendarSubscription::for_user(&calendar_token)))
+}
+
+pub fn regenerate_user_subscription(
+    (connection, user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let calendar_token =
+        CalendarToken::find_or_create_for_user(user.id(), connection)?.regenerate(connection)?;
+    Ok(HttpResponse::Ok().json(&CalendarSubscription::for_user(&calendar_token)))
+}
+
+pub fn user_feed(
+    (state, connection, query): (State<AppState>, Connection, Query<CalendarFeedParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let calendar_token = match CalendarToken::find_by_token(query.token, connection).optional()? {
+        Some(calendar_token) => calendar_token,
+        None => return application::unauthorized(),
+    };
+    let user_id = match calendar_token.user_id {
+        Some(user_id) => user_id,
+        None => return application::unauthorized(),
+    };
+    let user = User::find(user_id, connection)?;
+
+    let mut calendar = Calendar::new(&format!("{} tickets", state.config.app_name));
+    for (event, _tickets) in
+        TicketInstance::find_for_user_for_display(user.id, None, None, None, connection)?
+    {
+        calendar.add_event(&event, &state.config);
+    }
+
+    Ok(HttpResponse::Ok()
+        .header(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)
+        .body(calendar.to_ics(&state.config)))
+}
+
+pub fn show_organization_subscription(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgRead, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, connection)?;
+    let subscription = CalendarSubscription::for_organization(organization.id, &calendar_token);
+    Ok(HttpResponse::Ok().json(&subscription))
+}
+
+pub fn regenerate_organization_subscription(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, connection)?
+            .regenerate(connection)?;
+    let subscription = CalendarSubscription::for_organization(organization.id, &calendar_token);
+    Ok(HttpResponse::Ok().json(&subscription))
+}
+
+pub fn organization_feed(
+    (state, connection, path, query): (
+        State<AppState>,
+        Connection,
+        Path<PathParameters>,
+        Query<CalendarFeedParameters>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let calendar_token = match CalendarToken::find_by_token(query.token, connection).optional()? {
+        Some(calendar_token) => calendar_token,
+        None => return application::unauthorized(),
+    };
+    if calendar_token.organization_id != Some(path.id) {
+        return application::unauthorized();
+    }
+    let organization = Organization::find(path.id, connection)?;
+
+    let mut calendar = Calendar::new(&organization.name);
+    for event in Event::find_published_for_organization(organization.id, connection)? {
+        calendar.add_event(&event.for_display(connection)?, &state.config);
+    }
+
+    Ok(HttpResponse::Ok()
+        .header(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)
+        .body(calendar.to_ics(&state.config)))
+}
@@ -4,10 +4,15 @@ use actix_web::{http::StatusCode, HttpResponse};
 use auth::user::User;
 use bigneon_db::models::*;
 use bigneon_db::utils::errors::Optional;
+use config::Config;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
 use db::Connection;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
 use errors::BigNeonError;
 use helpers::application;
 use itertools::Itertools;
+use mail::mailers;
 use payments::PaymentProcessor;
 use server::AppState;
 use std::collections::HashMap;
@@ -28,6 +33,7 @@ pub struct AddToCartRequestItem {
 #[derive(Deserialize)]
 pub struct AddToCartRequest {
     pub items: Vec<AddToCartRequestItem>,
+    pub presale_code: Option<String>,
 }
 
 pub fn add(
@@ -53,7 +59,12 @@ pub fn add(
     {
         let quantity = request_items.fold(0, |sum, request_item| sum + request_item.quantity);
 
-        cart.add_tickets(ticket_type_id, quantity, connection)?;
+        cart.add_tickets_with_presale_code(
+            ticket_type_id,
+            quantity,
+            json.presale_code.as_ref().map(|c| c.as_str()),
+            connection,
+        )?;
     }
 
     cart.update_event_fees(connection)?;
@@ -128,6 +139,17 @@ pub fn checkout(
     (connection, json, user, state): (Connection, Json<CheckoutCartRequest>, User, State<AppState>),
 ) -> Result<HttpResponse, BigNeonError> {
     let req = json.into_inner();
+    if let PaymentRequest::Card {
+        save_payment_method,
+        set_default,
+        ..
+    } = req.method
+    {
+        // Saved payment methods can only be changed by the user themselves
+        if user.is_impersonated() && (save_payment_method || set_default) {
+            return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+        }
+    }
 
     info!("CART: Checking out");
     let mut order = match Order::find_cart_for_user(user.id(), connection.get())? {
@@ -240,11 +262,37 @@ pub fn checkout(
                 ),
             }
         }
+
+        // The order is already paid for so failing to email the confirmation is only logged.
+        // The savepoint keeps the payment when a query fails while building the emails
+        let connection = connection.get();
+        if let Err(e) =
+            connection.transaction(|| send_confirmation_emails(&state.config, order.id, connection))
+        {
+            error!("Could not send order confirmation for {}: {}", order.id, e);
+        }
     }
 
     Ok(payment_response)
 }
 
+fn send_confirmation_emails(
+    config: &Config,
+    order_id: Uuid,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let order = Order::find(order_id, connection)?;
+    if order.status() != OrderStatus::Paid {
+        return Ok(());
+    }
+    for mut mailer in mailers::orders::confirmation_emails(config, &order, connection)? {
+        if let Err(e) = mailer.deliver() {
+            error!("Could not send order confirmation for {}: {}", order.id, e);
+        }
+    }
+    Ok(())
+}
+
 // TODO: This should actually probably move to an `orders` controller, since the
 // user will not be calling this.
 fn checkout_external(
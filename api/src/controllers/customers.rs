@@ -0,0 +1,124 @@
+use actix_web::{HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::{Paging, PagingParameters, PathParameters, Payload};
+use utils::csv::Csv;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CustomerDirectoryParameters {
+    pub query: Option<String>,
+    pub event_id: Option<Uuid>,
+    pub min_spent_in_cents: Option<i64>,
+    pub min_events_attended: Option<i64>,
+    pub purchased_after: Option<NaiveDateTime>,
+    pub purchased_before: Option<NaiveDateTime>,
+    pub format: Option<String>,
+}
+
+impl CustomerDirectoryParameters {
+    fn filter(&self) -> OrganizationCustomerFilter {
+        OrganizationCustomerFilter {
+            query: self.query.clone(),
+            event_id: self.event_id,
+            min_spent_in_cents: self.min_spent_in_cents,
+            min_events_attended: self.min_events_attended,
+            purchased_after: self.purchased_after,
+            purchased_before: self.purchased_before,
+        }
+    }
+}
+
+pub fn index(
+    (connection, path, paging_parameters, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        Query<CustomerDirectoryParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgCustomers, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let filter = query.filter();
+    match query.format.as_ref().map(|f| f.as_str()) {
+        None | Some("json") => {
+            let paging = Paging::new(&paging_parameters.into_inner());
+            let (customers, total) = OrganizationCustomer::find_for_organization(
+                organization.id,
+                &filter,
+                Some(paging.limit as i64),
+                (paging.page * paging.limit) as i64,
+                connection,
+            )?;
+            Ok(HttpResponse::Ok().json(&Payload {
+                data: customers,
+                paging: Paging::clone_with_new_total(&paging, total as u64),
+            }))
+        }
+        // Exports contain every customer matching the filter
+        Some("csv") => {
+            let (customers, _) = OrganizationCustomer::find_for_organization(
+                organization.id,
+                &filter,
+                None,
+                0,
+                connection,
+            )?;
+            application::attachment(
+                "text/csv; charset=utf-8",
+                &format!("{} customers.csv", organization.name),
+                customers_to_csv(&customers).into_bytes(),
+            )
+        }
+        Some(_) => application::unprocessable("Customer format must be one of json or csv"),
+    }
+}
+
+fn customers_to_csv(customers: &[OrganizationCustomer]) -> String {
+    let mut csv = Csv::new();
+    csv.add_row(vec![
+        "User Id",
+        "First Name",
+        "Last Name",
+        "Email",
+        "Phone",
+        "Lifetime Spent (cents)",
+        "Tickets",
+        "Events Attended",
+        "First Purchase",
+        "Last Purchase",
+        "Interests",
+    ]);
+    for customer in customers {
+        csv.add_row(vec![
+            customer.user_id.to_string(),
+            customer.first_name.clone(),
+            customer.last_name.clone(),
+            customer.email.clone().unwrap_or_default(),
+            customer.phone.clone().unwrap_or_default(),
+            customer.lifetime_spent_in_cents.to_string(),
+            customer.ticket_count.to_string(),
+            customer.events_attended.to_string(),
+            customer
+                .first_purchase_at
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            customer
+                .last_purchase_at
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            customer.interests.join("; "),
+        ]);
+    }
+
+    csv.into_string()
+}
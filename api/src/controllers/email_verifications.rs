@@ -0,0 +1,45 @@
+use actix_web::{HttpResponse, Json, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::concerns::users::email_verifiable::*;
+use bigneon_db::models::User;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use mail::mailers;
+use server::AppState;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct UpdateEmailVerificationParameters {
+    pub email_verification_token: Uuid,
+}
+
+pub fn create(
+    (state, connection, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let user = User::find(user.id(), connection)?;
+    if user.is_email_verified() {
+        return application::unprocessable("Email address is already verified");
+    }
+    if user.email.is_none() {
+        return application::unprocessable("No email address is set for this account");
+    }
+    if !user.can_resend_email_verification() {
+        return application::too_many_requests(
+            "A verification email was sent recently, please wait a few minutes before requesting another",
+        );
+    }
+
+    let user = user.create_email_verification_token(connection)?;
+    mailers::user::email_verification_email(&state.config, &user).deliver()?;
+
+    Ok(HttpResponse::Created().finish())
+}
+
+pub fn update(
+    (connection, parameters): (Connection, Json<UpdateEmailVerificationParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    User::consume_email_verification_token(&parameters.email_verification_token, connection.get())?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,85 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct AddEventPromoterRequest {
+    pub organization_id: Uuid,
+    pub permission: EventPromoterPermissions,
+    /// Share of the event's ticket revenue, 100 basis points is 1%
+    pub revenue_split_in_basis_points: i64,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    if !user.has_scope_for_event(Scopes::OrgRead, &event, connection)? {
+        return application::unauthorized();
+    }
+
+    let promoters = EventPromoter::find_for_event(event.id, connection)?;
+    Ok(HttpResponse::Ok().json(&promoters))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<AddEventPromoterRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    // Only the owning organization decides who co-promotes its events
+    if !user.has_scope(Scopes::OrgWrite, Some(&event.organization(connection)?), connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    let promoter = EventPromoter::create(
+        event.id,
+        json.organization_id,
+        json.permission,
+        json.revenue_split_in_basis_points,
+    ).commit(connection)?;
+    user.audit(AuditActions::Create, Tables::EventPromoters, Some(promoter.id))
+        .for_organization(event.organization_id)
+        .with_changes(None, Some(&promoter))
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&promoter))
+}
+
+pub fn destroy(
+    (connection, path, organization_id, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<Uuid>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&event.organization(connection)?), connection)? {
+        return application::unauthorized();
+    }
+
+    let promoter = EventPromoter::find_by_event_and_organization(
+        event.id,
+        organization_id.into_inner(),
+        connection,
+    )?;
+    promoter.destroy(connection)?;
+    user.audit(AuditActions::Delete, Tables::EventPromoters, Some(promoter.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&promoter), None)
+        .commit(connection)?;
+    application::no_content()
+}
@@ -2,6 +2,7 @@ use actix_web::{HttpResponse, Json, Path, Query};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
+use controllers::organizations::FeeScheduleWithRanges;
 use db::Connection;
 use errors::*;
 use helpers::application;
@@ -11,6 +12,8 @@ use models::{
 };
 use serde_with::{self, CommaSeparator};
 use std::collections::HashMap;
+use utils::csv::Csv;
+use utils::pdf::PdfDocument;
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -186,7 +189,6 @@ pub fn show(
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
     let organization = event.organization(connection)?;
-    let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection)?;
 
     let venue = event.venue(connection)?;
     let event_artists = EventArtist::find_all_from_event(event.id, connection)?;
@@ -199,6 +201,7 @@ pub fn show(
     let ticket_types = TicketType::find_by_event_id(parameters.id, connection)?;
     let mut display_ticket_types = Vec::new();
     for ticket_type in ticket_types {
+        let fee_schedule = FeeSchedule::find_for_ticket_type(&ticket_type, connection)?;
         display_ticket_types.push(UserDisplayTicketType::from_ticket_type(
             &ticket_type,
             &fee_schedule,
@@ -284,8 +287,12 @@ pub fn publish(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = connection.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &event.organization(conn)?, conn)?;
-    event.publish(conn)?;
+    user.requires_scope_for_event(Scopes::EventWrite, &event, conn)?;
+    let published_event = event.clone().publish(conn)?;
+    user.audit(AuditActions::Publish, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&event), Some(&published_event))
+        .commit(conn)?;
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -339,6 +346,10 @@ pub fn create(
     }
 
     let event = new_event.commit(connection)?;
+    user.audit(AuditActions::Create, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(None, Some(&event))
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&event))
 }
 
@@ -352,15 +363,29 @@ pub fn update(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
-    if !user.has_scope(
-        Scopes::EventWrite,
-        Some(&event.organization(connection)?),
-        connection,
-    )? {
+    if !user.has_scope_for_event(Scopes::EventWrite, &event, connection)? {
+        return application::unauthorized();
+    }
+
+    // Fees and cancellation are left to the owning organization, co-promoters with edit
+    // permission can only change the listing
+    let event_parameters = event_parameters.into_inner();
+    if (event_parameters.fee_in_cents.is_some()
+        || event_parameters.fee_mode.is_some()
+        || event_parameters.cancelled_at.is_some())
+        && !user.has_scope(
+            Scopes::EventWrite,
+            Some(&event.organization(connection)?),
+            connection,
+        )? {
         return application::unauthorized();
     }
 
-    let updated_event = event.update(event_parameters.into_inner(), connection)?;
+    let updated_event = event.update(event_parameters, connection)?;
+    user.audit(AuditActions::Update, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&event), Some(&updated_event))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(&updated_event))
 }
 
@@ -369,6 +394,7 @@ pub fn cancel(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
+    // Only the owning organization can cancel, not co-promoters
     if !user.has_scope(
         Scopes::EventWrite,
         Some(&event.organization(connection)?),
@@ -378,7 +404,11 @@ pub fn cancel(
     }
 
     //Doing this in the DB layer so it can use the DB time as now.
-    let updated_event = event.cancel(connection)?;
+    let updated_event = event.clone().cancel(connection)?;
+    user.audit(AuditActions::Cancel, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&event), Some(&updated_event))
+        .commit(connection)?;
 
     Ok(HttpResponse::Ok().json(&updated_event))
 }
@@ -448,11 +478,7 @@ pub fn add_artist(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
-    if !user.has_scope(
-        Scopes::EventWrite,
-        Some(&event.organization(connection)?),
-        connection,
-    )? {
+    if !user.has_scope_for_event(Scopes::EventWrite, &event, connection)? {
         return application::unauthorized();
     }
 
@@ -462,6 +488,10 @@ pub fn add_artist(
         event_artist.rank,
         event_artist.set_time,
     ).commit(connection)?;
+    user.audit(AuditActions::Create, Tables::EventArtists, Some(event_artist.id))
+        .for_organization(event.organization_id)
+        .with_changes(None, Some(&event_artist))
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&event_artist))
 }
 
@@ -475,14 +505,11 @@ pub fn update_artists(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
-    if !user.has_scope(
-        Scopes::EventWrite,
-        Some(&event.organization(connection)?),
-        connection,
-    )? {
+    if !user.has_scope_for_event(Scopes::EventWrite, &event, connection)? {
         return application::unauthorized();
     }
 
+    let previous_artists = EventArtist::find_all_from_event(event.id, connection)?;
     EventArtist::clear_all_from_event(parameters.id, connection)?;
 
     let mut rank = 0;
@@ -495,6 +522,12 @@ pub fn update_artists(
         rank += 1;
     }
 
+    user.audit(AuditActions::Update, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(
+            Some(&json!({ "artists": previous_artists })),
+            Some(&json!({ "artists": added_artists })),
+        ).commit(connection)?;
     Ok(HttpResponse::Ok().json(&added_artists))
 }
 
@@ -534,11 +567,7 @@ pub fn guest_list(
     let queryparms = query.create_paging_struct();
     let conn = connection.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(
-        Scopes::EventViewGuests,
-        &event.organization(conn)?,
-        conn,
-    )?;
+    user.requires_scope_for_event(Scopes::EventViewGuests, &event, conn)?;
     let tickets = event.guest_list(&query.query, conn)?;
     let tickets_count = tickets.len();
     let mut payload = Payload {
@@ -548,3 +577,169 @@ pub fn guest_list(
     payload.paging.limit = tickets_count as u64;
     Ok(HttpResponse::Ok().json(payload))
 }
+
+pub fn add_fee_schedule(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewFeeSchedule>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::OrgAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let event = Event::find(path.id, connection)?;
+    // Replacing the event's fee schedule adds a version to it
+    let fee_schedule = match event.fee_schedule_id {
+        Some(fee_schedule_id) => json.into_inner().commit_as_version_of(
+            &FeeSchedule::find(fee_schedule_id, connection)?,
+            connection,
+        )?,
+        None => json.into_inner().commit(connection)?,
+    };
+    let updated_event = event.set_fee_schedule(Some(&fee_schedule), connection)?;
+    user.audit(AuditActions::Create, Tables::FeeSchedules, Some(fee_schedule.id))
+        .for_organization(event.organization_id)
+        .with_changes(None, Some(&fee_schedule))
+        .commit(connection)?;
+    user.audit(AuditActions::Update, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&event), Some(&updated_event))
+        .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(FeeScheduleWithRanges {
+        id: fee_schedule.id,
+        name: fee_schedule.name.clone(),
+        version: fee_schedule.version,
+        created_at: fee_schedule.created_at,
+        ranges: fee_schedule.ranges(connection)?,
+    }))
+}
+
+pub fn remove_fee_schedule(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::OrgAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let event = Event::find(path.id, connection)?;
+    let updated_event = event.set_fee_schedule(None, connection)?;
+    user.audit(AuditActions::Update, Tables::Events, Some(event.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&event), Some(&updated_event))
+        .commit(connection)?;
+    application::no_content()
+}
+
+#[derive(Deserialize)]
+pub struct GuestListExportParameters {
+    pub format: Option<String>,
+}
+
+pub fn export_guest_list(
+    (connection, query, path, user): (
+        Connection,
+        Query<GuestListExportParameters>,
+        Path<PathParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection.get();
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_event(Scopes::EventViewGuests, &event, conn)?;
+    let guests = event.guest_list_export(conn)?;
+
+    match query.format.as_ref().map(|f| f.as_str()) {
+        None | Some("csv") => application::attachment(
+            "text/csv; charset=utf-8",
+            &format!("{} guest list.csv", event.name),
+            guest_list_to_csv(&guests).into_bytes(),
+        ),
+        Some("pdf") => application::attachment(
+            "application/pdf",
+            &format!("{} guest list.pdf", event.name),
+            guest_list_to_pdf(&event, &guests),
+        ),
+        Some(_) => application::unprocessable("Guest list format must be one of csv or pdf"),
+    }
+}
+
+fn guest_list_to_csv(guests: &[GuestListEntry]) -> String {
+    let mut csv = Csv::new();
+    csv.add_row(vec![
+        "Last Name",
+        "First Name",
+        "Email",
+        "Phone",
+        "Ticket Type",
+        "Status",
+        "Redeem Key",
+        "Order Id",
+        "Purchaser Name",
+        "Purchaser Email",
+        "Ticket Id",
+    ]);
+    for guest in guests {
+        let purchaser_name = match (&guest.purchaser_first_name, &guest.purchaser_last_name) {
+            (&Some(ref first_name), &Some(ref last_name)) => format!("{} {}", first_name, last_name),
+            _ => String::new(),
+        };
+        csv.add_row(vec![
+            guest.last_name.clone(),
+            guest.first_name.clone(),
+            guest.email.clone().unwrap_or_default(),
+            guest.phone.clone().unwrap_or_default(),
+            guest.ticket_type.clone(),
+            guest.status.clone(),
+            guest.redeem_key.clone().unwrap_or_default(),
+            guest.order_id.map(|id| id.to_string()).unwrap_or_default(),
+            purchaser_name,
+            guest.purchaser_email.clone().unwrap_or_default(),
+            guest.id.to_string(),
+        ]);
+    }
+    csv.into_string()
+}
+
+fn guest_list_to_pdf(event: &Event, guests: &[GuestListEntry]) -> Vec<u8> {
+    let mut document = PdfDocument::new();
+    document.heading(&format!("{} - Guest List", event.name));
+    if let Some(event_start) = event.event_start {
+        document.text_line(&event_start.format("%A %e %B %Y, %H:%M").to_string());
+    }
+    document.text_line(&format!("{} tickets", guests.len()));
+
+    // Guests are already sorted by last name, start a new section for each letter
+    let mut current_section = None;
+    for guest in guests {
+        let section = guest
+            .last_name
+            .chars()
+            .next()
+            .map(|c| c.to_uppercase().to_string())
+            .unwrap_or_else(|| "#".to_string());
+        if current_section.as_ref() != Some(&section) {
+            document.heading(&section);
+            current_section = Some(section);
+        }
+
+        let name: String = format!("{}, {}", guest.last_name, guest.first_name)
+            .chars()
+            .take(32)
+            .collect();
+        let ticket_type: String = guest.ticket_type.chars().take(24).collect();
+        document.checkbox_line(&[
+            (name.as_str(), 190.0),
+            (ticket_type.as_str(), 140.0),
+            (guest.redeem_key.as_ref().map(|k| k.as_str()).unwrap_or(""), 80.0),
+            (guest.status.as_str(), 70.0),
+        ]);
+    }
+
+    document.into_bytes()
+}
@@ -1,6 +1,6 @@
-use actix_web::{HttpResponse, Json, State};
-use auth::TokenResponse;
+use actix_web::{HttpRequest, HttpResponse, Json};
 use bigneon_db::models::{ExternalLogin, User};
+use controllers::auth;
 use db::Connection;
 use errors::*;
 use models::FacebookWebLoginToken;
@@ -21,7 +21,11 @@ struct FacebookGraphResponse {
 
 // TODO: Not covered by tests
 pub fn web_login(
-    (state, connection, auth_token): (State<AppState>, Connection, Json<FacebookWebLoginToken>),
+    (http_request, connection, auth_token): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<FacebookWebLoginToken>,
+    ),
 ) -> Result<HttpResponse, BigNeonError> {
     info!("Finding user");
     let url = format!(
@@ -83,10 +87,5 @@ pub fn web_login(
         }
     };
     info!("Saving access token");
-    let response = TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &user,
-    );
-    Ok(HttpResponse::Ok().json(response))
+    auth::token_response_for_user(&http_request, &user, connection)
 }
@@ -0,0 +1,136 @@
+use actix_web::{HttpRequest, HttpResponse, Json, Query};
+use auth::oidc::OidcProvider;
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use controllers::auth;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use mail::mailers;
+use server::AppState;
+
+#[derive(Deserialize)]
+pub struct OidcAuthorizeParameters {
+    pub redirect_uri: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+    pub state: String,
+}
+
+#[derive(Deserialize)]
+pub struct OidcWebLoginRequest {
+    pub code: String,
+    pub redirect_uri: String,
+    pub state: String,
+}
+
+/// Starts a login, the state and nonce in the returned URL are required by `web_login`
+pub fn authorize(
+    (http_request, connection, parameters): (
+        HttpRequest<AppState>,
+        Connection,
+        Query<OidcAuthorizeParameters>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let provider = match OidcProvider::from_config(&http_request.state().config) {
+        Some(provider) => provider,
+        None => return application::not_found(),
+    };
+    let login_request =
+        OidcLoginRequest::create(&parameters.redirect_uri).commit(connection.get())?;
+    let discovery = provider.discover()?;
+    let authorization_url = provider.authorization_url(
+        &discovery,
+        &login_request.redirect_uri,
+        &login_request.state,
+        &login_request.nonce,
+    )?;
+    Ok(HttpResponse::Ok().json(OidcAuthorizeResponse {
+        authorization_url,
+        state: login_request.state,
+    }))
+}
+
+pub fn web_login(
+    (http_request, connection, parameters): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<OidcWebLoginRequest>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let config = &http_request.state().config;
+    let provider = match OidcProvider::from_config(config) {
+        Some(provider) => provider,
+        None => return application::not_found(),
+    };
+    let connection = connection.get();
+    let login_request =
+        match OidcLoginRequest::consume(&parameters.state, &parameters.redirect_uri, connection)? {
+            Some(login_request) => login_request,
+            None => return application::unauthorized_with_message("Invalid or expired login state"),
+        };
+    let login = provider.login(&parameters.code, &parameters.redirect_uri, &login_request.nonce)?;
+    let claims = &login.claims;
+
+    // The issuer identifies the provider so subjects from different providers never collide
+    let user = match ExternalLogin::find_user(&claims.sub, &claims.iss, connection)? {
+        Some(external_login) => {
+            info!("Found existing user with id: {}", &external_login.user_id);
+            User::find(external_login.user_id, connection)?
+        }
+        None => {
+            let email = match claims.email {
+                Some(ref email) => email,
+                None => {
+                    return application::unprocessable(
+                        "Identity provider did not supply an email address",
+                    )
+                }
+            };
+
+            match User::find_by_email(email, connection).optional()? {
+                Some(user) => {
+                    // Only link to an existing account when the provider vouches for the email
+                    if claims.verified_email().is_none() {
+                        return application::unprocessable(
+                            "An account already exists for this email address",
+                        );
+                    }
+                    info!("User has existing account, linking external service");
+                    user.add_external_login(
+                        claims.sub.clone(),
+                        claims.iss.clone(),
+                        login.access_token.clone(),
+                        connection,
+                    )?;
+                    user
+                }
+                None => {
+                    info!("Creating new user");
+                    let (first_name, last_name) = claims.names();
+                    let user = User::create_from_external_login(
+                        claims.sub.clone(),
+                        first_name,
+                        last_name,
+                        email.clone(),
+                        claims.iss.clone(),
+                        login.access_token.clone(),
+                        connection,
+                    )?;
+                    if claims.verified_email().is_some() {
+                        user.mark_email_verified(connection)?
+                    } else {
+                        let user = user.create_email_verification_token(connection)?;
+                        mailers::user::email_verification_email(config, &user).deliver()?;
+                        user
+                    }
+                }
+            }
+        }
+    };
+    auth::token_response_for_user(&http_request, &user, connection)
+}
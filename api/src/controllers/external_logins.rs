@@ -0,0 +1,37 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let external_logins = ExternalLogin::find_for_user(user.id(), connection.get())?;
+    Ok(HttpResponse::Ok().json(&external_logins))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let connection = connection.get();
+    match ExternalLogin::find(parameters.id, connection).optional()? {
+        Some(ref external_login) if external_login.user_id == user.id() => {
+            // Users without a password have to keep a way to sign in
+            let external_logins = ExternalLogin::find_for_user(user.id(), connection)?;
+            if !user.user.password_set && external_logins.len() <= 1 {
+                return application::unprocessable(
+                    "Set a password before removing your only sign in method",
+                );
+            }
+            external_login.destroy(connection)?;
+            application::no_content()
+        }
+        _ => application::not_found(),
+    }
+}
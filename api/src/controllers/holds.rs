@@ -6,6 +6,7 @@ use db::Connection;
 use errors::BigNeonError;
 use helpers::application;
 use models::PathParameters;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
@@ -36,7 +37,7 @@ pub fn create(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &event.organization(conn)?, conn)?;
+    user.requires_scope_for_event(Scopes::HoldWrite, &event, conn)?;
 
     let hold = Hold::create(
         req.name.clone(),
@@ -50,6 +51,11 @@ pub fn create(
     for line in &req.items {
         hold.set_quantity(line.ticket_type_id, line.quantity, conn)?;
     }
+    user.audit(AuditActions::Create, Tables::Holds, Some(hold.id))
+        .for_organization(event.organization_id)
+        .with_changes(None, Some(&hold))
+        .with_change("items", &Vec::new(), &req.items)
+        .commit(conn)?;
 
     application::created(json!(hold))
 }
@@ -65,8 +71,14 @@ pub fn update(
     let conn = conn.get();
 
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &hold.organization(conn)?, conn)?;
-    Ok(HttpResponse::Ok().json(hold.update(req.into_inner(), conn)))
+    let event = Event::find(hold.event_id, conn)?;
+    user.requires_scope_for_event(Scopes::HoldWrite, &event, conn)?;
+    let updated_hold = hold.update(req.into_inner(), conn)?;
+    user.audit(AuditActions::Update, Tables::Holds, Some(hold.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&hold), Some(&updated_hold))
+        .commit(conn)?;
+    Ok(HttpResponse::Ok().json(&updated_hold))
 }
 
 #[derive(Deserialize)]
@@ -84,10 +96,20 @@ pub fn add_remove_from_hold(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    user.requires_scope_for_event(Scopes::HoldWrite, &event, conn)?;
+    // Held quantities by ticket type id
+    let mut previous_quantities = HashMap::new();
+    let mut quantities = HashMap::new();
     for line in &req.items {
+        previous_quantities.insert(line.ticket_type_id, hold.quantity(line.ticket_type_id, conn)?);
         hold.set_quantity(line.ticket_type_id, line.quantity, conn)?;
+        quantities.insert(line.ticket_type_id, line.quantity);
     }
+    user.audit(AuditActions::Update, Tables::Holds, Some(hold.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&previous_quantities), Some(&quantities))
+        .commit(conn)?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -0,0 +1,101 @@
+use actix_web::{HttpRequest, HttpResponse, Json, Path};
+use auth::user::User as AuthUser;
+use auth::ImpersonationTokenResponse;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use controllers::auth::remote_ip_address;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use server::AppState;
+use uuid::Uuid;
+
+/// Returned for sensitive actions such as transfers that an impersonating admin can not take
+pub const IMPERSONATION_NOT_ALLOWED: &str = "Not allowed while impersonating a user";
+
+#[derive(Default, Deserialize)]
+pub struct CreateImpersonationRequest {
+    pub reason: Option<String>,
+}
+
+/// Impersonation as shown to the impersonated user
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayImpersonation {
+    pub id: Uuid,
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+    pub actions: Vec<ImpersonationAction>,
+}
+
+pub fn create(
+    (http_request, connection, parameters, json, user): (
+        HttpRequest<AppState>,
+        Connection,
+        Path<PathParameters>,
+        Json<CreateImpersonationRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if user.api_key.is_some()
+        || user.is_impersonated()
+        || !user.user.role.contains(&Roles::Admin.to_string())
+    {
+        return application::unauthorized();
+    }
+
+    let impersonated_user = User::find(parameters.id, connection)?;
+    let impersonation = Impersonation::start(
+        &user.user,
+        &impersonated_user,
+        json.into_inner().reason,
+        remote_ip_address(&http_request),
+        connection,
+    )?;
+    let config = &http_request.state().config;
+    let response = ImpersonationTokenResponse::create_for_impersonation(
+        &config.token_secret,
+        &config.token_issuer,
+        &impersonation,
+        user.two_factor_authenticated,
+    );
+    Ok(HttpResponse::Created().json(&response))
+}
+
+/// Ends an impersonation, either by an admin or from within the impersonation itself
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let impersonation = Impersonation::find(parameters.id, connection)?;
+    let is_current = user.impersonation.as_ref().map(|i| i.id) == Some(impersonation.id);
+    if !is_current
+        && (user.is_impersonated() || !user.has_scope(Scopes::UserAdmin, None, connection)?)
+    {
+        return application::unauthorized();
+    }
+
+    impersonation.end(connection)?;
+    application::no_content()
+}
+
+/// Lists when the current user was impersonated and the changes made
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let mut impersonations = Vec::new();
+    for impersonation in Impersonation::find_for_user(user.id(), connection)? {
+        impersonations.push(DisplayImpersonation {
+            actions: impersonation.actions(connection)?,
+            id: impersonation.id,
+            reason: impersonation.reason,
+            created_at: impersonation.created_at,
+            expires_at: impersonation.expires_at,
+            ended_at: impersonation.ended_at,
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(&impersonations))
+}
@@ -0,0 +1,34 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+
+pub fn opt_out(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::EventInterest, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let organization = Organization::find(path.id, connection)?;
+    let opt_out =
+        InterestNotificationOptOut::create(organization.id, user.id()).commit(connection)?;
+    Ok(HttpResponse::Created().json(&opt_out))
+}
+
+pub fn opt_in(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::EventInterest, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let organization = Organization::find(path.id, connection)?;
+    InterestNotificationOptOut::remove(organization.id, user.id(), connection)?;
+    application::no_content()
+}
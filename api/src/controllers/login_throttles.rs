@@ -0,0 +1,33 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::UserAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let throttles = LoginThrottle::find_throttled(connection)?;
+    Ok(HttpResponse::Ok().json(&throttles))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::UserAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let throttle = LoginThrottle::find(parameters.id, connection)?;
+    user.audit(AuditActions::Delete, Tables::Users, throttle.user_id)
+        .with_changes(Some(&throttle), None)
+        .commit(connection)?;
+    throttle.destroy(connection)?;
+    application::no_content()
+}
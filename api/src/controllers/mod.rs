@@ -1,16 +1,35 @@
+pub mod api_keys;
 pub mod artists;
+pub mod audit_entries;
 pub mod auth;
+pub mod calendars;
 pub mod cart;
+pub mod customers;
+pub mod email_verifications;
+pub mod event_promoters;
 pub mod events;
 pub mod external;
+pub mod external_logins;
 pub mod holds;
+pub mod impersonations;
+pub mod interest_notifications;
+pub mod login_throttles;
 pub mod orders;
+pub mod organization_email_templates;
 pub mod organization_invites;
+pub mod organization_roles;
 pub mod organizations;
 pub mod password_resets;
 pub mod payment_methods;
+pub mod phone_verifications;
 pub mod regions;
+pub mod reports;
+pub mod sessions;
+pub mod settlements;
 pub mod ticket_types;
 pub mod tickets;
+pub mod two_factor;
+pub mod user_erasure_requests;
 pub mod users;
 pub mod venues;
+pub mod webhooks;
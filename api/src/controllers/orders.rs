@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, Path, Query};
+use actix_web::{HttpResponse, Json, Path, Query};
 use auth::user::User;
 use bigneon_db::models::*;
 use db::Connection;
@@ -36,3 +36,33 @@ pub fn show(
 
     Ok(HttpResponse::Ok().json(json!(order.for_display(conn.get())?)))
 }
+
+#[derive(Deserialize)]
+pub struct OrderReversalRequest {
+    pub reversal_type: LedgerEntryType,
+}
+
+/// Records a refund or chargeback processed with the payment provider in the ledger
+pub fn create_reversal(
+    (conn, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<OrderReversalRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    if !user.has_scope(Scopes::OrgAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let order = Order::find(path.id, connection)?;
+    let entries = LedgerEntry::create_reversal_for_order(&order, json.reversal_type, connection)?;
+    for organization_id in order.organization_ids(connection)? {
+        user.audit(AuditActions::Update, Tables::Orders, Some(order.id))
+            .for_organization(organization_id)
+            .with_change("reversal_type", &None, &Some(json.reversal_type))
+            .commit(connection)?;
+    }
+    Ok(HttpResponse::Created().json(&entries))
+}
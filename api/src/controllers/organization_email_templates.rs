@@ -0,0 +1,197 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use mail::branding;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct EmailTemplateResponse {
+    pub template_type: EmailTemplateTypes,
+    pub subject: String,
+    pub body: String,
+    /// False when the organization uses the default template
+    pub customized: bool,
+    pub placeholders: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UpdateEmailTemplateRequest {
+    pub template_type: EmailTemplateTypes,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PreviewEmailTemplateRequest {
+    pub template_type: EmailTemplateTypes,
+    /// Previews the organization's current subject when not provided
+    pub subject: Option<String>,
+    /// Previews the organization's current body when not provided
+    pub body: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PreviewEmailTemplateResponse {
+    pub subject: String,
+    pub body: String,
+    pub html_body: String,
+}
+
+const EMAIL_TEMPLATE_TYPES: [EmailTemplateTypes; 3] = [
+    EmailTemplateTypes::TicketConfirmation,
+    EmailTemplateTypes::TicketTransfer,
+    EmailTemplateTypes::EventReminder,
+];
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgRead, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let templates = OrganizationEmailTemplate::find_for_organization(organization.id, connection)?;
+    let response: Vec<EmailTemplateResponse> = EMAIL_TEMPLATE_TYPES
+        .iter()
+        .map(|template_type| {
+            let template = templates
+                .iter()
+                .find(|t| t.template_type() == *template_type);
+            EmailTemplateResponse {
+                template_type: *template_type,
+                subject: template.map_or(template_type.default_subject().to_string(), |t| {
+                    t.subject.clone()
+                }),
+                body: template.map_or(template_type.default_body().to_string(), |t| {
+                    t.body.clone()
+                }),
+                customized: template.is_some(),
+                placeholders: template_type
+                    .placeholders()
+                    .iter()
+                    .map(|(name, _)| name.to_string())
+                    .collect(),
+            }
+        }).collect();
+    Ok(HttpResponse::Ok().json(&response))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<UpdateEmailTemplateRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    let existing =
+        OrganizationEmailTemplate::find_by_type(organization.id, json.template_type, connection)
+            .optional()?;
+    let template = match existing {
+        Some(ref existing) => existing.update(
+            OrganizationEmailTemplateEditableAttributes {
+                subject: Some(json.subject),
+                body: Some(json.body),
+            },
+            connection,
+        )?,
+        None => OrganizationEmailTemplate::create(
+            organization.id,
+            json.template_type,
+            json.subject,
+            json.body,
+        ).commit(connection)?,
+    };
+    let action = if existing.is_some() {
+        AuditActions::Update
+    } else {
+        AuditActions::Create
+    };
+    user.audit(action, Tables::OrganizationEmailTemplates, Some(template.id))
+        .for_organization(organization.id)
+        .with_changes(existing.as_ref(), Some(&template))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&template))
+}
+
+/// Removes the organization's template so the default template is used again
+pub fn destroy(
+    (connection, path, template_type, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<EmailTemplateTypes>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let template = OrganizationEmailTemplate::find_by_type(
+        organization.id,
+        template_type.into_inner(),
+        connection,
+    )?;
+    template.destroy(connection)?;
+    user.audit(AuditActions::Delete, Tables::OrganizationEmailTemplates, Some(template.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&template), None)
+        .commit(connection)?;
+    application::no_content()
+}
+
+/// Renders a template with sample values and the organization's branding without saving it
+pub fn preview(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<PreviewEmailTemplateRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    let template_type = json.template_type;
+    let existing =
+        OrganizationEmailTemplate::find_by_type(organization.id, template_type, connection)
+            .optional()?;
+    let subject = json.subject.unwrap_or_else(|| {
+        existing.as_ref().map_or(template_type.default_subject().to_string(), |t| {
+            t.subject.clone()
+        })
+    });
+    let body = json.body.unwrap_or_else(|| {
+        existing
+            .as_ref()
+            .map_or(template_type.default_body().to_string(), |t| t.body.clone())
+    });
+
+    let mut values = template_type.sample_values();
+    values.insert("organization_name", organization.name.clone());
+    let rendered = OrganizationEmailTemplate::render(template_type, &subject, &body, &values)?;
+    Ok(HttpResponse::Ok().json(&PreviewEmailTemplateResponse {
+        html_body: branding::html_body(&organization, &rendered.body),
+        subject: rendered.subject,
+        body: rendered.body,
+    }))
+}
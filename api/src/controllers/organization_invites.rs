@@ -1,7 +1,9 @@
 use actix_web::{HttpResponse, Json, Path, Query, State};
 use auth::user::User as AuthUser;
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
 use bigneon_db::models::*;
 use bigneon_db::utils::errors::Optional;
+use chrono::NaiveDateTime;
 use db::Connection;
 use diesel::Connection as dConnection;
 use diesel::PgConnection;
@@ -28,6 +30,65 @@ pub struct InviteResponseQuery {
 pub struct NewOrgInviteRequest {
     pub user_email: Option<String>,
     pub user_id: Option<Uuid>,
+    pub organization_role_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct InviteIndexParameters {
+    pub status: Option<OrganizationInviteStatus>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayOrganizationInvite {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub inviter_id: Uuid,
+    pub user_email: String,
+    pub user_id: Option<Uuid>,
+    pub organization_role_id: Option<Uuid>,
+    pub status: OrganizationInviteStatus,
+    pub sent_invite: bool,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<OrganizationInvite> for DisplayOrganizationInvite {
+    fn from(invite: OrganizationInvite) -> Self {
+        DisplayOrganizationInvite {
+            status: invite.status(),
+            id: invite.id,
+            organization_id: invite.organization_id,
+            inviter_id: invite.inviter_id,
+            user_email: invite.user_email,
+            user_id: invite.user_id,
+            organization_role_id: invite.organization_role_id,
+            sent_invite: invite.sent_invite,
+            expires_at: invite.expires_at,
+            created_at: invite.created_at,
+        }
+    }
+}
+
+pub fn index(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<InviteIndexParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let invites: Vec<DisplayOrganizationInvite> =
+        OrganizationInvite::find_for_organization(organization.id, query.status, connection)?
+            .into_iter()
+            .map(|invite| invite.into())
+            .collect();
+    Ok(HttpResponse::Ok().json(&invites))
 }
 
 pub fn create(
@@ -91,9 +152,9 @@ pub fn create(
             }
         },
     }
-    //If an active invite exists for this email then first expire it before issuing the new invite.
-    if let Some(i) = OrganizationInvite::find_active_invite_by_email(&email, connection)? {
-        i.change_invite_status(0, connection)?;
+    //If an active invite exists for this email then first revoke it before issuing the new invite.
+    if let Some(i) = OrganizationInvite::find_active_invite_by_email(path.id, &email, connection)? {
+        i.revoke(connection)?;
     }
 
     invite = NewOrganizationInvite {
@@ -102,9 +163,63 @@ pub fn create(
         user_email: email.clone(),
         security_token: None,
         user_id,
+        expires_at: None,
+        organization_role_id: invite_args.organization_role_id,
     };
 
     let invite = invite.commit(connection)?;
+    auth_user
+        .audit(AuditActions::Create, Tables::OrganizationInvites, Some(invite.id))
+        .for_organization(invite.organization_id)
+        .with_change("user_email", &None, &Some(invite.user_email.clone()))
+        .commit(connection)?;
+    send_invite_email(&state, &invite, recipient, connection)?;
+    Ok(HttpResponse::Created().json(invite))
+}
+
+pub fn resend(
+    (state, connection, path, user): (State<AppState>, Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let invite = OrganizationInvite::find(path.id, connection)?;
+    let organization = Organization::find(invite.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let invite = invite.resend(connection)?;
+    let recipient = match invite.user_id {
+        Some(user_id) => User::find(user_id, connection)?.full_name(),
+        None => "New user".to_string(),
+    };
+    send_invite_email(&state, &invite, recipient, connection)?;
+    Ok(HttpResponse::Ok().json(DisplayOrganizationInvite::from(invite)))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let invite = OrganizationInvite::find(path.id, connection)?;
+    let organization = Organization::find(invite.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    invite.revoke(connection)?;
+    user.audit(AuditActions::Delete, Tables::OrganizationInvites, Some(invite.id))
+        .for_organization(organization.id)
+        .with_change("user_email", &Some(invite.user_email.clone()), &None)
+        .commit(connection)?;
+    application::no_content()
+}
+
+fn send_invite_email(
+    state: &AppState,
+    invite: &OrganizationInvite,
+    recipient: String,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
     let organization = Organization::find(invite.organization_id, connection)?;
 
     let thread_config = state.config.clone();
@@ -121,7 +236,7 @@ pub fn create(
             let _unused = thread_invite.change_sent_status(true, &thread_connection.unwrap());
         }
     });
-    Ok(HttpResponse::Created().json(invite))
+    Ok(())
 }
 
 pub fn view(
@@ -148,10 +263,17 @@ pub fn accept_request(
                     && invite_details.user_email != u.email().unwrap())
             {
                 return application::unauthorized();
+            } else if !u.user.is_email_verified() {
+                return application::forbidden(
+                    "Email address must be verified to accept an invite",
+                );
             } else {
                 let accept_details = invite_details.change_invite_status(1, connection)?;
                 let org = Organization::find(accept_details.organization_id, connection)?;
                 let _ = org.add_user(u.id(), connection)?;
+                if accept_details.organization_role_id.is_some() {
+                    org.set_user_role(u.id(), accept_details.organization_role_id, connection)?;
+                }
             }
         }
         None => return application::unauthorized(),
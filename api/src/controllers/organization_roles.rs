@@ -0,0 +1,132 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateOrganizationRoleRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AssignOrganizationRoleRequest {
+    pub user_id: Uuid,
+    /// Leave empty to restore the default member scopes
+    pub organization_role_id: Option<Uuid>,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    if !user.has_scope(Scopes::OrgRead, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let roles = OrganizationRole::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&roles))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateOrganizationRoleRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    let role =
+        OrganizationRole::create(organization.id, &json.name, json.scopes).commit(connection)?;
+    user.audit(AuditActions::Create, Tables::OrganizationRoles, Some(role.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&role))
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&role))
+}
+
+pub fn update(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<OrganizationRoleEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let role = OrganizationRole::find(parameters.id, connection)?;
+    let organization = Organization::find(role.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let updated_role = role.update(json.into_inner(), connection)?;
+    user.audit(AuditActions::Update, Tables::OrganizationRoles, Some(role.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&role), Some(&updated_role))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&updated_role))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let role = OrganizationRole::find(parameters.id, connection)?;
+    let organization = Organization::find(role.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    role.destroy(connection)?;
+    user.audit(AuditActions::Delete, Tables::OrganizationRoles, Some(role.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&role), None)
+        .commit(connection)?;
+    application::no_content()
+}
+
+pub fn assign(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<AssignOrganizationRoleRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    let previous_organization_role_id =
+        OrganizationUser::find_for_user(organization.id, json.user_id, connection)
+            .optional()?
+            .and_then(|organization_user| organization_user.organization_role_id);
+    let organization_user =
+        organization.set_user_role(json.user_id, json.organization_role_id, connection)?;
+    user.audit(AuditActions::Update, Tables::OrganizationUsers, Some(json.user_id))
+        .for_organization(organization.id)
+        .with_change(
+            "organization_role_id",
+            &previous_organization_role_id,
+            &json.organization_role_id,
+        )
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&organization_user))
+}
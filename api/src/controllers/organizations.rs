@@ -38,6 +38,13 @@ pub struct NewOrganizationRequest {
     pub country: Option<String>,
     pub postal_code: Option<String>,
     pub phone: Option<String>,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateParentRequest {
+    /// Leave empty to make the organization a top level organization
+    pub parent_id: Option<Uuid>,
 }
 
 pub fn index(
@@ -103,6 +110,7 @@ pub fn create(
         vec![NewFeeScheduleRange {
             min_price: 0,
             fee_in_cents: 0,
+            ..Default::default()
         }],
     ).commit(connection)?;
 
@@ -117,11 +125,16 @@ pub fn create(
         country: new_organization.country.clone(),
         postal_code: new_organization.postal_code.clone(),
         phone: new_organization.phone.clone(),
+        parent_id: new_organization.parent_id,
     };
 
     let organization = new_organization_with_fee_schedule.commit(connection)?;
 
     Wallet::create_for_organization(organization.id, "Default".to_string(), connection)?;
+    user.audit(AuditActions::Create, Tables::Organizations, Some(organization.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&organization))
+        .commit(connection)?;
 
     Ok(HttpResponse::Created().json(&organization))
 }
@@ -145,6 +158,10 @@ pub fn update(
         organization_update.fee_schedule_id = None;
     }
     let updated_organization = organization.update(organization_update, connection)?;
+    user.audit(AuditActions::Update, Tables::Organizations, Some(organization.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&organization), Some(&updated_organization))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(&updated_organization))
 }
 
@@ -163,9 +180,46 @@ pub fn update_owner(
     let organization = Organization::find(parameters.id, connection)?;
     let updated_organization =
         organization.set_owner(json.into_inner().owner_user_id, connection)?;
+    user.audit(AuditActions::Update, Tables::Organizations, Some(organization.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&organization), Some(&updated_organization))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(&updated_organization))
 }
 
+pub fn update_parent(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<UpdateParentRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::OrgAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+    let organization = Organization::find(parameters.id, connection)?;
+    let updated_organization = organization.set_parent(json.into_inner().parent_id, connection)?;
+    user.audit(AuditActions::Update, Tables::Organizations, Some(organization.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&organization), Some(&updated_organization))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&updated_organization))
+}
+
+pub fn show_children(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    if !user.has_scope(Scopes::OrgRead, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    Ok(HttpResponse::Ok().json(&organization.children(connection)?))
+}
+
 pub fn add_venue(
     (connection, parameters, new_venue, user): (
         Connection,
@@ -183,6 +237,10 @@ pub fn add_venue(
     let mut new_venue = new_venue.into_inner();
     new_venue.organization_id = Some(parameters.id);
     let venue = new_venue.commit(connection)?;
+    user.audit(AuditActions::Create, Tables::Venues, Some(venue.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&venue))
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&venue))
 }
 
@@ -204,6 +262,10 @@ pub fn add_artist(
     new_artist.organization_id = Some(parameters.id);
 
     let artist = new_artist.commit(connection)?;
+    user.audit(AuditActions::Create, Tables::Artists, Some(artist.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&artist))
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&artist))
 }
 
@@ -220,7 +282,15 @@ pub fn add_user(
     if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
         return application::unauthorized();
     }
-    organization.add_user(add_request.user_id, connection)?;
+    let organization_user = organization.add_user(add_request.user_id, connection)?;
+    // Memberships are identified by the member's user id
+    user.audit(
+        AuditActions::Create,
+        Tables::OrganizationUsers,
+        Some(add_request.user_id),
+    ).for_organization(organization.id)
+    .with_changes(None, Some(&organization_user))
+    .commit(connection)?;
     Ok(HttpResponse::Created().finish())
 }
 
@@ -233,8 +303,13 @@ pub fn remove_user(
         return application::unauthorized();
     }
 
-    let organization = organization.remove_user(user_id.into_inner(), connection)?;
-    Ok(HttpResponse::Ok().json(&organization))
+    let user_id = user_id.into_inner();
+    let removed_count = organization.remove_user(user_id, connection)?;
+    // Memberships are identified by the member's user id
+    user.audit(AuditActions::Delete, Tables::OrganizationUsers, Some(user_id))
+        .for_organization(organization.id)
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&removed_count))
 }
 
 pub fn list_organization_members(
@@ -277,7 +352,7 @@ pub fn show_fee_schedule(
         return application::unauthorized();
     }
 
-    let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection)?;
+    let fee_schedule = FeeSchedule::find_for_organization(&organization, connection)?;
     let fee_schedule_ranges = fee_schedule.ranges(connection)?;
 
     Ok(HttpResponse::Ok().json(FeeScheduleWithRanges {
@@ -302,7 +377,11 @@ pub fn add_fee_schedule(
         return application::unauthorized();
     }
 
-    let fee_schedule = json.into_inner().commit(connection)?;
+    let organization = Organization::find(parameters.id, connection)?;
+    let fee_schedule = json.into_inner().commit_as_version_of(
+        &FeeSchedule::find(organization.fee_schedule_id, connection)?,
+        connection,
+    )?;
     let fee_schedule_ranges = fee_schedule.ranges(connection)?;
 
     let update_fee_schedule_id = OrganizationEditableAttributes {
@@ -310,7 +389,16 @@ pub fn add_fee_schedule(
         ..Default::default()
     };
 
-    Organization::find(parameters.id, connection)?.update(update_fee_schedule_id, connection)?;
+    let updated_organization = organization.update(update_fee_schedule_id, connection)?;
+    user.audit(AuditActions::Create, Tables::FeeSchedules, Some(fee_schedule.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&fee_schedule))
+        .with_change("ranges", &Vec::new(), &fee_schedule_ranges)
+        .commit(connection)?;
+    user.audit(AuditActions::Update, Tables::Organizations, Some(organization.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&organization), Some(&updated_organization))
+        .commit(connection)?;
 
     Ok(HttpResponse::Created().json(FeeScheduleWithRanges {
         id: fee_schedule.id,
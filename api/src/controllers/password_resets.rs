@@ -1,9 +1,10 @@
-use actix_web::{HttpResponse, Json, State};
-use auth::TokenResponse;
+use actix_web::{HttpRequest, HttpResponse, Json};
 use bigneon_db::models::concerns::users::password_resetable::*;
-use bigneon_db::models::User;
+use bigneon_db::models::{LoginThrottle, Session, User};
+use controllers::auth;
 use db::Connection;
 use errors::*;
+use helpers::application;
 use mail::mailers;
 use server::AppState;
 use uuid::Uuid;
@@ -20,44 +21,61 @@ pub struct UpdatePasswordResetParameters {
 }
 
 pub fn create(
-    (state, connection, parameters): (
-        State<AppState>,
+    (http_request, request_connection, parameters): (
+        HttpRequest<AppState>,
         Connection,
         Json<CreatePasswordResetParameters>,
     ),
 ) -> Result<HttpResponse, BigNeonError> {
+    let connection = request_connection.get();
+    if auth::is_login_throttled(&http_request, None, connection)? {
+        return application::too_many_requests(auth::LOGIN_THROTTLED_MESSAGE);
+    }
+
     let request_pending_response = Ok(HttpResponse::Created().json(json!({
         "message": format!("Your request has been received; {} will receive an email shortly with a link to reset your password if it is an account on file.", parameters.email)
     })));
 
-    let connection = connection.get();
     let user = match User::find_by_email(&parameters.email, connection) {
         Ok(user) => user,
-        Err(_) => return request_pending_response,
+        Err(_) => {
+            auth::record_failed_login(&http_request, None, &request_connection)?;
+            return request_pending_response;
+        }
     };
 
     let user = user.create_password_reset_token(connection)?;
-    mailers::user::password_reset_email(&state.config, &user).deliver()?;
+    mailers::user::password_reset_email(&http_request.state().config, &user).deliver()?;
 
     request_pending_response
 }
 
 pub fn update(
-    (state, connection, parameters): (
-        State<AppState>,
+    (http_request, request_connection, parameters): (
+        HttpRequest<AppState>,
         Connection,
         Json<UpdatePasswordResetParameters>,
     ),
 ) -> Result<HttpResponse, BigNeonError> {
-    let user = User::consume_password_reset_token(
+    let connection = request_connection.get();
+    if auth::is_login_throttled(&http_request, None, connection)? {
+        return application::too_many_requests(auth::LOGIN_THROTTLED_MESSAGE);
+    }
+
+    let user = match User::consume_password_reset_token(
         &parameters.password_reset_token,
         &parameters.password,
-        connection.get(),
-    )?;
-
-    Ok(HttpResponse::Ok().json(&TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &user,
-    )))
+        connection,
+    ) {
+        Ok(user) => user,
+        Err(e) => {
+            auth::record_failed_login(&http_request, None, &request_connection)?;
+            return Err(e.into());
+        }
+    };
+
+    // Sign out devices that were using the old password and lift any sign in lockout
+    Session::revoke_all_for_user(user.id, connection)?;
+    LoginThrottle::clear_for_user(user.id, connection)?;
+    auth::token_response_for_user(&http_request, &user, connection)
 }
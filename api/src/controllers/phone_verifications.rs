@@ -0,0 +1,80 @@
+use actix_web::{HttpRequest, HttpResponse, Json};
+use auth::user::User as AuthUser;
+use bigneon_db::models::{LoginCode, LoginCodePurpose, User};
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use server::AppState;
+use sms::SmsMessage;
+
+#[derive(Deserialize)]
+pub struct UpdatePhoneVerificationParameters {
+    pub code: String,
+}
+
+/// Sends a code by SMS that proves the user owns their phone number
+pub fn create(
+    (http_request, connection, user): (HttpRequest<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let state = http_request.state();
+    let connection = connection.get();
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let user = User::find(user.id(), connection)?;
+    if user.is_phone_verified() {
+        return application::unprocessable("Phone number is already verified");
+    }
+    let phone = match user.phone {
+        Some(ref phone) => phone,
+        None => return application::unprocessable("No phone number is set for this account"),
+    };
+    let sms_transport = match state.config.sms_transport {
+        Some(ref sms_transport) => sms_transport,
+        None => return application::unprocessable("Phone numbers cannot be verified by SMS"),
+    };
+    if LoginCode::recently_sent(user.id, LoginCodePurpose::PhoneVerification, connection)? {
+        return application::too_many_requests(
+            "A verification code was sent recently, please wait a minute before requesting another",
+        );
+    }
+
+    let (_, code) = LoginCode::create_for_phone_verification(user.id, phone, connection)?;
+    sms_transport.clone().send(SmsMessage::new(
+        phone,
+        format!("Your {} verification code is {}", state.config.app_name, code),
+    ))?;
+
+    Ok(HttpResponse::Created().finish())
+}
+
+pub fn update(
+    (request_connection, parameters, user): (
+        Connection,
+        Json<UpdatePhoneVerificationParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let connection = request_connection.get();
+    let user = User::find(user.id(), connection)?;
+    let phone = match user.phone {
+        Some(ref phone) => phone.clone(),
+        None => return application::unprocessable("No phone number is set for this account"),
+    };
+
+    // Failed attempts are kept when the request transaction is rolled back
+    let user_id = user.id;
+    let code = parameters.into_inner().code;
+    if !request_connection.persist(move |conn| {
+        LoginCode::redeem_phone_verification(user_id, &phone, &code, conn)
+    })? {
+        return application::unprocessable("Verification code incorrect or expired");
+    }
+
+    user.mark_phone_verified(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
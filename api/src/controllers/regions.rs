@@ -36,6 +36,9 @@ pub fn create(
         return application::unauthorized();
     }
     let region = new_region.into_inner().commit(connection)?;
+    user.audit(AuditActions::Create, Tables::Regions, Some(region.id))
+        .with_changes(None, Some(&region))
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&region))
 }
 
@@ -53,5 +56,8 @@ pub fn update(
     }
     let region = Region::find(&parameters.id, connection)?;
     let updated_region = region.update(region_parameters.into_inner(), connection)?;
+    user.audit(AuditActions::Update, Tables::Regions, Some(region.id))
+        .with_changes(Some(&region), Some(&updated_region))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(updated_region))
 }
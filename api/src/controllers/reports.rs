@@ -0,0 +1,170 @@
+use actix_web::{HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use utils::csv::Csv;
+
+#[derive(Deserialize)]
+pub struct SalesReportParameters {
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+    pub format: Option<String>,
+    /// Rolls up the sales of child organizations, ignored for event reports
+    pub include_children: Option<bool>,
+}
+
+pub fn event_sales(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<SalesReportParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    if !user.has_scope_for_event(Scopes::OrgReports, &event, connection)? {
+        return application::unauthorized();
+    }
+
+    let report = SalesReport::for_event(&event, query.start_utc, query.end_utc, connection)?;
+    render_sales_report(&report, &format!("{} sales", event.name), &query)
+}
+
+pub fn organization_sales(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<SalesReportParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgReports, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let report = if query.include_children.unwrap_or(false) {
+        SalesReport::for_organization_with_children(
+            &organization,
+            query.start_utc,
+            query.end_utc,
+            connection,
+        )?
+    } else {
+        SalesReport::for_organization(&organization, query.start_utc, query.end_utc, connection)?
+    };
+    render_sales_report(&report, &format!("{} sales", organization.name), &query)
+}
+
+fn render_sales_report(
+    report: &SalesReport,
+    title: &str,
+    query: &SalesReportParameters,
+) -> Result<HttpResponse, BigNeonError> {
+    match query.format.as_ref().map(|f| f.as_str()) {
+        None | Some("json") => Ok(HttpResponse::Ok().json(report)),
+        Some("csv") => application::attachment(
+            "text/csv; charset=utf-8",
+            &format!("{}.csv", title),
+            sales_report_to_csv(report).into_bytes(),
+        ),
+        Some(_) => application::unprocessable("Report format must be one of json or csv"),
+    }
+}
+
+fn sales_report_to_csv(report: &SalesReport) -> String {
+    let mut csv = Csv::new();
+    csv.add_row(vec![
+        "Date",
+        "Event Id",
+        "Ticket Type",
+        "Pricing Tier",
+        "Sold",
+        "Comped",
+        "Refunded",
+        "Gross Revenue (cents)",
+        "Per Unit Fees (cents)",
+        "Absorbed Per Unit Fees (cents)",
+    ]);
+    for row in &report.ticket_sales {
+        csv.add_row(vec![
+            row.sales_date.to_string(),
+            row.event_id.to_string(),
+            row.ticket_type_name.clone(),
+            row.ticket_pricing_name.clone(),
+            row.sold_quantity.to_string(),
+            row.comped_quantity.to_string(),
+            row.refunded_quantity.to_string(),
+            row.gross_revenue_in_cents.to_string(),
+            row.per_unit_fees_in_cents.to_string(),
+            row.absorbed_per_unit_fees_in_cents.to_string(),
+        ]);
+    }
+
+    csv.add_blank_row();
+    csv.add_row(vec![
+        "Event Id",
+        "Ticket Type",
+        "Sold",
+        "Reserved",
+        "Held",
+        "Available",
+        "Redeemed",
+    ]);
+    for row in &report.inventory {
+        csv.add_row(vec![
+            row.event_id.to_string(),
+            row.ticket_type_name.clone(),
+            row.sold_quantity.to_string(),
+            row.reserved_quantity.to_string(),
+            row.held_quantity.to_string(),
+            row.available_quantity.to_string(),
+            row.redeemed_quantity.to_string(),
+        ]);
+    }
+
+    csv.add_blank_row();
+    csv.add_row(vec![
+        "Event Id",
+        "Event",
+        "Event Fees (cents)",
+        "Absorbed Event Fees (cents)",
+    ]);
+    for row in &report.event_fees {
+        csv.add_row(vec![
+            row.event_id.to_string(),
+            row.event_name.clone(),
+            row.event_fees_in_cents.to_string(),
+            row.absorbed_event_fees_in_cents.to_string(),
+        ]);
+    }
+
+    csv.add_blank_row();
+    csv.add_row(vec!["Payment Method", "Payments", "Amount (cents)"]);
+    for row in &report.payments {
+        csv.add_row(vec![
+            row.payment_method.clone(),
+            row.payment_count.to_string(),
+            row.amount_in_cents.to_string(),
+        ]);
+    }
+
+    if !report.revenue_splits.is_empty() {
+        csv.add_blank_row();
+        csv.add_row(vec!["Organization Id", "Ticket Revenue (cents)"]);
+        for row in &report.revenue_splits {
+            csv.add_row(vec![
+                row.organization_id.to_string(),
+                row.ticket_revenue_in_cents.to_string(),
+            ]);
+        }
+    }
+
+    csv.into_string()
+}
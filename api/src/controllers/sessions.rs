@@ -0,0 +1,75 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use chrono::NaiveDateTime;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySession {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub current: bool,
+}
+
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let sessions: Vec<DisplaySession> = Session::find_active_for_user(user.id(), connection.get())?
+        .into_iter()
+        .map(|session| DisplaySession {
+            current: user.session_id == Some(session.id),
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            last_used_at: session.last_used_at,
+            created_at: session.created_at,
+        }).collect();
+
+    Ok(HttpResponse::Ok().json(&sessions))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let connection = connection.get();
+    match Session::find(parameters.id, connection).optional()? {
+        Some(ref session) if session.user_id == user.id() => {
+            session.revoke(connection)?;
+            application::no_content()
+        }
+        _ => application::not_found(),
+    }
+}
+
+pub fn destroy_all(
+    (connection, user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    Session::revoke_all_for_user(user.id(), connection.get())?;
+    application::no_content()
+}
+
+pub fn destroy_all_for_user(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::UserAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let revoked_user = User::find(parameters.id, connection)?;
+    Session::revoke_all_for_user(revoked_user.id, connection)?;
+    application::no_content()
+}
@@ -0,0 +1,188 @@
+use actix_web::{HttpResponse, Json, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use utils::csv::Csv;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct NewSettlementRequest {
+    pub event_id: Option<Uuid>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize)]
+pub struct SettlementParameters {
+    pub format: Option<String>,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgReports, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let settlements = Settlement::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&settlements))
+}
+
+pub fn create(
+    (connection, path, new_settlement, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewSettlementRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgAdmin, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let settlement = Settlement::create(
+        organization.id,
+        new_settlement.event_id,
+        new_settlement.start_utc,
+        new_settlement.end_utc,
+        user.id(),
+    ).commit(connection)?;
+    user.audit(AuditActions::Create, Tables::Settlements, Some(settlement.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&settlement))
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&settlement.statement(connection)?))
+}
+
+pub fn show(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<SettlementParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = Settlement::find(path.id, connection)?;
+    let organization = settlement.organization(connection)?;
+    if !user.has_scope(Scopes::OrgReports, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let statement = settlement.statement(connection)?;
+    match query.format.as_ref().map(|f| f.as_str()) {
+        None | Some("json") => Ok(HttpResponse::Ok().json(&statement)),
+        Some("csv") => application::attachment(
+            "text/csv; charset=utf-8",
+            &format!(
+                "{} settlement {}.csv",
+                organization.name,
+                settlement.created_at.format("%Y-%m-%d")
+            ),
+            settlement_statement_to_csv(&statement).into_bytes(),
+        ),
+        Some(_) => application::unprocessable("Statement format must be one of json or csv"),
+    }
+}
+
+pub fn paid_out(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = Settlement::find(path.id, connection)?;
+    let organization = settlement.organization(connection)?;
+    if !user.has_scope(Scopes::OrgAdmin, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let paid_out_settlement = settlement.mark_paid_out(user.id(), connection)?;
+    user.audit(AuditActions::Update, Tables::Settlements, Some(settlement.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&settlement), Some(&paid_out_settlement))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&paid_out_settlement))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = Settlement::find(path.id, connection)?;
+    let organization = settlement.organization(connection)?;
+    if !user.has_scope(Scopes::OrgAdmin, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    settlement.destroy(connection)?;
+    user.audit(AuditActions::Delete, Tables::Settlements, Some(settlement.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&settlement), None)
+        .commit(connection)?;
+    application::no_content()
+}
+
+fn settlement_statement_to_csv(statement: &SettlementStatement) -> String {
+    let settlement = &statement.settlement;
+    let optional_to_string = |value: Option<NaiveDateTime>| {
+        value
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "".to_string())
+    };
+
+    let mut csv = Csv::new();
+    csv.add_row(vec![
+        "Settlement Id",
+        "Status",
+        "Period Start",
+        "Period End",
+        "Paid Out",
+    ]);
+    csv.add_row(vec![
+        settlement.id.to_string(),
+        settlement.status.clone(),
+        optional_to_string(settlement.start_utc),
+        optional_to_string(settlement.end_utc),
+        optional_to_string(settlement.paid_out_at),
+    ]);
+
+    csv.add_blank_row();
+    csv.add_row(vec![
+        "Event Id",
+        "Event",
+        "Entry Type",
+        "Account",
+        "Entries",
+        "Amount (cents)",
+    ]);
+    for line in &statement.lines {
+        csv.add_row(vec![
+            line.event_id.map(|id| id.to_string()).unwrap_or_default(),
+            line.event_name.clone().unwrap_or_default(),
+            line.entry_type.clone(),
+            line.account.clone(),
+            line.entry_count.to_string(),
+            line.amount_in_cents.to_string(),
+        ]);
+    }
+
+    csv.add_blank_row();
+    csv.add_row(vec!["Account", "Total (cents)"]);
+    csv.add_row(vec![
+        LedgerAccount::Organization.to_string(),
+        statement.organization_total_in_cents.to_string(),
+    ]);
+    csv.add_row(vec![
+        LedgerAccount::Platform.to_string(),
+        statement.platform_total_in_cents.to_string(),
+    ]);
+
+    csv.into_string()
+}
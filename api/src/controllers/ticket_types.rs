@@ -2,6 +2,7 @@ use actix_web::{HttpResponse, Json, Path, Query, State};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
+use controllers::organizations::FeeScheduleWithRanges;
 use db::Connection;
 use errors::*;
 use helpers::application;
@@ -29,6 +30,9 @@ pub struct CreateTicketTypeRequest {
     pub end_date: NaiveDateTime,
     pub ticket_pricing: Vec<CreateTicketPricingRequest>,
     pub increment: Option<i32>,
+    pub presale_start_date: Option<NaiveDateTime>,
+    pub presale_interest_cutoff: Option<NaiveDateTime>,
+    pub presale_code: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -48,6 +52,9 @@ pub struct UpdateTicketTypeRequest {
     pub end_date: Option<NaiveDateTime>,
     pub ticket_pricing: Option<Vec<UpdateTicketPricingRequest>>,
     pub increment: Option<i32>,
+    pub presale_start_date: Option<NaiveDateTime>,
+    pub presale_interest_cutoff: Option<NaiveDateTime>,
+    pub presale_code: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -66,11 +73,7 @@ pub fn create(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(path.id, connection)?;
-    if !user.has_scope(
-        Scopes::EventWrite,
-        Some(&event.organization(connection)?),
-        connection,
-    )? {
+    if !user.has_scope_for_event(Scopes::EventWrite, &event, connection)? {
         return application::unauthorized();
     }
     //Retrieve default wallet
@@ -86,15 +89,37 @@ pub fn create(
         data.increment,
         connection,
     )?;
+    let ticket_type = if data.presale_start_date.is_some() {
+        ticket_type.update(
+            TicketTypeEditableAttributes {
+                presale_start_date: data.presale_start_date,
+                presale_interest_cutoff: data.presale_interest_cutoff,
+                presale_code: data.presale_code.clone(),
+                ..Default::default()
+            },
+            connection,
+        )?
+    } else {
+        ticket_type
+    };
+    user.audit(AuditActions::Create, Tables::TicketTypes, Some(ticket_type.id))
+        .for_organization(event.organization_id)
+        .with_changes(None, Some(&ticket_type))
+        .with_change("capacity", &0, &data.capacity)
+        .commit(connection)?;
     //Add each ticket pricing entry for newly created ticket type
     for current_pricing_entry in &data.ticket_pricing {
-        let _pricing_result = ticket_type.add_ticket_pricing(
+        let ticket_pricing = ticket_type.add_ticket_pricing(
             current_pricing_entry.name.clone(),
             current_pricing_entry.start_date,
             current_pricing_entry.end_date,
             current_pricing_entry.price_in_cents,
             connection,
         )?;
+        user.audit(AuditActions::Create, Tables::TicketPricing, Some(ticket_pricing.id))
+            .for_organization(event.organization_id)
+            .with_changes(None, Some(&ticket_pricing))
+            .commit(connection)?;
     }
 
     ticket_type.validate_record(connection)?;
@@ -133,12 +158,10 @@ pub fn index(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(path.id, connection)?;
-    let organization = &event.organization(connection)?;
-    if !user.has_scope(Scopes::EventWrite, Some(organization), connection)? {
+    if !user.has_scope_for_event(Scopes::EventWrite, &event, connection)? {
         return application::unauthorized();
     }
 
-    let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection)?;
     //TODO refactor using paging params
     let ticket_types = TicketType::find_by_event_id(path.id, connection)?;
     let query_parameters = Paging::new(&query_parameters.into_inner());
@@ -147,6 +170,7 @@ pub fn index(
         paging: Paging::clone_with_new_total(&query_parameters, 0 as u64),
     };
     for t in ticket_types {
+        let fee_schedule = FeeSchedule::find_for_ticket_type(&t, connection)?;
         payload.data.push(AdminDisplayTicketType::from_ticket_type(
             &t,
             &fee_schedule,
@@ -169,15 +193,12 @@ pub fn update(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let event = Event::find(path.event_id, connection)?;
-    if !user.has_scope(
-        Scopes::EventWrite,
-        Some(&event.organization(connection)?),
-        connection,
-    )? {
+    if !user.has_scope_for_event(Scopes::EventWrite, &event, connection)? {
         return application::unauthorized();
     }
 
     let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    let previous_capacity = ticket_type.valid_ticket_count(connection)?;
     if data.capacity.is_some() {
         let valid_ticket_count = ticket_type.valid_ticket_count(connection)?;
         let requested_capacity = data.capacity.unwrap();
@@ -248,8 +269,19 @@ pub fn update(
         start_date: data.start_date,
         end_date: data.end_date,
         increment: data.increment,
+        presale_start_date: data.presale_start_date,
+        presale_interest_cutoff: data.presale_interest_cutoff,
+        presale_code: data.presale_code.clone(),
     };
     let updated_ticket_type = ticket_type.update(update_parameters, connection)?;
+    user.audit(AuditActions::Update, Tables::TicketTypes, Some(ticket_type.id))
+        .for_organization(event.organization_id)
+        .with_changes(Some(&ticket_type), Some(&updated_ticket_type))
+        .with_change(
+            "capacity",
+            &previous_capacity,
+            &updated_ticket_type.valid_ticket_count(connection)?,
+        ).commit(connection)?;
 
     if data.ticket_pricing.is_some() {
         let data_ticket_pricing = data.into_inner().ticket_pricing.unwrap();
@@ -267,6 +299,13 @@ pub fn update(
             }
             if !found_flag {
                 current_ticket_pricing.destroy(connection)?;
+                user.audit(
+                    AuditActions::Delete,
+                    Tables::TicketPricing,
+                    Some(current_ticket_pricing.id),
+                ).for_organization(event.organization_id)
+                .with_changes(Some(current_ticket_pricing), None)
+                .commit(connection)?;
             }
         }
 
@@ -285,7 +324,17 @@ pub fn update(
                     .iter()
                     .position(|ref r| r.id == current_ticket_pricing_id);
                 match found_index {
-                    Some(index) => ticket_pricing[index].update(update_parameters, connection)?,
+                    Some(index) => {
+                        let updated_ticket_pricing =
+                            ticket_pricing[index].update(update_parameters, connection)?;
+                        user.audit(
+                            AuditActions::Update,
+                            Tables::TicketPricing,
+                            Some(current_ticket_pricing_id),
+                        ).for_organization(event.organization_id)
+                        .with_changes(Some(&ticket_pricing[index]), Some(&updated_ticket_pricing))
+                        .commit(connection)?;
+                    }
                     None => {
                         return application::internal_server_error(&format!(
                             "Unable to find specified ticket pricing with id {}",
@@ -302,16 +351,22 @@ pub fn update(
                 let current_ticket_pricing_name = current_ticket_pricing.name.clone().unwrap();
 
                 //Add new ticket pricing
-                let _pricing_result = updated_ticket_type.add_ticket_pricing(
+                let new_ticket_pricing = updated_ticket_type.add_ticket_pricing(
                     current_ticket_pricing_name,
                     current_ticket_pricing.start_date.unwrap(),
                     current_ticket_pricing.end_date.unwrap(),
                     current_ticket_pricing.price_in_cents.unwrap(),
                     connection,
                 )?;
+                user.audit(
+                    AuditActions::Create,
+                    Tables::TicketPricing,
+                    Some(new_ticket_pricing.id),
+                ).for_organization(event.organization_id)
+                .with_changes(None, Some(&new_ticket_pricing))
+                .commit(connection)?;
             } else {
                 //TODO send error when all data was not specified
-
             }
         }
         updated_ticket_type.validate_record(connection)?;
@@ -319,3 +374,68 @@ pub fn update(
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub fn add_fee_schedule(
+    (connection, path, json, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<NewFeeSchedule>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::OrgAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+    // Replacing the ticket type's fee schedule adds a version to it
+    let fee_schedule = match ticket_type.fee_schedule_id {
+        Some(fee_schedule_id) => json.into_inner().commit_as_version_of(
+            &FeeSchedule::find(fee_schedule_id, connection)?,
+            connection,
+        )?,
+        None => json.into_inner().commit(connection)?,
+    };
+    let updated_ticket_type = ticket_type.set_fee_schedule(Some(&fee_schedule), connection)?;
+    let organization_id = Event::find(ticket_type.event_id, connection)?.organization_id;
+    user.audit(AuditActions::Create, Tables::FeeSchedules, Some(fee_schedule.id))
+        .for_organization(organization_id)
+        .with_changes(None, Some(&fee_schedule))
+        .commit(connection)?;
+    user.audit(AuditActions::Update, Tables::TicketTypes, Some(ticket_type.id))
+        .for_organization(organization_id)
+        .with_changes(Some(&ticket_type), Some(&updated_ticket_type))
+        .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(FeeScheduleWithRanges {
+        id: fee_schedule.id,
+        name: fee_schedule.name.clone(),
+        version: fee_schedule.version,
+        created_at: fee_schedule.created_at,
+        ranges: fee_schedule.ranges(connection)?,
+    }))
+}
+
+pub fn remove_fee_schedule(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::OrgAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+    let updated_ticket_type = ticket_type.set_fee_schedule(None, connection)?;
+    user.audit(AuditActions::Update, Tables::TicketTypes, Some(ticket_type.id))
+        .for_organization(Event::find(ticket_type.event_id, connection)?.organization_id)
+        .with_changes(Some(&ticket_type), Some(&updated_ticket_type))
+        .commit(connection)?;
+    application::no_content()
+}
@@ -1,8 +1,10 @@
 use actix_web::State;
 use actix_web::{http::StatusCode, HttpResponse, Json, Path, Query};
 use auth::user::User;
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
 use bigneon_db::models::*;
 use chrono::prelude::*;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
 use db::Connection;
 use errors::*;
 use helpers::application;
@@ -103,9 +105,8 @@ pub fn show(
     let connection = connection.get();
     let (event, user, ticket) = TicketInstance::find_for_display(parameters.id, connection)?;
     let db_event = Event::find(event.id, connection)?;
-    let organization = db_event.organization(connection)?;
 
-    if !auth_user.has_scope(Scopes::TicketAdmin, Some(&organization), connection)?
+    if !auth_user.has_scope_for_event(Scopes::TicketAdmin, &db_event, connection)?
         && (user.is_none() || user.as_ref().unwrap().id != auth_user.id())
     {
         return application::unauthorized();
@@ -132,9 +133,8 @@ pub fn redeem(
     let connection = connection.get();
     let (event, ticket) = TicketInstance::find_for_processing(parameters.id, connection)?;
     let db_event = Event::find(event.id, connection)?;
-    let organization = db_event.organization(connection)?;
 
-    if !auth_user.has_scope(Scopes::TicketAdmin, Some(&organization), connection)? {
+    if !auth_user.has_scope_for_event(Scopes::TicketAdmin, &db_event, connection)? {
         return application::unauthorized();
     }
 
@@ -173,9 +173,8 @@ pub fn show_redeemable_ticket(
     let connection = connection.get();
     let (event, user, _ticket) = TicketInstance::find_for_display(parameters.id, connection)?;
     let db_event = Event::find(event.id, connection)?;
-    let organization = db_event.organization(connection)?;
 
-    if !auth_user.has_scope(Scopes::TicketAdmin, Some(&organization), connection)?
+    if !auth_user.has_scope_for_event(Scopes::TicketAdmin, &db_event, connection)?
         && (user.is_none() || user.unwrap().id != auth_user.id())
     {
         return application::unauthorized();
@@ -198,6 +197,9 @@ pub fn send_via_email(
     if !auth_user.has_scope(Scopes::TicketTransfer, None, connection)? {
         return application::unauthorized();
     }
+    if auth_user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
 
     let authorization = TicketInstance::authorize_ticket_transfer(
         auth_user.id(),
@@ -208,6 +210,15 @@ pub fn send_via_email(
         connection,
     )?;
 
+    // Transfers of tickets for a single organization's events are sent on its behalf
+    let organization_ids =
+        TicketInstance::organization_ids(&send_tickets_request.ticket_ids, connection)?;
+    let organization = if organization_ids.len() == 1 {
+        Some(Organization::find(organization_ids[0], connection)?)
+    } else {
+        None
+    };
+
     mailers::tickets::send_tickets(
         &state.config,
         &send_tickets_request.email,
@@ -216,7 +227,9 @@ pub fn send_via_email(
         &authorization.transfer_key.to_string(),
         &authorization.signature,
         &auth_user.user,
-    ).deliver()?;
+        organization.as_ref(),
+        connection,
+    )?.deliver()?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -239,6 +252,9 @@ pub fn transfer_authorization(
     if !auth_user.has_scope(Scopes::TicketTransfer, None, connection)? {
         return application::unauthorized();
     }
+    if auth_user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
 
     let transfer_authorization = TicketInstance::authorize_ticket_transfer(
         auth_user.id(),
@@ -262,6 +278,12 @@ pub fn receive_transfer(
     if !auth_user.has_scope(Scopes::TicketTransfer, None, connection)? {
         return application::unauthorized();
     }
+    if auth_user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    if !auth_user.user.is_email_verified() {
+        return application::forbidden("Email address must be verified to receive tickets");
+    }
 
     let sender_wallet =
         Wallet::find_default_for_user(transfer_authorization.sender_user_id, connection)?;
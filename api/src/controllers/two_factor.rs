@@ -0,0 +1,160 @@
+use actix_web::{HttpResponse, Json, State};
+use auth::totp;
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use helpers::application;
+use server::AppState;
+use uuid::Uuid;
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    pub required: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TwoFactorEnrolmentResponse {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+pub fn show((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    Ok(HttpResponse::Ok().json(&TwoFactorStatusResponse {
+        enabled: TwoFactorCredential::is_enabled_for_user(user.id(), connection)?,
+        required: user.user.two_factor_required(connection)?,
+        recovery_codes_remaining: TwoFactorRecoveryCode::remaining_for_user(user.id(), connection)?,
+    }))
+}
+
+pub fn enroll(
+    (state, connection, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let credential = TwoFactorCredential::create(user.id()).commit(connection.get())?;
+    let account_name = user.email().unwrap_or_else(|| user.id().to_string());
+
+    Ok(HttpResponse::Created().json(&TwoFactorEnrolmentResponse {
+        provisioning_uri: totp::provisioning_uri(
+            &credential.secret,
+            &account_name,
+            &state.config.app_name,
+        ),
+        secret: credential.secret,
+    }))
+}
+
+pub fn enable(
+    (connection, json, user): (Connection, Json<TwoFactorCodeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let connection = connection.get();
+    let credential = TwoFactorCredential::find_for_user(user.id(), connection)?;
+    if credential.is_enabled() {
+        return application::unprocessable("Two factor authentication is already enabled");
+    }
+
+    let step = match json
+        .code
+        .as_ref()
+        .and_then(|code| totp::verify(&credential.secret, code, totp::current_step()))
+    {
+        Some(step) => step,
+        None => return application::unprocessable("Two factor code incorrect"),
+    };
+    credential.enable(step as i64, connection)?;
+
+    Ok(HttpResponse::Ok().json(&RecoveryCodesResponse {
+        recovery_codes: TwoFactorRecoveryCode::regenerate_for_user(user.id(), connection)?,
+    }))
+}
+
+pub fn disable(
+    (connection, json, user): (Connection, Json<TwoFactorCodeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let connection = connection.get();
+    if user.user.two_factor_required(connection)? {
+        return application::unprocessable(
+            "Two factor authentication is required by your organization",
+        );
+    }
+    if !verify_code(user.id(), &json, connection)? {
+        return application::unprocessable("Two factor code incorrect");
+    }
+
+    TwoFactorCredential::find_for_user(user.id(), connection)?.destroy(connection)?;
+    application::no_content()
+}
+
+pub fn regenerate_recovery_codes(
+    (connection, json, user): (Connection, Json<TwoFactorCodeRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let connection = connection.get();
+    if !verify_code(user.id(), &json, connection)? {
+        return application::unprocessable("Two factor code incorrect");
+    }
+
+    Ok(HttpResponse::Ok().json(&RecoveryCodesResponse {
+        recovery_codes: TwoFactorRecoveryCode::regenerate_for_user(user.id(), connection)?,
+    }))
+}
+
+/// Checks an authenticator app code, or failing that an unused recovery code, for a user with
+/// two factor authentication enabled
+pub fn verify_code(
+    user_id: Uuid,
+    request: &TwoFactorCodeRequest,
+    connection: &PgConnection,
+) -> Result<bool, BigNeonError> {
+    let credential = match TwoFactorCredential::find_for_user(user_id, connection).optional()? {
+        Some(credential) => credential,
+        None => return Ok(false),
+    };
+    if !credential.is_enabled() {
+        return Ok(false);
+    }
+
+    if let Some(ref code) = request.code {
+        return match totp::verify(&credential.secret, code, totp::current_step()) {
+            Some(step) => Ok(credential.use_step(step as i64, connection)?),
+            None => Ok(false),
+        };
+    }
+
+    if let Some(ref recovery_code) = request.recovery_code {
+        return Ok(TwoFactorRecoveryCode::redeem(
+            user_id,
+            recovery_code,
+            connection,
+        )?);
+    }
+
+    Ok(false)
+}
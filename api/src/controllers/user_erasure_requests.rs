@@ -0,0 +1,100 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+
+#[derive(Default, Deserialize)]
+pub struct CreateUserErasureRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct ReviewUserErasureRequest {
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PendingUserErasureRequest {
+    pub erasure_request: UserErasureRequest,
+    pub user: DisplayUser,
+}
+
+pub fn create(
+    (connection, parameters, user): (Connection, Json<CreateUserErasureRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.api_key.is_some() {
+        return application::unauthorized();
+    }
+    if user.is_impersonated() {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
+    let erasure_request = UserErasureRequest::create(user.id(), parameters.into_inner().reason)
+        .commit(connection.get())?;
+    Ok(HttpResponse::Created().json(&erasure_request))
+}
+
+/// Queue of erasure requests waiting for review by an admin
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::UserAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let mut pending = Vec::new();
+    for erasure_request in UserErasureRequest::find_pending(connection)? {
+        let user = User::find(erasure_request.user_id, connection)?.for_display()?;
+        pending.push(PendingUserErasureRequest {
+            erasure_request,
+            user,
+        });
+    }
+    Ok(HttpResponse::Ok().json(&pending))
+}
+
+pub fn approve(
+    (connection, path, parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<ReviewUserErasureRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::UserAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let erasure_request = UserErasureRequest::find(path.id, connection)?;
+    let approved_request =
+        erasure_request.approve(user.id(), parameters.into_inner().notes, connection)?;
+    user.audit(AuditActions::Update, Tables::Users, Some(erasure_request.user_id))
+        .with_changes(Some(&erasure_request), Some(&approved_request))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&approved_request))
+}
+
+pub fn reject(
+    (connection, path, parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<ReviewUserErasureRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if !user.has_scope(Scopes::UserAdmin, None, connection)? {
+        return application::unauthorized();
+    }
+
+    let erasure_request = UserErasureRequest::find(path.id, connection)?;
+    let rejected_request =
+        erasure_request.reject(user.id(), parameters.into_inner().notes, connection)?;
+    user.audit(AuditActions::Update, Tables::Users, Some(erasure_request.user_id))
+        .with_changes(Some(&erasure_request), Some(&rejected_request))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&rejected_request))
+}
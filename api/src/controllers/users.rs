@@ -1,14 +1,21 @@
-use actix_web::{http::StatusCode, HttpResponse, Json, Path, Query};
+use actix_web::{
+    http::{header, StatusCode},
+    HttpResponse, Json, Path, Query, State,
+};
 use auth::user::User as AuthUser;
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
 use bigneon_db::models::*;
 use bigneon_db::utils::errors::Optional;
+use controllers::impersonations::IMPERSONATION_NOT_ALLOWED;
 use db::Connection;
 use diesel::PgConnection;
 use errors::*;
 use helpers::application;
+use mail::mailers;
 use models::{
     Paging, PagingParameters, PathParameters, Payload, RegisterRequest, UserProfileAttributes,
 };
+use server::AppState;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -20,6 +27,7 @@ pub struct SearchUserByEmail {
 #[derive(Serialize, Deserialize)]
 pub struct CurrentUser {
     pub user: DisplayUser,
+    pub email_verified: bool,
     pub roles: Vec<String>,
     pub scopes: Vec<String>,
     pub organization_roles: HashMap<Uuid, Vec<String>>,
@@ -39,6 +47,13 @@ pub fn update_current_user(
     (connection, user_parameters, user): (Connection, Json<UserProfileAttributes>, AuthUser),
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
+    // The email address and phone number are used to sign in so they can not be changed by an
+    // admin
+    if user.is_impersonated()
+        && (user_parameters.email.is_some() || user_parameters.phone.is_some())
+    {
+        return application::forbidden(IMPERSONATION_NOT_ALLOWED);
+    }
     let user = User::find(user.id(), connection)?;
 
     let updated_user = user.update(&user_parameters.into_inner().into(), connection)?;
@@ -100,25 +115,42 @@ pub fn find_by_email(
 }
 
 pub fn register(
-    (connection, parameters): (Connection, Json<RegisterRequest>),
+    (state, connection, parameters): (State<AppState>, Connection, Json<RegisterRequest>),
 ) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
     let new_user: NewUser = parameters.into_inner().into();
-    new_user.commit(connection.get())?;
+    let user = new_user.commit(connection)?;
+
+    let user = user.create_email_verification_token(connection)?;
+    mailers::user::email_verification_email(&state.config, &user).deliver()?;
+
     Ok(HttpResponse::Created().finish())
 }
 
+/// Everything held about the current user as a downloadable JSON archive
+pub fn export_data(
+    (connection, user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let user = User::find(user.id(), connection)?;
+    let export = UserDataExport::for_user(&user, connection)?;
+    Ok(HttpResponse::Ok()
+        .header(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"bigneon-data-export.json\"",
+        ).json(&export))
+}
+
 fn current_user_from_user(
     user: &User,
     connection: &PgConnection,
 ) -> Result<CurrentUser, BigNeonError> {
     let roles_by_organization = user.get_roles_by_organization(connection)?;
-    let mut scopes_by_organization = HashMap::new();
-    for (organization_id, roles) in &roles_by_organization {
-        scopes_by_organization.insert(organization_id.clone(), scopes::get_scopes(roles.clone()));
-    }
+    let scopes_by_organization = user.get_scopes_by_organization(connection)?;
 
     Ok(CurrentUser {
         user: user.clone().for_display()?,
+        email_verified: user.is_email_verified(),
         roles: user.role.clone(),
         scopes: user.get_global_scopes(),
         organization_roles: roles_by_organization,
@@ -79,6 +79,10 @@ pub fn create(
     if venue.organization_id.is_some() {
         venue = venue.set_privacy(true, connection)?;
     }
+    user.audit(AuditActions::Create, Tables::Venues, Some(venue.id))
+        .for_organization(venue.organization_id)
+        .with_changes(None, Some(&venue))
+        .commit(connection)?;
 
     Ok(HttpResponse::Created().json(&venue))
 }
@@ -93,6 +97,10 @@ pub fn toggle_privacy(
 
     let venue = Venue::find(parameters.id, connection)?;
     let updated_venue = venue.set_privacy(!venue.is_private, connection)?;
+    user.audit(AuditActions::Update, Tables::Venues, Some(venue.id))
+        .for_organization(venue.organization_id)
+        .with_changes(Some(&venue), Some(&updated_venue))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(updated_venue))
 }
 
@@ -117,6 +125,10 @@ pub fn update(
     }
 
     let updated_venue = venue.update(venue_parameters.into_inner(), connection)?;
+    user.audit(AuditActions::Update, Tables::Venues, Some(venue.id))
+        .for_organization(venue.organization_id)
+        .with_changes(Some(&venue), Some(&updated_venue))
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(updated_venue))
 }
 
@@ -137,7 +149,13 @@ pub fn add_to_organization(
     if venue.organization_id.is_some() {
         Ok(HttpResponse::Conflict().json(json!({"error": "An error has occurred"})))
     } else {
-        let venue = venue.add_to_organization(&add_request.organization_id, connection)?;
-        Ok(HttpResponse::Created().json(&venue))
+        let updated_venue = venue
+            .clone()
+            .add_to_organization(&add_request.organization_id, connection)?;
+        user.audit(AuditActions::Update, Tables::Venues, Some(venue.id))
+            .for_organization(add_request.organization_id)
+            .with_changes(Some(&venue), Some(&updated_venue))
+            .commit(connection)?;
+        Ok(HttpResponse::Created().json(&updated_venue))
     }
 }
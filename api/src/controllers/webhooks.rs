@@ -0,0 +1,162 @@
+use actix_web::{HttpResponse, Json, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+use utils::webhooks;
+use uuid::Uuid;
+
+const WEBHOOK_DELIVERIES_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateWebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    /// Secret used to sign payloads, only returned when the webhook is created
+    pub secret: String,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let subscriptions = WebhookSubscription::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&subscriptions))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateWebhookRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let json = json.into_inner();
+    if let Some(address) = webhooks::non_public_address(&json.url) {
+        return application::unprocessable(&format!(
+            "Webhook URL must not resolve to a non public address, {} was found",
+            address
+        ));
+    }
+    let subscription =
+        WebhookSubscription::create(organization.id, json.url, json.event_types, user.id())
+            .commit(connection)?;
+    user.audit(AuditActions::Create, Tables::WebhookSubscriptions, Some(subscription.id))
+        .for_organization(organization.id)
+        .with_changes(None, Some(&subscription))
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&CreateWebhookResponse {
+        id: subscription.id,
+        url: subscription.url,
+        event_types: subscription.event_types,
+        active: subscription.active,
+        secret: subscription.secret,
+    }))
+}
+
+pub fn update(
+    (connection, path, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<WebhookSubscriptionEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscription = WebhookSubscription::find(path.id, connection)?;
+    let organization = Organization::find(subscription.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let attributes = attributes.into_inner();
+    if let Some(address) = attributes
+        .url
+        .as_ref()
+        .and_then(|url| webhooks::non_public_address(url))
+    {
+        return application::unprocessable(&format!(
+            "Webhook URL must not resolve to a non public address, {} was found",
+            address
+        ));
+    }
+    let updated_subscription = subscription.update(attributes, connection)?;
+    user.audit(AuditActions::Update, Tables::WebhookSubscriptions, Some(subscription.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&subscription), Some(&updated_subscription))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&updated_subscription))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscription = WebhookSubscription::find(path.id, connection)?;
+    let organization = Organization::find(subscription.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    subscription.destroy(connection)?;
+    user.audit(AuditActions::Delete, Tables::WebhookSubscriptions, Some(subscription.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&subscription), None)
+        .commit(connection)?;
+    application::no_content()
+}
+
+pub fn deliveries(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscription = WebhookSubscription::find(path.id, connection)?;
+    let organization = Organization::find(subscription.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let deliveries = subscription.deliveries(WEBHOOK_DELIVERIES_LIMIT, connection)?;
+    Ok(HttpResponse::Ok().json(&deliveries))
+}
+
+pub fn redeliver(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let delivery = WebhookDelivery::find(path.id, connection)?;
+    let organization =
+        Organization::find(delivery.subscription(connection)?.organization_id, connection)?;
+    if !user.has_scope(Scopes::OrgWrite, Some(&organization), connection)? {
+        return application::unauthorized();
+    }
+
+    let redelivery = delivery.redeliver(connection)?;
+    user.audit(AuditActions::Update, Tables::WebhookDeliveries, Some(delivery.id))
+        .for_organization(organization.id)
+        .with_changes(Some(&delivery), Some(&redelivery))
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&redelivery))
+}
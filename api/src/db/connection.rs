@@ -1,4 +1,5 @@
 use actix_web::{FromRequest, HttpRequest, Result};
+use bigneon_db::utils::errors::DatabaseError;
 use db::*;
 use diesel;
 use diesel::connection::TransactionManager;
@@ -6,17 +7,22 @@ use diesel::Connection as DieselConnection;
 use diesel::PgConnection;
 use errors::BigNeonError;
 use server::AppState;
-use std::sync::Arc;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+type PersistentWrite = Box<Fn(&PgConnection) -> Result<(), DatabaseError>>;
 
 #[derive(Clone)]
 pub struct Connection {
     inner: Arc<ConnectionType>,
+    persistent_writes: Arc<Mutex<Vec<PersistentWrite>>>,
 }
 
 impl From<ConnectionType> for Connection {
     fn from(connection_type: ConnectionType) -> Self {
         Connection {
             inner: Arc::new(connection_type),
+            persistent_writes: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -46,6 +52,41 @@ impl Connection {
             .transaction_manager()
             .begin_transaction(self.get())
     }
+
+    /// Makes a write that has to be kept even when the request fails, such as recording a
+    /// failed sign in attempt. The write is part of the request transaction and is made again
+    /// by the `DatabaseTransaction` middleware when that transaction is rolled back
+    pub fn persist<T, F>(&self, write: F) -> Result<T, DatabaseError>
+    where
+        F: Fn(&PgConnection) -> Result<T, DatabaseError> + 'static,
+    {
+        let result = write(self.get())?;
+        self.persistent_writes
+            .lock()
+            .unwrap()
+            .push(Box::new(move |connection| write(connection).map(|_| ())));
+        Ok(result)
+    }
+
+    /// Repeats the persistent writes of a request whose transaction was rolled back
+    pub fn replay_persistent_writes(&self) -> Result<(), BigNeonError> {
+        let writes = mem::replace(&mut *self.persistent_writes.lock().unwrap(), Vec::new());
+        if writes.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.get();
+        connection.transaction::<_, BigNeonError, _>(|| {
+            for write in &writes {
+                write(connection)?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn clear_persistent_writes(&self) {
+        self.persistent_writes.lock().unwrap().clear();
+    }
 }
 
 impl FromRequest<AppState> for Connection {
@@ -1,4 +1,4 @@
-use actix_web::{http::StatusCode, HttpResponse};
+use actix_web::{http::header, http::StatusCode, HttpResponse};
 use errors::*;
 use serde_json;
 
@@ -39,6 +39,17 @@ pub fn unprocessable(message: &str) -> Result<HttpResponse, BigNeonError> {
         .json(json!({"error":message.to_string()})))
 }
 
+pub fn too_many_requests(message: &str) -> Result<HttpResponse, BigNeonError> {
+    warn!("Too many requests: {}", message);
+    let error: BigNeonError = ApplicationError {
+        reason: message.to_string(),
+    }.into();
+    Ok(HttpResponse::from_error(error.into())
+        .into_builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .json(json!({"error":message.to_string()})))
+}
+
 pub fn internal_server_error(message: &str) -> Result<HttpResponse, BigNeonError> {
     error!("Internal Server Error: {}", message);
     let error: BigNeonError = ApplicationError {
@@ -65,3 +76,53 @@ pub fn created(json: serde_json::Value) -> Result<HttpResponse, BigNeonError> {
         .into_builder()
         .json(json))
 }
+
+pub fn attachment(
+    content_type: &str,
+    filename: &str,
+    body: Vec<u8>,
+) -> Result<HttpResponse, BigNeonError> {
+    Ok(HttpResponse::Ok()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_DISPOSITION, content_disposition(filename))
+        .body(body))
+}
+
+/// Filenames are built from event and organization names so they are sanitized before being
+/// placed in the header: a plain ASCII `filename` for older clients and an RFC 5987 encoded
+/// `filename*` with the original name
+pub fn content_disposition(filename: &str) -> String {
+    let filename: String = filename.chars().filter(|c| !c.is_control()).collect();
+    let ascii_filename: String = filename
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() => c,
+            _ => '_',
+        }).collect();
+    let mut encoded_filename = String::new();
+    for byte in filename.bytes() {
+        match byte {
+            b'a'...b'z'
+            | b'A'...b'Z'
+            | b'0'...b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'&'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => encoded_filename.push(byte as char),
+            _ => encoded_filename.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        ascii_filename, encoded_filename
+    )
+}
@@ -18,7 +18,7 @@ extern crate lettre;
 extern crate lettre_email;
 extern crate scheduled_thread_pool;
 
-//extern crate serde;
+extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 #[macro_use]
@@ -27,6 +27,7 @@ extern crate crypto;
 extern crate jwt;
 extern crate serde_with;
 extern crate tari_client;
+extern crate untrusted;
 //extern crate url;
 extern crate uuid;
 #[macro_use]
@@ -34,6 +35,7 @@ extern crate log;
 extern crate chrono;
 extern crate log4rs;
 extern crate reqwest;
+extern crate ring;
 extern crate rustc_serialize;
 extern crate stripe;
 #[macro_use]
@@ -52,4 +54,5 @@ pub mod models;
 mod payments;
 mod routing;
 pub mod server;
+pub mod sms;
 pub mod utils;
@@ -0,0 +1,51 @@
+use bigneon_db::models::Organization;
+
+const DEFAULT_PRIMARY_COLOR: &str = "#000000";
+const DEFAULT_SECONDARY_COLOR: &str = "#FFFFFF";
+
+/// Wraps a plain text email body in a layout with the organization's logo and colors. The
+/// text is escaped so templates cannot add markup of their own
+pub fn html_body(organization: &Organization, text: &str) -> String {
+    let primary_color = organization
+        .primary_color
+        .as_ref()
+        .map(|c| c.as_str())
+        .unwrap_or(DEFAULT_PRIMARY_COLOR);
+    let secondary_color = organization
+        .secondary_color
+        .as_ref()
+        .map(|c| c.as_str())
+        .unwrap_or(DEFAULT_SECONDARY_COLOR);
+    let header = match organization.logo_url {
+        Some(ref logo_url) => format!(
+            "<img src=\"{}\" alt=\"{}\" style=\"max-height: 80px;\">",
+            escape(logo_url),
+            escape(&organization.name)
+        ),
+        None => format!(
+            "<h1 style=\"color: {};\">{}</h1>",
+            escape(primary_color),
+            escape(&organization.name)
+        ),
+    };
+    let paragraphs: Vec<String> = text
+        .lines()
+        .map(|line| format!("<p>{}</p>", escape(line)))
+        .collect();
+
+    format!(
+        "<html><body style=\"margin: 0; background-color: {};\"><div style=\"max-width: 600px; margin: 0 auto; padding: 24px; background-color: #FFFFFF; border-top: 6px solid {};\">{}{}</div></body></html>",
+        escape(secondary_color),
+        escape(primary_color),
+        header,
+        paragraphs.join("")
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,76 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::BigNeonError;
+use mail::mailers::Mailer;
+use std::collections::HashMap;
+
+pub fn interest_notification_email(
+    config: &Config,
+    notification: &PendingEventInterestNotification,
+    organization: &Organization,
+) -> Mailer {
+    let event_link = format!(
+        "{}/events/{}",
+        config.front_end_url.clone(),
+        notification.event_id
+    );
+    let opt_out_link = format!(
+        "{}/organizations/{}/interest-notifications/opt-out",
+        config.front_end_url.clone(),
+        notification.organization_id
+    );
+    let recipient_name = format!("{} {}", notification.first_name, notification.last_name);
+
+    Mailer::from_organization(
+        config.clone(),
+        (notification.email.clone(), recipient_name),
+        organization,
+        RenderedEmailTemplate {
+            subject: format!("{} is now on sale", notification.event_name),
+            body: format!(
+                "Hi {}\r\nTickets for {} are now on sale: {}\r\nYou are receiving this email because you are interested in this event. To stop receiving these emails for events by {} please click this link: {}",
+                notification.first_name,
+                notification.event_name,
+                event_link,
+                notification.organization_name,
+                opt_out_link
+            ),
+        },
+    )
+}
+
+pub fn event_reminder_email(
+    config: &Config,
+    reminder: &PendingEventReminder,
+    organization: &Organization,
+    conn: &PgConnection,
+) -> Result<Mailer, BigNeonError> {
+    let recipient_name = format!("{} {}", reminder.first_name, reminder.last_name);
+    let mut values = HashMap::new();
+    values.insert("recipient_name", recipient_name.clone());
+    values.insert("organization_name", organization.name.clone());
+    values.insert("event_name", reminder.event_name.clone());
+    values.insert(
+        "event_start",
+        reminder.event_start.format("%Y-%m-%d %H:%M").to_string(),
+    );
+    values.insert("venue_name", reminder.venue_name.clone().unwrap_or_default());
+    values.insert(
+        "tickets_link",
+        format!("{}/events/{}", config.front_end_url, reminder.event_id),
+    );
+
+    let email = OrganizationEmailTemplate::render_for_organization(
+        organization.id,
+        EmailTemplateTypes::EventReminder,
+        &values,
+        conn,
+    )?;
+    Ok(Mailer::from_organization(
+        config.clone(),
+        (reminder.email.clone(), recipient_name),
+        organization,
+        email,
+    ))
+}
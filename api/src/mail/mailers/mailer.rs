@@ -1,13 +1,17 @@
+use bigneon_db::models::{Organization, RenderedEmailTemplate};
 use config::Config;
 use errors::BigNeonError;
 use lettre_email::EmailBuilder;
+use mail::branding;
 
 pub struct Mailer {
     config: Config,
     to: (String, String),
     from: (String, String),
+    reply_to: Option<String>,
     subject: String,
     body: String,
+    html_body: Option<String>,
 }
 
 impl Mailer {
@@ -22,11 +26,34 @@ impl Mailer {
             config,
             to,
             from,
+            reply_to: None,
             subject,
             body,
+            html_body: None,
         }
     }
 
+    /// Email sent on behalf of an organization, buyers see the organization as the sender and
+    /// replies go to the organization rather than to us
+    pub fn from_organization(
+        config: Config,
+        to: (String, String),
+        organization: &Organization,
+        email: RenderedEmailTemplate,
+    ) -> Mailer {
+        let from = (
+            config.mail_from_email.clone(),
+            organization
+                .email_sender_name
+                .clone()
+                .unwrap_or_else(|| organization.name.clone()),
+        );
+        let mut mailer = Mailer::new(config, to, from, email.subject, email.body);
+        mailer.reply_to = organization.email_reply_to.clone();
+        mailer.html_body = Some(branding::html_body(organization, &mailer.body));
+        mailer
+    }
+
     pub fn to(&self) -> (String, String) {
         self.to.clone()
     }
@@ -35,6 +62,10 @@ impl Mailer {
         self.from.clone()
     }
 
+    pub fn reply_to(&self) -> Option<String> {
+        self.reply_to.clone()
+    }
+
     pub fn subject(&self) -> String {
         self.subject.clone()
     }
@@ -43,14 +74,23 @@ impl Mailer {
         self.body.clone()
     }
 
+    pub fn html_body(&self) -> Option<String> {
+        self.html_body.clone()
+    }
+
     pub fn deliver(&mut self) -> Result<(), BigNeonError> {
-        let email = EmailBuilder::new()
+        let mut builder = EmailBuilder::new()
             .to(self.to())
             .from(self.from())
-            .subject(self.subject())
-            .text(self.body())
-            .build()
-            .unwrap();
+            .subject(self.subject());
+        if let Some(reply_to) = self.reply_to() {
+            builder = builder.reply_to(reply_to);
+        }
+        builder = match self.html_body() {
+            Some(html_body) => builder.alternative(html_body, self.body()),
+            None => builder.text(self.body()),
+        };
+        let email = builder.build().unwrap();
         self.config.mail_transport.send(email)
     }
 }
@@ -1,6 +1,8 @@
 pub use self::mailer::Mailer;
 
+pub mod events;
 pub mod mailer;
+pub mod orders;
 pub mod organization_invites;
 pub mod tickets;
 pub mod user;
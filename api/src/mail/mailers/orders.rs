@@ -0,0 +1,71 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::BigNeonError;
+use mail::mailers::Mailer;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Confirmation of a paid order, sent by each organization whose tickets were bought
+pub fn confirmation_emails(
+    config: &Config,
+    order: &Order,
+    conn: &PgConnection,
+) -> Result<Vec<Mailer>, BigNeonError> {
+    let user = User::find(order.user_id, conn)?;
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut events_by_organization: HashMap<Uuid, Vec<(Event, i64)>> = HashMap::new();
+    for item in order.items(conn)? {
+        if item.item_type() != OrderItemTypes::Tickets {
+            continue;
+        }
+        if let Some(event_id) = item.event_id {
+            let event = Event::find(event_id, conn)?;
+            let events = events_by_organization
+                .entry(event.organization_id)
+                .or_insert_with(Vec::new);
+            match events.iter().position(|(e, _)| e.id == event.id) {
+                Some(index) => events[index].1 += item.quantity,
+                None => events.push((event, item.quantity)),
+            }
+        }
+    }
+
+    let mut mailers = Vec::new();
+    for (organization_id, events) in events_by_organization {
+        let organization = Organization::find(organization_id, conn)?;
+        let event_names: Vec<String> = events.iter().map(|(e, _)| e.name.clone()).collect();
+
+        let mut values = HashMap::new();
+        values.insert("recipient_name", user.full_name());
+        values.insert("organization_name", organization.name.clone());
+        values.insert("order_id", order.id.to_string());
+        values.insert("event_names", event_names.join(", "));
+        values.insert(
+            "ticket_count",
+            events.iter().map(|(_, q)| q).sum::<i64>().to_string(),
+        );
+        values.insert(
+            "tickets_link",
+            format!("{}/orders/{}", config.front_end_url, order.id),
+        );
+
+        let rendered = OrganizationEmailTemplate::render_for_organization(
+            organization.id,
+            EmailTemplateTypes::TicketConfirmation,
+            &values,
+            conn,
+        )?;
+        mailers.push(Mailer::from_organization(
+            config.clone(),
+            (email.clone(), user.full_name()),
+            &organization,
+            rendered,
+        ));
+    }
+    Ok(mailers)
+}
@@ -1,6 +1,9 @@
-use bigneon_db::models::User;
+use bigneon_db::models::{EmailTemplateTypes, Organization, OrganizationEmailTemplate, User};
 use config::Config;
+use diesel::PgConnection;
+use errors::BigNeonError;
 use mail::mailers::Mailer;
+use std::collections::HashMap;
 
 pub fn send_tickets(
     config: &Config,
@@ -10,7 +13,9 @@ pub fn send_tickets(
     transfer_key: &str,
     signature: &str,
     from_user: &User,
-) -> Mailer {
+    organization: Option<&Organization>,
+    conn: &PgConnection,
+) -> Result<Mailer, BigNeonError> {
     let receive_tickets_link = format!(
         "{}/tickets/receive?sender_user_id={}&transfer_key={}&num_tickets={}&signature={}",
         config.front_end_url.clone(),
@@ -22,17 +27,40 @@ pub fn send_tickets(
 
     println!("Email link:{}", receive_tickets_link);
 
-    Mailer::new(
-        config.clone(),
-        (email.to_string(), email.to_string()),
-        (
-            config.mail_from_email.clone(),
-            from_user.full_name(),
-        ),
-        format!("{} has sent you some tickets", from_user.full_name()),
-        format!(
-            "This link to receive the tickets is valid for 7 days: {}\nIf you did not request it please ignore this message.",
-            receive_tickets_link
-        ),
-    )
+    let mut values = HashMap::new();
+    values.insert("recipient_name", email.to_string());
+    values.insert("sender_name", from_user.full_name());
+    values.insert("ticket_count", num_tickets.to_string());
+    values.insert("receive_tickets_link", receive_tickets_link);
+
+    let template_type = EmailTemplateTypes::TicketTransfer;
+    let to = (email.to_string(), email.to_string());
+    // Tickets from several organizations are sent without any organization's branding
+    Ok(match organization {
+        Some(organization) => {
+            values.insert("organization_name", organization.name.clone());
+            let email = OrganizationEmailTemplate::render_for_organization(
+                organization.id,
+                template_type,
+                &values,
+                conn,
+            )?;
+            Mailer::from_organization(config.clone(), to, organization, email)
+        }
+        None => {
+            let email = OrganizationEmailTemplate::render(
+                template_type,
+                template_type.default_subject(),
+                template_type.default_body(),
+                &values,
+            )?;
+            Mailer::new(
+                config.clone(),
+                to,
+                (config.mail_from_email.clone(), from_user.full_name()),
+                email.subject,
+                email.body,
+            )
+        }
+    })
 }
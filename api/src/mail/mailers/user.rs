@@ -1,4 +1,5 @@
 use bigneon_db::models::User;
+use chrono::NaiveDateTime;
 use config::Config;
 use mail::mailers::Mailer;
 
@@ -29,3 +30,65 @@ pub fn password_reset_email(config: &Config, user: &User) -> Mailer {
         ),
     )
 }
+
+pub fn email_verification_email(config: &Config, user: &User) -> Mailer {
+    let email_verification_link = format!(
+        "{}/verify-email?token={}",
+        config.front_end_url.clone(),
+        user.email_verification_token
+            .expect("Email verification token is not set")
+    );
+
+    let email: &str = user.email.as_ref().expect("Email is not set");
+
+    Mailer::new(
+        config.clone(),
+        (email.to_string(), user.full_name()),
+        (
+            config.mail_from_email.clone(),
+            config.mail_from_name.clone(),
+        ),
+        format!("{}: Please verify your email address", config.app_name),
+        format!(
+            "Please verify your email address by following this link within 7 days: {}\nIf you did not create an account please ignore this message.",
+            email_verification_link
+        ),
+    )
+}
+
+pub fn account_locked_email(config: &Config, user: &User, locked_until: NaiveDateTime) -> Mailer {
+    let email: &str = user.email.as_ref().expect("Email is not set");
+
+    Mailer::new(
+        config.clone(),
+        (email.to_string(), user.full_name()),
+        (
+            config.mail_from_email.clone(),
+            config.mail_from_name.clone(),
+        ),
+        format!("{}: Sign in temporarily locked", config.app_name),
+        format!(
+            "Sign in to your account has been locked until {} UTC after several failed attempts.\nIf this was not you, we recommend resetting your password: {}/password-reset",
+            locked_until.format("%Y-%m-%d %H:%M"),
+            config.front_end_url
+        ),
+    )
+}
+
+pub fn login_code_email(config: &Config, user: &User, code: &str) -> Mailer {
+    let email: &str = user.email.as_ref().expect("Email is not set");
+
+    Mailer::new(
+        config.clone(),
+        (email.to_string(), user.full_name()),
+        (
+            config.mail_from_email.clone(),
+            config.mail_from_name.clone(),
+        ),
+        format!("{}: Your login code", config.app_name),
+        format!(
+            "Your login code is {}. It is valid for 10 minutes.\nIf you did not request it please ignore this message.",
+            code
+        ),
+    )
+}
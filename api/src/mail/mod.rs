@@ -1,2 +1,3 @@
+pub mod branding;
 pub mod mailers;
 pub mod transports;
@@ -52,6 +52,15 @@ impl Middleware<AppState> for DatabaseTransaction {
                     return Ok(Response::Done(error.error_response()));
                 }
             }
+
+            // Writes such as failed sign in attempts are kept even though the request failed
+            if response.error().is_some() {
+                if let Err(e) = connection.replay_persistent_writes() {
+                    error!("Could not save persistent writes: {}", e);
+                }
+            } else {
+                connection.clear_persistent_writes();
+            }
         };
 
         Ok(Response::Done(response))
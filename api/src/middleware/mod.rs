@@ -1,3 +1,5 @@
 pub use self::database_transaction::*;
+pub use self::request_id::*;
 
 mod database_transaction;
+mod request_id;
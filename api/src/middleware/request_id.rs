@@ -0,0 +1,48 @@
+use actix_web::http::header::HeaderValue;
+use actix_web::middleware::{Middleware, Response, Started};
+use actix_web::{HttpRequest, HttpResponse, Result};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Identifies a request so that audit entries and logs written while handling it can be
+/// matched up with what the client saw
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    pub fn from_request<S>(request: &HttpRequest<S>) -> Option<String> {
+        request
+            .extensions()
+            .get::<RequestId>()
+            .map(|request_id| request_id.0.clone())
+    }
+}
+
+/// Assigns every request a new id and returns it in the `X-Request-Id` response header. Ids sent
+/// by clients are ignored so they cannot be used to forge or confuse audit entries
+pub struct RequestIdentifier {}
+
+impl RequestIdentifier {
+    pub fn new() -> RequestIdentifier {
+        RequestIdentifier {}
+    }
+}
+
+impl<S> Middleware<S> for RequestIdentifier {
+    fn start(&self, request: &HttpRequest<S>) -> Result<Started> {
+        request
+            .extensions_mut()
+            .insert(RequestId(Uuid::new_v4().to_string()));
+        Ok(Started::Done)
+    }
+
+    fn response(&self, request: &HttpRequest<S>, mut response: HttpResponse) -> Result<Response> {
+        if let Some(request_id) = RequestId::from_request(request) {
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                response.headers_mut().insert(REQUEST_ID_HEADER, value);
+            }
+        }
+        Ok(Response::Done(response))
+    }
+}
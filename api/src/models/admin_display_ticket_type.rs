@@ -15,6 +15,9 @@ pub struct AdminDisplayTicketType {
     pub end_date: NaiveDateTime,
     pub quantity: u32,
     pub increment: i32,
+    pub presale_start_date: Option<NaiveDateTime>,
+    pub presale_interest_cutoff: Option<NaiveDateTime>,
+    pub presale_code: Option<String>,
     pub ticket_pricing: Vec<DisplayTicketPricing>,
 }
 
@@ -45,6 +48,9 @@ impl AdminDisplayTicketType {
             quantity,
             capacity,
             increment: ticket_type.increment,
+            presale_start_date: ticket_type.presale_start_date,
+            presale_interest_cutoff: ticket_type.presale_interest_cutoff,
+            presale_code: ticket_type.presale_code.clone(),
         })
     }
 }
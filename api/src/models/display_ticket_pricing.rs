@@ -23,7 +23,9 @@ impl DisplayTicketPricing {
     ) -> Result<DisplayTicketPricing, DatabaseError> {
         let fee_in_cents = fee_schedule
             .get_range(ticket_pricing.price_in_cents, conn)?
-            .and_then(|fee_schedule_range| Some(fee_schedule_range.fee_in_cents));
+            .and_then(|fee_schedule_range| {
+                Some(fee_schedule_range.calculate_fee(ticket_pricing.price_in_cents))
+            });
 
         Ok(DisplayTicketPricing {
             id: ticket_pricing.id,
@@ -1,4 +1,4 @@
-use bigneon_db::models::{FeeSchedule, TicketType, TicketTypeStatus};
+use bigneon_db::models::{Event, FeeMode, FeeSchedule, TicketType, TicketTypeStatus};
 use bigneon_db::utils::errors::*;
 use chrono::NaiveDateTime;
 use diesel::PgConnection;
@@ -14,6 +14,8 @@ pub struct UserDisplayTicketType {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub increment: i32,
+    pub presale_start_date: Option<NaiveDateTime>,
+    pub fee_mode: String,
     pub ticket_pricing: Option<DisplayTicketPricing>,
 }
 
@@ -27,7 +29,9 @@ impl UserDisplayTicketType {
         let mut status = ticket_type_status.to_string();
         let quantity = ticket_type.remaining_ticket_count(conn)?;
 
-        let ticket_pricing = match ticket_type.current_ticket_pricing(conn).optional()? {
+        let fee_mode = Event::find(ticket_type.event_id, conn)?.fee_mode(conn)?;
+
+        let mut ticket_pricing = match ticket_type.current_ticket_pricing(conn).optional()? {
             Some(ticket_pricing) => Some(DisplayTicketPricing::from_ticket_pricing(
                 &ticket_pricing,
                 fee_schedule,
@@ -35,12 +39,20 @@ impl UserDisplayTicketType {
             )?),
             None => None,
         };
+        // Buyers see the all-in price when fees are absorbed by the organization
+        if fee_mode == FeeMode::Absorbed {
+            if let Some(ref mut ticket_pricing) = ticket_pricing {
+                ticket_pricing.fee_in_cents = Some(0);
+            }
+        }
 
         if ticket_type_status == TicketTypeStatus::Published {
             if quantity == 0 {
                 status = TicketTypeStatus::SoldOut.to_string();
             } else if ticket_pricing.is_none() {
                 status = TicketTypeStatus::NoActivePricing.to_string();
+            } else if ticket_type.is_in_presale() {
+                status = TicketTypeStatus::Presale.to_string();
             }
         }
 
@@ -53,6 +65,8 @@ impl UserDisplayTicketType {
             ticket_pricing,
             quantity,
             increment: ticket_type.increment,
+            presale_start_date: ticket_type.presale_start_date,
+            fee_mode: fee_mode.to_string(),
         })
     }
 }
@@ -5,7 +5,11 @@ use server::AppState;
 
 pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     // Please try to keep in alphabetical order
-    app.resource("/artists/{id}/toggle_privacy", |r| {
+    app.resource("/api_keys/{id}", |r| {
+        r.method(Method::DELETE).with(api_keys::destroy);
+    }).resource("/api_keys/{id}/requests", |r| {
+        r.method(Method::GET).with(api_keys::requests);
+    }).resource("/artists/{id}/toggle_privacy", |r| {
         r.method(Method::PUT).with(artists::toggle_privacy);
     }).resource("/artists/{id}", |r| {
         r.method(Method::GET).with(artists::show);
@@ -13,9 +17,13 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     }).resource("/artists", |r| {
         r.method(Method::GET).with(artists::index);
         r.method(Method::POST).with(artists::create);
+    }).resource("/auth/login_codes", |r| {
+        r.method(Method::POST).with(auth::request_login_code)
     }).resource("/auth/token", |r| r.method(Method::POST).with(auth::token))
     .resource("/auth/token/refresh", |r| {
         r.method(Method::POST).with(auth::token_refresh)
+    }).resource("/auth/token/two_factor", |r| {
+        r.method(Method::POST).with(auth::token_two_factor)
     }).resource("/cart", |r| {
         r.method(Method::POST).with(cart::add);
         r.method(Method::GET).with(cart::show);
@@ -24,6 +32,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(cart::checkout);
     }).resource("/cart/{id}", |r| {
         r.method(Method::GET).with(cart::show);
+    }).resource("/email_verification", |r| {
+        r.method(Method::POST).with(email_verifications::create);
+        r.method(Method::PUT).with(email_verifications::update);
     }).resource("/events", |r| {
         r.method(Method::GET).with(events::index);
         r.method(Method::POST).with(events::create);
@@ -34,14 +45,25 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     }).resource("/events/{id}/artists", |r| {
         r.method(Method::POST).with(events::add_artist);
         r.method(Method::PUT).with(events::update_artists);
+    }).resource("/events/{id}/fee_schedule", |r| {
+        r.method(Method::POST).with(events::add_fee_schedule);
+        r.method(Method::DELETE).with(events::remove_fee_schedule);
     }).resource("/events/{id}/guests", |r| {
         r.method(Method::GET).with(events::guest_list);
+    }).resource("/events/{id}/guests/export", |r| {
+        r.method(Method::GET).with(events::export_guest_list);
     }).resource("/events/{id}/interest", |r| {
         r.method(Method::GET).with(events::list_interested_users);
         r.method(Method::POST).with(events::add_interest);
         r.method(Method::DELETE).with(events::remove_interest);
+    }).resource("/events/{id}/promoters", |r| {
+        r.method(Method::GET).with(event_promoters::index);
+        r.method(Method::POST).with(event_promoters::create);
+        r.method(Method::DELETE).with(event_promoters::destroy);
     }).resource("/events/{id}/publish", |r| {
         r.method(Method::POST).with(events::publish);
+    }).resource("/events/{id}/reports/sales", |r| {
+        r.method(Method::GET).with(reports::event_sales);
     }).resource("/events/{id}/tickets", |r| {
         r.method(Method::GET).with(tickets::index);
     }).resource("/events/{id}/ticket_types", |r| {
@@ -49,10 +71,17 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(ticket_types::create);
     }).resource("/events/{event_id}/ticket_types/{ticket_type_id}", |r| {
         r.method(Method::PATCH).with(ticket_types::update);
+    }).resource("/events/{event_id}/ticket_types/{ticket_type_id}/fee_schedule", |r| {
+        r.method(Method::POST).with(ticket_types::add_fee_schedule);
+        r.method(Method::DELETE).with(ticket_types::remove_fee_schedule);
     }).resource("/events/{id}/holds", |r| {
         r.method(Method::POST).with(holds::create);
     }).resource("/external/facebook/web_login", |r| {
         r.method(Method::POST).with(external::facebook::web_login)
+    }).resource("/external/oidc/authorize", |r| {
+        r.method(Method::GET).with(external::oidc::authorize)
+    }).resource("/external/oidc/web_login", |r| {
+        r.method(Method::POST).with(external::oidc::web_login)
     }).resource("/invitations/{id}", |r| {
         r.method(Method::GET).with(organization_invites::view);
     }).resource("/invitations", |r| {
@@ -64,22 +93,76 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PUT).with(holds::add_remove_from_hold);
     }).resource("/holds/{id}", |r| {
         r.method(Method::PATCH).with(holds::update);
+    }).resource("/impersonations/{id}", |r| {
+        r.method(Method::DELETE).with(impersonations::destroy);
+    }).resource("/login_throttles", |r| {
+        r.method(Method::GET).with(login_throttles::index);
+    }).resource("/login_throttles/{id}", |r| {
+        r.method(Method::DELETE).with(login_throttles::destroy);
     }).resource("/orders", |r| {
         r.method(Method::GET).with(orders::index);
+    }).resource("/orders/{id}/reversals", |r| {
+        r.method(Method::POST).with(orders::create_reversal);
     }).resource("/orders/{id}", |r| {
         r.method(Method::GET).with(orders::show);
+    }).resource("/organization_invites/{id}/resend", |r| {
+        r.method(Method::POST).with(organization_invites::resend);
+    }).resource("/organization_invites/{id}", |r| {
+        r.method(Method::DELETE).with(organization_invites::destroy);
+    }).resource("/organization_roles/{id}", |r| {
+        r.method(Method::PATCH).with(organization_roles::update);
+        r.method(Method::DELETE).with(organization_roles::destroy);
+    }).resource("/organizations/{id}/api_keys", |r| {
+        r.method(Method::GET).with(api_keys::index);
+        r.method(Method::POST).with(api_keys::create);
     }).resource("/organizations/{id}/artists", |r| {
         r.method(Method::GET).with(artists::show_from_organizations);
         r.method(Method::POST).with(organizations::add_artist);
+    }).resource("/organizations/{id}/audit", |r| {
+        r.method(Method::GET).with(audit_entries::index);
+    }).resource("/organizations/{id}/calendar", |r| {
+        r.method(Method::GET)
+            .with(calendars::show_organization_subscription);
+        r.method(Method::POST)
+            .with(calendars::regenerate_organization_subscription);
+    }).resource("/organizations/{id}/children", |r| {
+        r.method(Method::GET).with(organizations::show_children);
+    }).resource("/organizations/{id}/customers", |r| {
+        r.method(Method::GET).with(customers::index);
+    }).resource("/organizations/{id}/email_templates", |r| {
+        r.method(Method::GET).with(organization_email_templates::index);
+        r.method(Method::PUT).with(organization_email_templates::update);
+        r.method(Method::DELETE).with(organization_email_templates::destroy);
+    }).resource("/organizations/{id}/email_templates/preview", |r| {
+        r.method(Method::POST).with(organization_email_templates::preview);
     }).resource("/organizations/{id}/events", |r| {
         r.method(Method::GET).with(events::show_from_organizations);
+    }).resource("/organizations/{id}/events.ics", |r| {
+        r.method(Method::GET).with(calendars::organization_feed);
     }).resource("/organizations/{id}/fee_schedule", |r| {
         r.method(Method::GET).with(organizations::show_fee_schedule);
         r.method(Method::POST).with(organizations::add_fee_schedule);
+    }).resource("/organizations/{id}/interest_notifications/opt_out", |r| {
+        r.method(Method::POST).with(interest_notifications::opt_out);
+        r.method(Method::DELETE).with(interest_notifications::opt_in);
     }).resource("/organizations/{id}/invite", |r| {
         r.method(Method::POST).with(organization_invites::create);
+    }).resource("/organizations/{id}/invites", |r| {
+        r.method(Method::GET).with(organization_invites::index);
     }).resource("/organizations/{id}/owner", |r| {
         r.method(Method::PUT).with(organizations::update_owner);
+    }).resource("/organizations/{id}/parent", |r| {
+        r.method(Method::PUT).with(organizations::update_parent);
+    }).resource("/organizations/{id}/reports/sales", |r| {
+        r.method(Method::GET).with(reports::organization_sales);
+    }).resource("/organizations/{id}/roles", |r| {
+        r.method(Method::GET).with(organization_roles::index);
+        r.method(Method::POST).with(organization_roles::create);
+    }).resource("/organizations/{id}/settlements", |r| {
+        r.method(Method::GET).with(settlements::index);
+        r.method(Method::POST).with(settlements::create);
+    }).resource("/organizations/{id}/users/role", |r| {
+        r.method(Method::PUT).with(organization_roles::assign);
     }).resource("/organizations/{id}/users", |r| {
         r.method(Method::POST).with(organizations::add_user);
         r.method(Method::DELETE).with(organizations::remove_user);
@@ -88,6 +171,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     }).resource("/organizations/{id}/venues", |r| {
         r.method(Method::GET).with(venues::show_from_organizations);
         r.method(Method::POST).with(organizations::add_venue);
+    }).resource("/organizations/{id}/webhooks", |r| {
+        r.method(Method::GET).with(webhooks::index);
+        r.method(Method::POST).with(webhooks::create);
     }).resource("/organizations/{id}", |r| {
         r.method(Method::GET).with(organizations::show);
         r.method(Method::PATCH).with(organizations::update);
@@ -99,12 +185,20 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PUT).with(password_resets::update);
     }).resource("/payment_methods", |r| {
         r.method(Method::GET).with(payment_methods::index);
+    }).resource("/phone_verification", |r| {
+        r.method(Method::POST).with(phone_verifications::create);
+        r.method(Method::PUT).with(phone_verifications::update);
     }).resource("/regions/{id}", |r| {
         r.method(Method::GET).with(regions::show);
         r.method(Method::PUT).with(regions::update);
     }).resource("/regions", |r| {
         r.method(Method::GET).with(regions::index);
         r.method(Method::POST).with(regions::create)
+    }).resource("/settlements/{id}/paid_out", |r| {
+        r.method(Method::POST).with(settlements::paid_out);
+    }).resource("/settlements/{id}", |r| {
+        r.method(Method::GET).with(settlements::show);
+        r.method(Method::DELETE).with(settlements::destroy);
     }).resource("/status", |r| {
         r.method(Method::GET).f(|_| HttpResponse::Ok())
     }).resource("/tickets/transfer", |r| {
@@ -120,17 +214,56 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     }).resource("/tickets/{id}/redeem", |r| {
         r.method(Method::GET).with(tickets::show_redeemable_ticket);
         r.method(Method::POST).with(tickets::redeem);
+    }).resource("/user_erasure_requests", |r| {
+        r.method(Method::GET).with(user_erasure_requests::index);
+    }).resource("/user_erasure_requests/{id}/approve", |r| {
+        r.method(Method::POST).with(user_erasure_requests::approve);
+    }).resource("/user_erasure_requests/{id}/reject", |r| {
+        r.method(Method::POST).with(user_erasure_requests::reject);
     }).resource("/users/me", |r| {
         r.method(Method::GET).with(users::current_user);
         r.method(Method::PUT).with(users::update_current_user);
+    }).resource("/users/me/calendar", |r| {
+        r.method(Method::GET).with(calendars::show_user_subscription);
+        r.method(Method::POST)
+            .with(calendars::regenerate_user_subscription);
+    }).resource("/users/me/calendar.ics", |r| {
+        r.method(Method::GET).with(calendars::user_feed);
+    }).resource("/users/me/erasure_request", |r| {
+        r.method(Method::POST).with(user_erasure_requests::create);
+    }).resource("/users/me/export", |r| {
+        r.method(Method::GET).with(users::export_data);
+    }).resource("/users/me/external_logins", |r| {
+        r.method(Method::GET).with(external_logins::index);
+    }).resource("/users/me/external_logins/{id}", |r| {
+        r.method(Method::DELETE).with(external_logins::destroy);
+    }).resource("/users/me/impersonations", |r| {
+        r.method(Method::GET).with(impersonations::index);
+    }).resource("/users/me/sessions", |r| {
+        r.method(Method::GET).with(sessions::index);
+        r.method(Method::DELETE).with(sessions::destroy_all);
+    }).resource("/users/me/sessions/{id}", |r| {
+        r.method(Method::DELETE).with(sessions::destroy);
+    }).resource("/users/me/two_factor", |r| {
+        r.method(Method::GET).with(two_factor::show);
+        r.method(Method::POST).with(two_factor::enroll);
+        r.method(Method::DELETE).with(two_factor::disable);
+    }).resource("/users/me/two_factor/enable", |r| {
+        r.method(Method::POST).with(two_factor::enable);
+    }).resource("/users/me/two_factor/recovery_codes", |r| {
+        r.method(Method::POST).with(two_factor::regenerate_recovery_codes);
     }).resource("/users/register", |r| {
         r.method(Method::POST).with(users::register)
     }).resource("/users", |r| {
         r.method(Method::GET).with(users::find_by_email);
     }).resource("/users/{id}", |r| {
         r.method(Method::GET).with(users::show);
+    }).resource("/users/{id}/impersonate", |r| {
+        r.method(Method::POST).with(impersonations::create);
     }).resource("/users/{id}/organizations", |r| {
         r.method(Method::GET).with(users::list_organizations);
+    }).resource("/users/{id}/sessions", |r| {
+        r.method(Method::DELETE).with(sessions::destroy_all_for_user);
     }).resource("/venues/{id}/events", |r| {
         r.method(Method::GET).with(events::show_from_venues);
     }).resource("/venues/{id}/organizations", |r| {
@@ -143,6 +276,13 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     }).resource("/venues", |r| {
         r.method(Method::GET).with(venues::index);
         r.method(Method::POST).with(venues::create);
+    }).resource("/webhook_deliveries/{id}/redeliver", |r| {
+        r.method(Method::POST).with(webhooks::redeliver);
+    }).resource("/webhooks/{id}/deliveries", |r| {
+        r.method(Method::GET).with(webhooks::deliveries);
+    }).resource("/webhooks/{id}", |r| {
+        r.method(Method::PATCH).with(webhooks::update);
+        r.method(Method::DELETE).with(webhooks::destroy);
     }).register()
     .default_resource(|r| {
         r.method(Method::GET)
@@ -6,6 +6,11 @@ use config::Config;
 use db::*;
 use middleware::*;
 use routing;
+use scheduled_thread_pool::ScheduledThreadPool;
+use std::time::Duration;
+use utils::event_reminders;
+use utils::interest_notifications;
+use utils::webhooks;
 use utils::ServiceLocator;
 
 pub struct AppState {
@@ -30,10 +35,14 @@ pub struct Server {
 impl Server {
     pub fn start(config: Config) {
         let bind_addr = format!("{}:{}", config.api_url, config.api_port);
+        let _interest_notification_pool = Server::schedule_interest_notifications(&config);
+        let _event_reminder_pool = Server::schedule_event_reminders(&config);
+        let _webhook_pool = Server::schedule_webhook_deliveries(&config);
         info!("Listening on {}", bind_addr);
         server::new({
             move || {
                 App::with_state(AppState::new(config.clone()))
+                    .middleware(RequestIdentifier::new())
                     .middleware(DatabaseTransaction::new())
                     .middleware(Logger::default())
                     .configure(|a| {
@@ -48,6 +57,7 @@ impl Server {
                                 http::header::AUTHORIZATION,
                                 http::header::ACCEPT,
                             ]).allowed_header(http::header::CONTENT_TYPE)
+                            .expose_headers(vec![REQUEST_ID_HEADER])
                             .max_age(3600);
 
                         routing::routes(&mut cors_config)
@@ -58,4 +68,55 @@ impl Server {
         .unwrap_or_else(|_| panic!("Can not bind to {}", bind_addr))
         .run();
     }
+
+    fn schedule_interest_notifications(config: &Config) -> ScheduledThreadPool {
+        let config = config.clone();
+        let database = Database::from_config(&config);
+        let interval = Duration::from_secs(config.interest_notification_interval);
+        let pool = ScheduledThreadPool::new(1);
+        pool.execute_at_fixed_rate(interval, interval, move || {
+            let connection = database.get_connection();
+            match interest_notifications::send_interest_notifications(&config, connection.get()) {
+                Ok(sent) => if sent > 0 {
+                    info!("Sent {} event interest notifications", sent);
+                },
+                Err(e) => error!("Could not send event interest notifications: {}", e),
+            }
+        });
+        pool
+    }
+
+    fn schedule_event_reminders(config: &Config) -> ScheduledThreadPool {
+        let config = config.clone();
+        let database = Database::from_config(&config);
+        let interval = Duration::from_secs(config.event_reminder_interval);
+        let pool = ScheduledThreadPool::new(1);
+        pool.execute_at_fixed_rate(interval, interval, move || {
+            let connection = database.get_connection();
+            match event_reminders::send_event_reminders(&config, connection.get()) {
+                Ok(sent) => if sent > 0 {
+                    info!("Sent {} event reminders", sent);
+                },
+                Err(e) => error!("Could not send event reminders: {}", e),
+            }
+        });
+        pool
+    }
+
+    fn schedule_webhook_deliveries(config: &Config) -> ScheduledThreadPool {
+        let config = config.clone();
+        let database = Database::from_config(&config);
+        let interval = Duration::from_secs(config.webhook_interval);
+        let pool = ScheduledThreadPool::new(1);
+        pool.execute_at_fixed_rate(interval, interval, move || {
+            let connection = database.get_connection();
+            match webhooks::process_webhook_deliveries(&config, connection.get()) {
+                Ok(attempted) => if attempted > 0 {
+                    info!("Attempted {} webhook deliveries", attempted);
+                },
+                Err(e) => error!("Could not process webhook deliveries: {}", e),
+            }
+        });
+        pool
+    }
 }
@@ -0,0 +1,4 @@
+pub use self::sms_message::SmsMessage;
+
+pub mod sms_message;
+pub mod transports;
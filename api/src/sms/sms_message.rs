@@ -0,0 +1,14 @@
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmsMessage {
+    pub to: String,
+    pub body: String,
+}
+
+impl SmsMessage {
+    pub fn new(to: &str, body: String) -> SmsMessage {
+        SmsMessage {
+            to: to.to_string(),
+            body,
+        }
+    }
+}
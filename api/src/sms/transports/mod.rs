@@ -0,0 +1,7 @@
+pub use self::sms_transport::SmsTransport;
+pub use self::test_sms_transport::TestSmsTransport;
+pub use self::twilio_sms_transport::TwilioSmsTransport;
+
+pub mod sms_transport;
+pub mod test_sms_transport;
+pub mod twilio_sms_transport;
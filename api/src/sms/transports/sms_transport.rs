@@ -0,0 +1,15 @@
+use errors::BigNeonError;
+use sms::SmsMessage;
+use std::any::Any;
+
+pub trait SmsTransport {
+    fn as_any(&self) -> &Any;
+    fn send(&mut self, message: SmsMessage) -> Result<(), BigNeonError>;
+    fn box_clone(&self) -> Box<SmsTransport + Send + Sync>;
+}
+
+impl Clone for Box<SmsTransport + Send + Sync> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
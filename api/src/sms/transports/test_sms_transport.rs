@@ -0,0 +1,36 @@
+use errors::BigNeonError;
+use sms::transports::SmsTransport;
+use sms::SmsMessage;
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub struct TestSmsTransport {
+    pub sent: Arc<Mutex<Vec<SmsMessage>>>,
+}
+
+impl TestSmsTransport {
+    pub fn new() -> Self {
+        TestSmsTransport {
+            sent: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl SmsTransport for TestSmsTransport {
+    fn send(&mut self, message: SmsMessage) -> Result<(), BigNeonError> {
+        {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(message);
+        }
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<SmsTransport + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
@@ -0,0 +1,55 @@
+use errors::*;
+use reqwest;
+use sms::transports::SmsTransport;
+use sms::SmsMessage;
+use std::any::Any;
+
+const TWILIO_API_URL: &str = "https://api.twilio.com/2010-04-01";
+
+#[derive(Clone)]
+pub struct TwilioSmsTransport {
+    account_sid: String,
+    auth_token: String,
+    from_number: String,
+}
+
+impl SmsTransport for TwilioSmsTransport {
+    fn send(&mut self, message: SmsMessage) -> Result<(), BigNeonError> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(&format!(
+                "{}/Accounts/{}/Messages.json",
+                TWILIO_API_URL, self.account_sid
+            )).basic_auth(self.account_sid.clone(), Some(self.auth_token.clone()))
+            .form(&[
+                ("From", self.from_number.as_str()),
+                ("To", message.to.as_str()),
+                ("Body", message.body.as_str()),
+            ]).send()?;
+        if !response.status().is_success() {
+            return Err(ApplicationError::new(format!(
+                "SMS could not be sent, Twilio responded with {}",
+                response.status()
+            )).into());
+        }
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<SmsTransport + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+impl TwilioSmsTransport {
+    pub fn new(account_sid: &str, auth_token: &str, from_number: &str) -> Self {
+        TwilioSmsTransport {
+            account_sid: account_sid.to_string(),
+            auth_token: auth_token.to_string(),
+            from_number: from_number.to_string(),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use bigneon_db::models::{DisplayEvent, DisplayVenue};
+use chrono::prelude::*;
+use config::Config;
+
+const CALENDAR_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const MAX_LINE_LENGTH: usize = 75;
+
+pub struct Calendar {
+    name: String,
+    entries: Vec<CalendarEntry>,
+}
+
+struct CalendarEntry {
+    uid: String,
+    summary: String,
+    description: Option<String>,
+    location: Option<String>,
+    url: String,
+    starts_at: NaiveDateTime,
+}
+
+impl Calendar {
+    pub fn new(name: &str) -> Calendar {
+        Calendar {
+            name: name.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add_event(&mut self, event: &DisplayEvent, config: &Config) {
+        // Events without a start time cannot be placed on a calendar
+        let starts_at = match event.event_start {
+            Some(event_start) => event_start,
+            None => return,
+        };
+
+        let mut description = Vec::new();
+        if let Some(ref top_line_info) = event.top_line_info {
+            description.push(top_line_info.clone());
+        }
+        if let Some(door_time) = event.door_time {
+            description.push(format!(
+                "Doors open at {}",
+                door_time.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        if let Some(ref additional_info) = event.additional_info {
+            description.push(additional_info.clone());
+        }
+
+        self.entries.push(CalendarEntry {
+            uid: format!("{}@{}", event.id, config.domain),
+            summary: event.name.clone(),
+            description: if description.is_empty() {
+                None
+            } else {
+                Some(description.join("\n"))
+            },
+            location: event.venue.as_ref().map(venue_location),
+            url: format!("{}/events/{}", config.front_end_url, event.id),
+            starts_at,
+        });
+    }
+
+    pub fn to_ics(&self, config: &Config) -> String {
+        let stamp = Utc::now().naive_utc().format(CALENDAR_DATE_FORMAT);
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:-//{}//Events//EN", escape(&config.app_name)),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", escape(&self.name)),
+        ];
+
+        for entry in &self.entries {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", entry.uid));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!(
+                "DTSTART:{}",
+                entry.starts_at.format(CALENDAR_DATE_FORMAT)
+            ));
+            lines.push(format!("SUMMARY:{}", escape(&entry.summary)));
+            if let Some(ref description) = entry.description {
+                lines.push(format!("DESCRIPTION:{}", escape(description)));
+            }
+            if let Some(ref location) = entry.location {
+                lines.push(format!("LOCATION:{}", escape(location)));
+            }
+            lines.push(format!("URL:{}", entry.url));
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        let mut ics = String::new();
+        for line in lines {
+            ics.push_str(&fold(&line));
+            ics.push_str("\r\n");
+        }
+        ics
+    }
+}
+
+fn venue_location(venue: &DisplayVenue) -> String {
+    let mut parts = vec![venue.name.clone()];
+    for part in vec![
+        &venue.address,
+        &venue.city,
+        &venue.state,
+        &venue.postal_code,
+        &venue.country,
+    ] {
+        if let &Some(ref value) = part {
+            parts.push(value.clone());
+        }
+    }
+    parts.join(", ")
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace("\\", "\\\\")
+        .replace(";", "\\;")
+        .replace(",", "\\,")
+        .replace("\r\n", "\\n")
+        .replace("\n", "\\n")
+}
+
+// RFC 5545 limits content lines to 75 octets, continuation lines start with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_length = 0;
+    for c in line.chars() {
+        if line_length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+    folded
+}
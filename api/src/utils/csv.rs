@@ -0,0 +1,48 @@
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Minimal RFC 4180 writer used by the export endpoints
+#[derive(Default)]
+pub struct Csv {
+    content: String,
+}
+
+impl Csv {
+    pub fn new() -> Csv {
+        Default::default()
+    }
+
+    pub fn add_row<I, S>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let row: Vec<String> = values
+            .into_iter()
+            .map(|value| escape(value.as_ref()))
+            .collect();
+        self.content.push_str(&row.join(","));
+        self.content.push_str("\r\n");
+    }
+
+    pub fn add_blank_row(&mut self) {
+        self.content.push_str("\r\n");
+    }
+
+    pub fn into_string(self) -> String {
+        self.content
+    }
+}
+
+fn escape(value: &str) -> String {
+    // Spreadsheets evaluate cells starting with these characters as formulas
+    let value = if value.starts_with(|c: char| FORMULA_PREFIXES.contains(&c)) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace("\"", "\"\""))
+    } else {
+        value
+    }
+}
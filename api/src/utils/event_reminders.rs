@@ -0,0 +1,37 @@
+use bigneon_db::models::{EventReminder, Organization, PendingEventReminder};
+use config::Config;
+use diesel::PgConnection;
+use errors::BigNeonError;
+use mail::mailers;
+
+/// Queues reminders for events starting soon and emails the next batch of pending reminders
+/// using each organization's reminder template. Called periodically. A failed send is logged
+/// and recorded on the reminder so it does not hold up the others
+pub fn send_event_reminders(config: &Config, conn: &PgConnection) -> Result<usize, BigNeonError> {
+    EventReminder::queue_due(conn)?;
+
+    let mut sent = 0;
+    for reminder in EventReminder::claim_pending(config.event_reminder_batch_size, conn)? {
+        match send_event_reminder(config, &reminder, conn) {
+            Ok(_) => {
+                EventReminder::mark_sent(&[reminder.id], conn)?;
+                sent += 1;
+            }
+            Err(e) => {
+                error!("Could not send event reminder {}: {}", reminder.id, e);
+                EventReminder::mark_failed(reminder.id, &e.to_string(), conn)?;
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+fn send_event_reminder(
+    config: &Config,
+    reminder: &PendingEventReminder,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let organization = Organization::find(reminder.organization_id, conn)?;
+    mailers::events::event_reminder_email(config, reminder, &organization, conn)?.deliver()
+}
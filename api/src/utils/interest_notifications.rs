@@ -0,0 +1,47 @@
+use bigneon_db::models::{
+    EventInterestNotification, Organization, PendingEventInterestNotification,
+};
+use config::Config;
+use diesel::PgConnection;
+use errors::BigNeonError;
+use mail::mailers;
+
+/// Queues notifications for newly opened pricing tiers and emails the next batch of pending
+/// notifications. Called periodically so that delivery is rate limited to one batch per interval.
+/// A failed send is logged and recorded on the notification so it does not hold up the others
+pub fn send_interest_notifications(
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<usize, BigNeonError> {
+    EventInterestNotification::queue_for_opened_pricing(conn)?;
+
+    let mut sent = 0;
+    for notification in
+        EventInterestNotification::claim_pending(config.interest_notification_batch_size, conn)?
+    {
+        match send_interest_notification(config, &notification, conn) {
+            Ok(_) => {
+                EventInterestNotification::mark_sent(&[notification.id], conn)?;
+                sent += 1;
+            }
+            Err(e) => {
+                error!(
+                    "Could not send event interest notification {}: {}",
+                    notification.id, e
+                );
+                EventInterestNotification::mark_failed(notification.id, &e.to_string(), conn)?;
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+fn send_interest_notification(
+    config: &Config,
+    notification: &PendingEventInterestNotification,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let organization = Organization::find(notification.organization_id, conn)?;
+    mailers::events::interest_notification_email(config, notification, &organization).deliver()
+}
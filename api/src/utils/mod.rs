@@ -1,3 +1,9 @@
 pub use self::service_locator::*;
 
+pub mod calendar;
+pub mod csv;
+pub mod event_reminders;
+pub mod interest_notifications;
+pub mod pdf;
+pub mod webhooks;
 mod service_locator;
@@ -0,0 +1,157 @@
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 50.0;
+const LINE_HEIGHT: f32 = 16.0;
+const FONT_SIZE: f32 = 10.0;
+const HEADING_FONT_SIZE: f32 = 14.0;
+const CHECKBOX_SIZE: f32 = 9.0;
+
+/// Small PDF writer for printable text documents (lists and forms) using the
+/// standard Helvetica fonts so no font data needs to be embedded
+pub struct PdfDocument {
+    pages: Vec<String>,
+    current_page: String,
+    y: f32,
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        PdfDocument::new()
+    }
+}
+
+impl PdfDocument {
+    pub fn new() -> PdfDocument {
+        PdfDocument {
+            pages: Vec::new(),
+            current_page: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    pub fn heading(&mut self, text: &str) {
+        // Keep headings with at least one following line
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.y -= LINE_HEIGHT / 2.0;
+        self.write_text("F2", HEADING_FONT_SIZE, MARGIN, text);
+        self.y -= LINE_HEIGHT * 1.5;
+    }
+
+    pub fn text_line(&mut self, text: &str) {
+        self.ensure_space(LINE_HEIGHT);
+        self.write_text("F1", FONT_SIZE, MARGIN, text);
+        self.y -= LINE_HEIGHT;
+    }
+
+    pub fn checkbox_line(&mut self, columns: &[(&str, f32)]) {
+        self.ensure_space(LINE_HEIGHT);
+        self.current_page.push_str(&format!(
+            "0.5 w {:.2} {:.2} {:.2} {:.2} re S\n",
+            MARGIN,
+            self.y - 1.0,
+            CHECKBOX_SIZE,
+            CHECKBOX_SIZE
+        ));
+        let mut x = MARGIN + CHECKBOX_SIZE + 8.0;
+        for &(text, width) in columns {
+            self.write_text("F1", FONT_SIZE, x, text);
+            x += width;
+        }
+        self.y -= LINE_HEIGHT;
+    }
+
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        if !self.current_page.is_empty() || self.pages.is_empty() {
+            let page = self.current_page.clone();
+            self.pages.push(page);
+        }
+
+        // Object 1 is the catalog, 2 the page tree, 3 and 4 the fonts, followed by a
+        // page and content stream object for each page
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 5 + i * 2).collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids
+                    .iter()
+                    .map(|id| format!("{} 0 R", id))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                page_ids.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        for (page_id, content) in page_ids.iter().zip(self.pages.iter()) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                encode(content).len(),
+                content
+            ));
+        }
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(encode(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object)));
+        }
+        let xref_offset = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            trailer.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        trailer.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        pdf.extend(encode(&trailer));
+        pdf
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let page = self.current_page.clone();
+            self.pages.push(page);
+            self.current_page = String::new();
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn write_text(&mut self, font: &str, size: f32, x: f32, text: &str) {
+        self.current_page.push_str(&format!(
+            "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+            font,
+            size,
+            x,
+            self.y,
+            escape(text)
+        ));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace("\\", "\\\\")
+        .replace("(", "\\(")
+        .replace(")", "\\)")
+        .replace("\r", "")
+        .replace("\n", " ")
+}
+
+// Content is written as Latin-1 to match the WinAnsi font encoding, characters
+// outside of that range cannot be rendered by the standard fonts
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if (c as u32) < 256 { c as u8 } else { b'?' })
+        .collect()
+}
@@ -0,0 +1,193 @@
+use bigneon_db::models::WebhookDelivery;
+use config::Config;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use diesel::PgConnection;
+use errors::BigNeonError;
+use reqwest::{self, header::*, RedirectPolicy, Url};
+use rustc_serialize::hex::ToHex;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-BigNeon-Signature";
+pub const EVENT_HEADER: &str = "X-BigNeon-Event";
+pub const DELIVERY_HEADER: &str = "X-BigNeon-Delivery";
+const REQUEST_TIMEOUT_IN_SECONDS: u64 = 10;
+// Only the start of the response is kept in the delivery log
+const MAX_RESPONSE_BODY_LENGTH: usize = 1000;
+
+/// Queues deliveries for new domain events and attempts the next batch of due deliveries.
+/// Called periodically, failed deliveries are retried by later calls
+pub fn process_webhook_deliveries(
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<usize, BigNeonError> {
+    WebhookDelivery::queue_pending(conn)?;
+
+    // Redirects are not followed as they could lead to an internal address
+    let mut client_builder = reqwest::Client::builder();
+    client_builder
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_IN_SECONDS))
+        .redirect(RedirectPolicy::none());
+    if let Some(ref webhook_proxy) = config.webhook_proxy {
+        client_builder.proxy(reqwest::Proxy::all(webhook_proxy.as_str())?);
+    }
+    let client = client_builder.build()?;
+    let mut attempted = 0;
+    for delivery in WebhookDelivery::claim_due(config.webhook_batch_size, conn)? {
+        deliver(&client, &delivery, conn)?;
+        attempted += 1;
+    }
+
+    Ok(attempted)
+}
+
+/// Posts the delivery's payload to the subscriber, any 2xx response counts as delivered
+pub fn deliver(
+    client: &reqwest::Client,
+    delivery: &WebhookDelivery,
+    conn: &PgConnection,
+) -> Result<WebhookDelivery, BigNeonError> {
+    let subscription = delivery.subscription(conn)?;
+    // Checked again before every attempt as the host may have been pointed elsewhere since the
+    // webhook was created
+    let (url, host) = match request_target(&subscription.url) {
+        Ok(target) => target,
+        Err(error) => return Ok(delivery.mark_attempt_failed(None, None, Some(error), conn)?),
+    };
+    let (event_type, payload) = delivery.payload(conn)?;
+    let body = payload.to_string();
+
+    let mut headers = Headers::new();
+    if let Some(host) = host {
+        headers.set(host);
+    }
+    headers.set(ContentType::json());
+    headers.set_raw(SIGNATURE_HEADER, signature(&subscription.secret, &body));
+    headers.set_raw(EVENT_HEADER, event_type);
+    headers.set_raw(DELIVERY_HEADER, delivery.id.to_string());
+
+    match client
+        .post(url)
+        .headers(headers)
+        .body(body)
+        .send()
+    {
+        Ok(mut response) => {
+            let status = i32::from(response.status().as_u16());
+            let response_body = response.text().ok().map(|text| truncate(&text));
+            if response.status().is_success() {
+                Ok(delivery.mark_delivered(status, response_body, conn)?)
+            } else {
+                Ok(delivery.mark_attempt_failed(Some(status), response_body, None, conn)?)
+            }
+        }
+        Err(e) => {
+            warn!("Could not deliver webhook {}: {}", delivery.id, e);
+            Ok(delivery.mark_attempt_failed(None, None, Some(e.to_string()), conn)?)
+        }
+    }
+}
+
+/// Hex encoded HMAC-SHA256 of the body using the subscription secret, prefixed with the
+/// algorithm so receivers can verify the payload
+pub fn signature(secret: &str, body: &str) -> String {
+    let mut hmac = Hmac::new(Sha256::new(), secret.as_bytes());
+    hmac.input(body.as_bytes());
+    format!("sha256={}", hmac.result().code().to_hex())
+}
+
+/// URL to send a delivery to and the `Host` header to send with it. The host is resolved once
+/// and plain HTTP requests are sent to the checked address so the host can not be pointed at an
+/// internal address between the check and the request. HTTPS requests need the host name for
+/// the TLS handshake, they are only protected from that when sent through an egress proxy that
+/// refuses internal addresses (`WEBHOOK_PROXY`)
+pub fn request_target(url: &str) -> Result<(Url, Option<Host>), String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    let addresses = resolve(&url);
+    if let Some(address) = addresses.iter().find(|address| !is_public_address(address)) {
+        return Err(format!(
+            "Webhook URL resolves to non public address {}",
+            address
+        ));
+    }
+
+    match (url.scheme(), url.domain(), addresses.first()) {
+        ("http", Some(domain), Some(address)) => {
+            let mut address_url = url.clone();
+            address_url
+                .set_ip_host(*address)
+                .map_err(|_| "Invalid webhook URL".to_string())?;
+            Ok((address_url, Some(Host::new(domain.to_string(), url.port()))))
+        }
+        _ => Ok((url, None)),
+    }
+}
+
+/// First loopback, private, link local or otherwise non public address the URL's host resolves
+/// to. Hosts that cannot be resolved are left for the request itself to fail on
+pub fn non_public_address(url: &str) -> Option<IpAddr> {
+    let url = Url::parse(url).ok()?;
+    resolve(&url)
+        .into_iter()
+        .find(|address| !is_public_address(address))
+}
+
+fn resolve(url: &Url) -> Vec<IpAddr> {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return Vec::new(),
+    };
+    // IPv6 addresses are enclosed in brackets
+    let host = if host.starts_with('[') {
+        &host[1..host.len() - 1]
+    } else {
+        host
+    };
+    let port = match url.port_or_known_default() {
+        Some(port) => port,
+        None => return Vec::new(),
+    };
+    match (host, port).to_socket_addrs() {
+        Ok(addresses) => addresses.map(|address| address.ip()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn is_public_address(address: &IpAddr) -> bool {
+    match *address {
+        IpAddr::V4(address) => {
+            let octets = address.octets();
+            !(address.is_private()
+                || address.is_loopback()
+                || address.is_link_local()
+                || address.is_broadcast()
+                || address.is_multicast()
+                || address.is_unspecified()
+                || octets[0] == 0
+                // Shared address space used by carrier grade NAT
+                || (octets[0] == 100 && octets[1] & 0xc0 == 64))
+        }
+        IpAddr::V6(address) => {
+            let first_segment = address.segments()[0];
+            match address.to_ipv4() {
+                // IPv4 mapped and compatible addresses
+                Some(ipv4_address) if !address.is_loopback() => {
+                    is_public_address(&IpAddr::V4(ipv4_address))
+                }
+                _ => !(address.is_loopback()
+                    || address.is_multicast()
+                    || address.is_unspecified()
+                    // Unique local addresses
+                    || first_segment & 0xfe00 == 0xfc00
+                    // Link local addresses
+                    || first_segment & 0xffc0 == 0xfe80),
+            }
+        }
+    }
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_RESPONSE_BODY_LENGTH).collect()
+}
@@ -0,0 +1,243 @@
+use actix_web::{http::Method, http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::api_keys::{self, CreateApiKeyRequest, CreateApiKeyResponse};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_api_key(
+    organization: &Organization,
+    scopes: Vec<&str>,
+    database: &TestDatabase,
+) -> (OrganizationApiKey, String) {
+    let user = database.create_user().finish();
+    OrganizationApiKey::create(
+        organization.id,
+        "Box office",
+        scopes.iter().map(|s| s.to_string()).collect(),
+        user.id,
+        &*database.connection,
+    ).unwrap()
+}
+
+fn api_key_auth_user(api_key: &OrganizationApiKey, database: &TestDatabase) -> AuthUser {
+    let service_user = User::find(api_key.service_user_id, &*database.connection).unwrap();
+    let mut auth_user = AuthUser::new(service_user);
+    auth_user.api_key = Some(api_key.clone());
+    auth_user
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let (api_key, _) = create_api_key(&organization, vec!["org:read"], &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        api_keys::index((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![api_key]).unwrap());
+    assert!(!body.contains("hashed_secret"));
+}
+
+#[test]
+fn index_org_member() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        api_keys::index((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateApiKeyRequest {
+        name: "Box office".to_string(),
+        scopes: vec!["event:write".to_string()],
+    });
+    let response: HttpResponse =
+        api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let created: CreateApiKeyResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(created.name, "Box office");
+    assert_eq!(created.scopes, vec!["event:write".to_string()]);
+
+    let api_key = OrganizationApiKey::find_by_key(&created.key, &*database.connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(api_key.id, created.id);
+    assert_eq!(api_key.organization_id, organization.id);
+}
+
+#[test]
+fn create_with_invalid_scopes() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateApiKeyRequest {
+        name: "Box office".to_string(),
+        scopes: vec!["user:admin".to_string()],
+    });
+    let response: HttpResponse =
+        api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn create_with_api_key() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let scopes = OrganizationApiKey::available_scopes();
+    let (api_key, _) = create_api_key(
+        &organization,
+        scopes.iter().map(|s| s.as_str()).collect(),
+        &database,
+    );
+    let auth_user = api_key_auth_user(&api_key, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateApiKeyRequest {
+        name: "Another key".to_string(),
+        scopes: vec!["org:read".to_string()],
+    });
+    let response: HttpResponse =
+        api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let (api_key, key) = create_api_key(&organization, vec!["org:read"], &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = api_key.id;
+    let response: HttpResponse =
+        api_keys::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(OrganizationApiKey::find_by_key(&key, &*database.connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn destroy_other_organization() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let (api_key, _) = create_api_key(&other_organization, vec!["org:read"], &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = api_key.id;
+    let response: HttpResponse =
+        api_keys::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+    assert!(OrganizationApiKey::find(api_key.id, &*database.connection)
+        .unwrap()
+        .is_active());
+}
+
+#[test]
+fn requests() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let (api_key, _) = create_api_key(&organization, vec!["org:read"], &database);
+    let request = api_key
+        .record_use("GET", "/organizations", None, &*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = api_key.id;
+    let response: HttpResponse =
+        api_keys::requests((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![request]).unwrap());
+}
+
+#[test]
+fn requests_include_refused_requests() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let (api_key, key) = create_api_key(&organization, vec!["org:read"], &database);
+
+    let test_request =
+        TestRequest::create_with_authorization(Method::GET, &format!("ApiKey {}", key));
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let auth_user = AuthUser::extract(&test_request.request).unwrap();
+        let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+        path.id = other_organization.id;
+        api_keys::index((connection, path, auth_user)).into()
+    });
+
+    support::expects_unauthorized(&response);
+    let requests = api_key.requests(10, &*database.connection).unwrap();
+    assert_eq!(requests.len(), 1);
+}
+
+#[test]
+fn api_key_scopes() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let (api_key, _) = create_api_key(&organization, vec!["event:write"], &database);
+    let auth_user = api_key_auth_user(&api_key, &database);
+    let connection = &*database.connection;
+
+    assert!(auth_user
+        .has_scope(Scopes::EventWrite, Some(&organization), connection)
+        .unwrap());
+    assert!(!auth_user
+        .has_scope(Scopes::OrgRead, Some(&organization), connection)
+        .unwrap());
+    assert!(!auth_user
+        .has_scope(Scopes::EventWrite, Some(&other_organization), connection)
+        .unwrap());
+    assert!(!auth_user
+        .has_scope(Scopes::EventWrite, None, connection)
+        .unwrap());
+}
@@ -0,0 +1,108 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path, Query};
+use bigneon_api::controllers::{audit_entries, events};
+use bigneon_api::models::{PagingParameters, PathParameters};
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::audit_entries::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::audit_entries::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::audit_entries::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::audit_entries::index(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+fn index_with_filters() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let user = database.create_user().finish();
+    AuditEntry::create(Some(user.id), AuditActions::Create, Tables::Venues, None)
+        .for_organization(organization.id)
+        .commit(&database.connection)
+        .unwrap();
+    let entry = AuditEntry::create(Some(user.id), AuditActions::Update, Tables::Events, None)
+        .for_organization(organization.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/audit?user_id={}&table_name=Events",
+        user.id
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let paging_parameters =
+        Query::<PagingParameters>::from_request(&test_request.request, &()).unwrap();
+    let filter = Query::<AuditEntryFilter>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = audit_entries::index((
+        database.connection.clone().into(),
+        path,
+        paging_parameters,
+        filter,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["paging"]["total"], json!(1));
+    assert_eq!(payload["data"][0]["id"], json!(entry.id));
+}
+
+#[test]
+fn event_update_is_audited() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let mut auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    auth_user.request_id = Some("request-1".to_string());
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(EventEditableAttributes {
+        name: Some("New Event Name".to_string()),
+        ..Default::default()
+    });
+
+    let response: HttpResponse =
+        events::update((database.connection.clone().into(), path, json, auth_user.clone())).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let entries =
+        AuditEntry::find_for_target(Tables::Events, event.id, &database.connection).unwrap();
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.action, "Update");
+    assert_eq!(entry.organization_id, Some(organization.id));
+    assert_eq!(entry.user_id, Some(auth_user.id()));
+    assert_eq!(entry.request_id, Some("request-1".to_string()));
+    let changes = entry.changes.clone().unwrap();
+    assert_eq!(
+        changes["name"],
+        json!({"before": event.name, "after": "New Event Name"})
+    );
+}
@@ -1,10 +1,22 @@
 use actix_web::{http::StatusCode, HttpResponse, Json};
-use bigneon_api::auth::{claims::AccessToken, claims::RefreshToken, TokenResponse};
+use bigneon_api::auth::{
+    claims::AccessToken, claims::RefreshToken, totp, TokenResponse, TwoFactorChallengeResponse,
+};
 use bigneon_api::controllers::auth;
-use bigneon_api::controllers::auth::{LoginRequest, RefreshRequest};
+use bigneon_api::controllers::auth::{LoginRequest, RefreshRequest, TwoFactorLoginRequest};
+use bigneon_db::models::{
+    LoginThrottle, Session, TwoFactorCredential, TwoFactorRecoveryCode, User,
+};
+use bigneon_db::schema::login_throttles;
+use chrono::NaiveDateTime;
 use crypto::sha2::Sha256;
+use diesel;
+use diesel::prelude::*;
 use jwt::{Header, Token};
+use lettre::SendableEmail;
 use serde_json;
+use std::net::IpAddr;
+use std::str;
 use support;
 use support::database::TestDatabase;
 use support::test_request::TestRequest;
@@ -24,8 +36,11 @@ fn token() {
     let test_request = TestRequest::create();
     let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
 
-    let response: HttpResponse =
-        auth::token((test_request.request, database.connection.into(), json)).into();
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
 
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -36,6 +51,164 @@ fn token() {
 
     assert_eq!(access_token.claims.get_id(), user.id);
     assert_eq!(refresh_token.claims.get_id(), user.id);
+
+    // Both tokens belong to the new session
+    let sessions = Session::find_active_for_user(user.id, &*database.connection).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(access_token.claims.get_session_id(), sessions[0].id);
+    assert_eq!(refresh_token.claims.get_session_id(), sessions[0].id);
+}
+
+#[test]
+fn token_two_factor_enabled() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let credential = enable_two_factor(&user, &database);
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+
+    // No tokens are issued until the second step is completed
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let challenge: TwoFactorChallengeResponse = serde_json::from_str(&body).unwrap();
+    assert!(challenge.two_factor_required);
+
+    let test_request = TestRequest::create();
+    let code = totp::code_for_step(&credential.secret, totp::current_step()).unwrap();
+    let json = Json(TwoFactorLoginRequest::new(
+        &challenge.two_factor_token,
+        Some(&code),
+        None,
+    ));
+    let response: HttpResponse =
+        auth::token_two_factor((test_request.request, database.connection.into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let access_token = Token::<Header, AccessToken>::parse(&response.access_token).unwrap();
+    let refresh_token = Token::<Header, RefreshToken>::parse(&response.refresh_token).unwrap();
+    assert_eq!(access_token.claims.get_id(), user.id);
+    assert!(access_token.claims.two_factor);
+    assert!(refresh_token.claims.two_factor);
+}
+
+#[test]
+fn token_two_factor_incorrect_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let credential = enable_two_factor(&user, &database);
+    let test_request = TestRequest::create();
+    let challenge = TwoFactorChallengeResponse::create_for_user(
+        &test_request.config.token_secret,
+        &test_request.config.token_issuer,
+        &user,
+    );
+
+    let json = Json(TwoFactorLoginRequest::new(
+        &challenge.two_factor_token,
+        Some("000000"),
+        None,
+    ));
+    let response: HttpResponse = auth::token_two_factor((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Two factor code incorrect"}).to_string()
+    );
+
+    // A code cannot be used a second time
+    let code = totp::code_for_step(&credential.secret, totp::current_step()).unwrap();
+    for expected_status in &[StatusCode::OK, StatusCode::UNAUTHORIZED] {
+        let test_request = TestRequest::create();
+        let json = Json(TwoFactorLoginRequest::new(
+            &challenge.two_factor_token,
+            Some(&code),
+            None,
+        ));
+        let response: HttpResponse = auth::token_two_factor((
+            test_request.request,
+            database.connection.clone().into(),
+            json,
+        )).into();
+        assert_eq!(response.status(), *expected_status);
+    }
+}
+
+#[test]
+fn token_two_factor_recovery_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    enable_two_factor(&user, &database);
+    let recovery_codes =
+        TwoFactorRecoveryCode::regenerate_for_user(user.id, &*database.connection).unwrap();
+    let test_request = TestRequest::create();
+    let challenge = TwoFactorChallengeResponse::create_for_user(
+        &test_request.config.token_secret,
+        &test_request.config.token_issuer,
+        &user,
+    );
+
+    let json = Json(TwoFactorLoginRequest::new(
+        &challenge.two_factor_token,
+        None,
+        Some(&recovery_codes[0]),
+    ));
+    let response: HttpResponse = auth::token_two_factor((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, &*database.connection).unwrap(),
+        9
+    );
+}
+
+#[test]
+fn token_two_factor_invalid_token() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let credential = enable_two_factor(&user, &database);
+    let test_request = TestRequest::create();
+
+    // Other tokens cannot be used in place of the two factor token
+    let session = create_session(&user, &database);
+    let tokens = TokenResponse::create_from_session(
+        &test_request.config.token_secret,
+        &test_request.config.token_issuer,
+        &session,
+        false,
+    );
+    let code = totp::code_for_step(&credential.secret, totp::current_step()).unwrap();
+    let json = Json(TwoFactorLoginRequest::new(
+        &tokens.access_token,
+        Some(&code),
+        None,
+    ));
+    let response: HttpResponse =
+        auth::token_two_factor((test_request.request, database.connection.into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Invalid token"}).to_string());
 }
 
 #[test]
@@ -79,14 +252,116 @@ fn token_incorrect_password() {
     );
 }
 
+#[test]
+fn token_clears_failed_attempts() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    LoginThrottle::record_failure_for_user(user.id, &*database.connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+        auth::token((test_request.request.clone(), connection, json)).into()
+    });
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(LoginThrottle::find_for_user(user.id, &*database.connection).is_err());
+}
+
+#[test]
+fn token_failed_attempt_kept_after_rollback() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+
+    let test_request = TestRequest::create();
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let json = Json(LoginRequest::new("fake@localhost", "incorrect"));
+        auth::token((test_request.request.clone(), connection, json)).into()
+    });
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let throttle = LoginThrottle::find_for_user(user.id, &*database.connection).unwrap();
+    assert_eq!(throttle.failed_attempts, 1);
+}
+
+#[test]
+fn token_locked_out() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    for _ in 0..9 {
+        LoginThrottle::record_failure_for_user(user.id, &*database.connection).unwrap();
+    }
+    // Skip the backoff delay so the next attempt is allowed
+    diesel::update(login_throttles::table.filter(login_throttles::user_id.eq(user.id)))
+        .set(login_throttles::locked_until.eq(None::<NaiveDateTime>))
+        .execute(&*database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let json = Json(LoginRequest::new("fake@localhost", "incorrect"));
+        auth::token((test_request.request.clone(), connection, json)).into()
+    });
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(
+        LoginThrottle::find_for_user(user.id, &*database.connection)
+            .unwrap()
+            .is_locked_out()
+    );
+
+    // The failure that locked the account notifies the user
+    {
+        let mail_transport = test_request.test_transport();
+        let sent = mail_transport.sent.lock().unwrap();
+        let mail = sent.first().expect("An account locked email was expected");
+        let email_body = str::from_utf8(*mail.message()).unwrap();
+        assert!(email_body.contains("Sign in to your account has been locked"));
+    }
+
+    // Even the correct password is refused while locked out
+    let test_request = TestRequest::create();
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+        auth::token((test_request.request.clone(), connection, json)).into()
+    });
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(
+        Session::find_active_for_user(user.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
 #[test]
 fn token_refresh() {
     let database = TestDatabase::new();
     let user = database.create_user().finish();
+    let session = create_session(&user, &database);
 
     let test_request = TestRequest::create();
     let state = test_request.extract_state();
-    let refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
     let header: Header = Default::default();
     let refresh_token = Token::new(header, refresh_token_claims)
         .signed(state.config.token_secret.as_bytes(), Sha256::new())
@@ -104,14 +379,78 @@ fn token_refresh() {
     assert_eq!(access_token.claims.get_id(), user.id);
 }
 
+#[test]
+fn token_refresh_revoked_session() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = create_session(&user, &database);
+    session.revoke(&*database.connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
+    let header: Header = Default::default();
+    let refresh_token = Token::new(header, refresh_token_claims)
+        .signed(state.config.token_secret.as_bytes(), Sha256::new())
+        .unwrap();
+    let json = Json(RefreshRequest::new(&refresh_token));
+
+    let response: HttpResponse =
+        auth::token_refresh((state, database.connection.into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Invalid token"}).to_string());
+}
+
+#[test]
+fn token_refresh_two_factor_enabled_since_issued() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = create_session(&user, &database);
+    enable_two_factor(&user, &database);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
+    let header: Header = Default::default();
+    let refresh_token = Token::new(header, refresh_token_claims)
+        .signed(state.config.token_secret.as_bytes(), Sha256::new())
+        .unwrap();
+    let json = Json(RefreshRequest::new(&refresh_token));
+
+    let response: HttpResponse =
+        auth::token_refresh((state, database.connection.into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Invalid token"}).to_string());
+}
+
 #[test]
 fn token_refresh_invalid_refresh_token_secret() {
     let database = TestDatabase::new();
     let user = database.create_user().finish();
+    let session = create_session(&user, &database);
 
     let test_request = TestRequest::create();
     let state = test_request.extract_state();
-    let refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
     let header: Header = Default::default();
     let refresh_token = Token::new(header, refresh_token_claims)
         .signed(b"incorrect-secret", Sha256::new())
@@ -149,10 +488,16 @@ fn token_refresh_user_does_not_exist() {
     let database = TestDatabase::new();
 
     let user = database.create_user().finish();
+    let session = create_session(&user, &database);
     let test_request = TestRequest::create();
 
     let state = test_request.extract_state();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
     refresh_token_claims.sub = Uuid::new_v4().to_string();
 
     let header: Header = Default::default();
@@ -172,12 +517,18 @@ fn token_refresh_password_reset_since_issued() {
     let database = TestDatabase::new();
 
     let user = database.create_user().finish();
+    let session = create_session(&user, &database);
     let password_modified_timestamp = user.password_modified_at.timestamp() as u64;
 
     let test_request = TestRequest::create();
 
     let state = test_request.extract_state();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
 
     // Issued a second prior to the latest password
     refresh_token_claims.issued = password_modified_timestamp - 1;
@@ -200,12 +551,18 @@ fn token_refreshed_after_password_change() {
     let database = TestDatabase::new();
 
     let user = database.create_user().finish();
+    let session = create_session(&user, &database);
     let password_modified_timestamp = user.password_modified_at.timestamp() as u64;
 
     let test_request = TestRequest::create();
 
     let state = test_request.extract_state();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        state.config.token_issuer.clone(),
+        false,
+    );
 
     // Issued a second after the latest password
     refresh_token_claims.issued = password_modified_timestamp + 1;
@@ -226,3 +583,47 @@ fn token_refreshed_after_password_change() {
     assert_eq!(response.refresh_token, refresh_token);
     assert_eq!(access_token.claims.get_id(), user.id);
 }
+
+#[test]
+fn client_ip_address() {
+    let client: IpAddr = "203.0.113.7".parse().unwrap();
+    let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+    let trusted_proxies = vec![proxy];
+
+    // Forwarding headers sent directly by a client are ignored
+    assert_eq!(
+        auth::client_ip_address(Some(client), Some("198.51.100.1"), &trusted_proxies),
+        Some(client)
+    );
+    // The trusted proxy's view of the client is used, addresses the client added are not
+    assert_eq!(
+        auth::client_ip_address(
+            Some(proxy),
+            Some("198.51.100.1, 203.0.113.7"),
+            &trusted_proxies
+        ),
+        Some(client)
+    );
+    assert_eq!(
+        auth::client_ip_address(Some(proxy), Some("invalid"), &trusted_proxies),
+        Some(proxy)
+    );
+    assert_eq!(
+        auth::client_ip_address(None, Some("198.51.100.1"), &trusted_proxies),
+        None
+    );
+}
+
+fn enable_two_factor(user: &User, database: &TestDatabase) -> TwoFactorCredential {
+    TwoFactorCredential::create(user.id)
+        .commit(&*database.connection)
+        .unwrap()
+        .enable(0, &*database.connection)
+        .unwrap()
+}
+
+fn create_session(user: &User, database: &TestDatabase) -> Session {
+    Session::create(user.id, None, None)
+        .commit(&*database.connection)
+        .unwrap()
+}
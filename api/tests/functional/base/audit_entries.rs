@@ -0,0 +1,45 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::audit_entries;
+use bigneon_api::models::{PagingParameters, PathParameters};
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let user = database.create_user().finish();
+    let entry = AuditEntry::create(Some(user.id), AuditActions::Create, Tables::Venues, None)
+        .for_organization(organization.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/audit?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let paging_parameters =
+        Query::<PagingParameters>::from_request(&test_request.request, &()).unwrap();
+    let filter = Query::<AuditEntryFilter>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = audit_entries::index((
+        database.connection.clone().into(),
+        path,
+        paging_parameters,
+        filter,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["paging"]["total"], json!(1));
+    assert_eq!(payload["data"][0]["id"], json!(entry.id));
+    assert_eq!(payload["data"][0]["user_id"], json!(user.id));
+}
@@ -0,0 +1,70 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::calendars::{self, CalendarSubscription};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn show_organization_subscription(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse = calendars::show_organization_subscription((
+        database.connection.clone().into(),
+        path,
+        user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let subscription: CalendarSubscription = serde_json::from_str(&body).unwrap();
+    let calendar_token =
+        CalendarToken::find_by_token(subscription.token, &database.connection).unwrap();
+    assert_eq!(calendar_token.organization_id, Some(organization.id));
+    assert_eq!(
+        subscription.path,
+        format!(
+            "/organizations/{}/events.ics?token={}",
+            organization.id, calendar_token.token
+        )
+    );
+}
+
+pub fn regenerate_organization_subscription(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, &database.connection)
+            .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse = calendars::regenerate_organization_subscription((
+        database.connection.clone().into(),
+        path,
+        user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let subscription: CalendarSubscription = serde_json::from_str(&body).unwrap();
+    assert_ne!(subscription.token, calendar_token.token);
+}
@@ -0,0 +1,53 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::customers::{self, CustomerDirectoryParameters};
+use bigneon_api::models::{PagingParameters, PathParameters};
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+
+    let test_request = TestRequest::create_with_uri("/customers?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let paging_parameters =
+        Query::<PagingParameters>::from_request(&test_request.request, &()).unwrap();
+    let query =
+        Query::<CustomerDirectoryParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = customers::index((
+        database.connection.clone().into(),
+        path,
+        paging_parameters,
+        query,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["paging"]["total"], json!(1));
+    assert_eq!(payload["data"][0]["user_id"], json!(user.id));
+    assert_eq!(payload["data"][0]["ticket_count"], json!(10));
+}
@@ -0,0 +1,117 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::event_promoters::{self, AddEventPromoterRequest};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let co_promoter = database.create_organization().finish();
+    let promoter = EventPromoter::create(
+        event.id,
+        co_promoter.id,
+        EventPromoterPermissions::View,
+        1000,
+    ).commit(&database.connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse =
+        event_promoters::index((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let promoters: Vec<EventPromoter> = serde_json::from_str(&body).unwrap();
+    assert_eq!(promoters, vec![promoter]);
+}
+
+pub fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let co_promoter = database.create_organization().finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(AddEventPromoterRequest {
+        organization_id: co_promoter.id,
+        permission: EventPromoterPermissions::Edit,
+        revenue_split_in_basis_points: 2500,
+    });
+
+    let response: HttpResponse =
+        event_promoters::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let promoter: EventPromoter = serde_json::from_str(&body).unwrap();
+    assert_eq!(promoter.organization_id, co_promoter.id);
+    assert_eq!(promoter.permission(), EventPromoterPermissions::Edit);
+    assert_eq!(promoter.revenue_split_in_basis_points, 2500);
+}
+
+pub fn destroy(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let co_promoter = database.create_organization().finish();
+    EventPromoter::create(
+        event.id,
+        co_promoter.id,
+        EventPromoterPermissions::View,
+        1000,
+    ).commit(&database.connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse = event_promoters::destroy((
+        database.connection.clone().into(),
+        path,
+        Json(co_promoter.id),
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        EventPromoter::find_for_event(event.id, &database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -346,3 +346,37 @@ pub fn guest_list(role: Roles, should_test_succeed: bool) {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
 }
+
+pub fn export_guest_list(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database.create_order().for_event(&event).is_paid().finish();
+
+    let test_request =
+        TestRequest::create_with_uri(&format!("/events/{}/guests/export", event.id));
+    let query_parameters =
+        Query::<GuestListExportParameters>::from_request(&test_request.request, &()).unwrap();
+    let mut path_parameters = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path_parameters.id = event.id;
+    let response: HttpResponse = events::export_guest_list((
+        database.connection.into(),
+        query_parameters,
+        path_parameters,
+        auth_user,
+    )).into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+    } else {
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
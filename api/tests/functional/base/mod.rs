@@ -1,9 +1,17 @@
 pub mod artists;
+pub mod audit_entries;
+pub mod calendars;
+pub mod customers;
+pub mod event_promoters;
 pub mod events;
+pub mod organization_email_templates;
 pub mod organization_invites;
 pub mod organizations;
 pub mod regions;
+pub mod reports;
+pub mod settlements;
 pub mod ticket_types;
 pub mod tickets;
 pub mod users;
 pub mod venues;
+pub mod webhooks;
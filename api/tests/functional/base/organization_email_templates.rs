@@ -0,0 +1,163 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::organization_email_templates::{
+    self, EmailTemplateResponse, PreviewEmailTemplateRequest, PreviewEmailTemplateResponse,
+    UpdateEmailTemplateRequest,
+};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        "Tickets from {{sender_name}}".to_string(),
+        "{{receive_tickets_link}}".to_string(),
+    ).commit(&database.connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse = organization_email_templates::index((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let templates: Vec<EmailTemplateResponse> = serde_json::from_str(&body).unwrap();
+    assert_eq!(templates.len(), 3);
+    let transfer = templates
+        .iter()
+        .find(|t| t.template_type == EmailTemplateTypes::TicketTransfer)
+        .unwrap();
+    assert!(transfer.customized);
+    assert_eq!(transfer.subject, "Tickets from {{sender_name}}");
+    let confirmation = templates
+        .iter()
+        .find(|t| t.template_type == EmailTemplateTypes::TicketConfirmation)
+        .unwrap();
+    assert!(!confirmation.customized);
+    assert_eq!(
+        confirmation.subject,
+        EmailTemplateTypes::TicketConfirmation.default_subject()
+    );
+    assert!(confirmation.placeholders.contains(&"tickets_link".to_string()));
+}
+
+pub fn update(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(UpdateEmailTemplateRequest {
+        template_type: EmailTemplateTypes::EventReminder,
+        subject: "{{event_name}} is soon".to_string(),
+        body: "See you at {{venue_name}}".to_string(),
+    });
+
+    let response: HttpResponse = organization_email_templates::update((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let template = OrganizationEmailTemplate::find_by_type(
+        organization.id,
+        EmailTemplateTypes::EventReminder,
+        &database.connection,
+    ).unwrap();
+    assert_eq!(template.subject, "{{event_name}} is soon");
+    assert_eq!(template.body, "See you at {{venue_name}}");
+}
+
+pub fn destroy(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        "Tickets from {{sender_name}}".to_string(),
+        "{{receive_tickets_link}}".to_string(),
+    ).commit(&database.connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse = organization_email_templates::destroy((
+        database.connection.clone().into(),
+        path,
+        Json(EmailTemplateTypes::TicketTransfer),
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, &database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+pub fn preview(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(PreviewEmailTemplateRequest {
+        template_type: EmailTemplateTypes::TicketConfirmation,
+        subject: Some("Thanks from {{organization_name}}".to_string()),
+        body: None,
+    });
+
+    let response: HttpResponse = organization_email_templates::preview((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let preview: PreviewEmailTemplateResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(preview.subject, format!("Thanks from {}", organization.name));
+    // The default body is rendered with sample values
+    assert!(preview.body.contains("Jane Doe"));
+    assert!(preview.html_body.contains("<p>Hi Jane Doe</p>"));
+}
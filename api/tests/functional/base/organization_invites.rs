@@ -28,6 +28,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
     let json = Json(NewOrgInviteRequest {
         user_email: Some(email.into()),
         user_id: None,
+        organization_role_id: None,
     });
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
@@ -95,6 +96,7 @@ pub fn create_for_existing_user_via_user_id(role: Roles, should_test_succeed: bo
     let json = Json(NewOrgInviteRequest {
         user_email: None,
         user_id: Some(invited_user.id),
+        organization_role_id: None,
     });
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
@@ -156,6 +158,7 @@ pub fn create_for_new_user(role: Roles, should_test_succeed: bool) {
     let json = Json(NewOrgInviteRequest {
         user_email: Some(email.into()),
         user_id: None,
+        organization_role_id: None,
     });
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
@@ -215,6 +218,7 @@ pub fn create_failure_missing_required_parameters(role: Roles, should_test_succe
     let json = Json(NewOrgInviteRequest {
         user_email: None,
         user_id: None,
+        organization_role_id: None,
     });
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
@@ -337,3 +341,101 @@ pub fn decline_invite_status_of_invite(role: Roles, should_test_true: bool) {
         assert_eq!(body, organization_expected_json);
     }
 }
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+
+    let test_request = TestRequest::create_with_uri("/invites?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<InviteIndexParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse =
+        organization_invites::index((database.connection.into(), path, query, auth_user)).into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let invites: Vec<DisplayOrganizationInvite> = serde_json::from_str(&body).unwrap();
+        assert_eq!(invites, vec![DisplayOrganizationInvite::from(invite)]);
+        assert_eq!(invites[0].status, OrganizationInviteStatus::Pending);
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub fn resend(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .with_email(&"new_user@tari.com".to_string())
+        .finish();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = invite.id;
+
+    let response: HttpResponse =
+        organization_invites::resend((state, database.connection.clone().into(), path, auth_user))
+            .into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let invite = OrganizationInvite::find(invite.id, &*database.connection).unwrap();
+        //wait for thread to create email before testing for it
+        thread::sleep(time::Duration::from_millis(1000));
+        let mail_transport = test_request.test_transport();
+        let sent = mail_transport.sent.lock().unwrap();
+        let mail = sent.first().expect("An invite mail was expected");
+        let email_body = str::from_utf8(*mail.message()).unwrap();
+        assert!(email_body.contains("Hi New user"));
+        assert!(email_body.contains(invite.security_token.unwrap().to_string().as_str()));
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub fn destroy(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = invite.id;
+
+    let response: HttpResponse =
+        organization_invites::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    let invite = OrganizationInvite::find(invite.id, &*database.connection).unwrap();
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(invite.status(), OrganizationInviteStatus::Revoked);
+    } else {
+        support::expects_unauthorized(&response);
+        assert_eq!(invite.status(), OrganizationInviteStatus::Pending);
+    }
+}
@@ -3,7 +3,7 @@ use bigneon_api::controllers::organizations;
 use bigneon_api::controllers::organizations::*;
 use bigneon_api::models::{Paging, PagingParameters, PathParameters, Payload, SortingDir};
 use bigneon_db::models::*;
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime, Utc};
 use serde_json;
 use support;
 use support::database::TestDatabase;
@@ -169,6 +169,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         vec![NewFeeScheduleRange {
             min_price: 0,
             fee_in_cents: 0,
+            ..Default::default()
         }],
     ).commit(&*database.connection)
     .unwrap();
@@ -183,6 +184,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         postal_code: None,
         country: None,
         phone: None,
+        parent_id: None,
     });
 
     let response: HttpResponse =
@@ -221,6 +223,8 @@ pub fn update(role: Roles, should_succeed: bool) {
         phone: Some("phone".to_string()),
         fee_schedule_id: None,
         event_fee_in_cents: Some(100),
+        require_two_factor: None,
+        fee_mode: None,
     });
 
     let response: HttpResponse =
@@ -382,6 +386,60 @@ pub fn update_owner(role: Roles, should_succeed: bool) {
     assert_eq!(updated_organization.owner_user_id, new_owner.id);
 }
 
+pub fn update_parent(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let parent = database.create_organization().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(UpdateParentRequest {
+        parent_id: Some(parent.id),
+    });
+
+    let response: HttpResponse =
+        organizations::update_parent((database.connection.into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_organization: Organization = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_organization.parent_id, Some(parent.id));
+}
+
+pub fn show_children(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let child = database
+        .create_organization()
+        .with_parent(&organization)
+        .finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse =
+        organizations::show_children((database.connection.into(), path, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let children: Vec<Organization> = serde_json::from_str(&body).unwrap();
+    assert_eq!(children, vec![child]);
+}
+
 pub fn list_organization_members(role: Roles, should_succeed: bool) {
     let database = TestDatabase::new();
     let user1 = database
@@ -457,6 +515,28 @@ pub fn show_fee_schedule(role: Roles, should_succeed: bool) {
     let auth_user =
         support::create_auth_user_from_user(&user, role, Some(&organization), &database);
 
+    // Versions that are not yet in effect are not shown
+    let mut new_fee_schedule = FeeSchedule::create(
+        fee_schedule.name.clone(),
+        vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 100,
+            ..Default::default()
+        }],
+    );
+    new_fee_schedule.effective_at = Some(Utc::now().naive_utc() + Duration::days(1));
+    let future_fee_schedule = new_fee_schedule
+        .commit_as_version_of(&fee_schedule, &database.connection)
+        .unwrap();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                fee_schedule_id: Some(future_fee_schedule.id),
+                ..Default::default()
+            },
+            &database.connection,
+        ).unwrap();
+
     #[derive(Serialize)]
     struct FeeScheduleWithRanges {
         id: Uuid,
@@ -499,14 +579,17 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
 
     let json = Json(NewFeeSchedule {
         name: "Fees".to_string(),
+        effective_at: None,
         ranges: vec![
             NewFeeScheduleRange {
                 min_price: 20,
                 fee_in_cents: 10,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price: 1000,
                 fee_in_cents: 100,
+                ..Default::default()
             },
         ],
     });
@@ -514,8 +597,12 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
 
-    let response: HttpResponse =
-        organizations::add_fee_schedule((database.connection.into(), path, json, auth_user)).into();
+    let response: HttpResponse = organizations::add_fee_schedule((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    )).into();
 
     if !should_succeed {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
@@ -525,4 +612,12 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
     let body = support::unwrap_body_to_string(&response).unwrap();
     let result: FeeScheduleWithRanges = serde_json::from_str(&body).unwrap();
     assert_eq!(result.name, "Fees".to_string());
+
+    // The new fee schedule is the next version of the organization's fee schedule
+    let fee_schedule = FeeSchedule::find(result.id, &database.connection).unwrap();
+    assert_eq!(fee_schedule.version, 1);
+    assert_eq!(
+        fee_schedule.root_fee_schedule_id,
+        organization.fee_schedule_id
+    );
 }
@@ -1,7 +1,9 @@
 use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
 use bigneon_api::controllers::regions;
 use bigneon_api::models::PathParameters;
-use bigneon_db::models::{NewRegion, Region, RegionEditableAttributes, Roles};
+use bigneon_db::models::{
+    AuditEntry, NewRegion, Region, RegionEditableAttributes, Roles, Tables,
+};
 use serde_json;
 use support;
 use support::database::TestDatabase;
@@ -16,7 +18,10 @@ pub fn create(role: Roles, should_succeed: bool) {
         name: name.to_string(),
     });
 
-    let response: HttpResponse = regions::create((database.connection.into(), json, user)).into();
+    let user_id = user.id();
+
+    let response: HttpResponse =
+        regions::create((database.connection.clone().into(), json, user)).into();
 
     if !should_succeed {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
@@ -26,6 +31,11 @@ pub fn create(role: Roles, should_succeed: bool) {
     let body = support::unwrap_body_to_string(&response).unwrap();
     let region: Region = serde_json::from_str(&body).unwrap();
     assert_eq!(region.name, name);
+
+    let audit_entries =
+        AuditEntry::find_for_target(Tables::Regions, region.id, &*database.connection).unwrap();
+    assert_eq!(audit_entries.len(), 1);
+    assert_eq!(audit_entries[0].user_id, Some(user_id));
 }
 
 pub fn update(role: Roles, should_succeed: bool) {
@@ -0,0 +1,69 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::reports::{self, SalesReportParameters};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn event_sales(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+
+    let test_request = TestRequest::create_with_uri("/reports?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query = Query::<SalesReportParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = reports::event_sales((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let report: SalesReport = serde_json::from_str(&body).unwrap();
+    assert_eq!(report.event_id, Some(event.id));
+    assert_eq!(report.inventory.len(), 1);
+}
+
+pub fn organization_sales(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri("/reports?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<SalesReportParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = reports::organization_sales((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+    )).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let report: SalesReport = serde_json::from_str(&body).unwrap();
+    assert_eq!(report.organization_id, organization.id);
+    assert_eq!(report.event_id, None);
+}
@@ -0,0 +1,163 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path, Query};
+use bigneon_api::controllers::settlements::{self, *};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse =
+        settlements::index((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_settlements: Vec<Settlement> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_settlements, vec![settlement]);
+}
+
+pub fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database.create_order().for_event(&event).is_paid().finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(NewSettlementRequest {
+        event_id: Some(event.id),
+        start_utc: None,
+        end_utc: None,
+    });
+
+    let response: HttpResponse =
+        settlements::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let statement: SettlementStatement = serde_json::from_str(&body).unwrap();
+    assert_eq!(statement.settlement.organization_id, organization.id);
+    assert_eq!(statement.settlement.event_id, Some(event.id));
+    assert_eq!(statement.settlement.created_by, user.id);
+    assert_eq!(statement.organization_total_in_cents, 1500);
+}
+
+pub fn show(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/settlements?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+    let query = Query::<SettlementParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse =
+        settlements::show((database.connection.clone().into(), path, query, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let statement: SettlementStatement = serde_json::from_str(&body).unwrap();
+    assert_eq!(statement.settlement, settlement);
+    assert!(statement.lines.is_empty());
+}
+
+pub fn paid_out(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+
+    let response: HttpResponse =
+        settlements::paid_out((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let settlement: Settlement = serde_json::from_str(&body).unwrap();
+    assert_eq!(settlement.status(), SettlementStatus::PaidOut);
+    assert_eq!(settlement.paid_out_by, Some(user.id));
+
+    let entries =
+        AuditEntry::find_for_target(Tables::Settlements, settlement.id, &database.connection)
+            .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, AuditActions::Update.to_string());
+    assert_eq!(entries[0].user_id, Some(user.id));
+    assert_eq!(
+        entries[0].changes.clone().unwrap()["status"]["after"],
+        json!(SettlementStatus::PaidOut.to_string())
+    );
+}
+
+pub fn destroy(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+
+    let response: HttpResponse =
+        settlements::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(Settlement::find(settlement.id, &database.connection).is_err());
+}
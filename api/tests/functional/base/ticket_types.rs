@@ -49,6 +49,9 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         end_date,
         ticket_pricing,
         increment: None,
+        presale_start_date: None,
+        presale_interest_cutoff: None,
+        presale_code: None,
     };
     let response: HttpResponse = ticket_types::create((
         database.connection.into(),
@@ -125,6 +128,9 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         end_date,
         ticket_pricing: Some(request_ticket_pricing),
         increment: None,
+        presale_start_date: None,
+        presale_interest_cutoff: None,
+        presale_code: None,
     };
     let request_json = serde_json::to_string(&request_data).unwrap();
 
@@ -170,6 +176,9 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         end_date: Some(updated_ticket_type.end_date),
         ticket_pricing: Some(new_ticket_pricing),
         increment: None,
+        presale_start_date: None,
+        presale_interest_cutoff: None,
+        presale_code: None,
     };
     let updated_json = serde_json::to_string(&updated_data).unwrap();
 
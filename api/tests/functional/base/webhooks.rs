@@ -0,0 +1,205 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::webhooks::{self, *};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+pub fn create_subscription(
+    organization: &Organization,
+    database: &TestDatabase,
+) -> WebhookSubscription {
+    let user = database.create_user().finish();
+    WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string()],
+        user.id,
+    ).commit(&*database.connection)
+    .unwrap()
+}
+
+pub fn create_delivery(
+    subscription: &WebhookSubscription,
+    database: &TestDatabase,
+) -> WebhookDelivery {
+    DomainEvent::create(
+        DomainEventTypes::OrderPaid,
+        "Order was paid".to_string(),
+        Tables::Orders,
+        Some(Uuid::new_v4()),
+        None,
+    ).for_organization(subscription.organization_id)
+    .commit(&*database.connection)
+    .unwrap();
+    WebhookDelivery::queue_pending(&*database.connection).unwrap();
+    subscription
+        .deliveries(1, &*database.connection)
+        .unwrap()
+        .remove(0)
+}
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let subscription = create_subscription(&organization, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        webhooks::index((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![subscription]).unwrap());
+    assert!(!body.contains("secret"));
+}
+
+pub fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateWebhookRequest {
+        url: "https://example.com/webhooks".to_string(),
+        event_types: vec!["order.paid".to_string(), "order.refunded".to_string()],
+    });
+    let response: HttpResponse =
+        webhooks::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let created: CreateWebhookResponse = serde_json::from_str(&body).unwrap();
+    let subscription = WebhookSubscription::find(created.id, &*database.connection).unwrap();
+    assert_eq!(subscription.organization_id, organization.id);
+    assert_eq!(subscription.created_by, user.id);
+    assert_eq!(created.secret, subscription.secret);
+    assert_eq!(
+        created.event_types,
+        vec!["order.paid".to_string(), "order.refunded".to_string()]
+    );
+
+    let entries = AuditEntry::find_for_target(
+        Tables::WebhookSubscriptions,
+        subscription.id,
+        &database.connection,
+    ).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, AuditActions::Create.to_string());
+    assert_eq!(entries[0].organization_id, Some(organization.id));
+    // The secret is never written to the audit log
+    assert!(!entries[0].changes.clone().unwrap().to_string().contains(&subscription.secret));
+}
+
+pub fn update(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let subscription = create_subscription(&organization, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = subscription.id;
+    let json = Json(WebhookSubscriptionEditableAttributes {
+        active: Some(false),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        webhooks::update((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let subscription = WebhookSubscription::find(subscription.id, &*database.connection).unwrap();
+    assert!(!subscription.active);
+}
+
+pub fn destroy(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let subscription = create_subscription(&organization, &database);
+    create_delivery(&subscription, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = subscription.id;
+    let response: HttpResponse =
+        webhooks::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(WebhookSubscription::find(subscription.id, &*database.connection).is_err());
+}
+
+pub fn deliveries(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let subscription = create_subscription(&organization, &database);
+    let delivery = create_delivery(&subscription, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = subscription.id;
+    let response: HttpResponse =
+        webhooks::deliveries((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![delivery]).unwrap());
+    assert!(!body.contains("response_body"));
+}
+
+pub fn redeliver(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let subscription = create_subscription(&organization, &database);
+    let delivery = create_delivery(&subscription, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = delivery.id;
+    let response: HttpResponse =
+        webhooks::redeliver((database.connection.clone().into(), path, auth_user)).into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let deliveries = subscription
+        .deliveries(100, &*database.connection)
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].id, delivery.id);
+    assert_eq!(deliveries[0].status(), WebhookDeliveryStatus::Pending);
+}
@@ -0,0 +1,208 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::calendars::{self, CalendarFeedParameters, CalendarSubscription};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod show_organization_subscription_tests {
+    use super::*;
+    #[test]
+    fn show_organization_subscription_org_member() {
+        base::calendars::show_organization_subscription(Roles::OrgMember, true);
+    }
+    #[test]
+    fn show_organization_subscription_admin() {
+        base::calendars::show_organization_subscription(Roles::Admin, true);
+    }
+    #[test]
+    fn show_organization_subscription_user() {
+        base::calendars::show_organization_subscription(Roles::User, false);
+    }
+    #[test]
+    fn show_organization_subscription_org_owner() {
+        base::calendars::show_organization_subscription(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod regenerate_organization_subscription_tests {
+    use super::*;
+    #[test]
+    fn regenerate_organization_subscription_org_member() {
+        base::calendars::regenerate_organization_subscription(Roles::OrgMember, false);
+    }
+    #[test]
+    fn regenerate_organization_subscription_admin() {
+        base::calendars::regenerate_organization_subscription(Roles::Admin, true);
+    }
+    #[test]
+    fn regenerate_organization_subscription_user() {
+        base::calendars::regenerate_organization_subscription(Roles::User, false);
+    }
+    #[test]
+    fn regenerate_organization_subscription_org_owner() {
+        base::calendars::regenerate_organization_subscription(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+fn show_user_subscription() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        calendars::show_user_subscription((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let subscription: CalendarSubscription = serde_json::from_str(&body).unwrap();
+    let calendar_token =
+        CalendarToken::find_or_create_for_user(user.id, &database.connection).unwrap();
+    assert_eq!(subscription.token, calendar_token.token);
+    assert_eq!(
+        subscription.path,
+        format!("/users/me/calendar.ics?token={}", calendar_token.token)
+    );
+}
+
+#[test]
+fn user_feed() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let venue = database
+        .create_venue()
+        .with_name("The Venue".to_string())
+        .finish();
+    let event = database
+        .create_event()
+        .with_name("Event1".into())
+        .with_event_start(&NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11))
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let mut cart = Order::find_or_create_cart(&user, &database.connection).unwrap();
+    let ticket_type = &event.ticket_types(&database.connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 1, &database.connection)
+        .unwrap();
+    let total = cart.calculate_total(&database.connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, &database.connection)
+        .unwrap();
+    let calendar_token =
+        CalendarToken::find_or_create_for_user(user.id, &database.connection).unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/users/me/calendar.ics?token={}",
+        calendar_token.token
+    ));
+    let query = Query::<CalendarFeedParameters>::from_request(&test_request.request, &()).unwrap();
+    let response: HttpResponse = calendars::user_feed((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        query,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(body.contains(&format!("UID:{}@", event.id)));
+    assert!(body.contains("SUMMARY:Event1\r\n"));
+    assert!(body.contains("DTSTART:20160708T091011Z\r\n"));
+    assert!(body.contains("LOCATION:The Venue"));
+    assert!(body.contains("DESCRIPTION:Doors open at 2016-07-08 07:08 UTC\r\n"));
+    assert!(body.contains(&format!("/events/{}\r\n", event.id)));
+}
+
+#[test]
+fn user_feed_invalid_token() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    // Organization tokens cannot be used to read a user calendar
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, &database.connection)
+            .unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/users/me/calendar.ics?token={}",
+        calendar_token.token
+    ));
+    let query = Query::<CalendarFeedParameters>::from_request(&test_request.request, &()).unwrap();
+    let response: HttpResponse = calendars::user_feed((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        query,
+    )).into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn organization_feed() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_name("Published Event".into())
+        .with_organization(&organization)
+        .finish();
+    let draft_event = database
+        .create_event()
+        .with_name("Draft Event".into())
+        .with_status(EventStatus::Draft)
+        .with_organization(&organization)
+        .finish();
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, &database.connection)
+            .unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/organizations/{}/events.ics?token={}",
+        organization.id, calendar_token.token
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<CalendarFeedParameters>::from_request(&test_request.request, &()).unwrap();
+    let response: HttpResponse = calendars::organization_feed((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        query,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.contains(&format!("UID:{}@", event.id)));
+    assert!(!body.contains(&format!("UID:{}@", draft_event.id)));
+}
+
+#[test]
+fn organization_feed_token_for_other_organization() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let organization2 = database.create_organization().finish();
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization2.id, &database.connection)
+            .unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/organizations/{}/events.ics?token={}",
+        organization.id, calendar_token.token
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<CalendarFeedParameters>::from_request(&test_request.request, &()).unwrap();
+    let response: HttpResponse = calendars::organization_feed((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        query,
+    )).into();
+
+    support::expects_unauthorized(&response);
+}
@@ -7,7 +7,9 @@ use chrono::prelude::*;
 use chrono::Duration;
 use diesel;
 use diesel::prelude::*;
+use lettre::SendableEmail;
 use serde_json;
+use std::str;
 use support;
 use support::database::TestDatabase;
 use support::test_request::TestRequest;
@@ -76,6 +78,7 @@ fn add() {
             ticket_type_id,
             quantity: 2,
         }],
+        presale_code: None,
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -129,6 +132,7 @@ fn add_multiple() {
                 quantity: 3,
             },
         ],
+        presale_code: None,
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -202,6 +206,7 @@ fn add_with_increment() {
             ticket_type_id,
             quantity: 4,
         }],
+        presale_code: None,
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -252,6 +257,7 @@ fn add_with_increment_failure_invalid_quantity() {
             ticket_type_id,
             quantity: 2,
         }],
+        presale_code: None,
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -268,6 +274,57 @@ fn add_with_increment_failure_invalid_quantity() {
     assert_eq!(body, expected_json);
 }
 
+#[test]
+fn add_during_presale() {
+    let database = TestDatabase::new();
+    let connection = database.connection.clone();
+    let event = database
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+
+    let user = database.create_user().finish();
+    let ticket_type = event.ticket_types(&connection).unwrap().remove(0);
+    let update_parameters = TicketTypeEditableAttributes {
+        presale_start_date: Some(Utc::now().naive_utc() - Duration::days(1)),
+        presale_code: Some("FANCLUB".to_string()),
+        ..Default::default()
+    };
+    let ticket_type = ticket_type.update(update_parameters, &connection).unwrap();
+    let ticket_type_id = ticket_type.id;
+
+    let input = Json(cart::AddToCartRequest {
+        items: vec![cart::AddToCartRequestItem {
+            ticket_type_id,
+            quantity: 2,
+        }],
+        presale_code: None,
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse =
+        cart::add((database.connection.clone().into(), input, auth_user)).into();
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body.contains("presale_only"));
+
+    let input = Json(cart::AddToCartRequest {
+        items: vec![cart::AddToCartRequestItem {
+            ticket_type_id,
+            quantity: 2,
+        }],
+        presale_code: Some("FANCLUB".to_string()),
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response = cart::add((database.connection.into(), input, auth_user)).unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let cart = Order::find_cart_for_user(user.id, &connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(cart.items(&connection).unwrap()[0].quantity, 2);
+}
+
 #[test]
 fn add_with_existing_cart() {
     let database = TestDatabase::new();
@@ -287,6 +344,7 @@ fn add_with_existing_cart() {
             ticket_type_id,
             quantity: 2,
         }],
+        presale_code: None,
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -624,3 +682,51 @@ fn checkout_external() {
     )).unwrap();
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[test]
+fn checkout_external_sends_confirmation() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let order = database
+        .create_cart()
+        .for_user(&user)
+        .for_event(&event)
+        .finish();
+    let total = order.calculate_total(&database.connection).unwrap();
+    let request = TestRequest::create();
+
+    let input = Json(cart::CheckoutCartRequest {
+        amount: total,
+        method: PaymentRequest::External {
+            reference: "TestRef".to_string(),
+        },
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        request.extract_state(),
+    )).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mail_transport = request.test_transport();
+    let sent = mail_transport.sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    let mail = &sent[0];
+    assert_eq!(
+        format!("{:?}", mail.envelope().to()),
+        format!("[EmailAddress(\"{}\")]", user.email.clone().unwrap())
+    );
+    let email_body = str::from_utf8(*mail.message()).unwrap();
+    assert!(email_body.contains(&event.name));
+    assert!(email_body.contains(&organization.name));
+}
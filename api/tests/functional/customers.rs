@@ -0,0 +1,112 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::customers::{self, CustomerDirectoryParameters};
+use bigneon_api::models::{PagingParameters, PathParameters};
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::customers::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::customers::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::customers::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::customers::index(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+fn index_csv() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = database
+        .create_user()
+        .with_first_name("Jane".to_string())
+        .with_last_name("Doe".to_string())
+        .finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+
+    let test_request = TestRequest::create_with_uri("/customers?format=csv&query=jane");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let paging_parameters =
+        Query::<PagingParameters>::from_request(&test_request.request, &()).unwrap();
+    let query =
+        Query::<CustomerDirectoryParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = customers::index((
+        database.connection.clone().into(),
+        path,
+        paging_parameters,
+        query,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let mut lines = body.split("\r\n");
+    assert_eq!(
+        lines.next().unwrap(),
+        "User Id,First Name,Last Name,Email,Phone,Lifetime Spent (cents),Tickets,Events Attended,First Purchase,Last Purchase,Interests"
+    );
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .starts_with(&format!("{},Jane,Doe,", user.id))
+    );
+    assert_eq!(lines.next(), Some(""));
+}
+
+#[test]
+fn index_invalid_format() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri("/customers?format=xlsx");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let paging_parameters =
+        Query::<PagingParameters>::from_request(&test_request.request, &()).unwrap();
+    let query =
+        Query::<CustomerDirectoryParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = customers::index((
+        database.connection.clone().into(),
+        path,
+        paging_parameters,
+        query,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
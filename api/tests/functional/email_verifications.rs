@@ -0,0 +1,152 @@
+use actix_web::{http::StatusCode, HttpResponse, Json};
+use bigneon_api::controllers::email_verifications::{self, UpdateEmailVerificationParameters};
+use bigneon_db::models::concerns::users::email_verifiable::*;
+use bigneon_db::models::*;
+use chrono::{Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+use lettre::SendableEmail;
+use std::str;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database.create_user().with_unverified_email().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = email_verifications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert!(user.email_verification_token.is_some());
+
+    let mail_transport = test_request.test_transport();
+    let sent = mail_transport.sent.lock().unwrap();
+    let mail = sent
+        .first()
+        .expect("An email verification email was expected");
+    let email_body = str::from_utf8(*mail.message()).unwrap();
+    assert_eq!(
+        format!("{:?}", mail.envelope().to()),
+        format!("[EmailAddress(\"{}\")]", user.email.clone().unwrap())
+    );
+    assert!(email_body.contains(&format!(
+        "{}/verify-email?token={}",
+        test_request.config.front_end_url,
+        user.email_verification_token.unwrap()
+    )));
+}
+
+#[test]
+fn create_when_already_verified() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = email_verifications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn create_throttled() {
+    let database = TestDatabase::new();
+    let user = database.create_user().with_unverified_email().finish();
+    let user = user
+        .create_email_verification_token(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = email_verifications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let user = database.create_user().with_unverified_email().finish();
+    let user = user
+        .create_email_verification_token(&*database.connection)
+        .unwrap();
+
+    let json = Json(UpdateEmailVerificationParameters {
+        email_verification_token: user.email_verification_token.unwrap(),
+    });
+    let response: HttpResponse =
+        email_verifications::update((database.connection.clone().into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert!(user.is_email_verified());
+    assert!(user.email_verification_token.is_none());
+}
+
+#[test]
+fn update_expired_token() {
+    use bigneon_db::schema::users::dsl::*;
+    let database = TestDatabase::new();
+    let user = database.create_user().with_unverified_email().finish();
+    let token = Uuid::new_v4();
+    let user: User = diesel::update(users.filter(id.eq(user.id)))
+        .set(EmailVerification {
+            email_verified_at: None,
+            email_verification_token: Some(token),
+            email_verification_sent_at: Some(Utc::now().naive_utc() - Duration::days(8)),
+        }).get_result(&*database.connection)
+        .unwrap();
+
+    let json = Json(UpdateEmailVerificationParameters {
+        email_verification_token: token,
+    });
+    let response: HttpResponse =
+        email_verifications::update((database.connection.clone().into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert!(!user.is_email_verified());
+}
+
+#[test]
+fn update_invalid_token() {
+    let database = TestDatabase::new();
+
+    let json = Json(UpdateEmailVerificationParameters {
+        email_verification_token: Uuid::new_v4(),
+    });
+    let response: HttpResponse =
+        email_verifications::update((database.connection.clone().into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
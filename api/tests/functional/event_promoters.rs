@@ -0,0 +1,152 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::events;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::event_promoters::index(Roles::OrgMember, true);
+    }
+    #[test]
+    fn index_admin() {
+        base::event_promoters::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::event_promoters::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::event_promoters::index(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_promoters::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_promoters::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_promoters::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_promoters::create(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::event_promoters::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::event_promoters::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::event_promoters::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::event_promoters::destroy(Roles::OrgOwner, true);
+    }
+}
+
+fn update_as_co_promoter(
+    permission: EventPromoterPermissions,
+    attributes: EventEditableAttributes,
+) -> HttpResponse {
+    let database = TestDatabase::new();
+    let co_promoter = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&co_promoter), &database);
+    let event = database.create_event().finish();
+    EventPromoter::create(event.id, co_promoter.id, permission, 0)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(attributes);
+
+    events::update((database.connection.clone().into(), path, json, auth_user)).into()
+}
+
+fn rename_attributes() -> EventEditableAttributes {
+    EventEditableAttributes {
+        name: Some("New Event Name".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn update_as_co_promoter_with_edit_permission() {
+    let response = update_as_co_promoter(EventPromoterPermissions::Edit, rename_attributes());
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn update_as_co_promoter_with_view_permission() {
+    let response = update_as_co_promoter(EventPromoterPermissions::View, rename_attributes());
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn update_fees_as_co_promoter() {
+    let response = update_as_co_promoter(
+        EventPromoterPermissions::Edit,
+        EventEditableAttributes {
+            fee_in_cents: Some(0),
+            ..Default::default()
+        },
+    );
+    support::expects_unauthorized(&response);
+
+    let response = update_as_co_promoter(
+        EventPromoterPermissions::Edit,
+        EventEditableAttributes {
+            fee_mode: Some(Some(FeeMode::Absorbed.to_string())),
+            ..Default::default()
+        },
+    );
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn cancel_as_co_promoter() {
+    let database = TestDatabase::new();
+    let co_promoter = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&co_promoter), &database);
+    let event = database.create_event().finish();
+    EventPromoter::create(event.id, co_promoter.id, EventPromoterPermissions::Edit, 0)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        events::cancel((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+    let event = Event::find(event.id, &database.connection).unwrap();
+    assert!(event.cancelled_at.is_none());
+}
@@ -1,6 +1,7 @@
 use actix_web::Query;
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
 use bigneon_api::controllers::events;
+use bigneon_api::controllers::organizations::FeeScheduleWithRanges;
 use bigneon_api::controllers::events::SearchParameters;
 use bigneon_api::models::{
     Paging, PagingParameters, PathParameters, Payload, SearchParam, SortingDir,
@@ -456,6 +457,133 @@ mod guest_list_tests {
     }
 }
 
+#[cfg(test)]
+mod export_guest_list_tests {
+    use super::*;
+    #[test]
+    fn export_guest_list_org_member() {
+        base::events::export_guest_list(Roles::OrgMember, true);
+    }
+    #[test]
+    fn export_guest_list_admin() {
+        base::events::export_guest_list(Roles::Admin, true);
+    }
+    #[test]
+    fn export_guest_list_user() {
+        base::events::export_guest_list(Roles::User, false);
+    }
+    #[test]
+    fn export_guest_list_org_owner() {
+        base::events::export_guest_list(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+pub fn export_guest_list_csv() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let guest = database
+        .create_user()
+        .with_first_name("Ada".to_string())
+        .with_last_name("Lovelace".to_string())
+        .finish();
+    database
+        .create_order()
+        .for_user(&guest)
+        .for_event(&event)
+        .is_paid()
+        .finish();
+
+    let test_request =
+        TestRequest::create_with_uri(&format!("/events/{}/guests/export?format=csv", event.id));
+    let query_parameters =
+        Query::<GuestListExportParameters>::from_request(&test_request.request, &()).unwrap();
+    let mut path_parameters = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path_parameters.id = event.id;
+    let response: HttpResponse = events::export_guest_list((
+        database.connection.into(),
+        query_parameters,
+        path_parameters,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let lines: Vec<&str> = body.split("\r\n").filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 11);
+    assert!(lines[0].starts_with("Last Name,First Name,Email"));
+    assert!(lines[1].starts_with(&format!("Lovelace,Ada,{}", guest.email)));
+}
+
+#[test]
+pub fn export_guest_list_pdf() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database.create_order().for_event(&event).is_paid().finish();
+
+    let test_request =
+        TestRequest::create_with_uri(&format!("/events/{}/guests/export?format=pdf", event.id));
+    let query_parameters =
+        Query::<GuestListExportParameters>::from_request(&test_request.request, &()).unwrap();
+    let mut path_parameters = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path_parameters.id = event.id;
+    let response: HttpResponse = events::export_guest_list((
+        database.connection.into(),
+        query_parameters,
+        path_parameters,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.starts_with("%PDF-1.4"));
+    assert!(body.trim_right().ends_with("%%EOF"));
+}
+
+#[test]
+pub fn show_with_absorbed_fees() {
+    let database = TestDatabase::new();
+    let event = database
+        .create_event()
+        .with_ticket_pricing()
+        .finish();
+    event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(Some(FeeMode::Absorbed.to_string())),
+                ..Default::default()
+            },
+            &database.connection,
+        ).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse =
+        events::show((database.connection.clone().into(), path, None)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let event: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let ticket_type = &event["ticket_types"][0];
+    assert_eq!(ticket_type["fee_mode"], json!("Absorbed"));
+    assert_eq!(ticket_type["ticket_pricing"]["price_in_cents"], json!(150));
+    assert_eq!(ticket_type["ticket_pricing"]["fee_in_cents"], json!(0));
+}
+
 #[test]
 pub fn show_from_organizations() {
     let database = TestDatabase::new();
@@ -556,6 +684,92 @@ pub fn show_from_venues() {
     assert_eq!(body, expected_json);
 }
 
+#[test]
+pub fn add_fee_schedule() {
+    let database = TestDatabase::new();
+    let event = database.create_event().finish();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(NewFeeSchedule {
+        name: "Event fees".to_string(),
+        effective_at: None,
+        ranges: vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 25,
+            percentage_in_basis_points: 500,
+            ..Default::default()
+        }],
+    });
+
+    let response: HttpResponse =
+        events::add_fee_schedule((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: FeeScheduleWithRanges = serde_json::from_str(&body).unwrap();
+    assert_eq!(result.ranges[0].percentage_in_basis_points, 500);
+    let event = Event::find(event.id, &database.connection).unwrap();
+    assert_eq!(event.fee_schedule_id, Some(result.id));
+}
+
+#[test]
+pub fn add_fee_schedule_as_org_owner() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(NewFeeSchedule {
+        name: "Event fees".to_string(),
+        effective_at: None,
+        ranges: vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 0,
+            ..Default::default()
+        }],
+    });
+
+    let response: HttpResponse =
+        events::add_fee_schedule((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    support::expects_unauthorized(&response);
+    let event = Event::find(event.id, &database.connection).unwrap();
+    assert_eq!(event.fee_schedule_id, None);
+}
+
+#[test]
+pub fn remove_fee_schedule() {
+    let database = TestDatabase::new();
+    let fee_schedule = database.create_fee_schedule().finish();
+    let event = database.create_event().finish();
+    event
+        .set_fee_schedule(Some(&fee_schedule), &database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse =
+        events::remove_fee_schedule((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let event = Event::find(event.id, &database.connection).unwrap();
+    assert_eq!(event.fee_schedule_id, None);
+}
+
 fn expected_show_json(
     event: Event,
     organization: Organization,
@@ -0,0 +1,109 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::external_logins;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let external_login = user
+        .add_external_login(
+            "123".to_string(),
+            "https://accounts.google.com".to_string(),
+            "secret-token".to_string(),
+            &*database.connection,
+        ).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        external_logins::index((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![external_login]).unwrap());
+    assert!(!body.contains("secret-token"));
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let external_login = user
+        .add_external_login(
+            "123".to_string(),
+            "https://accounts.google.com".to_string(),
+            "token".to_string(),
+            &*database.connection,
+        ).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = external_login.id;
+    let response: HttpResponse =
+        external_logins::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        ExternalLogin::find_user("123", "https://accounts.google.com", &*database.connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn destroy_only_sign_in_method() {
+    let database = TestDatabase::new();
+    let user = User::create_from_external_login(
+        "123".to_string(),
+        "Jane".to_string(),
+        "Doe".to_string(),
+        "jane@localhost".to_string(),
+        "https://accounts.google.com".to_string(),
+        "token".to_string(),
+        &*database.connection,
+    ).unwrap();
+    let external_login = ExternalLogin::find_for_user(user.id, &*database.connection)
+        .unwrap()
+        .remove(0);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = external_login.id;
+    let response: HttpResponse =
+        external_logins::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(ExternalLogin::find(external_login.id, &*database.connection).is_ok());
+}
+
+#[test]
+fn destroy_other_users_login() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let external_login = database
+        .create_user()
+        .finish()
+        .add_external_login(
+            "123".to_string(),
+            "https://accounts.google.com".to_string(),
+            "token".to_string(),
+            &*database.connection,
+        ).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = external_login.id;
+    let response: HttpResponse =
+        external_logins::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(ExternalLogin::find(external_login.id, &*database.connection).is_ok());
+}
@@ -0,0 +1,330 @@
+use actix_web::{http::Method, http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::auth::claims::AccessToken;
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::auth::ImpersonationTokenResponse;
+use bigneon_api::controllers::impersonations::{
+    self, CreateImpersonationRequest, DisplayImpersonation, IMPERSONATION_NOT_ALLOWED,
+};
+use bigneon_api::controllers::api_keys::{self, CreateApiKeyRequest};
+use bigneon_api::controllers::tickets::{self, TransferTicketRequest};
+use bigneon_api::controllers::{external_logins, sessions, users};
+use bigneon_api::models::{PathParameters, UserProfileAttributes};
+use bigneon_db::models::*;
+use jwt::{Header, Token};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn impersonating_auth_user(user: &User, database: &TestDatabase) -> (AuthUser, Impersonation) {
+    let admin = database.create_user().finish();
+    let impersonation =
+        Impersonation::start(&admin, user, None, None, &*database.connection).unwrap();
+    let mut auth_user = support::create_auth_user_from_user(user, Roles::User, None, database);
+    auth_user.session_id = Some(impersonation.session_id);
+    auth_user.impersonation = Some(impersonation.clone());
+    (auth_user, impersonation)
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let json = Json(CreateImpersonationRequest {
+        reason: Some("Cart issue".to_string()),
+    });
+    let response: HttpResponse = impersonations::create((
+        test_request.request,
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user.clone(),
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: ImpersonationTokenResponse = serde_json::from_str(&body).unwrap();
+    let impersonation =
+        Impersonation::find(response.impersonation_id, &*database.connection).unwrap();
+    assert_eq!(impersonation.admin_user_id, auth_user.id());
+    assert_eq!(impersonation.user_id, user.id);
+    assert_eq!(impersonation.reason, Some("Cart issue".to_string()));
+
+    // The token acts as the user and carries the impersonation
+    let access_token = Token::<Header, AccessToken>::parse(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id(), user.id);
+    assert_eq!(
+        access_token.claims.get_session_id(),
+        impersonation.session_id
+    );
+    assert_eq!(
+        access_token.claims.get_impersonation_id(),
+        Some(impersonation.id)
+    );
+    assert_eq!(
+        access_token.claims.exp,
+        impersonation.expires_at.timestamp() as u64
+    );
+}
+
+#[test]
+fn create_without_admin_role() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse = impersonations::create((
+        test_request.request,
+        database.connection.clone().into(),
+        path,
+        Json(Default::default()),
+        auth_user,
+    )).into();
+
+    support::expects_unauthorized(&response);
+    assert!(Impersonation::find_for_user(user.id, &*database.connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn create_for_admin() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .finish()
+        .add_role(Roles::Admin, &*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse = impersonations::create((
+        test_request.request,
+        database.connection.clone().into(),
+        path,
+        Json(Default::default()),
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (_, impersonation) = impersonating_auth_user(&user, &database);
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = impersonation.id;
+    let response: HttpResponse =
+        impersonations::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let impersonation = Impersonation::find(impersonation.id, &*database.connection).unwrap();
+    assert!(!impersonation.is_active());
+}
+
+#[test]
+fn destroy_from_impersonation() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (auth_user, impersonation) = impersonating_auth_user(&user, &database);
+    let (_, other_impersonation) = impersonating_auth_user(&user, &database);
+
+    // Other impersonations can not be ended
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = other_impersonation.id;
+    let response: HttpResponse =
+        impersonations::destroy((database.connection.clone().into(), path, auth_user.clone()))
+            .into();
+    support::expects_unauthorized(&response);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = impersonation.id;
+    let response: HttpResponse =
+        impersonations::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (_, impersonation) = impersonating_auth_user(&user, &database);
+    let action = impersonation
+        .record_action("POST", "/cart", &*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        impersonations::index((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let displayed: Vec<DisplayImpersonation> = serde_json::from_str(&body).unwrap();
+    assert_eq!(displayed.len(), 1);
+    assert_eq!(displayed[0].id, impersonation.id);
+    assert_eq!(displayed[0].actions, vec![action]);
+}
+
+#[test]
+fn audit_entries_record_admin() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (auth_user, impersonation) = impersonating_auth_user(&user, &database);
+
+    let audit_entry = auth_user
+        .audit(AuditActions::Update, Tables::Users, Some(user.id))
+        .commit(&*database.connection)
+        .unwrap();
+    assert_eq!(audit_entry.user_id, Some(user.id));
+    assert_eq!(
+        audit_entry.impersonated_by,
+        Some(impersonation.admin_user_id)
+    );
+}
+
+#[test]
+fn transfer_authorization_while_impersonating() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (auth_user, _) = impersonating_auth_user(&user, &database);
+
+    let response: HttpResponse = tickets::transfer_authorization((
+        database.connection.clone().into(),
+        Json(TransferTicketRequest {
+            ticket_ids: vec![],
+            validity_period_in_seconds: 600,
+        }),
+        auth_user,
+    )).into();
+
+    support::expects_forbidden(&response, Some(IMPERSONATION_NOT_ALLOWED));
+}
+
+#[test]
+fn update_phone_while_impersonating() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (auth_user, _) = impersonating_auth_user(&user, &database);
+    let mut attributes: UserProfileAttributes = Default::default();
+    attributes.phone = Some("555-555-5555".to_string());
+
+    let response: HttpResponse = users::update_current_user((
+        database.connection.clone().into(),
+        Json(attributes),
+        auth_user,
+    )).into();
+
+    support::expects_forbidden(&response, Some(IMPERSONATION_NOT_ALLOWED));
+}
+
+#[test]
+fn unlink_external_login_while_impersonating() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let external_login = user
+        .add_external_login(
+            "123".to_string(),
+            "https://accounts.google.com".to_string(),
+            "token".to_string(),
+            &*database.connection,
+        ).unwrap();
+    let (auth_user, _) = impersonating_auth_user(&user, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = external_login.id;
+    let response: HttpResponse =
+        external_logins::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_forbidden(&response, Some(IMPERSONATION_NOT_ALLOWED));
+    assert!(ExternalLogin::find(external_login.id, &*database.connection).is_ok());
+}
+
+#[test]
+fn sign_out_everywhere_while_impersonating() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (auth_user, impersonation) = impersonating_auth_user(&user, &database);
+
+    let response: HttpResponse =
+        sessions::destroy_all((database.connection.clone().into(), auth_user)).into();
+
+    support::expects_forbidden(&response, Some(IMPERSONATION_NOT_ALLOWED));
+    assert!(
+        Session::find(impersonation.session_id, &*database.connection)
+            .unwrap()
+            .is_active()
+    );
+}
+
+#[test]
+fn create_api_key_while_impersonating() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_owner(&user).finish();
+    let (auth_user, _) = impersonating_auth_user(&user, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateApiKeyRequest {
+        name: "Box office".to_string(),
+        scopes: vec!["event:write".to_string()],
+    });
+    let response: HttpResponse =
+        api_keys::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    support::expects_forbidden(&response, Some(IMPERSONATION_NOT_ALLOWED));
+    assert!(
+        OrganizationApiKey::find_for_organization(organization.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn refused_action_is_recorded() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (_, impersonation) = impersonating_auth_user(&user, &database);
+    let config = TestRequest::config();
+    let token = ImpersonationTokenResponse::create_for_impersonation(
+        &config.token_secret,
+        &config.token_issuer,
+        &impersonation,
+        false,
+    );
+
+    let test_request = TestRequest::create_with_authorization(
+        Method::PUT,
+        &format!("Bearer {}", token.access_token),
+    );
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let auth_user = AuthUser::extract(&test_request.request).unwrap();
+        let mut attributes: UserProfileAttributes = Default::default();
+        attributes.email = Some("new-email@localhost".to_string());
+        users::update_current_user((connection, Json(attributes), auth_user)).into()
+    });
+
+    support::expects_forbidden(&response, Some(IMPERSONATION_NOT_ALLOWED));
+    let actions = impersonation.actions(&*database.connection).unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].method, "PUT");
+}
@@ -0,0 +1,168 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::interest_notifications;
+use bigneon_api::models::PathParameters;
+use bigneon_api::utils::interest_notifications::send_interest_notifications;
+use bigneon_db::models::*;
+use chrono::{Duration, Utc};
+use lettre::SendableEmail;
+use std::str;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_event_on_sale(database: &TestDatabase, organization: &Organization) -> Event {
+    // Published before the current pricing tier opened
+    database
+        .create_event()
+        .with_organization(organization)
+        .with_ticket_pricing()
+        .finish()
+        .update(
+            EventEditableAttributes {
+                publish_date: Some(Utc::now().naive_utc() - Duration::days(3)),
+                ..Default::default()
+            },
+            &*database.connection,
+        )
+        .unwrap()
+}
+
+#[test]
+fn opt_out() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        interest_notifications::opt_out((database.connection.clone().into(), path, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let opt_outs =
+        InterestNotificationOptOut::find_for_user(user.id, &*database.connection).unwrap();
+    assert_eq!(opt_outs.len(), 1);
+    assert_eq!(opt_outs[0].organization_id, organization.id);
+}
+
+#[test]
+fn opt_in() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    InterestNotificationOptOut::create(organization.id, user.id)
+        .commit(&*database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        interest_notifications::opt_in((database.connection.clone().into(), path, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        InterestNotificationOptOut::find_for_user(user.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn send_notifications() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = create_event_on_sale(&database, &organization);
+    let user = database.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(&*database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let sent = send_interest_notifications(&test_request.config, &*database.connection).unwrap();
+    assert_eq!(sent, 1);
+
+    {
+        let mail_transport = test_request.test_transport();
+        let sent = mail_transport.sent.lock().unwrap();
+        let mail = sent
+            .first()
+            .expect("An event interest notification was expected");
+        let email_body = str::from_utf8(*mail.message()).unwrap();
+        assert_eq!(
+            format!("{:?}", mail.envelope().to()),
+            format!("[EmailAddress(\"{}\")]", user.email.clone().unwrap())
+        );
+        assert!(email_body.contains(&format!(
+            "{}/events/{}",
+            test_request.config.front_end_url, event.id
+        )));
+        assert!(email_body.contains(&format!(
+            "{}/organizations/{}/interest-notifications/opt-out",
+            test_request.config.front_end_url, organization.id
+        )));
+    }
+
+    // Users are not notified twice for the same on sale
+    let sent = send_interest_notifications(&test_request.config, &*database.connection).unwrap();
+    assert_eq!(sent, 0);
+    assert_eq!(test_request.test_transport().sent.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn send_notifications_in_batches() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = create_event_on_sale(&database, &organization);
+    for _ in 0..3 {
+        let user = database.create_user().finish();
+        EventInterest::create(event.id, user.id)
+            .commit(&*database.connection)
+            .unwrap();
+    }
+
+    let mut test_request = TestRequest::create();
+    test_request.config.interest_notification_batch_size = 2;
+    let config = &test_request.config;
+    assert_eq!(
+        send_interest_notifications(config, &*database.connection).unwrap(),
+        2
+    );
+    assert_eq!(
+        send_interest_notifications(config, &*database.connection).unwrap(),
+        1
+    );
+    assert_eq!(
+        send_interest_notifications(config, &*database.connection).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn send_notifications_opted_out() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = create_event_on_sale(&database, &organization);
+    let user = database.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(&*database.connection)
+        .unwrap();
+    InterestNotificationOptOut::create(organization.id, user.id)
+        .commit(&*database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let sent = send_interest_notifications(&test_request.config, &*database.connection).unwrap();
+    assert_eq!(sent, 0);
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+}
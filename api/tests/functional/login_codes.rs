@@ -0,0 +1,303 @@
+use actix_web::{http::StatusCode, HttpResponse, Json};
+use bigneon_api::auth::{claims::AccessToken, TokenResponse};
+use bigneon_api::controllers::auth::{self, LoginCodeRequest, LoginRequest};
+use bigneon_db::models::{LoginCode, LoginCodeChannel, LoginCodePurpose, Session};
+use jwt::{Header, Token};
+use lettre::SendableEmail;
+use serde_json;
+use std::str;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn request_login_code_by_email() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .finish();
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginCodeRequest {
+        email: Some("fake@localhost".to_string()),
+        phone: None,
+    });
+    let response: HttpResponse = auth::request_login_code((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let code = {
+        let mail_transport = test_request.test_transport();
+        let sent = mail_transport.sent.lock().unwrap();
+        let mail = sent.first().expect("A login code email was expected");
+        let email_body = str::from_utf8(*mail.message()).unwrap();
+        let start = email_body.find("Your login code is ").unwrap() + 19;
+        email_body[start..start + 6].to_string()
+    };
+    let login_code =
+        LoginCode::find_latest_for_user(user.id, LoginCodePurpose::Login, &*database.connection)
+            .unwrap();
+    assert_eq!(login_code.channel, LoginCodeChannel::Email.to_string());
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new_with_code(
+        Some("fake@localhost"),
+        None,
+        &code,
+    ));
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let access_token = Token::<Header, AccessToken>::parse(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id(), user.id);
+    assert_eq!(
+        Session::find_active_for_user(user.id, &*database.connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn request_login_code_by_sms() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .with_verified_phone()
+        .finish();
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginCodeRequest {
+        email: None,
+        phone: Some("555-555-1234".to_string()),
+    });
+    let response: HttpResponse = auth::request_login_code((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let code = {
+        let sms_transport = test_request.test_sms_transport();
+        let sent = sms_transport.sent.lock().unwrap();
+        let message = sent.first().expect("A login code SMS was expected");
+        assert_eq!(message.to, "555-555-1234");
+        message.body[message.body.len() - 6..].to_string()
+    };
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new_with_code(
+        None,
+        Some("555-555-1234"),
+        &code,
+    ));
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let access_token = Token::<Header, AccessToken>::parse(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id(), user.id);
+}
+
+#[test]
+fn request_login_code_unknown_user() {
+    let database = TestDatabase::new();
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginCodeRequest {
+        email: Some("unknown@localhost".to_string()),
+        phone: None,
+    });
+    let response: HttpResponse = auth::request_login_code((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        json,
+    )).into();
+
+    // The response does not reveal whether the user exists
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn request_login_code_missing_email_and_phone() {
+    let database = TestDatabase::new();
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginCodeRequest {
+        email: None,
+        phone: None,
+    });
+    let response: HttpResponse = auth::request_login_code((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn request_login_code_recently_sent() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .finish();
+    LoginCode::create_for_user(user.id, LoginCodeChannel::Email, &*database.connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginCodeRequest {
+        email: Some("fake@localhost".to_string()),
+        phone: None,
+    });
+    let response: HttpResponse = auth::request_login_code((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn token_with_incorrect_login_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .finish();
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, &*database.connection)
+            .unwrap();
+    let incorrect_code = if code == "000000" { "111111" } else { "000000" };
+
+    let test_request = TestRequest::create();
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        let json = Json(LoginRequest::new_with_code(
+            Some("fake@localhost"),
+            None,
+            incorrect_code,
+        ));
+        auth::token((test_request.request.clone(), connection, json)).into()
+    });
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Login code incorrect or expired"}).to_string()
+    );
+    // The attempt is kept even though the request transaction was rolled back
+    let login_code =
+        LoginCode::find_latest_for_user(user.id, LoginCodePurpose::Login, &*database.connection)
+            .unwrap();
+    assert_eq!(login_code.attempts, 1);
+}
+
+#[test]
+fn token_with_unverified_phone() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .finish();
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Sms, &*database.connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new_with_code(
+        None,
+        Some("555-555-1234"),
+        &code,
+    ));
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn token_with_phone_verification_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .with_verified_phone()
+        .finish();
+    let (_, code) =
+        LoginCode::create_for_phone_verification(user.id, "555-555-1234", &*database.connection)
+            .unwrap();
+
+    // Codes sent to verify a phone number can not be used to sign in
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new_with_code(
+        None,
+        Some("555-555-1234"),
+        &code,
+    ));
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn token_with_used_login_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .finish();
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, &*database.connection)
+            .unwrap();
+    assert!(LoginCode::redeem(user.id, &code, &*database.connection).unwrap());
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new_with_code(
+        Some("fake@localhost"),
+        None,
+        &code,
+    ));
+    let response: HttpResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,85 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::login_throttles;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn lock_out(user: &User, database: &TestDatabase) -> LoginThrottle {
+    let mut throttle =
+        LoginThrottle::record_failure_for_user(user.id, &*database.connection).unwrap();
+    while !throttle.is_locked_out() {
+        throttle = LoginThrottle::record_failure_for_user(user.id, &*database.connection).unwrap();
+    }
+    throttle
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let throttle = lock_out(&database.create_user().finish(), &database);
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let response: HttpResponse =
+        login_throttles::index((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![throttle]).unwrap());
+}
+
+#[test]
+fn index_without_scope() {
+    let database = TestDatabase::new();
+    lock_out(&database.create_user().finish(), &database);
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let response: HttpResponse =
+        login_throttles::index((database.connection.clone().into(), auth_user)).into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let throttle = lock_out(&user, &database);
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let admin_id = auth_user.id();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = throttle.id;
+    let response: HttpResponse =
+        login_throttles::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(LoginThrottle::find_for_user(user.id, &*database.connection).is_err());
+    let audit_entries =
+        AuditEntry::find_for_target(Tables::Users, user.id, &*database.connection).unwrap();
+    assert_eq!(audit_entries.len(), 1);
+    assert_eq!(audit_entries[0].user_id, Some(admin_id));
+    assert_eq!(audit_entries[0].action, AuditActions::Delete.to_string());
+}
+
+#[test]
+fn destroy_without_scope() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let throttle = lock_out(&user, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = throttle.id;
+    let response: HttpResponse =
+        login_throttles::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+    assert!(LoginThrottle::find_for_user(user.id, &*database.connection)
+        .unwrap()
+        .is_locked_out());
+}
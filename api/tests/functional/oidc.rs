@@ -0,0 +1,284 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Query};
+use bigneon_api::controllers::external::oidc::{
+    self, OidcAuthorizeParameters, OidcAuthorizeResponse, OidcWebLoginRequest,
+};
+use bigneon_db::models::*;
+use serde_json::{self, Value};
+use support;
+use support::database::TestDatabase;
+use support::mock_idp::{self, MockIdp};
+use support::test_request::TestRequest;
+
+const REDIRECT_URI: &str = "http://localhost/oidc/callback";
+
+fn login(
+    idp: Option<&MockIdp>,
+    mut claims: Value,
+    code: &str,
+    database: &TestDatabase,
+) -> (HttpResponse, TestRequest) {
+    let login_request = OidcLoginRequest::create(REDIRECT_URI)
+        .commit(&*database.connection)
+        .unwrap();
+    claims["nonce"] = json!(login_request.nonce);
+    login_with_state(idp, claims, code, &login_request.state, database)
+}
+
+fn login_with_state(
+    idp: Option<&MockIdp>,
+    claims: Value,
+    code: &str,
+    state: &str,
+    database: &TestDatabase,
+) -> (HttpResponse, TestRequest) {
+    let mut config = TestRequest::config();
+    if let Some(idp) = idp {
+        idp.configure(&mut config);
+        idp.set_claims(claims);
+    }
+    let test_request = TestRequest::create_with_config(config);
+    let json = Json(OidcWebLoginRequest {
+        code: code.to_string(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        state: state.to_string(),
+    });
+    let response: HttpResponse = oidc::web_login((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        json,
+    )).into();
+    (response, test_request)
+}
+
+#[test]
+fn web_login_creates_user() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let claims = idp.claims("subject-1", "ada@example.com");
+
+    let (response, _) = login(Some(&idp), claims, mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let external_login = ExternalLogin::find_user("subject-1", &idp.issuer, &*database.connection)
+        .unwrap()
+        .unwrap();
+    let user = User::find(external_login.user_id, &*database.connection).unwrap();
+    assert_eq!(user.email, Some("ada@example.com".to_string()));
+    assert_eq!(user.first_name, "Ada");
+    assert_eq!(user.last_name, "Lovelace");
+    assert!(user.email_verified_at.is_some());
+}
+
+#[test]
+fn web_login_existing_external_login() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let user = database.create_user().finish();
+    user.add_external_login(
+        "subject-1".to_string(),
+        idp.issuer.clone(),
+        "token".to_string(),
+        &*database.connection,
+    ).unwrap();
+    // The provider's email no longer matches the account, the subject is what identifies it
+    let claims = idp.claims("subject-1", "changed@example.com");
+
+    let (response, _) = login(Some(&idp), claims, mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        ExternalLogin::find_for_user(user.id, &*database.connection)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(User::find_by_email("changed@example.com", &*database.connection).is_err());
+}
+
+#[test]
+fn web_login_links_verified_email() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let user = database.create_user().finish();
+    let claims = idp.claims("subject-1", &user.email.clone().unwrap());
+
+    let (response, _) = login(Some(&idp), claims, mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let external_login = ExternalLogin::find_user("subject-1", &idp.issuer, &*database.connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(external_login.user_id, user.id);
+}
+
+#[test]
+fn web_login_does_not_link_unverified_email() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let user = database.create_user().finish();
+    let mut claims = idp.claims("subject-1", &user.email.clone().unwrap());
+    claims["email_verified"] = json!(false);
+
+    let (response, _) = login(Some(&idp), claims, mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(ExternalLogin::find_for_user(user.id, &*database.connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn web_login_new_user_with_unverified_email() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let mut claims = idp.claims("subject-1", "ada@example.com");
+    // Apple sends this claim as a string
+    claims["email_verified"] = json!("false");
+
+    let (response, test_request) =
+        login(Some(&idp), claims, mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = User::find_by_email("ada@example.com", &*database.connection).unwrap();
+    assert!(user.email_verified_at.is_none());
+    let mail_transport = test_request.test_transport();
+    assert_eq!(mail_transport.sent.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn web_login_invalid_code() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let claims = idp.claims("subject-1", "ada@example.com");
+
+    let (response, _) = login(Some(&idp), claims, "invalid-code", &database);
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(
+        ExternalLogin::find_user("subject-1", &idp.issuer, &*database.connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn web_login_expired_id_token() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let mut claims = idp.claims("subject-1", "ada@example.com");
+    claims["exp"] = json!(claims["iat"].as_i64().unwrap() - 3600);
+
+    let (response, _) = login(Some(&idp), claims, mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn web_login_not_configured() {
+    let database = TestDatabase::new();
+
+    let (response, _) = login(None, json!({}), mock_idp::AUTHORIZATION_CODE, &database);
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn authorize() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let mut config = TestRequest::config();
+    idp.configure(&mut config);
+    let test_request = TestRequest::create_with_uri_and_config(
+        "/external/oidc/authorize?redirect_uri=http%3A%2F%2Flocalhost%2Foidc%2Fcallback",
+        config,
+    );
+    let parameters =
+        Query::<OidcAuthorizeParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = oidc::authorize((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        parameters,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let authorize_response: OidcAuthorizeResponse = serde_json::from_str(&body).unwrap();
+    let authorization_url = authorize_response.authorization_url;
+    assert!(authorization_url.starts_with(&format!("{}/authorize?", idp.issuer)));
+    assert!(authorization_url.contains(&format!("state={}", authorize_response.state)));
+    assert!(authorization_url.contains("nonce="));
+    assert!(authorization_url.contains("redirect_uri=http%3A%2F%2Flocalhost%2Foidc%2Fcallback"));
+}
+
+#[test]
+fn web_login_requires_state() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let claims = idp.claims("subject-1", "ada@example.com");
+
+    let (response, _) = login_with_state(
+        Some(&idp),
+        claims,
+        mock_idp::AUTHORIZATION_CODE,
+        "unknown-state",
+        &database,
+    );
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn web_login_state_used_once() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let login_request = OidcLoginRequest::create(REDIRECT_URI)
+        .commit(&*database.connection)
+        .unwrap();
+    let mut claims = idp.claims("subject-1", "ada@example.com");
+    claims["nonce"] = json!(login_request.nonce);
+
+    let (response, _) = login_with_state(
+        Some(&idp),
+        claims.clone(),
+        mock_idp::AUTHORIZATION_CODE,
+        &login_request.state,
+        &database,
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (response, _) = login_with_state(
+        Some(&idp),
+        claims,
+        mock_idp::AUTHORIZATION_CODE,
+        &login_request.state,
+        &database,
+    );
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn web_login_nonce_mismatch() {
+    let database = TestDatabase::new();
+    let idp = MockIdp::start();
+    let login_request = OidcLoginRequest::create(REDIRECT_URI)
+        .commit(&*database.connection)
+        .unwrap();
+    // ID token issued for a different login
+    let claims = idp.claims("subject-1", "ada@example.com");
+
+    let (response, _) = login_with_state(
+        Some(&idp),
+        claims,
+        mock_idp::AUTHORIZATION_CODE,
+        &login_request.state,
+        &database,
+    );
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(
+        ExternalLogin::find_user("subject-1", &idp.issuer, &*database.connection)
+            .unwrap()
+            .is_none()
+    );
+}
@@ -1,7 +1,9 @@
-use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
-use bigneon_api::controllers::orders;
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path, Query};
+use bigneon_api::controllers::orders::{self, OrderReversalRequest};
 use bigneon_api::models::{PagingParameters, PathParameters, Payload};
-use bigneon_db::models::{DisplayOrder, OrderStatus, Roles};
+use bigneon_db::models::{
+    DisplayOrder, LedgerAccount, LedgerEntry, LedgerEntryType, OrderStatus, Roles,
+};
 use bigneon_db::schema;
 use chrono::prelude::*;
 use diesel;
@@ -101,3 +103,65 @@ pub fn index() {
     let order_ids: Vec<Uuid> = orders.data.iter().map(|o| o.id).collect();
     assert_eq!(order_ids, vec![order2.id, order1.id]);
 }
+
+#[test]
+pub fn create_reversal() {
+    let database = TestDatabase::new();
+    let order = database.create_order().is_paid().finish();
+    let sale_entries = LedgerEntry::find_for_order(order.id, &database.connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = order.id;
+    let json = Json(OrderReversalRequest {
+        reversal_type: LedgerEntryType::Chargeback,
+    });
+
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let response: HttpResponse = orders::create_reversal((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let entries: Vec<LedgerEntry> = serde_json::from_str(&body).unwrap();
+    assert_eq!(entries.len(), sale_entries.len());
+    let organization_total: i64 = LedgerEntry::find_for_order(order.id, &database.connection)
+        .unwrap()
+        .iter()
+        .filter(|e| e.account == LedgerAccount::Organization.to_string())
+        .map(|e| e.amount_in_cents)
+        .sum();
+    assert_eq!(organization_total, 0);
+}
+
+#[test]
+pub fn create_reversal_requires_admin() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let order = database.create_order().is_paid().finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = order.id;
+    let json = Json(OrderReversalRequest {
+        reversal_type: LedgerEntryType::Refund,
+    });
+
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let response: HttpResponse = orders::create_reversal((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    )).into();
+
+    support::expects_unauthorized(&response);
+    assert!(LedgerEntry::find_for_order(order.id, &database.connection)
+        .unwrap()
+        .iter()
+        .all(|e| e.entry_type != LedgerEntryType::Refund.to_string()));
+}
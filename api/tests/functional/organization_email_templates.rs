@@ -0,0 +1,116 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::organization_email_templates::{self, PreviewEmailTemplateRequest};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::organization_email_templates::index(Roles::OrgMember, true);
+    }
+    #[test]
+    fn index_admin() {
+        base::organization_email_templates::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::organization_email_templates::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::organization_email_templates::index(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[test]
+    fn update_org_member() {
+        base::organization_email_templates::update(Roles::OrgMember, false);
+    }
+    #[test]
+    fn update_admin() {
+        base::organization_email_templates::update(Roles::Admin, true);
+    }
+    #[test]
+    fn update_user() {
+        base::organization_email_templates::update(Roles::User, false);
+    }
+    #[test]
+    fn update_org_owner() {
+        base::organization_email_templates::update(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::organization_email_templates::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::organization_email_templates::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::organization_email_templates::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::organization_email_templates::destroy(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod preview_tests {
+    use super::*;
+    #[test]
+    fn preview_org_member() {
+        base::organization_email_templates::preview(Roles::OrgMember, false);
+    }
+    #[test]
+    fn preview_admin() {
+        base::organization_email_templates::preview(Roles::Admin, true);
+    }
+    #[test]
+    fn preview_user() {
+        base::organization_email_templates::preview(Roles::User, false);
+    }
+    #[test]
+    fn preview_org_owner() {
+        base::organization_email_templates::preview(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+fn preview_with_unknown_placeholder() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(PreviewEmailTemplateRequest {
+        template_type: EmailTemplateTypes::TicketTransfer,
+        subject: None,
+        body: Some("Your password is {{password}}".to_string()),
+    });
+
+    let response: HttpResponse = organization_email_templates::preview((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
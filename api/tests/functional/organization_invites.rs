@@ -1,5 +1,15 @@
-use bigneon_db::models::Roles;
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::organization_invites::{
+    self as organization_invites_controller, DisplayOrganizationInvite, InviteIndexParameters,
+    InviteResponseQuery,
+};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
 use functional::base::organization_invites;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
 
 #[cfg(test)]
 mod create_tests {
@@ -85,6 +95,69 @@ mod create_for_new_user_tests {
     }
 }
 
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        organization_invites::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        organization_invites::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        organization_invites::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        organization_invites::index(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod resend_tests {
+    use super::*;
+    #[test]
+    fn resend_org_member() {
+        organization_invites::resend(Roles::OrgMember, false);
+    }
+    #[test]
+    fn resend_admin() {
+        organization_invites::resend(Roles::Admin, true);
+    }
+    #[test]
+    fn resend_user() {
+        organization_invites::resend(Roles::User, false);
+    }
+    #[test]
+    fn resend_org_owner() {
+        organization_invites::resend(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        organization_invites::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        organization_invites::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        organization_invites::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        organization_invites::destroy(Roles::OrgOwner, true);
+    }
+}
+
 #[cfg(test)]
 mod accept_tests {
     use super::*;
@@ -126,3 +199,162 @@ mod decline_tests {
         organization_invites::decline_invite_status_of_invite(Roles::OrgOwner, true);
     }
 }
+
+#[test]
+fn accept_invite_with_unverified_email() {
+    let database = TestDatabase::new();
+    let user = database.create_user().with_unverified_email().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .with_email(&user.email.clone().unwrap())
+        .with_security_token(None)
+        .finish();
+
+    let test_request = TestRequest::create_with_uri(
+        format!(
+            "/accept_invite?security_token={}",
+            &invite.security_token.unwrap().to_string()
+        ).as_str(),
+    );
+    let parameters =
+        Query::<InviteResponseQuery>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = organization_invites_controller::accept_request((
+        database.connection.clone().into(),
+        parameters,
+        Some(auth_user),
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(
+        organization
+            .users(&database.connection)
+            .unwrap()
+            .iter()
+            .all(|u| u.id != user.id)
+    );
+}
+
+#[test]
+fn index_filtered_by_status() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_owner(&user).finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let pending_invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+    let revoked_invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish()
+        .revoke(&*database.connection)
+        .unwrap();
+
+    for (status, invite) in vec![("Pending", pending_invite), ("Revoked", revoked_invite)] {
+        let test_request = TestRequest::create_with_uri(&format!("/invites?status={}", status));
+        let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+        path.id = organization.id;
+        let query =
+            Query::<InviteIndexParameters>::from_request(&test_request.request, &()).unwrap();
+
+        let response: HttpResponse = organization_invites_controller::index((
+            database.connection.clone().into(),
+            path,
+            query,
+            auth_user.clone(),
+        )).into();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let invites: Vec<DisplayOrganizationInvite> = serde_json::from_str(&body).unwrap();
+        assert_eq!(invites, vec![DisplayOrganizationInvite::from(invite)]);
+    }
+}
+
+#[test]
+fn resend_accepted_invite() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_owner(&user).finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish()
+        .accept_invite(&*database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = invite.id;
+
+    let response: HttpResponse = organization_invites_controller::resend((
+        state,
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(test_request
+        .test_transport()
+        .sent
+        .lock()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn accept_invite_with_role() {
+    let database = TestDatabase::new();
+    let owner = database.create_user().finish();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_owner(&owner).finish();
+    let role = OrganizationRole::create(
+        organization.id,
+        "Box Office",
+        vec![Scopes::OrgRead.to_string()],
+    ).commit(&*database.connection)
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let invite = database
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&owner)
+        .with_email(&user.email.clone().unwrap())
+        .with_role(&role)
+        .finish();
+
+    let test_request = TestRequest::create_with_uri(
+        format!(
+            "/accept_invite?security_token={}",
+            &invite.security_token.unwrap().to_string()
+        ).as_str(),
+    );
+    let parameters =
+        Query::<InviteResponseQuery>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = organization_invites_controller::accept_request((
+        database.connection.clone().into(),
+        parameters,
+        Some(auth_user),
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        organization
+            .get_organization_role_for_user(&user, &*database.connection)
+            .unwrap(),
+        Some(role)
+    );
+}
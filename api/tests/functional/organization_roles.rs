@@ -0,0 +1,250 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::organization_roles::{
+    self, AssignOrganizationRoleRequest, CreateOrganizationRoleRequest,
+};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_role(organization: &Organization, database: &TestDatabase) -> OrganizationRole {
+    OrganizationRole::create(
+        organization.id,
+        "Door Staff",
+        vec!["ticket:admin".to_string()],
+    ).commit(&*database.connection)
+    .unwrap()
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let role = create_role(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        organization_roles::index((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![role]).unwrap());
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateOrganizationRoleRequest {
+        name: "Finance".to_string(),
+        scopes: vec!["org:reports".to_string()],
+    });
+    let response: HttpResponse =
+        organization_roles::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let roles =
+        OrganizationRole::find_for_organization(organization.id, &*database.connection).unwrap();
+    assert_eq!(roles.len(), 1);
+    assert_eq!(roles[0].name, "Finance");
+    assert_eq!(roles[0].scopes, vec!["org:reports".to_string()]);
+}
+
+#[test]
+fn create_org_member() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateOrganizationRoleRequest {
+        name: "Finance".to_string(),
+        scopes: vec!["org:reports".to_string()],
+    });
+    let response: HttpResponse =
+        organization_roles::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn create_with_invalid_scopes() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateOrganizationRoleRequest {
+        name: "Finance".to_string(),
+        scopes: vec!["user:admin".to_string()],
+    });
+    let response: HttpResponse =
+        organization_roles::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let role = create_role(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = role.id;
+    let json = Json(OrganizationRoleEditableAttributes {
+        scopes: Some(vec![
+            "ticket:admin".to_string(),
+            "event:view-guests".to_string(),
+        ]),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        organization_roles::update((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let role = OrganizationRole::find(role.id, &*database.connection).unwrap();
+    assert_eq!(role.name, "Door Staff");
+    assert_eq!(
+        role.scopes,
+        vec!["ticket:admin".to_string(), "event:view-guests".to_string()]
+    );
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let role = create_role(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = role.id;
+    let response: HttpResponse =
+        organization_roles::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(OrganizationRole::find(role.id, &*database.connection).is_err());
+}
+
+#[test]
+fn destroy_other_organization() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let organization2 = database.create_organization().finish();
+    let role = create_role(&organization2, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = role.id;
+    let response: HttpResponse =
+        organization_roles::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+    assert!(OrganizationRole::find(role.id, &*database.connection).is_ok());
+}
+
+#[test]
+fn assign() {
+    let database = TestDatabase::new();
+    let member = database.create_user().finish();
+    let organization = database.create_organization().with_user(&member).finish();
+    let role = create_role(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(AssignOrganizationRoleRequest {
+        user_id: member.id,
+        organization_role_id: Some(role.id),
+    });
+    let response: HttpResponse =
+        organization_roles::assign((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    // Door staff can no longer edit events
+    let scopes = organization
+        .get_scopes_for_user(&member, &*database.connection)
+        .unwrap();
+    assert!(scopes.contains(&"ticket:admin".to_string()));
+    assert!(!scopes.contains(&"event:write".to_string()));
+}
+
+#[test]
+fn assign_is_audited_with_previous_role() {
+    let database = TestDatabase::new();
+    let member = database.create_user().finish();
+    let organization = database.create_organization().with_user(&member).finish();
+    let role = create_role(&organization, &database);
+    organization
+        .set_user_role(member.id, Some(role.id), &*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(AssignOrganizationRoleRequest {
+        user_id: member.id,
+        organization_role_id: None,
+    });
+    let response: HttpResponse =
+        organization_roles::assign((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let entries =
+        AuditEntry::find_for_target(Tables::OrganizationUsers, member.id, &database.connection)
+            .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        entries[0].changes.clone().unwrap()["organization_role_id"],
+        json!({"before": role.id, "after": null})
+    );
+}
+
+#[test]
+fn assign_non_member() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let role = create_role(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(AssignOrganizationRoleRequest {
+        user_id: user.id,
+        organization_role_id: Some(role.id),
+    });
+    let response: HttpResponse =
+        organization_roles::assign((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -211,6 +211,48 @@ mod update_owner_tests {
     }
 }
 
+#[cfg(test)]
+mod update_parent_tests {
+    use super::*;
+    #[test]
+    fn update_parent_org_member() {
+        organizations::update_parent(Roles::OrgMember, false);
+    }
+    #[test]
+    fn update_parent_admin() {
+        organizations::update_parent(Roles::Admin, true);
+    }
+    #[test]
+    fn update_parent_user() {
+        organizations::update_parent(Roles::User, false);
+    }
+    #[test]
+    fn update_parent_org_owner() {
+        organizations::update_parent(Roles::OrgOwner, false);
+    }
+}
+
+#[cfg(test)]
+mod show_children_tests {
+    use super::*;
+    #[test]
+    fn show_children_org_member() {
+        organizations::show_children(Roles::OrgMember, true);
+    }
+    #[test]
+    fn show_children_admin() {
+        organizations::show_children(Roles::Admin, true);
+    }
+    #[test]
+    fn show_children_user() {
+        organizations::show_children(Roles::User, false);
+    }
+    #[test]
+    fn show_children_org_owner() {
+        organizations::show_children(Roles::OrgOwner, true);
+    }
+}
+
 #[cfg(test)]
 mod list_organization_members_tests {
     use super::*;
@@ -34,11 +34,11 @@ fn create() {
     }).to_string();
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(CreatePasswordResetParameters {
         email: email.to_string(),
     });
-    let response: HttpResponse = password_resets::create((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::create((test_request.request.clone(), connection_object, json)).into();
 
     // Reload user
     let user = User::find(user.id, &*database.connection).expect("User to reload");
@@ -76,12 +76,14 @@ fn create_fake_email() {
     }).to_string();
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(CreatePasswordResetParameters {
         email: email.to_string(),
     });
-    let response: HttpResponse =
-        password_resets::create((state, database.connection.into(), json)).into();
+    let response: HttpResponse = password_resets::create((
+        test_request.request.clone(),
+        database.connection.into(),
+        json,
+    )).into();
 
     let mail_transport = test_request.test_transport();
 
@@ -107,13 +109,13 @@ fn update() {
     assert!(!user.check_password(&new_password));
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
-    let token_secret = state.config.token_secret.clone();
+    let token_secret = test_request.config.token_secret.clone();
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: user.password_reset_token.unwrap(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, &*database.connection).unwrap();
     assert!(user.password_reset_token.is_none());
@@ -152,12 +154,12 @@ fn update_expired_token() {
     assert!(!user.check_password(&new_password));
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: token,
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, &*database.connection).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -180,12 +182,12 @@ fn update_incorrect_token() {
     assert!(!user.check_password(&new_password));
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: Uuid::new_v4(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, &*database.connection).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -0,0 +1,150 @@
+use actix_web::{http::StatusCode, HttpResponse, Json};
+use bigneon_api::controllers::phone_verifications::{self, UpdatePhoneVerificationParameters};
+use bigneon_db::models::{
+    LoginCode, LoginCodeChannel, LoginCodePurpose, Roles, User, UserEditableAttributes,
+};
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = phone_verifications::create((
+        test_request.request.clone(),
+        database.connection.clone().into(),
+        auth_user.clone(),
+    )).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let code = {
+        let sms_transport = test_request.test_sms_transport();
+        let sent = sms_transport.sent.lock().unwrap();
+        let message = sent.first().expect("A verification code SMS was expected");
+        assert_eq!(message.to, "555-555-1234");
+        message.body[message.body.len() - 6..].to_string()
+    };
+
+    let response: HttpResponse = phone_verifications::update((
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters { code }),
+        auth_user,
+    )).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert!(user.is_phone_verified());
+}
+
+#[test]
+fn update_with_incorrect_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let (_, code) =
+        LoginCode::create_for_phone_verification(user.id, "555-555-1234", &*database.connection)
+            .unwrap();
+    let incorrect_code = if code == "000000" { "111111" } else { "000000" };
+
+    let test_request = TestRequest::create();
+    let request = test_request.request.clone();
+    let response = support::run_with_database_transaction(&request, &database, |connection| {
+        phone_verifications::update((
+            connection,
+            Json(UpdatePhoneVerificationParameters {
+                code: incorrect_code.to_string(),
+            }),
+            auth_user,
+        )).into()
+    });
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let login_code = LoginCode::find_latest_for_user(
+        user.id,
+        LoginCodePurpose::PhoneVerification,
+        &*database.connection,
+    ).unwrap();
+    assert_eq!(login_code.attempts, 1);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert!(!user.is_phone_verified());
+}
+
+#[test]
+fn update_phone_verified_by_another_user() {
+    let database = TestDatabase::new();
+    database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .with_verified_phone()
+        .finish();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let (_, code) =
+        LoginCode::create_for_phone_verification(user.id, "555-555-1234", &*database.connection)
+            .unwrap();
+
+    let response: HttpResponse = phone_verifications::update((
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters { code }),
+        auth_user,
+    )).into();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[test]
+fn update_with_login_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Sms, &*database.connection).unwrap();
+
+    let response: HttpResponse = phone_verifications::update((
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters { code }),
+        auth_user,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn update_after_phone_changed() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_phone("555-555-1234".to_string())
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let (_, code) =
+        LoginCode::create_for_phone_verification(user.id, "555-555-1234", &*database.connection)
+            .unwrap();
+
+    // The code was sent to the previous number so it does not verify the new one
+    let mut attributes: UserEditableAttributes = Default::default();
+    attributes.phone = Some("555-555-9876".to_string());
+    user.update(&attributes, &*database.connection).unwrap();
+
+    let response: HttpResponse = phone_verifications::update((
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters { code }),
+        auth_user,
+    )).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert!(!user.is_phone_verified());
+}
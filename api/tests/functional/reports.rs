@@ -0,0 +1,168 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::reports::{self, SalesReportParameters};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod event_sales_tests {
+    use super::*;
+    #[test]
+    fn event_sales_org_member() {
+        base::reports::event_sales(Roles::OrgMember, false);
+    }
+    #[test]
+    fn event_sales_admin() {
+        base::reports::event_sales(Roles::Admin, true);
+    }
+    #[test]
+    fn event_sales_user() {
+        base::reports::event_sales(Roles::User, false);
+    }
+    #[test]
+    fn event_sales_org_owner() {
+        base::reports::event_sales(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod organization_sales_tests {
+    use super::*;
+    #[test]
+    fn organization_sales_org_member() {
+        base::reports::organization_sales(Roles::OrgMember, false);
+    }
+    #[test]
+    fn organization_sales_admin() {
+        base::reports::organization_sales(Roles::Admin, true);
+    }
+    #[test]
+    fn organization_sales_user() {
+        base::reports::organization_sales(Roles::User, false);
+    }
+    #[test]
+    fn organization_sales_org_owner() {
+        base::reports::organization_sales(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+fn event_sales_csv() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(&database.connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, &database.connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, &database.connection)
+        .unwrap();
+    let total = cart.calculate_total(&database.connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, &database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/reports?format=csv");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query = Query::<SalesReportParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = reports::event_sales((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let mut lines = body.split("\r\n");
+    assert_eq!(
+        lines.next().unwrap(),
+        "Date,Event Id,Ticket Type,Pricing Tier,Sold,Comped,Refunded,Gross Revenue (cents),Per Unit Fees (cents),Absorbed Per Unit Fees (cents)"
+    );
+    assert!(lines.next().unwrap().ends_with(&format!(
+        ",{},{},Standard,2,0,0,300,100,0",
+        event.id, ticket_type.name
+    )));
+    assert!(body.contains(&format!("\r\nExternal,1,{}\r\n", total)));
+}
+
+#[test]
+fn event_sales_invalid_format() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let test_request = TestRequest::create_with_uri("/reports?format=xlsx");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query = Query::<SalesReportParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = reports::event_sales((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn organization_sales_with_children() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let child_organization = database
+        .create_organization()
+        .with_parent(&organization)
+        .finish();
+    // Owners of the parent organization can report on its children
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&child_organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(&database.connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, &database.connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, &database.connection)
+        .unwrap();
+    let total = cart.calculate_total(&database.connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, &database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/reports?include_children=true");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<SalesReportParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse = reports::organization_sales((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let report: SalesReport = serde_json::from_str(&body).unwrap();
+    assert_eq!(report.child_organization_ids, vec![child_organization.id]);
+    assert_eq!(report.totals.sold_quantity, 2);
+}
@@ -0,0 +1,140 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::sessions::{self, DisplaySession};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_session(user: &User, database: &TestDatabase) -> Session {
+    Session::create(user.id, Some("Mozilla/5.0".to_string()), None)
+        .commit(&*database.connection)
+        .unwrap()
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = create_session(&user, &database);
+    let session2 = create_session(&user, &database);
+    create_session(&database.create_user().finish(), &database);
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    auth_user.session_id = Some(session.id);
+
+    let response: HttpResponse =
+        sessions::index((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let displayed: Vec<DisplaySession> = serde_json::from_str(&body).unwrap();
+    assert_eq!(displayed.len(), 2);
+    let current = displayed.iter().find(|s| s.id == session.id).unwrap();
+    assert!(current.current);
+    assert_eq!(current.user_agent, Some("Mozilla/5.0".to_string()));
+    let other = displayed.iter().find(|s| s.id == session2.id).unwrap();
+    assert!(!other.current);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = create_session(&user, &database);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = session.id;
+    let response: HttpResponse =
+        sessions::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(!Session::find(session.id, &*database.connection)
+        .unwrap()
+        .is_active());
+}
+
+#[test]
+fn destroy_other_users_session() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = create_session(&database.create_user().finish(), &database);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = session.id;
+    let response: HttpResponse =
+        sessions::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(Session::find(session.id, &*database.connection)
+        .unwrap()
+        .is_active());
+}
+
+#[test]
+fn destroy_all() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    create_session(&user, &database);
+    create_session(&user, &database);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        sessions::destroy_all((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        Session::find_active_for_user(user.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn destroy_all_for_user() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    create_session(&user, &database);
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse =
+        sessions::destroy_all_for_user((database.connection.clone().into(), path, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        Session::find_active_for_user(user.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn destroy_all_for_user_without_scope() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    create_session(&user, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse =
+        sessions::destroy_all_for_user((database.connection.clone().into(), path, auth_user))
+            .into();
+
+    support::expects_unauthorized(&response);
+    assert_eq!(
+        Session::find_active_for_user(user.id, &*database.connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,198 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::settlements::{self, SettlementParameters};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::settlements::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::settlements::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::settlements::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::settlements::index(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::settlements::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::settlements::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::settlements::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::settlements::create(Roles::OrgOwner, false);
+    }
+}
+
+#[cfg(test)]
+mod show_tests {
+    use super::*;
+    #[test]
+    fn show_org_member() {
+        base::settlements::show(Roles::OrgMember, false);
+    }
+    #[test]
+    fn show_admin() {
+        base::settlements::show(Roles::Admin, true);
+    }
+    #[test]
+    fn show_user() {
+        base::settlements::show(Roles::User, false);
+    }
+    #[test]
+    fn show_org_owner() {
+        base::settlements::show(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod paid_out_tests {
+    use super::*;
+    #[test]
+    fn paid_out_org_member() {
+        base::settlements::paid_out(Roles::OrgMember, false);
+    }
+    #[test]
+    fn paid_out_admin() {
+        base::settlements::paid_out(Roles::Admin, true);
+    }
+    #[test]
+    fn paid_out_user() {
+        base::settlements::paid_out(Roles::User, false);
+    }
+    #[test]
+    fn paid_out_org_owner() {
+        base::settlements::paid_out(Roles::OrgOwner, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::settlements::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::settlements::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::settlements::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::settlements::destroy(Roles::OrgOwner, false);
+    }
+}
+
+#[test]
+fn show_csv() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database.create_order().for_event(&event).is_paid().finish();
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/settlements?format=csv");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+    let query = Query::<SettlementParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse =
+        settlements::show((database.connection.clone().into(), path, query, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let mut lines = body.split("\r\n");
+    assert_eq!(
+        lines.next().unwrap(),
+        "Settlement Id,Status,Period Start,Period End,Paid Out"
+    );
+    assert_eq!(lines.next().unwrap(), format!("{},Open,,,", settlement.id));
+    assert!(body.contains(&format!(
+        "\r\n{},{},TicketSale,Organization,1,1500\r\n",
+        event.id, event.name
+    )));
+    assert!(body.contains("\r\nOrganization,1500\r\n"));
+}
+
+#[test]
+fn show_invalid_format() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/settlements?format=pdf");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+    let query = Query::<SettlementParameters>::from_request(&test_request.request, &()).unwrap();
+
+    let response: HttpResponse =
+        settlements::show((database.connection.clone().into(), path, query, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn destroy_paid_out_settlement() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::Admin, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(&database.connection)
+        .unwrap()
+        .mark_paid_out(user.id, &database.connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+
+    let response: HttpResponse =
+        settlements::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(Settlement::find(settlement.id, &database.connection).is_ok());
+}
@@ -1,4 +1,5 @@
 use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::organizations::FeeScheduleWithRanges;
 use bigneon_api::controllers::ticket_types;
 use bigneon_api::controllers::ticket_types::*;
 use bigneon_api::models::{EventTicketPathParameters, PathParameters};
@@ -112,6 +113,9 @@ pub fn create_with_overlapping_periods() {
         end_date,
         ticket_pricing,
         increment: None,
+        presale_start_date: None,
+        presale_interest_cutoff: None,
+        presale_code: None,
     };
     let response: HttpResponse = ticket_types::create((
         database.connection.into(),
@@ -133,6 +137,74 @@ pub fn create_with_overlapping_periods() {
     assert_eq!(deserialized_response.error, "Validation error");
 }
 
+#[test]
+pub fn add_fee_schedule() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let event = database
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(&database.connection).unwrap().remove(0);
+
+    let test_request = TestRequest::create_with_uri_event_ticket("/");
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let json = Json(NewFeeSchedule {
+        name: "VIP fees".to_string(),
+        effective_at: None,
+        ranges: vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 100,
+            max_fee_in_cents: Some(100),
+            ..Default::default()
+        }],
+    });
+
+    let response: HttpResponse =
+        ticket_types::add_fee_schedule((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: FeeScheduleWithRanges = serde_json::from_str(&body).unwrap();
+    let ticket_type = TicketType::find(ticket_type.id, &database.connection).unwrap();
+    assert_eq!(ticket_type.fee_schedule_id, Some(result.id));
+}
+
+#[test]
+pub fn add_fee_schedule_for_other_event() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let event = database.create_event().with_tickets().finish();
+    let other_event = database.create_event().finish();
+    let ticket_type = event.ticket_types(&database.connection).unwrap().remove(0);
+
+    let test_request = TestRequest::create_with_uri_event_ticket("/");
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = other_event.id;
+    path.ticket_type_id = ticket_type.id;
+    let json = Json(NewFeeSchedule {
+        name: "VIP fees".to_string(),
+        effective_at: None,
+        ranges: vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 100,
+            ..Default::default()
+        }],
+    });
+
+    let response: HttpResponse =
+        ticket_types::add_fee_schedule((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let ticket_type = TicketType::find(ticket_type.id, &database.connection).unwrap();
+    assert_eq!(ticket_type.fee_schedule_id, None);
+}
+
 #[test]
 pub fn update_with_invalid_id() {
     let database = TestDatabase::new();
@@ -179,6 +251,9 @@ pub fn update_with_invalid_id() {
         end_date,
         ticket_pricing: Some(request_ticket_pricing),
         increment: None,
+        presale_start_date: None,
+        presale_interest_cutoff: None,
+        presale_code: None,
     };
 
     //Send update request
@@ -250,6 +325,9 @@ pub fn update_with_overlapping_periods() {
         end_date,
         ticket_pricing: Some(request_ticket_pricing),
         increment: None,
+        presale_start_date: None,
+        presale_interest_cutoff: None,
+        presale_code: None,
     };
 
     //Send update request
@@ -349,3 +349,36 @@ fn receive_ticket_transfer() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[test]
+fn receive_ticket_transfer_with_unverified_email() {
+    let database = TestDatabase::new();
+    let request = TestRequest::create();
+    let user = database.create_user().finish();
+    let event = database.create_event().with_ticket_pricing().finish();
+    let mut cart = Order::find_or_create_cart(&user, &database.connection).unwrap();
+    let ticket_type = &event.ticket_types(&database.connection).unwrap()[0];
+    let tickets = cart
+        .add_tickets(ticket_type.id, 1, &database.connection)
+        .unwrap();
+    let total = cart.calculate_total(&database.connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, &database.connection)
+        .unwrap();
+    let transfer_auth = TicketInstance::authorize_ticket_transfer(
+        user.id,
+        vec![tickets[0].id],
+        3600,
+        &database.connection,
+    ).unwrap();
+
+    let user2 = database.create_user().with_unverified_email().finish();
+    let auth_user2 = support::create_auth_user_from_user(&user2, Roles::User, None, &database);
+    let response = tickets::receive_transfer((
+        database.connection.clone().into(),
+        Json(transfer_auth),
+        auth_user2,
+        request.extract_state(),
+    )).unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,269 @@
+use actix_web::{http::StatusCode, HttpResponse, Json};
+use bigneon_api::auth::totp;
+use bigneon_api::controllers::two_factor::{
+    self, RecoveryCodesResponse, TwoFactorCodeRequest, TwoFactorEnrolmentResponse,
+    TwoFactorStatusResponse,
+};
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn enable_two_factor(user: &User, database: &TestDatabase) -> TwoFactorCredential {
+    TwoFactorCredential::create(user.id)
+        .commit(&*database.connection)
+        .unwrap()
+        .enable(0, &*database.connection)
+        .unwrap()
+}
+
+fn current_code(credential: &TwoFactorCredential) -> TwoFactorCodeRequest {
+    TwoFactorCodeRequest {
+        code: totp::code_for_step(&credential.secret, totp::current_step()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn show() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    enable_two_factor(&user, &database);
+    TwoFactorRecoveryCode::regenerate_for_user(user.id, &*database.connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        two_factor::show((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let status: TwoFactorStatusResponse = serde_json::from_str(&body).unwrap();
+    assert!(status.enabled);
+    assert!(!status.required);
+    assert_eq!(status.recovery_codes_remaining, 10);
+}
+
+#[test]
+fn enroll() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = two_factor::enroll((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let enrolment: TwoFactorEnrolmentResponse = serde_json::from_str(&body).unwrap();
+    assert!(enrolment.provisioning_uri.starts_with("otpauth://totp/"));
+    assert!(enrolment
+        .provisioning_uri
+        .contains(&format!("secret={}", enrolment.secret)));
+
+    // Enrolment alone does not enable two factor authentication
+    assert!(!TwoFactorCredential::is_enabled_for_user(user.id, &*database.connection).unwrap());
+}
+
+#[test]
+fn enable() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let credential = TwoFactorCredential::create(user.id)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(current_code(&credential));
+    let response: HttpResponse =
+        two_factor::enable((database.connection.clone().into(), json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let recovery_codes: RecoveryCodesResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(recovery_codes.recovery_codes.len(), 10);
+    assert!(TwoFactorCredential::is_enabled_for_user(user.id, &*database.connection).unwrap());
+}
+
+#[test]
+fn enable_incorrect_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    TwoFactorCredential::create(user.id)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(TwoFactorCodeRequest {
+        code: Some("12345".to_string()),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        two_factor::enable((database.connection.clone().into(), json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Two factor code incorrect"}).to_string()
+    );
+    assert!(!TwoFactorCredential::is_enabled_for_user(user.id, &*database.connection).unwrap());
+}
+
+#[test]
+fn disable() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let credential = enable_two_factor(&user, &database);
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(current_code(&credential));
+    let response: HttpResponse =
+        two_factor::disable((database.connection.clone().into(), json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(!TwoFactorCredential::is_enabled_for_user(user.id, &*database.connection).unwrap());
+}
+
+#[test]
+fn disable_when_required_by_organization() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let credential = enable_two_factor(&user, &database);
+    let organization = database.create_organization().finish();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            &*database.connection,
+        ).unwrap();
+    let auth_user = support::create_auth_user_from_user(
+        &user,
+        Roles::OrgMember,
+        Some(&organization),
+        &database,
+    );
+
+    let json = Json(current_code(&credential));
+    let response: HttpResponse =
+        two_factor::disable((database.connection.clone().into(), json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(TwoFactorCredential::is_enabled_for_user(user.id, &*database.connection).unwrap());
+}
+
+#[test]
+fn regenerate_recovery_codes() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    enable_two_factor(&user, &database);
+    let old_codes =
+        TwoFactorRecoveryCode::regenerate_for_user(user.id, &*database.connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(TwoFactorCodeRequest {
+        recovery_code: Some(old_codes[0].clone()),
+        ..Default::default()
+    });
+    let response: HttpResponse = two_factor::regenerate_recovery_codes((
+        database.connection.clone().into(),
+        json,
+        auth_user,
+    )).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let recovery_codes: RecoveryCodesResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(recovery_codes.recovery_codes.len(), 10);
+    assert!(!recovery_codes.recovery_codes.contains(&old_codes[1]));
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, &*database.connection).unwrap(),
+        10
+    );
+}
+
+#[test]
+fn parent_organization_scopes_require_two_factor() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let parent = database.create_organization().with_user(&user).finish();
+    let organization = database.create_organization().with_parent(&parent).finish();
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    assert!(auth_user
+        .has_scope(
+            Scopes::EventWrite,
+            Some(&organization),
+            &*database.connection
+        ).unwrap());
+
+    parent
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            &*database.connection,
+        ).unwrap();
+    assert!(!auth_user
+        .has_scope(
+            Scopes::EventWrite,
+            Some(&organization),
+            &*database.connection
+        ).unwrap());
+
+    auth_user.two_factor_authenticated = true;
+    assert!(auth_user
+        .has_scope(
+            Scopes::EventWrite,
+            Some(&organization),
+            &*database.connection
+        ).unwrap());
+}
+
+#[test]
+fn organization_scopes_require_two_factor() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let mut auth_user = support::create_auth_user_from_user(
+        &user,
+        Roles::OrgMember,
+        Some(&organization),
+        &database,
+    );
+    assert!(auth_user
+        .has_scope(
+            Scopes::EventWrite,
+            Some(&organization),
+            &*database.connection
+        ).unwrap());
+
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            &*database.connection,
+        ).unwrap();
+    assert!(!auth_user
+        .has_scope(
+            Scopes::EventWrite,
+            Some(&organization),
+            &*database.connection
+        ).unwrap());
+
+    auth_user.two_factor_authenticated = true;
+    assert!(auth_user
+        .has_scope(
+            Scopes::EventWrite,
+            Some(&organization),
+            &*database.connection
+        ).unwrap());
+}
@@ -0,0 +1,172 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::controllers::user_erasure_requests::{
+    self, CreateUserErasureRequest, ReviewUserErasureRequest,
+};
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(CreateUserErasureRequest {
+        reason: Some("Closing my account".to_string()),
+    });
+    let response: HttpResponse =
+        user_erasure_requests::create((database.connection.clone().into(), json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let erasure_requests =
+        UserErasureRequest::find_for_user(user.id, &*database.connection).unwrap();
+    assert_eq!(erasure_requests.len(), 1);
+    assert_eq!(
+        erasure_requests[0].reason,
+        Some("Closing my account".to_string())
+    );
+}
+
+#[test]
+fn create_for_organization_owner() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    database.create_organization().with_owner(&user).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(CreateUserErasureRequest::default());
+    let response: HttpResponse =
+        user_erasure_requests::create((database.connection.clone().into(), json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        UserErasureRequest::find_for_user(user.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let response: HttpResponse =
+        user_erasure_requests::index((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let pending: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        pending,
+        json!([{
+            "erasure_request": erasure_request,
+            "user": user.for_display().unwrap(),
+        }])
+    );
+}
+
+#[test]
+fn index_without_scope() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, None, &database);
+
+    let response: HttpResponse =
+        user_erasure_requests::index((database.connection.clone().into(), auth_user)).into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn approve() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let admin_id = auth_user.id();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = erasure_request.id;
+    let json = Json(ReviewUserErasureRequest {
+        notes: Some("Identity confirmed".to_string()),
+    });
+    let response: HttpResponse =
+        user_erasure_requests::approve((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = User::find(user.id, &*database.connection).unwrap();
+    assert_eq!(user.email, None);
+    assert!(!user.active);
+
+    // The review is audited after the user's audit history has been scrubbed
+    let audit_entries =
+        AuditEntry::find_for_target(Tables::Users, user.id, &*database.connection).unwrap();
+    assert_eq!(audit_entries.len(), 1);
+    assert_eq!(audit_entries[0].user_id, Some(admin_id));
+    assert!(
+        audit_entries[0]
+            .changes
+            .as_ref()
+            .map_or(false, |c| c.get("status").is_some())
+    );
+}
+
+#[test]
+fn approve_without_scope() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = erasure_request.id;
+    let json = Json(ReviewUserErasureRequest::default());
+    let response: HttpResponse =
+        user_erasure_requests::approve((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    support::expects_unauthorized(&response);
+    assert!(User::find(user.id, &*database.connection).unwrap().active);
+}
+
+#[test]
+fn reject() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = erasure_request.id;
+    let json = Json(ReviewUserErasureRequest::default());
+    let response: HttpResponse =
+        user_erasure_requests::reject((database.connection.clone().into(), path, json, auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let erasure_request =
+        UserErasureRequest::find(erasure_request.id, &*database.connection).unwrap();
+    assert_eq!(
+        erasure_request.status,
+        UserErasureRequestStatus::Rejected.to_string()
+    );
+    assert!(User::find(user.id, &*database.connection).unwrap().active);
+}
@@ -1,12 +1,19 @@
-use actix_web::{http::StatusCode, HttpResponse, Json};
+use actix_web::{
+    http::{header, StatusCode},
+    HttpResponse, Json,
+};
 use bigneon_api::controllers::users::{self, CurrentUser};
 use bigneon_api::models::{RegisterRequest, UserProfileAttributes};
-use bigneon_db::models::Roles;
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
+use bigneon_db::models::{EventInterest, OrganizationRole, Roles, User};
 use functional::base;
+use lettre::SendableEmail;
 use serde_json;
 use std::collections::HashMap;
+use std::str;
 use support;
 use support::database::TestDatabase;
+use support::test_request::TestRequest;
 
 #[cfg(test)]
 mod user_list_organizations_tests {
@@ -85,7 +92,9 @@ fn register_address_exists() {
         &"not_important",
     ));
 
-    let response: HttpResponse = users::register((database.connection.into(), json)).into();
+    let test_request = TestRequest::create();
+    let response: HttpResponse =
+        users::register((test_request.extract_state(), database.connection.into(), json)).into();
 
     if response.status() == StatusCode::OK {
         panic!("Duplicate email was allowed when it should not be")
@@ -104,8 +113,25 @@ fn register_succeeds() {
         &"not_important",
     ));
 
-    let response: HttpResponse = users::register((database.connection.into(), json)).into();
+    let test_request = TestRequest::create();
+    let response: HttpResponse = users::register((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        json,
+    )).into();
     assert_eq!(response.status(), StatusCode::CREATED);
+
+    let user = User::find_by_email("fake@localhost", &database.connection).unwrap();
+    assert!(!user.is_email_verified());
+    let mail_transport = test_request.test_transport();
+    let sent = mail_transport.sent.lock().unwrap();
+    let mail = sent.first().expect("An email verification email was expected");
+    let email_body = str::from_utf8(*mail.message()).unwrap();
+    assert!(email_body.contains(&format!(
+        "{}/verify-email?token={}",
+        test_request.config.front_end_url,
+        user.email_verification_token.unwrap()
+    )));
 }
 
 #[test]
@@ -120,7 +146,9 @@ fn register_with_validation_errors() {
         &"not_important",
     ));
 
-    let response: HttpResponse = users::register((database.connection.into(), json)).into();
+    let test_request = TestRequest::create();
+    let response: HttpResponse =
+        users::register((test_request.extract_state(), database.connection.into(), json)).into();
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     assert!(response.error().is_some());
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -144,6 +172,7 @@ fn current_user() {
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
     let current_user: CurrentUser = serde_json::from_str(&body).unwrap();
+    assert!(current_user.email_verified);
     let user = current_user.user;
     assert_eq!(user.id, user.id);
     assert_eq!(
@@ -176,11 +205,14 @@ fn current_user_admin() {
             "order::make-external-payment",
             "order:read",
             "org:admin",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "region:write",
             "ticket:admin",
             "ticket:transfer",
+            "user:admin",
             "user:read",
             "venue:write"
         ],
@@ -202,6 +234,7 @@ fn current_user_organization_owner() {
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
     let current_user: CurrentUser = serde_json::from_str(&body).unwrap();
+    assert!(current_user.email_verified);
     let user = current_user.user;
     assert_eq!(user.id, user.id);
     assert_eq!(
@@ -218,7 +251,9 @@ fn current_user_organization_owner() {
             "event:write",
             "hold:write",
             "order:read",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "ticket:admin",
             "ticket:transfer",
@@ -255,6 +290,7 @@ fn current_user_organization_member() {
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
     let current_user: CurrentUser = serde_json::from_str(&body).unwrap();
+    assert!(current_user.email_verified);
     let user = current_user.user;
     assert_eq!(user.id, user.id);
     assert_eq!(
@@ -286,6 +322,42 @@ fn current_user_organization_member() {
     assert_eq!(expected_roles, current_user.organization_roles);
 }
 
+#[test]
+fn current_user_organization_member_with_organization_role() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_user(&user).finish();
+    let role = OrganizationRole::create(
+        organization.id,
+        "Finance",
+        vec!["org:reports".to_string()],
+    ).commit(&*database.connection)
+    .unwrap();
+    organization
+        .set_user_role(user.id, Some(role.id), &*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        users::current_user((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let current_user: CurrentUser = serde_json::from_str(&body).unwrap();
+    let mut expected_scopes = HashMap::new();
+    expected_scopes.insert(
+        organization.id,
+        vec![
+            "event:interest",
+            "order:read",
+            "org:reports",
+            "ticket:transfer",
+        ].into_iter()
+        .map(|scope| scope.to_string())
+        .collect(),
+    );
+    assert_eq!(expected_scopes, current_user.organization_scopes);
+}
+
 #[test]
 pub fn update_current_user() {
     let database = TestDatabase::new();
@@ -339,3 +411,34 @@ fn update_current_user_address_exists() {
         users::update_current_user((database.connection.into(), json, user)).into();
     assert_eq!(response.status(), StatusCode::CONFLICT);
 }
+
+#[test]
+fn export_data() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let event = database.create_event().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(&*database.connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        users::export_data((database.connection.clone().into(), auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(header::CONTENT_DISPOSITION)
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        "attachment; filename=\"bigneon-data-export.json\""
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let export: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(export["profile"]["id"], json!(user.id));
+    assert_eq!(export["profile"]["email"], json!(user.email));
+    assert_eq!(export["event_interests"][0]["event_id"], json!(event.id));
+    assert!(!body.contains(&user.hashed_pw));
+}
@@ -0,0 +1,261 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path};
+use bigneon_api::config::{Config, Environment};
+use bigneon_api::controllers::webhooks::{self, CreateWebhookRequest};
+use bigneon_api::models::PathParameters;
+use bigneon_api::utils::webhooks::{process_webhook_deliveries, request_target, signature};
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::webhooks::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::webhooks::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::webhooks::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::webhooks::index(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::webhooks::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::webhooks::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::webhooks::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::webhooks::create(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[test]
+    fn update_org_member() {
+        base::webhooks::update(Roles::OrgMember, false);
+    }
+    #[test]
+    fn update_admin() {
+        base::webhooks::update(Roles::Admin, true);
+    }
+    #[test]
+    fn update_user() {
+        base::webhooks::update(Roles::User, false);
+    }
+    #[test]
+    fn update_org_owner() {
+        base::webhooks::update(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::webhooks::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::webhooks::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::webhooks::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::webhooks::destroy(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod deliveries_tests {
+    use super::*;
+    #[test]
+    fn deliveries_org_member() {
+        base::webhooks::deliveries(Roles::OrgMember, false);
+    }
+    #[test]
+    fn deliveries_admin() {
+        base::webhooks::deliveries(Roles::Admin, true);
+    }
+    #[test]
+    fn deliveries_user() {
+        base::webhooks::deliveries(Roles::User, false);
+    }
+    #[test]
+    fn deliveries_org_owner() {
+        base::webhooks::deliveries(Roles::OrgOwner, true);
+    }
+}
+
+#[cfg(test)]
+mod redeliver_tests {
+    use super::*;
+    #[test]
+    fn redeliver_org_member() {
+        base::webhooks::redeliver(Roles::OrgMember, false);
+    }
+    #[test]
+    fn redeliver_admin() {
+        base::webhooks::redeliver(Roles::Admin, true);
+    }
+    #[test]
+    fn redeliver_user() {
+        base::webhooks::redeliver(Roles::User, false);
+    }
+    #[test]
+    fn redeliver_org_owner() {
+        base::webhooks::redeliver(Roles::OrgOwner, true);
+    }
+}
+
+#[test]
+fn create_with_invalid_event_types() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateWebhookRequest {
+        url: "https://example.com/webhooks".to_string(),
+        event_types: vec!["order.created".to_string()],
+    });
+    let response: HttpResponse =
+        webhooks::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn redeliver_for_other_organization() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let subscription = base::webhooks::create_subscription(&other_organization, &database);
+    let delivery = base::webhooks::create_delivery(&subscription, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = delivery.id;
+    let response: HttpResponse =
+        webhooks::redeliver((database.connection.clone().into(), path, auth_user)).into();
+
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn create_with_private_address() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    for url in &[
+        "https://127.0.0.1/webhooks",
+        "https://10.0.0.1/webhooks",
+        "https://169.254.169.254/latest/meta-data",
+        "https://[::1]/webhooks",
+        "https://[::ffff:192.168.0.1]/webhooks",
+        "https://localhost/webhooks",
+    ] {
+        let test_request = TestRequest::create();
+        let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+        path.id = organization.id;
+        let json = Json(CreateWebhookRequest {
+            url: url.to_string(),
+            event_types: vec!["order.paid".to_string()],
+        });
+        let response: HttpResponse = webhooks::create((
+            database.connection.clone().into(),
+            path,
+            json,
+            auth_user.clone(),
+        )).into();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY, "{}", url);
+    }
+    assert!(
+        WebhookSubscription::find_for_organization(organization.id, &*database.connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn process_deliveries_with_private_address() {
+    let database = TestDatabase::new();
+    let config = Config::new(Environment::Test);
+    let organization = database.create_organization().finish();
+    // Stands in for a host that resolved to a public address when the webhook was created
+    let subscription = base::webhooks::create_subscription(&organization, &database)
+        .update(
+            WebhookSubscriptionEditableAttributes {
+                url: Some("https://127.0.0.1:9/webhooks".to_string()),
+                ..Default::default()
+            },
+            &*database.connection,
+        ).unwrap();
+    let delivery = base::webhooks::create_delivery(&subscription, &database);
+
+    assert!(process_webhook_deliveries(&config, &*database.connection).unwrap() >= 1);
+
+    // The refused attempt is logged and retried later
+    let delivery = WebhookDelivery::find(delivery.id, &*database.connection).unwrap();
+    assert_eq!(delivery.status(), WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(
+        delivery.error,
+        Some("Webhook URL resolves to non public address 127.0.0.1".to_string())
+    );
+    assert!(delivery.next_attempt_at.is_some());
+}
+
+#[test]
+fn delivery_request_target() {
+    assert_eq!(
+        request_target("https://10.0.0.1/webhooks").unwrap_err(),
+        "Webhook URL resolves to non public address 10.0.0.1"
+    );
+    assert!(request_target("not a url").is_err());
+
+    // Addresses need no resolving so the URL is used as is
+    let (url, host) = request_target("http://93.184.216.34:8080/webhooks").unwrap();
+    assert_eq!(url.as_str(), "http://93.184.216.34:8080/webhooks");
+    assert!(host.is_none());
+}
+
+#[test]
+fn payload_signature() {
+    assert_eq!(
+        signature("key", "The quick brown fox jumps over the lazy dog"),
+        "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+}
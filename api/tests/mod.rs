@@ -20,6 +20,9 @@ extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
 extern crate jwt;
+extern crate ring;
+extern crate rustc_serialize;
+extern crate untrusted;
 extern crate uuid;
 extern crate validator;
 
@@ -0,0 +1,178 @@
+use bigneon_api::auth::oidc::{DiscoveryDocument, JsonWebKeySet};
+use bigneon_api::config::Config;
+use chrono::Utc;
+use ring::{rand, signature};
+use rustc_serialize::base64::{ToBase64, STANDARD, URL_SAFE};
+use serde_json::{self, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use untrusted;
+
+pub const CLIENT_ID: &str = "bigneon-test";
+pub const CLIENT_SECRET: &str = "test-secret";
+pub const AUTHORIZATION_CODE: &str = "valid-code";
+pub const NONCE: &str = "test-nonce";
+const KEY_ID: &str = "test-key";
+// Public half of oidc_test_key.pk8
+const MODULUS: &str = "vcjo6zXbSoWHOVGajm0qo-PyK_UlWlkceF9clDvDgs1ZQhtfLFWu_EvdkZX7Fx_vLVrpRt1SPN9dZ8jGS9RH3UOQ-XqPZPxxbBpTM1M4Wehwj_xtR_HxfpIWdpHivhKspFqmhrym0ouvZvv3RNpBziCn3H_t4CVa9DM8wXrCAZBJf46PNhLcvFOuLtMZ8CD-S85pwDe8HebF7K1eBIXEvPBg8v7e0Uk586k9yitz_sJJO1G6FBMiTFJ-AyagwtIBVCiCNz7XrcRWdFUgNNYJZ_x_4VR9DXagpegw_Isyq0cBbVqaB6hC9lVbMgzGGqkR1MfATCsJpKezOjEHeuquoQ";
+const EXPONENT: &str = "AQAB";
+
+/// Local OpenID Connect identity provider serving discovery, token and JWKS endpoints.
+/// The token endpoint returns an ID token with whatever claims were last set.
+pub struct MockIdp {
+    pub issuer: String,
+    claims: Arc<Mutex<Value>>,
+}
+
+impl MockIdp {
+    pub fn start() -> MockIdp {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let claims = Arc::new(Mutex::new(json!({})));
+
+        let server_issuer = issuer.clone();
+        let server_claims = claims.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Ok(stream) = stream {
+                    handle_request(stream, &server_issuer, &server_claims);
+                }
+            }
+        });
+
+        MockIdp { issuer, claims }
+    }
+
+    pub fn configure(&self, config: &mut Config) {
+        config.oidc_discovery_url =
+            Some(format!("{}/.well-known/openid-configuration", self.issuer));
+        config.oidc_client_id = Some(CLIENT_ID.to_string());
+        config.oidc_client_secret = Some(CLIENT_SECRET.to_string());
+    }
+
+    /// Valid claims for the subject, tests adjust these before calling `set_claims`
+    pub fn claims(&self, sub: &str, email: &str) -> Value {
+        let now = Utc::now().timestamp();
+        json!({
+            "iss": self.issuer,
+            "sub": sub,
+            "aud": CLIENT_ID,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": NONCE,
+            "email": email,
+            "email_verified": true,
+            "given_name": "Ada",
+            "family_name": "Lovelace",
+        })
+    }
+
+    pub fn set_claims(&self, claims: Value) {
+        *self.claims.lock().unwrap() = claims;
+    }
+
+    pub fn discovery(&self) -> DiscoveryDocument {
+        serde_json::from_value(discovery(&self.issuer)).unwrap()
+    }
+
+    pub fn keys(&self) -> JsonWebKeySet {
+        serde_json::from_value(jwks()).unwrap()
+    }
+}
+
+pub fn sign_id_token(claims: &Value) -> String {
+    let header = json!({"alg": "RS256", "kid": KEY_ID, "typ": "JWT"});
+    let message = format!(
+        "{}.{}",
+        header.to_string().as_bytes().to_base64(URL_SAFE),
+        claims.to_string().as_bytes().to_base64(URL_SAFE)
+    );
+
+    let key_pair = signature::RSAKeyPair::from_pkcs8(untrusted::Input::from(include_bytes!(
+        "oidc_test_key.pk8"
+    ))).unwrap();
+    let mut signing_state = signature::RSASigningState::new(Arc::new(key_pair)).unwrap();
+    let mut signature = vec![0; signing_state.key_pair().public_modulus_len()];
+    signing_state
+        .sign(
+            &signature::RSA_PKCS1_SHA256,
+            &rand::SystemRandom::new(),
+            message.as_bytes(),
+            &mut signature,
+        ).unwrap();
+
+    format!("{}.{}", message, signature.to_base64(URL_SAFE))
+}
+
+fn discovery(issuer: &str) -> Value {
+    json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+    })
+}
+
+fn jwks() -> Value {
+    json!({
+        "keys": [{"kty": "RSA", "kid": KEY_ID, "alg": "RS256", "use": "sig", "n": MODULUS, "e": EXPONENT}]
+    })
+}
+
+fn handle_request(mut stream: TcpStream, issuer: &str, claims: &Mutex<Value>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut content_length = 0;
+    let mut authorization = String::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_right().to_string();
+        if line.is_empty() {
+            break;
+        }
+        let lower = line.to_lowercase();
+        if lower.starts_with("content-length:") {
+            content_length = line[15..].trim().parse().unwrap();
+        } else if lower.starts_with("authorization:") {
+            authorization = line[14..].trim().to_string();
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    let body = String::from_utf8(body).unwrap();
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (status, response) = match path {
+        "/.well-known/openid-configuration" => ("200 OK", discovery(issuer)),
+        "/jwks" => ("200 OK", jwks()),
+        "/token" => {
+            let credentials = format!("{}:{}", CLIENT_ID, CLIENT_SECRET);
+            let expected_authorization =
+                format!("Basic {}", credentials.as_bytes().to_base64(STANDARD));
+            let code = format!("code={}", AUTHORIZATION_CODE);
+            if authorization == expected_authorization && body.split('&').any(|p| p == code) {
+                let id_token = sign_id_token(&claims.lock().unwrap());
+                (
+                    "200 OK",
+                    json!({"access_token": "idp-access-token", "token_type": "Bearer", "id_token": id_token}),
+                )
+            } else {
+                ("400 Bad Request", json!({"error": "invalid_grant"}))
+            }
+        }
+        _ => ("404 Not Found", json!({})),
+    };
+
+    let response = response.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response.len(),
+        response
+    ).unwrap();
+}
@@ -1,8 +1,13 @@
 pub mod database;
+pub mod mock_idp;
 pub mod test_request;
 
-use actix_web::{http::StatusCode, Body::Binary, HttpResponse};
+use actix_web::middleware::{Middleware, Response};
+use actix_web::{http::StatusCode, Body::Binary, HttpRequest, HttpResponse};
 use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::db::Connection;
+use bigneon_api::middleware::DatabaseTransaction;
+use bigneon_api::server::AppState;
 use bigneon_db::models::{Organization, Roles, User};
 use std::str;
 use support::database::TestDatabase;
@@ -14,6 +19,27 @@ pub fn unwrap_body_to_string(response: &HttpResponse) -> Result<&str, &'static s
     }
 }
 
+/// Runs an action inside a request transaction that the `DatabaseTransaction` middleware
+/// commits or rolls back, as it would be when served
+pub fn run_with_database_transaction<F>(
+    request: &HttpRequest<AppState>,
+    database: &TestDatabase,
+    action: F,
+) -> HttpResponse
+where
+    F: FnOnce(Connection) -> HttpResponse,
+{
+    let connection: Connection = database.connection.clone().into();
+    connection.begin_transaction().unwrap();
+    request.extensions_mut().insert(connection.clone());
+
+    let response = action(connection);
+    match DatabaseTransaction::new().response(request, response).unwrap() {
+        Response::Done(response) => response,
+        _ => panic!("Unexpected middleware response"),
+    }
+}
+
 pub fn create_auth_user(
     role: Roles,
     organization: Option<&Organization>,
@@ -1,7 +1,8 @@
-use actix_web::{test, FromRequest, HttpRequest, State};
+use actix_web::{http::Method, test, FromRequest, HttpRequest, State};
 use bigneon_api::config::{Config, Environment};
 use bigneon_api::mail::transports::TestTransport;
 use bigneon_api::server::AppState;
+use bigneon_api::sms::transports::TestSmsTransport;
 
 pub struct TestRequest {
     pub request: HttpRequest<AppState>,
@@ -17,18 +18,51 @@ impl TestRequest {
             .unwrap()
     }
 
+    pub fn test_sms_transport(&self) -> &TestSmsTransport {
+        self.config
+            .sms_transport
+            .as_ref()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TestSmsTransport>()
+            .unwrap()
+    }
+
     pub fn create() -> TestRequest {
         TestRequest::create_with_uri("/")
     }
 
     pub fn create_with_uri(path: &str) -> TestRequest {
+        TestRequest::create_with_uri_and_config(path, TestRequest::config())
+    }
+
+    /// Test configuration that can be adjusted before creating a request with it
+    pub fn config() -> Config {
         let mut config = Config::new(Environment::Test);
         config.token_secret = "test_secret".into();
         config.token_issuer = "bn-api-test".into();
 
         config.mail_from_email = "support@bigneon.com".to_string();
         config.mail_from_name = "Big Neon".to_string();
+        config
+    }
+
+    pub fn create_with_config(config: Config) -> TestRequest {
+        TestRequest::create_with_uri_and_config("/", config)
+    }
+
+    /// Request sent with the given Authorization header so the user extractor can be used
+    pub fn create_with_authorization(method: Method, authorization: &str) -> TestRequest {
+        let config = TestRequest::config();
+        let request = test::TestRequest::with_state(AppState::new(config.clone()))
+            .method(method)
+            .header("Authorization", authorization)
+            .param("id", "0f85443e-9e70-45ba-bf28-0f59c183856f")
+            .finish();
+        TestRequest { request, config }
+    }
 
+    pub fn create_with_uri_and_config(path: &str, config: Config) -> TestRequest {
         let test_request = test::TestRequest::with_state(AppState::new(config.clone()));
 
         // TODO: actix-web test requests do not allow router customization except
@@ -0,0 +1,122 @@
+use bigneon_api::auth::oidc::OidcProvider;
+use serde_json::Value;
+use support::mock_idp::{self, MockIdp};
+
+fn validate(idp: &MockIdp, id_token: &str, nonce: &str) -> Result<String, String> {
+    let provider = OidcProvider::new(
+        format!("{}/.well-known/openid-configuration", idp.issuer),
+        mock_idp::CLIENT_ID.to_string(),
+        mock_idp::CLIENT_SECRET.to_string(),
+    );
+    provider
+        .validate_id_token(id_token, &idp.discovery(), &idp.keys(), nonce)
+        .map(|claims| claims.sub)
+        .map_err(|e| e.to_string())
+}
+
+fn validate_claims(idp: &MockIdp, claims: &Value) -> Result<String, String> {
+    validate(idp, &mock_idp::sign_id_token(claims), mock_idp::NONCE)
+}
+
+#[test]
+fn validate_id_token() {
+    let idp = MockIdp::start();
+    let claims = idp.claims("subject-1", "ada@example.com");
+    assert_eq!(validate_claims(&idp, &claims), Ok("subject-1".to_string()));
+}
+
+#[test]
+fn validate_id_token_signature() {
+    let idp = MockIdp::start();
+    let id_token = mock_idp::sign_id_token(&idp.claims("subject-1", "ada@example.com"));
+    let other_token = mock_idp::sign_id_token(&idp.claims("subject-2", "ada@example.com"));
+
+    // Claims from one token with the signature of another
+    let parts: Vec<&str> = id_token.split('.').collect();
+    let other_parts: Vec<&str> = other_token.split('.').collect();
+    let tampered = format!("{}.{}.{}", parts[0], other_parts[1], parts[2]);
+    assert_eq!(
+        validate(&idp, &tampered, mock_idp::NONCE),
+        Err("Invalid ID token: Invalid signature".to_string())
+    );
+
+    // Unsigned tokens are rejected
+    let unsigned = format!("eyJhbGciOiJub25lIn0.{}.", parts[1]);
+    assert_eq!(
+        validate(&idp, &unsigned, mock_idp::NONCE),
+        Err("Invalid ID token: Unsupported signing algorithm".to_string())
+    );
+    assert_eq!(
+        validate(&idp, "not-a-token", mock_idp::NONCE),
+        Err("Invalid ID token: Malformed token".to_string())
+    );
+}
+
+#[test]
+fn validate_id_token_claims() {
+    let idp = MockIdp::start();
+    let valid = idp.claims("subject-1", "ada@example.com");
+
+    let mut claims = valid.clone();
+    claims["iss"] = json!("https://evil.example.com");
+    assert_eq!(
+        validate_claims(&idp, &claims),
+        Err("Invalid ID token: Invalid issuer".to_string())
+    );
+
+    let mut claims = valid.clone();
+    claims["aud"] = json!("another-client");
+    assert_eq!(
+        validate_claims(&idp, &claims),
+        Err("Invalid ID token: Invalid audience".to_string())
+    );
+
+    // Tokens issued for several audiences must name us as the authorized party
+    let mut claims = valid.clone();
+    claims["aud"] = json!([mock_idp::CLIENT_ID, "another-client"]);
+    assert_eq!(
+        validate_claims(&idp, &claims),
+        Err("Invalid ID token: Invalid authorized party".to_string())
+    );
+    claims["azp"] = json!(mock_idp::CLIENT_ID);
+    assert_eq!(validate_claims(&idp, &claims), Ok("subject-1".to_string()));
+
+    let mut claims = valid.clone();
+    claims["exp"] = json!(valid["iat"].as_i64().unwrap() - 3600);
+    assert_eq!(
+        validate_claims(&idp, &claims),
+        Err("Invalid ID token: Token has expired".to_string())
+    );
+
+    let mut claims = valid.clone();
+    claims["iat"] = json!(valid["iat"].as_i64().unwrap() + 3600);
+    assert_eq!(
+        validate_claims(&idp, &claims),
+        Err("Invalid ID token: Token was issued in the future".to_string())
+    );
+}
+
+#[test]
+fn validate_id_token_nonce() {
+    let idp = MockIdp::start();
+    let mut claims = idp.claims("subject-1", "ada@example.com");
+    claims["nonce"] = json!("nonce-1");
+    let id_token = mock_idp::sign_id_token(&claims);
+
+    assert_eq!(
+        validate(&idp, &id_token, "nonce-1"),
+        Ok("subject-1".to_string())
+    );
+    assert_eq!(
+        validate(&idp, &id_token, "nonce-2"),
+        Err("Invalid ID token: Invalid nonce".to_string())
+    );
+
+    // Tokens without a nonce are rejected
+    claims.as_object_mut().unwrap().remove("nonce");
+    let id_token = mock_idp::sign_id_token(&claims);
+    assert_eq!(
+        validate(&idp, &id_token, "nonce-1"),
+        Err("Invalid ID token: Invalid nonce".to_string())
+    );
+}
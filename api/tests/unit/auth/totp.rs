@@ -0,0 +1,43 @@
+use bigneon_api::auth::totp;
+
+// RFC 6238 test vectors for SHA1 truncated to six digits
+const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+#[test]
+fn code_for_step() {
+    assert_eq!(totp::code_for_step(SECRET, 59 / 30).unwrap(), "287082");
+    assert_eq!(
+        totp::code_for_step(SECRET, 1111111109 / 30).unwrap(),
+        "081804"
+    );
+    assert_eq!(
+        totp::code_for_step(SECRET, 1234567890 / 30).unwrap(),
+        "005924"
+    );
+    assert_eq!(
+        totp::code_for_step(SECRET, 2000000000 / 30).unwrap(),
+        "279037"
+    );
+    assert!(totp::code_for_step("not base32!", 1).is_none());
+}
+
+#[test]
+fn verify() {
+    let step = 1234567890 / 30;
+    assert_eq!(totp::verify(SECRET, "005924", step), Some(step));
+    assert_eq!(totp::verify(SECRET, "005 924", step), Some(step));
+
+    // Codes from adjacent steps are accepted to allow for clock drift
+    assert_eq!(totp::verify(SECRET, "005924", step + 1), Some(step));
+    assert_eq!(totp::verify(SECRET, "005924", step - 1), Some(step));
+    assert_eq!(totp::verify(SECRET, "005924", step + 2), None);
+    assert_eq!(totp::verify(SECRET, "05924", step), None);
+}
+
+#[test]
+fn provisioning_uri() {
+    assert_eq!(
+        totp::provisioning_uri(SECRET, "jane@example.com", "Big Neon"),
+        "otpauth://totp/Big%20Neon:jane%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Big%20Neon&algorithm=SHA1&digits=6&period=30"
+    );
+}
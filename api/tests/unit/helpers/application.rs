@@ -15,3 +15,28 @@ fn unauthorized() {
     let body = support::unwrap_body_to_string(&response).unwrap();
     assert_eq!(body, expected_json);
 }
+
+#[test]
+fn content_disposition() {
+    assert_eq!(
+        application::content_disposition("sales.csv"),
+        "attachment; filename=\"sales.csv\"; filename*=UTF-8''sales.csv"
+    );
+    // Quotes and line breaks from event names cannot break out of the header
+    assert_eq!(
+        application::content_disposition("The \"Big\"\r\nShow é.csv"),
+        "attachment; filename=\"The _Big_Show _.csv\"; \
+         filename*=UTF-8''The%20%22Big%22Show%20%C3%A9.csv"
+    );
+}
+
+#[test]
+fn attachment() {
+    let response: HttpResponse =
+        application::attachment("text/csv", "Show\n.csv", b"a,b".to_vec()).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"Show.csv\"; filename*=UTF-8''Show.csv"
+    );
+}
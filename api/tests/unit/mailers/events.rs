@@ -0,0 +1,74 @@
+use bigneon_api::config::{Config, Environment};
+use bigneon_api::mail::mailers;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use support::database::TestDatabase;
+
+#[test]
+fn event_reminder_email() {
+    let mut config = Config::new(Environment::Test);
+    config.mail_from_email = "support@bigneon.com".to_string();
+    config.front_end_url = "https://example.com".to_string();
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let reminder = PendingEventReminder {
+        id: event.id,
+        event_id: event.id,
+        event_name: "The Band Live".to_string(),
+        event_start: NaiveDate::from_ymd(2019, 1, 1).and_hms(20, 0, 0),
+        venue_name: Some("The Hall".to_string()),
+        organization_id: organization.id,
+        user_id: user.id,
+        first_name: user.first_name.clone(),
+        last_name: user.last_name.clone(),
+        email: "recipient@example.com".to_string(),
+    };
+
+    let mailer = mailers::events::event_reminder_email(
+        &config,
+        &reminder,
+        &organization,
+        &database.connection,
+    ).unwrap();
+    assert_eq!(
+        mailer.to(),
+        ("recipient@example.com".to_string(), user.full_name())
+    );
+    assert_eq!(
+        mailer.from(),
+        ("support@bigneon.com".to_string(), organization.name.clone())
+    );
+    assert_eq!(mailer.subject(), "Reminder: The Band Live is coming up");
+    assert!(mailer.body().contains("The Band Live at The Hall starts at 2019-01-01 20:00"));
+    assert!(
+        mailer
+            .body()
+            .contains(&format!("https://example.com/events/{}", event.id))
+    );
+
+    // Organizations can replace the reminder with their own template
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::EventReminder,
+        "See you at {{venue_name}}".to_string(),
+        "{{event_name}} starts {{event_start}}".to_string(),
+    ).commit(&database.connection)
+    .unwrap();
+    let mailer = mailers::events::event_reminder_email(
+        &config,
+        &reminder,
+        &organization,
+        &database.connection,
+    ).unwrap();
+    assert_eq!(mailer.subject(), "See you at The Hall");
+    assert!(
+        mailer
+            .body()
+            .starts_with("The Band Live starts 2019-01-01 20:00")
+    );
+}
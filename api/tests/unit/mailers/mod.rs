@@ -1 +1,3 @@
+pub mod events;
+pub mod tickets;
 pub mod user;
@@ -0,0 +1,94 @@
+use bigneon_api::config::{Config, Environment};
+use bigneon_api::mail::mailers;
+use bigneon_db::models::*;
+use support::database::TestDatabase;
+
+#[test]
+fn send_tickets() {
+    let mut config = Config::new(Environment::Test);
+    config.mail_from_email = "support@bigneon.com".to_string();
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+
+    let mailer = mailers::tickets::send_tickets(
+        &config,
+        "recipient@example.com",
+        &user.id.to_string(),
+        2,
+        "transfer-key",
+        "signature",
+        &user,
+        None,
+        &database.connection,
+    ).unwrap();
+    assert_eq!(
+        mailer.from(),
+        ("support@bigneon.com".to_string(), user.full_name())
+    );
+    assert_eq!(mailer.reply_to(), None);
+    assert_eq!(mailer.html_body(), None);
+    assert_eq!(
+        mailer.subject(),
+        format!("{} has sent you some tickets", user.full_name())
+    );
+    assert!(mailer.body().contains("transfer_key=transfer-key"));
+}
+
+#[test]
+fn send_tickets_for_organization() {
+    let mut config = Config::new(Environment::Test);
+    config.mail_from_email = "support@bigneon.com".to_string();
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .finish()
+        .update(
+            OrganizationEditableAttributes {
+                name: Some("Joe's <Bar>".to_string()),
+                primary_color: Some("#FF0000".to_string()),
+                email_sender_name: Some("Joe's Bar Tickets".to_string()),
+                email_reply_to: Some("joe@example.com".to_string()),
+                ..Default::default()
+            },
+            &database.connection,
+        ).unwrap();
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        "{{organization_name}} tickets from {{sender_name}}".to_string(),
+        "Get your {{ticket_count}} tickets: {{receive_tickets_link}}".to_string(),
+    ).commit(&database.connection)
+    .unwrap();
+
+    let mailer = mailers::tickets::send_tickets(
+        &config,
+        "recipient@example.com",
+        &user.id.to_string(),
+        2,
+        "transfer-key",
+        "signature",
+        &user,
+        Some(&organization),
+        &database.connection,
+    ).unwrap();
+    assert_eq!(
+        mailer.from(),
+        (
+            "support@bigneon.com".to_string(),
+            "Joe's Bar Tickets".to_string()
+        )
+    );
+    assert_eq!(mailer.reply_to(), Some("joe@example.com".to_string()));
+    assert_eq!(
+        mailer.subject(),
+        format!("Joe's <Bar> tickets from {}", user.full_name())
+    );
+    assert!(mailer.body().starts_with("Get your 2 tickets: "));
+
+    let html_body = mailer.html_body().unwrap();
+    assert!(html_body.contains("border-top: 6px solid #FF0000"));
+    // Values are escaped in the branded layout
+    assert!(html_body.contains("Joe&#39;s &lt;Bar&gt;"));
+    assert!(!html_body.contains("<Bar>"));
+}
@@ -1,6 +1,8 @@
 use bigneon_api::config::{Config, Environment};
 use bigneon_api::mail::mailers;
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
 use bigneon_db::models::concerns::users::password_resetable::PasswordResetable;
+use chrono::NaiveDate;
 use support::database::TestDatabase;
 
 #[test]
@@ -43,3 +45,54 @@ fn password_reset_email() {
         user.password_reset_token.unwrap()
     )));
 }
+
+#[test]
+fn email_verification_email() {
+    let mut config = Config::new(Environment::Test);
+    config.mail_from_name = "Big Neon Support".to_string();
+    config.mail_from_email = "support@bigneon.com".to_string();
+    let database = TestDatabase::new();
+
+    let user = database.create_user().with_unverified_email().finish();
+    let user = user
+        .create_email_verification_token(&database.connection)
+        .unwrap();
+
+    let email_verification_email = mailers::user::email_verification_email(&config, &user);
+    assert_eq!(
+        email_verification_email.to(),
+        (user.email.clone().unwrap(), user.full_name())
+    );
+    assert_eq!(
+        email_verification_email.subject(),
+        "Big Neon: Please verify your email address".to_string()
+    );
+    assert!(email_verification_email.body().contains(&format!(
+        "{}/verify-email?token={}",
+        config.front_end_url,
+        user.email_verification_token.unwrap()
+    )));
+}
+
+#[test]
+fn account_locked_email() {
+    let config = Config::new(Environment::Test);
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+
+    let locked_until = NaiveDate::from_ymd(2018, 9, 10).and_hms(14, 30, 0);
+    let account_locked_email = mailers::user::account_locked_email(&config, &user, locked_until);
+    assert_eq!(
+        account_locked_email.to(),
+        (user.email.clone().unwrap(), user.full_name())
+    );
+    assert_eq!(
+        account_locked_email.subject(),
+        "Big Neon: Sign in temporarily locked".to_string()
+    );
+    assert!(
+        account_locked_email
+            .body()
+            .contains("locked until 2018-09-10 14:30 UTC")
+    );
+}
@@ -1,3 +1,5 @@
+pub mod auth;
 pub mod helpers;
 pub mod mailers;
 pub mod models;
+pub mod utils;
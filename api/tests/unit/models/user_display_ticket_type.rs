@@ -1,5 +1,7 @@
 use bigneon_api::models::{DisplayTicketPricing, UserDisplayTicketType};
-use bigneon_db::models::TicketTypeStatus;
+use bigneon_db::models::{TicketTypeEditableAttributes, TicketTypeStatus};
+use chrono::prelude::*;
+use chrono::Duration;
 use support::database::TestDatabase;
 
 #[test]
@@ -102,3 +104,42 @@ fn from_ticket_type() {
         TicketTypeStatus::NoActivePricing.to_string()
     );
 }
+
+#[test]
+fn from_ticket_type_in_presale() {
+    let database = TestDatabase::new();
+    let fee_schedule = database.create_fee_schedule().finish();
+    let organization = database
+        .create_organization()
+        .with_fee_schedule(&fee_schedule)
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event
+        .ticket_types(&database.connection)
+        .unwrap()
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                presale_start_date: Some(Utc::now().naive_utc() - Duration::days(1)),
+                ..Default::default()
+            },
+            &database.connection,
+        ).unwrap();
+
+    let display_ticket_type =
+        UserDisplayTicketType::from_ticket_type(&ticket_type, &fee_schedule, &database.connection)
+            .unwrap();
+    assert_eq!(
+        display_ticket_type.status,
+        TicketTypeStatus::Presale.to_string()
+    );
+    assert!(ticket_type.presale_start_date.is_some());
+    assert_eq!(
+        display_ticket_type.presale_start_date,
+        ticket_type.presale_start_date
+    );
+}
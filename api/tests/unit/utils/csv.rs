@@ -0,0 +1,25 @@
+use bigneon_api::utils::csv::Csv;
+
+#[test]
+fn add_row() {
+    let mut csv = Csv::new();
+    csv.add_row(vec!["Name", "Notes"]);
+    csv.add_row(vec!["Smith, Jane", "Said \"hi\"\nthen left"]);
+    csv.add_blank_row();
+
+    assert_eq!(
+        csv.into_string(),
+        "Name,Notes\r\n\"Smith, Jane\",\"Said \"\"hi\"\"\nthen left\"\r\n\r\n"
+    );
+}
+
+#[test]
+fn add_row_with_formulas() {
+    let mut csv = Csv::new();
+    csv.add_row(vec!["=1+1", "+1", "-1", "@SUM(A1)", "\tcell", "\rcell", "a=b"]);
+
+    assert_eq!(
+        csv.into_string(),
+        "'=1+1,'+1,'-1,'@SUM(A1),'\tcell,\"'\rcell\",a=b\r\n"
+    );
+}
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_calendar_tokens_organization_id;
+DROP INDEX IF EXISTS index_calendar_tokens_user_id;
+DROP INDEX IF EXISTS index_calendar_tokens_token;
+DROP TABLE IF EXISTS calendar_tokens;
@@ -0,0 +1,15 @@
+-- Define the calendar_tokens table
+CREATE TABLE calendar_tokens (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  token uuid NOT NULL DEFAULT gen_random_uuid(),
+  user_id uuid NULL REFERENCES users (id) ON DELETE CASCADE,
+  organization_id uuid NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now(),
+  CONSTRAINT calendar_tokens_single_owner CHECK ((user_id IS NULL) <> (organization_id IS NULL))
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_calendar_tokens_token ON calendar_tokens (token);
+CREATE UNIQUE INDEX index_calendar_tokens_user_id ON calendar_tokens (user_id);
+CREATE UNIQUE INDEX index_calendar_tokens_organization_id ON calendar_tokens (organization_id);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_interest_notification_opt_outs_user_id;
+DROP INDEX IF EXISTS index_interest_notification_opt_outs_organization_id_user_id;
+DROP TABLE IF EXISTS interest_notification_opt_outs;
+
+DROP INDEX IF EXISTS index_event_interest_notifications_sent_at;
+DROP INDEX IF EXISTS index_event_interest_notifications_user_id;
+DROP INDEX IF EXISTS index_event_interest_notifications_event_id_user_id_on_sale_at;
+DROP TABLE IF EXISTS event_interest_notifications;
@@ -0,0 +1,28 @@
+-- Define the event_interest_notifications table
+CREATE TABLE event_interest_notifications (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+  user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  on_sale_at TIMESTAMP NOT NULL,
+  sent_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_event_interest_notifications_event_id_user_id_on_sale_at ON event_interest_notifications (event_id, user_id, on_sale_at);
+CREATE INDEX index_event_interest_notifications_user_id ON event_interest_notifications (user_id);
+CREATE INDEX index_event_interest_notifications_sent_at ON event_interest_notifications (sent_at);
+
+-- Define the interest_notification_opt_outs table
+CREATE TABLE interest_notification_opt_outs (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_interest_notification_opt_outs_organization_id_user_id ON interest_notification_opt_outs (organization_id, user_id);
+CREATE INDEX index_interest_notification_opt_outs_user_id ON interest_notification_opt_outs (user_id);
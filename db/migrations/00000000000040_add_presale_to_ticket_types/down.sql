@@ -0,0 +1,4 @@
+ALTER TABLE ticket_types
+  DROP COLUMN presale_start_date,
+  DROP COLUMN presale_interest_cutoff,
+  DROP COLUMN presale_code;
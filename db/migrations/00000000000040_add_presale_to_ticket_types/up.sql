@@ -0,0 +1,4 @@
+ALTER TABLE ticket_types
+  ADD presale_start_date TIMESTAMP NULL,
+  ADD presale_interest_cutoff TIMESTAMP NULL,
+  ADD presale_code TEXT NULL;
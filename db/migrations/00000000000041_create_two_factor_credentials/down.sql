@@ -0,0 +1,8 @@
+ALTER TABLE organizations
+  DROP COLUMN require_two_factor;
+
+DROP INDEX IF EXISTS index_two_factor_recovery_codes_user_id;
+DROP TABLE IF EXISTS two_factor_recovery_codes;
+
+DROP INDEX IF EXISTS index_two_factor_credentials_user_id;
+DROP TABLE IF EXISTS two_factor_credentials;
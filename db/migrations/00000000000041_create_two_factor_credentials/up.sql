@@ -0,0 +1,29 @@
+-- Define the two_factor_credentials table
+CREATE TABLE two_factor_credentials (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  secret TEXT NOT NULL,
+  enabled_at TIMESTAMP NULL,
+  last_used_step BIGINT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_two_factor_credentials_user_id ON two_factor_credentials (user_id);
+
+-- Define the two_factor_recovery_codes table
+CREATE TABLE two_factor_recovery_codes (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  hashed_code TEXT NOT NULL,
+  used_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_two_factor_recovery_codes_user_id ON two_factor_recovery_codes (user_id);
+
+ALTER TABLE organizations
+  ADD require_two_factor BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_sessions_user_id;
+DROP TABLE IF EXISTS sessions;
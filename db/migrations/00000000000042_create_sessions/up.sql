@@ -0,0 +1,14 @@
+-- Define the sessions table
+CREATE TABLE sessions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  user_agent TEXT NULL,
+  ip_address TEXT NULL,
+  last_used_at TIMESTAMP NOT NULL DEFAULT now(),
+  revoked_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_sessions_user_id ON sessions (user_id);
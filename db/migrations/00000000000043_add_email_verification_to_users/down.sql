@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_users_email_verification_token;
+
+ALTER TABLE users
+  DROP COLUMN email_verified_at,
+  DROP COLUMN email_verification_token,
+  DROP COLUMN email_verification_sent_at;
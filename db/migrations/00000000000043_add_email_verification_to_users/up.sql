@@ -0,0 +1,10 @@
+ALTER TABLE users
+  ADD email_verified_at TIMESTAMP NULL,
+  ADD email_verification_token UUID NULL,
+  ADD email_verification_sent_at TIMESTAMP NULL;
+
+-- Accounts created before verification was introduced are treated as verified
+UPDATE users SET email_verified_at = created_at;
+
+CREATE UNIQUE INDEX index_users_email_verification_token
+  ON users (email_verification_token);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_login_throttles_user_id;
+DROP INDEX IF EXISTS index_login_throttles_ip_address;
+DROP TABLE IF EXISTS login_throttles;
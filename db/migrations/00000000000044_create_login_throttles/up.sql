@@ -0,0 +1,16 @@
+-- Define the login_throttles table
+CREATE TABLE login_throttles (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NULL REFERENCES users (id) ON DELETE CASCADE,
+  ip_address TEXT NULL,
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  last_failed_at TIMESTAMP NOT NULL DEFAULT now(),
+  locked_until TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now(),
+  CHECK ((user_id IS NULL) <> (ip_address IS NULL))
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_login_throttles_user_id ON login_throttles (user_id);
+CREATE UNIQUE INDEX index_login_throttles_ip_address ON login_throttles (ip_address);
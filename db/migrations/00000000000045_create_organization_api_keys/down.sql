@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_organization_api_key_requests_organization_api_key_id;
+DROP INDEX IF EXISTS index_organization_api_keys_prefix;
+DROP INDEX IF EXISTS index_organization_api_keys_organization_id;
+DROP TABLE IF EXISTS organization_api_key_requests;
+DROP TABLE IF EXISTS organization_api_keys;
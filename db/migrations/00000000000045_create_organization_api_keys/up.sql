@@ -0,0 +1,30 @@
+-- Define the organization_api_keys table
+CREATE TABLE organization_api_keys (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  service_user_id uuid NOT NULL REFERENCES users (id),
+  created_by uuid NOT NULL REFERENCES users (id),
+  name TEXT NOT NULL,
+  prefix TEXT NOT NULL,
+  hashed_secret TEXT NOT NULL,
+  scopes TEXT[] NOT NULL,
+  last_used_at TIMESTAMP NULL,
+  revoked_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Define the organization_api_key_requests table
+CREATE TABLE organization_api_key_requests (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_api_key_id uuid NOT NULL REFERENCES organization_api_keys (id) ON DELETE CASCADE,
+  method TEXT NOT NULL,
+  path TEXT NOT NULL,
+  ip_address TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_organization_api_keys_organization_id ON organization_api_keys (organization_id);
+CREATE UNIQUE INDEX index_organization_api_keys_prefix ON organization_api_keys (prefix);
+CREATE INDEX index_organization_api_key_requests_organization_api_key_id ON organization_api_key_requests (organization_api_key_id);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_user_erasure_requests_user_id_pending;
+DROP INDEX IF EXISTS index_user_erasure_requests_status;
+DROP INDEX IF EXISTS index_user_erasure_requests_user_id;
+DROP TABLE IF EXISTS user_erasure_requests;
@@ -0,0 +1,18 @@
+-- Define the user_erasure_requests table
+CREATE TABLE user_erasure_requests (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id uuid NOT NULL REFERENCES users (id),
+  status TEXT NOT NULL DEFAULT 'Pending',
+  reason TEXT NULL,
+  reviewed_by uuid NULL REFERENCES users (id),
+  review_notes TEXT NULL,
+  reviewed_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_user_erasure_requests_user_id ON user_erasure_requests (user_id);
+CREATE INDEX index_user_erasure_requests_status ON user_erasure_requests (status);
+-- Only one request per user can be waiting for review
+CREATE UNIQUE INDEX index_user_erasure_requests_user_id_pending ON user_erasure_requests (user_id) WHERE status = 'Pending';
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_organization_users_organization_role_id;
+ALTER TABLE organization_users
+  DROP COLUMN organization_role_id;
+DROP INDEX IF EXISTS index_organization_roles_organization_id_name;
+DROP TABLE IF EXISTS organization_roles;
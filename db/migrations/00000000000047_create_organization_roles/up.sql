@@ -0,0 +1,17 @@
+-- Define the organization_roles table
+CREATE TABLE organization_roles (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  scopes TEXT[] NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Members without a role keep the default organization member scopes
+ALTER TABLE organization_users
+  ADD organization_role_id uuid NULL REFERENCES organization_roles (id);
+
+-- Indices
+CREATE UNIQUE INDEX index_organization_roles_organization_id_name ON organization_roles (organization_id, name);
+CREATE INDEX index_organization_users_organization_role_id ON organization_users (organization_role_id);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_impersonation_actions_impersonation_id;
+DROP INDEX IF EXISTS index_impersonations_session_id;
+DROP INDEX IF EXISTS index_impersonations_user_id;
+DROP INDEX IF EXISTS index_impersonations_admin_user_id;
+DROP TABLE IF EXISTS impersonation_actions;
+DROP TABLE IF EXISTS impersonations;
@@ -0,0 +1,27 @@
+-- Define the impersonations table
+CREATE TABLE impersonations (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  admin_user_id uuid NOT NULL REFERENCES users (id),
+  user_id uuid NOT NULL REFERENCES users (id),
+  session_id uuid NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+  reason TEXT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  ended_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Define the impersonation_actions table
+CREATE TABLE impersonation_actions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  impersonation_id uuid NOT NULL REFERENCES impersonations (id) ON DELETE CASCADE,
+  method TEXT NOT NULL,
+  path TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_impersonations_admin_user_id ON impersonations (admin_user_id);
+CREATE INDEX index_impersonations_user_id ON impersonations (user_id);
+CREATE INDEX index_impersonations_session_id ON impersonations (session_id);
+CREATE INDEX index_impersonation_actions_impersonation_id ON impersonation_actions (impersonation_id);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_login_codes_user_id;
+DROP TABLE IF EXISTS login_codes;
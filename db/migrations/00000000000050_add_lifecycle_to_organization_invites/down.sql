@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_organization_invites_organization_role_id;
+ALTER TABLE organization_invites
+  DROP COLUMN organization_role_id,
+  DROP COLUMN revoked_at,
+  DROP COLUMN expires_at;
@@ -0,0 +1,13 @@
+-- Invites expire 7 days after they were last sent
+ALTER TABLE organization_invites
+  ADD expires_at TIMESTAMP NULL,
+  ADD revoked_at TIMESTAMP NULL,
+  ADD organization_role_id uuid NULL REFERENCES organization_roles (id) ON DELETE SET NULL;
+
+UPDATE organization_invites SET expires_at = created_at + INTERVAL '7 days';
+
+ALTER TABLE organization_invites
+  ALTER COLUMN expires_at SET NOT NULL;
+
+-- Indices
+CREATE INDEX index_organization_invites_organization_role_id ON organization_invites (organization_role_id);
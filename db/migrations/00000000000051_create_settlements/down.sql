@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_ledger_entries_settlement_id;
+DROP INDEX IF EXISTS index_ledger_entries_order_id;
+DROP INDEX IF EXISTS index_ledger_entries_event_id;
+DROP INDEX IF EXISTS index_ledger_entries_organization_id_created_at;
+DROP INDEX IF EXISTS index_settlements_event_id;
+DROP INDEX IF EXISTS index_settlements_organization_id;
+DROP TABLE IF EXISTS ledger_entries;
+DROP TABLE IF EXISTS settlements;
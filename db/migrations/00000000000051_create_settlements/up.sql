@@ -0,0 +1,56 @@
+-- Define the settlements table
+CREATE TABLE settlements (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id),
+  event_id uuid NULL REFERENCES events (id),
+  start_utc TIMESTAMP NULL,
+  end_utc TIMESTAMP NULL,
+  status TEXT NOT NULL,
+  created_by uuid NOT NULL REFERENCES users (id),
+  paid_out_by uuid NULL REFERENCES users (id),
+  paid_out_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Define the ledger_entries table, every money movement is recorded as entries that sum to zero
+CREATE TABLE ledger_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id),
+  event_id uuid NULL REFERENCES events (id),
+  order_id uuid NULL REFERENCES orders (id),
+  order_item_id uuid NULL REFERENCES order_items (id),
+  settlement_id uuid NULL REFERENCES settlements (id),
+  entry_type TEXT NOT NULL,
+  account TEXT NOT NULL,
+  amount_in_cents BIGINT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Record sales for orders paid before the ledger existed
+INSERT INTO ledger_entries (organization_id, event_id, order_id, order_item_id, entry_type, account, amount_in_cents, created_at)
+SELECT e.organization_id,
+       e.id,
+       oi.order_id,
+       oi.id,
+       CASE oi.item_type WHEN 'Tickets' THEN 'TicketSale' WHEN 'PerUnitFees' THEN 'PerUnitFee' ELSE 'EventFee' END,
+       a.account,
+       CASE a.account WHEN 'Customer' THEN -1 ELSE 1 END * oi.quantity * oi.unit_price_in_cents,
+       o.updated_at
+FROM order_items oi
+       INNER JOIN orders o ON oi.order_id = o.id
+       INNER JOIN events e ON oi.event_id = e.id
+       CROSS JOIN (VALUES ('Customer'), ('Organization'), ('Platform')) AS a(account)
+WHERE o.status = 'Paid'
+  AND oi.quantity * oi.unit_price_in_cents <> 0
+  AND (a.account = 'Customer'
+         OR (a.account = 'Organization' AND oi.item_type = 'Tickets')
+         OR (a.account = 'Platform' AND oi.item_type <> 'Tickets'));
+
+-- Indices
+CREATE INDEX index_settlements_organization_id ON settlements (organization_id);
+CREATE INDEX index_settlements_event_id ON settlements (event_id);
+CREATE INDEX index_ledger_entries_organization_id_created_at ON ledger_entries (organization_id, created_at);
+CREATE INDEX index_ledger_entries_event_id ON ledger_entries (event_id);
+CREATE INDEX index_ledger_entries_order_id ON ledger_entries (order_id);
+CREATE INDEX index_ledger_entries_settlement_id ON ledger_entries (settlement_id);
@@ -0,0 +1,12 @@
+DROP INDEX IF EXISTS index_ticket_types_fee_schedule_id;
+DROP INDEX IF EXISTS index_events_fee_schedule_id;
+ALTER TABLE ticket_types
+  DROP COLUMN fee_schedule_id;
+ALTER TABLE events
+  DROP COLUMN fee_schedule_id;
+ALTER TABLE fee_schedules
+  DROP COLUMN effective_at;
+ALTER TABLE fee_schedule_ranges
+  DROP COLUMN max_fee_in_cents,
+  DROP COLUMN min_fee_in_cents,
+  DROP COLUMN percentage_in_basis_points;
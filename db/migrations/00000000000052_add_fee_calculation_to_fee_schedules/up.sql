@@ -0,0 +1,26 @@
+-- Fees are a flat amount plus a percentage of the ticket price, limited by optional caps
+ALTER TABLE fee_schedule_ranges
+  ADD percentage_in_basis_points BIGINT NOT NULL DEFAULT 0,
+  ADD min_fee_in_cents BIGINT NULL,
+  ADD max_fee_in_cents BIGINT NULL;
+
+-- New versions of a fee schedule only apply to orders placed after they become effective
+ALTER TABLE fee_schedules
+  ADD effective_at TIMESTAMP NULL;
+
+UPDATE fee_schedules SET effective_at = created_at;
+
+ALTER TABLE fee_schedules
+  ALTER COLUMN effective_at SET NOT NULL,
+  ALTER COLUMN effective_at SET DEFAULT now();
+
+-- Events and ticket types can override the fee schedule of their organization
+ALTER TABLE events
+  ADD fee_schedule_id uuid NULL REFERENCES fee_schedules (id);
+
+ALTER TABLE ticket_types
+  ADD fee_schedule_id uuid NULL REFERENCES fee_schedules (id);
+
+-- Indices
+CREATE INDEX index_events_fee_schedule_id ON events (fee_schedule_id);
+CREATE INDEX index_ticket_types_fee_schedule_id ON ticket_types (fee_schedule_id);
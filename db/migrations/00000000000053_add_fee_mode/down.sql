@@ -0,0 +1,6 @@
+ALTER TABLE order_items
+  DROP COLUMN is_absorbed;
+ALTER TABLE events
+  DROP COLUMN fee_mode;
+ALTER TABLE organizations
+  DROP COLUMN fee_mode;
@@ -0,0 +1,10 @@
+-- Absorbed fees are included in the ticket price and paid out of the organization's share
+ALTER TABLE organizations
+  ADD fee_mode TEXT NOT NULL DEFAULT 'PassedToBuyer';
+
+-- Events without a fee mode use the fee mode of their organization
+ALTER TABLE events
+  ADD fee_mode TEXT NULL;
+
+ALTER TABLE order_items
+  ADD is_absorbed BOOLEAN NOT NULL DEFAULT false;
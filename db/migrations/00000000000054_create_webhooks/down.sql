@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS index_webhook_deliveries_status_next_attempt_at;
+DROP INDEX IF EXISTS index_webhook_deliveries_webhook_subscription_id_domain_event_id;
+DROP INDEX IF EXISTS index_webhook_subscriptions_organization_id;
+DROP INDEX IF EXISTS index_domain_events_organization_id_created_at;
+
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP TABLE IF EXISTS webhook_subscriptions;
+
+ALTER TABLE domain_events
+  DROP COLUMN organization_id;
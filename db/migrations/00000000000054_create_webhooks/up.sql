@@ -0,0 +1,39 @@
+-- Domain events are delivered to the webhooks of the organization they belong to
+ALTER TABLE domain_events
+  ADD organization_id uuid NULL REFERENCES organizations (id);
+
+-- Define the webhook_subscriptions table
+CREATE TABLE webhook_subscriptions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  created_by uuid NOT NULL REFERENCES users (id),
+  url TEXT NOT NULL,
+  secret TEXT NOT NULL,
+  event_types TEXT[] NOT NULL,
+  active BOOLEAN NOT NULL DEFAULT true,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Define the webhook_deliveries table, pending deliveries are retried until they succeed or run
+-- out of attempts
+CREATE TABLE webhook_deliveries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  webhook_subscription_id uuid NOT NULL REFERENCES webhook_subscriptions (id) ON DELETE CASCADE,
+  domain_event_id uuid NOT NULL REFERENCES domain_events (id),
+  status TEXT NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at TIMESTAMP NULL,
+  last_attempt_at TIMESTAMP NULL,
+  response_status INTEGER NULL,
+  response_body TEXT NULL,
+  error TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_domain_events_organization_id_created_at ON domain_events (organization_id, created_at);
+CREATE INDEX index_webhook_subscriptions_organization_id ON webhook_subscriptions (organization_id);
+CREATE INDEX index_webhook_deliveries_webhook_subscription_id_domain_event_id ON webhook_deliveries (webhook_subscription_id, domain_event_id);
+CREATE INDEX index_webhook_deliveries_status_next_attempt_at ON webhook_deliveries (status, next_attempt_at);
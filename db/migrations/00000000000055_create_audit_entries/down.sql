@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_audit_entries_table_name_target_id;
+DROP INDEX IF EXISTS index_audit_entries_user_id;
+DROP INDEX IF EXISTS index_audit_entries_organization_id_created_at;
+
+DROP TABLE IF EXISTS audit_entries;
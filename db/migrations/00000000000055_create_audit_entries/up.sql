@@ -0,0 +1,18 @@
+-- Define the audit_entries table, one row per administrative write made through the API
+CREATE TABLE audit_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NULL REFERENCES organizations (id),
+  user_id uuid NULL REFERENCES users (id),
+  request_id TEXT NULL,
+  action TEXT NOT NULL,
+  table_name TEXT NOT NULL,
+  target_id uuid NULL,
+  changes JSON NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE INDEX index_audit_entries_organization_id_created_at ON audit_entries (organization_id, created_at);
+CREATE INDEX index_audit_entries_user_id ON audit_entries (user_id);
+CREATE INDEX index_audit_entries_table_name_target_id ON audit_entries (table_name, target_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_organizations_parent_id;
+ALTER TABLE organizations
+  DROP COLUMN parent_id;
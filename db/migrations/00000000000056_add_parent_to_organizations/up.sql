@@ -0,0 +1,7 @@
+-- Child organizations inherit the members and scopes of their parent
+ALTER TABLE organizations
+  ADD parent_id uuid NULL REFERENCES organizations (id),
+  ADD CONSTRAINT organizations_parent_id_not_self CHECK (parent_id <> id);
+
+-- Indices
+CREATE INDEX index_organizations_parent_id ON organizations (parent_id);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_event_promoters_organization_id;
+DROP INDEX IF EXISTS index_event_promoters_event_id_organization_id;
+
+DROP TABLE IF EXISTS event_promoters;
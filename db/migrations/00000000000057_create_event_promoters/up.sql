@@ -0,0 +1,15 @@
+-- Organizations co-promoting an event alongside the organization that owns it
+CREATE TABLE event_promoters (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+  organization_id uuid NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  permission TEXT NOT NULL,
+  revenue_split_in_basis_points BIGINT NOT NULL DEFAULT 0
+    CHECK (revenue_split_in_basis_points BETWEEN 0 AND 10000),
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_event_promoters_event_id_organization_id ON event_promoters (event_id, organization_id);
+CREATE INDEX index_event_promoters_organization_id ON event_promoters (organization_id);
@@ -0,0 +1,6 @@
+ALTER TABLE organizations
+  DROP COLUMN logo_url,
+  DROP COLUMN primary_color,
+  DROP COLUMN secondary_color,
+  DROP COLUMN email_sender_name,
+  DROP COLUMN email_reply_to;
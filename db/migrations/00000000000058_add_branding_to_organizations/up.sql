@@ -0,0 +1,7 @@
+-- Branding used on emails sent to buyers on behalf of the organization
+ALTER TABLE organizations
+  ADD logo_url TEXT NULL,
+  ADD primary_color TEXT NULL,
+  ADD secondary_color TEXT NULL,
+  ADD email_sender_name TEXT NULL,
+  ADD email_reply_to TEXT NULL;
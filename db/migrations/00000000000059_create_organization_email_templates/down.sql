@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_organization_email_templates_organization_id_template_type;
+DROP TABLE IF EXISTS organization_email_templates;
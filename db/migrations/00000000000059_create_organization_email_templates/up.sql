@@ -0,0 +1,13 @@
+-- Organization specific replacements for the default buyer emails
+CREATE TABLE organization_email_templates (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id uuid NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+  template_type TEXT NOT NULL,
+  subject TEXT NOT NULL,
+  body TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_organization_email_templates_organization_id_template_type ON organization_email_templates (organization_id, template_type);
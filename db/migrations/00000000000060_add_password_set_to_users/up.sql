@@ -0,0 +1,10 @@
+-- False for users that signed up with an external login and have never chosen a password
+ALTER TABLE users
+  ADD password_set BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- Users created from an external login were inserted in the same transaction as the login
+UPDATE users
+SET password_set = FALSE
+FROM external_logins
+WHERE external_logins.user_id = users.id
+  AND external_logins.created_at = users.created_at;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_users_verified_phone;
+
+ALTER TABLE users
+  DROP COLUMN phone_verified_at;
@@ -0,0 +1,6 @@
+-- Phone numbers can only be used to sign in once verified, and only by a single user
+ALTER TABLE users
+  ADD phone_verified_at TIMESTAMP NULL;
+
+-- Indices
+CREATE UNIQUE INDEX index_users_verified_phone ON users (phone) WHERE phone_verified_at IS NOT NULL;
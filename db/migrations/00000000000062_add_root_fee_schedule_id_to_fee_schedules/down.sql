@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_fee_schedules_root_fee_schedule_id_version;
+
+CREATE UNIQUE INDEX index_fee_schedules_name ON fee_schedules(name, version);
+
+ALTER TABLE fee_schedules
+  DROP COLUMN root_fee_schedule_id;
@@ -0,0 +1,19 @@
+-- Versions of a fee schedule share the id of its first version instead of being matched by name
+ALTER TABLE fee_schedules
+  ADD root_fee_schedule_id uuid NULL REFERENCES fee_schedules (id);
+
+UPDATE fee_schedules
+SET root_fee_schedule_id = first_versions.id
+FROM fee_schedules first_versions
+WHERE first_versions.name = fee_schedules.name
+  AND first_versions.version = 0;
+
+UPDATE fee_schedules SET root_fee_schedule_id = id WHERE root_fee_schedule_id IS NULL;
+
+ALTER TABLE fee_schedules
+  ALTER COLUMN root_fee_schedule_id SET NOT NULL;
+
+DROP INDEX IF EXISTS index_fee_schedules_name;
+
+-- Indices
+CREATE UNIQUE INDEX index_fee_schedules_root_fee_schedule_id_version ON fee_schedules (root_fee_schedule_id, version);
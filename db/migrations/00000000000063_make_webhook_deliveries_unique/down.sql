@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_webhook_deliveries_webhook_subscription_id_domain_event_id;
+
+CREATE INDEX index_webhook_deliveries_webhook_subscription_id_domain_event_id ON webhook_deliveries (webhook_subscription_id, domain_event_id);
@@ -0,0 +1,11 @@
+-- A domain event is delivered to each webhook once, redelivering it reuses the delivery
+DELETE FROM webhook_deliveries
+USING webhook_deliveries newer
+WHERE newer.webhook_subscription_id = webhook_deliveries.webhook_subscription_id
+  AND newer.domain_event_id = webhook_deliveries.domain_event_id
+  AND (newer.created_at, newer.id) > (webhook_deliveries.created_at, webhook_deliveries.id);
+
+DROP INDEX IF EXISTS index_webhook_deliveries_webhook_subscription_id_domain_event_id;
+
+-- Indices
+CREATE UNIQUE INDEX index_webhook_deliveries_webhook_subscription_id_domain_event_id ON webhook_deliveries (webhook_subscription_id, domain_event_id);
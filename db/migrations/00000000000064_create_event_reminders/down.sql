@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_event_reminders_sent_at;
+DROP INDEX IF EXISTS index_event_reminders_user_id;
+DROP INDEX IF EXISTS index_event_reminders_event_id_user_id_event_start;
+
+DROP TABLE IF EXISTS event_reminders;
@@ -0,0 +1,16 @@
+-- Define the event_reminders table, ticket holders are reminded once for each start time of an
+-- event so they are reminded again if it is rescheduled
+CREATE TABLE event_reminders (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  event_id uuid NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+  user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  event_start TIMESTAMP NOT NULL,
+  sent_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_event_reminders_event_id_user_id_event_start ON event_reminders (event_id, user_id, event_start);
+CREATE INDEX index_event_reminders_user_id ON event_reminders (user_id);
+CREATE INDEX index_event_reminders_sent_at ON event_reminders (sent_at);
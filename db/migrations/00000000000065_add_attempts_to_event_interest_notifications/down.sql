@@ -0,0 +1,4 @@
+ALTER TABLE event_interest_notifications
+  DROP COLUMN last_error,
+  DROP COLUMN claimed_until,
+  DROP COLUMN attempts;
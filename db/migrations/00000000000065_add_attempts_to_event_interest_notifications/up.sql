@@ -0,0 +1,5 @@
+-- Failed sends are retried once their claim expires until they run out of attempts
+ALTER TABLE event_interest_notifications
+  ADD attempts INTEGER NOT NULL DEFAULT 0,
+  ADD claimed_until TIMESTAMP NULL,
+  ADD last_error TEXT NULL;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_oidc_login_requests_expires_at;
+DROP INDEX IF EXISTS index_oidc_login_requests_state;
+DROP TABLE IF EXISTS oidc_login_requests;
@@ -0,0 +1,15 @@
+-- State and nonce generated for each OpenID Connect login redirect, each can be used once
+CREATE TABLE oidc_login_requests (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  state TEXT NOT NULL,
+  nonce TEXT NOT NULL,
+  redirect_uri TEXT NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  used_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Indices
+CREATE UNIQUE INDEX index_oidc_login_requests_state ON oidc_login_requests (state);
+CREATE INDEX index_oidc_login_requests_expires_at ON oidc_login_requests (expires_at);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_login_codes_user_id_purpose;
+
+ALTER TABLE login_codes
+  DROP COLUMN phone,
+  DROP COLUMN purpose;
@@ -0,0 +1,7 @@
+-- Codes sent to verify a phone number can not be used to sign in and are tied to the number
+ALTER TABLE login_codes
+  ADD purpose TEXT NOT NULL DEFAULT 'Login',
+  ADD phone TEXT NULL;
+
+-- Indices
+CREATE INDEX index_login_codes_user_id_purpose ON login_codes (user_id, purpose);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_audit_entries_impersonated_by;
+
+ALTER TABLE audit_entries
+  DROP COLUMN impersonated_by;
@@ -0,0 +1,6 @@
+-- The admin who made the change while acting as user_id
+ALTER TABLE audit_entries
+  ADD impersonated_by uuid NULL REFERENCES users (id);
+
+-- Indices
+CREATE INDEX index_audit_entries_impersonated_by ON audit_entries (impersonated_by);
@@ -0,0 +1,4 @@
+ALTER TABLE event_reminders
+  DROP COLUMN last_error,
+  DROP COLUMN claimed_until,
+  DROP COLUMN attempts;
@@ -23,6 +23,7 @@ extern crate uuid;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde;
+#[macro_use]
 extern crate serde_json;
 #[macro_use]
 extern crate validator_derive;
@@ -0,0 +1,217 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use models::enums::*;
+use schema::audit_entries;
+use serde::Serialize;
+use serde_json::{self, Map, Value};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "audit_entries"]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub organization_id: Option<Uuid>,
+    /// The user who made the change, `None` for changes made by the system
+    pub user_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    pub action: String,
+    pub table_name: String,
+    pub target_id: Option<Uuid>,
+    /// Changed fields as `{"field": {"before": .., "after": ..}}`
+    pub changes: Option<Value>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// The admin who made the change while impersonating `user_id`
+    pub impersonated_by: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AuditEntryFilter {
+    pub user_id: Option<Uuid>,
+    pub table_name: Option<Tables>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+}
+
+impl AuditEntry {
+    pub fn create(
+        user_id: Option<Uuid>,
+        action: AuditActions,
+        table_name: Tables,
+        target_id: Option<Uuid>,
+    ) -> NewAuditEntry {
+        NewAuditEntry {
+            organization_id: None,
+            user_id,
+            request_id: None,
+            action: action.to_string(),
+            table_name: table_name.to_string(),
+            target_id,
+            changes: None,
+            impersonated_by: None,
+        }
+    }
+
+    pub fn find_for_target(
+        table_name: Tables,
+        target_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        audit_entries::table
+            .filter(audit_entries::table_name.eq(table_name.to_string()))
+            .filter(audit_entries::target_id.eq(target_id))
+            .order_by(audit_entries::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load audit entries")
+    }
+
+    /// Returns a page of the organization's audit log, newest first, along with the total
+    /// number of entries matching the filter
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        filter: &AuditEntryFilter,
+        limit: i64,
+        offset: i64,
+        conn: &PgConnection,
+    ) -> Result<(Vec<AuditEntry>, i64), DatabaseError> {
+        let total: i64 = AuditEntry::filtered(organization_id, filter)
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count audit entries")?;
+
+        let entries = AuditEntry::filtered(organization_id, filter)
+            .order_by(audit_entries::created_at.desc())
+            .then_order_by(audit_entries::id)
+            .limit(limit)
+            .offset(offset)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load audit entries")?;
+
+        Ok((entries, total))
+    }
+
+    fn filtered(
+        organization_id: Uuid,
+        filter: &AuditEntryFilter,
+    ) -> audit_entries::BoxedQuery<'static, Pg> {
+        let mut query = audit_entries::table
+            .filter(audit_entries::organization_id.eq(organization_id))
+            .into_boxed();
+
+        if let Some(user_id) = filter.user_id {
+            query = query.filter(audit_entries::user_id.eq(user_id));
+        }
+        if let Some(table_name) = filter.table_name {
+            query = query.filter(audit_entries::table_name.eq(table_name.to_string()));
+        }
+        if let Some(start_utc) = filter.start_utc {
+            query = query.filter(audit_entries::created_at.ge(start_utc));
+        }
+        if let Some(end_utc) = filter.end_utc {
+            query = query.filter(audit_entries::created_at.le(end_utc));
+        }
+
+        query
+    }
+
+    /// Fields whose serialized values differ between the two versions of a record. Records
+    /// that are being created or deleted have no `before` or `after` respectively.
+    pub fn diff<T: Serialize>(before: Option<&T>, after: Option<&T>) -> Value {
+        let before = AuditEntry::fields(before);
+        let after = AuditEntry::fields(after);
+
+        let mut changes = Map::new();
+        for key in before.keys().chain(after.keys()) {
+            let old_value = before.get(key).unwrap_or(&Value::Null);
+            let new_value = after.get(key).unwrap_or(&Value::Null);
+            if old_value != new_value && !changes.contains_key(key) {
+                changes.insert(
+                    key.clone(),
+                    json!({ "before": old_value, "after": new_value }),
+                );
+            }
+        }
+
+        Value::Object(changes)
+    }
+
+    fn fields<T: Serialize>(record: Option<&T>) -> Map<String, Value> {
+        match record.map(serde_json::to_value) {
+            Some(Ok(Value::Object(fields))) => fields,
+            _ => Map::new(),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "audit_entries"]
+pub struct NewAuditEntry {
+    pub organization_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    pub action: String,
+    pub table_name: String,
+    pub target_id: Option<Uuid>,
+    pub changes: Option<Value>,
+    pub impersonated_by: Option<Uuid>,
+}
+
+impl NewAuditEntry {
+    /// Entries that belong to an organization are listed in its audit log
+    pub fn for_organization<T: Into<Option<Uuid>>>(mut self, organization_id: T) -> NewAuditEntry {
+        self.organization_id = organization_id.into();
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> NewAuditEntry {
+        self.request_id = request_id;
+        self
+    }
+
+    pub fn impersonated_by(mut self, admin_user_id: Option<Uuid>) -> NewAuditEntry {
+        self.impersonated_by = admin_user_id;
+        self
+    }
+
+    pub fn with_changes<T: Serialize>(
+        mut self,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> NewAuditEntry {
+        self.changes = Some(AuditEntry::diff(before, after));
+        self
+    }
+
+    /// Records a change to a value that is not a field of the record itself
+    pub fn with_change<T: Serialize>(
+        mut self,
+        field: &str,
+        before: &T,
+        after: &T,
+    ) -> NewAuditEntry {
+        let before = serde_json::to_value(before).unwrap_or(Value::Null);
+        let after = serde_json::to_value(after).unwrap_or(Value::Null);
+        if before != after {
+            let mut changes = match self.changes.take() {
+                Some(Value::Object(changes)) => changes,
+                _ => Map::new(),
+            };
+            changes.insert(
+                field.to_string(),
+                json!({ "before": before, "after": after }),
+            );
+            self.changes = Some(Value::Object(changes));
+        }
+        self
+    }
+
+    pub fn commit(self, conn: &PgConnection) -> Result<AuditEntry, DatabaseError> {
+        diesel::insert_into(audit_entries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not insert audit entry")
+    }
+}
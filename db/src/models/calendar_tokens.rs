@@ -0,0 +1,109 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{Organization, User};
+use schema::calendar_tokens;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[belongs_to(User)]
+#[belongs_to(Organization)]
+#[table_name = "calendar_tokens"]
+pub struct CalendarToken {
+    pub id: Uuid,
+    pub token: Uuid,
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "calendar_tokens"]
+pub struct NewCalendarToken {
+    pub token: Uuid,
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+}
+
+impl NewCalendarToken {
+    pub fn commit(&self, conn: &PgConnection) -> Result<CalendarToken, DatabaseError> {
+        diesel::insert_into(calendar_tokens::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create calendar token")
+    }
+}
+
+impl CalendarToken {
+    pub fn create_for_user(user_id: Uuid) -> NewCalendarToken {
+        NewCalendarToken {
+            token: Uuid::new_v4(),
+            user_id: Some(user_id),
+            organization_id: None,
+        }
+    }
+
+    pub fn create_for_organization(organization_id: Uuid) -> NewCalendarToken {
+        NewCalendarToken {
+            token: Uuid::new_v4(),
+            user_id: None,
+            organization_id: Some(organization_id),
+        }
+    }
+
+    pub fn find_by_token(token: Uuid, conn: &PgConnection) -> Result<CalendarToken, DatabaseError> {
+        calendar_tokens::table
+            .filter(calendar_tokens::token.eq(token))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find calendar token")
+    }
+
+    pub fn find_or_create_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<CalendarToken, DatabaseError> {
+        let calendar_token = calendar_tokens::table
+            .filter(calendar_tokens::user_id.eq(user_id))
+            .first::<CalendarToken>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load calendar token")
+            .optional()?;
+
+        match calendar_token {
+            Some(calendar_token) => Ok(calendar_token),
+            None => CalendarToken::create_for_user(user_id).commit(conn),
+        }
+    }
+
+    pub fn find_or_create_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<CalendarToken, DatabaseError> {
+        let calendar_token = calendar_tokens::table
+            .filter(calendar_tokens::organization_id.eq(organization_id))
+            .first::<CalendarToken>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load calendar token")
+            .optional()?;
+
+        match calendar_token {
+            Some(calendar_token) => Ok(calendar_token),
+            None => CalendarToken::create_for_organization(organization_id).commit(conn),
+        }
+    }
+
+    /// Replaces the token so that any previously shared feed URL stops working
+    pub fn regenerate(&self, conn: &PgConnection) -> Result<CalendarToken, DatabaseError> {
+        diesel::update(self)
+            .set((
+                calendar_tokens::token.eq(Uuid::new_v4()),
+                calendar_tokens::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not regenerate calendar token",
+            )
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::users;
+use std::borrow::Cow;
+use utils::errors::{DatabaseError, ErrorCode};
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+const EMAIL_VERIFICATION_EXPIRATION_PERIOD_IN_DAYS: i64 = 7;
+const EMAIL_VERIFICATION_RESEND_PERIOD_IN_MINUTES: i64 = 5;
+
+#[derive(AsChangeset)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "users"]
+pub struct EmailVerification {
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_token: Option<Uuid>,
+    pub email_verification_sent_at: Option<NaiveDateTime>,
+}
+
+pub trait EmailVerifiable {
+    fn is_email_verified(&self) -> bool;
+    fn has_valid_email_verification_token(&self) -> bool;
+    fn can_resend_email_verification(&self) -> bool;
+    fn find_by_email_verification_token(
+        email_verification_token: &Uuid,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError>;
+    fn create_email_verification_token(&self, conn: &PgConnection) -> Result<User, DatabaseError>;
+    fn mark_email_verified(&self, conn: &PgConnection) -> Result<User, DatabaseError>;
+    fn consume_email_verification_token(
+        token: &Uuid,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError>;
+}
+
+impl EmailVerifiable for User {
+    fn is_email_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    fn has_valid_email_verification_token(&self) -> bool {
+        match (
+            self.email_verification_token,
+            self.email_verification_sent_at,
+        ) {
+            (Some(_), Some(email_verification_sent_at)) => {
+                let now = Utc::now().naive_utc();
+                now.signed_duration_since(email_verification_sent_at)
+                    .num_days()
+                    < EMAIL_VERIFICATION_EXPIRATION_PERIOD_IN_DAYS
+            }
+            _ => false,
+        }
+    }
+
+    fn can_resend_email_verification(&self) -> bool {
+        match self.email_verification_sent_at {
+            Some(email_verification_sent_at) => {
+                let now = Utc::now().naive_utc();
+                now.signed_duration_since(email_verification_sent_at)
+                    .num_minutes()
+                    >= EMAIL_VERIFICATION_RESEND_PERIOD_IN_MINUTES
+            }
+            None => true,
+        }
+    }
+
+    fn find_by_email_verification_token(
+        email_verification_token: &Uuid,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading user",
+            users::table
+                .filter(users::email_verification_token.eq(email_verification_token))
+                .first::<User>(conn),
+        )
+    }
+
+    fn create_email_verification_token(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        let data = EmailVerification {
+            email_verified_at: None,
+            email_verification_token: Some(Uuid::new_v4()),
+            email_verification_sent_at: Some(Utc::now().naive_utc()),
+        };
+
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not create token for verifying email",
+            diesel::update(self)
+                .set((data, users::updated_at.eq(dsl::now)))
+                .get_result(conn),
+        )
+    }
+
+    fn mark_email_verified(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        let data = EmailVerification {
+            email_verified_at: Some(Utc::now().naive_utc()),
+            email_verification_token: None,
+            email_verification_sent_at: None,
+        };
+
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not verify email for user",
+            diesel::update(self)
+                .set((data, users::updated_at.eq(dsl::now)))
+                .get_result(conn),
+        )
+    }
+
+    fn consume_email_verification_token(
+        token: &Uuid,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError> {
+        let user = User::find_by_email_verification_token(token, conn)?;
+        if user.has_valid_email_verification_token() {
+            user.mark_email_verified(conn)
+        } else {
+            // A new verification email can be requested, so this is reported as invalid input
+            let mut validation_error = ValidationError::new(&"expired");
+            validation_error.message = Some(Cow::from(
+                "Email verification token is expired, please request a new verification email",
+            ));
+            let mut errors = ValidationErrors::new();
+            errors.add("email_verification_token", validation_error);
+            Err(errors.into())
+        }
+    }
+}
@@ -1 +1,2 @@
+pub mod email_verifiable;
 pub mod password_resetable;
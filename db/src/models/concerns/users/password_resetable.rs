@@ -53,6 +53,7 @@ impl PasswordResetable for User {
                         diesel::update(users.filter(id.eq(user.id)))
                             .set((
                                 hashed_pw.eq(&hash.to_string()),
+                                password_set.eq(true),
                                 password_modified_at.eq(now),
                                 updated_at.eq(dsl::now),
                                 PasswordReset {
@@ -17,6 +17,7 @@ pub struct DomainEvent {
     pub main_id: Option<Uuid>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub organization_id: Option<Uuid>,
 }
 
 impl DomainEvent {
@@ -41,6 +42,32 @@ impl DomainEvent {
             .to_db_error(ErrorCode::QueryError, "Could not load domain events")
     }
 
+    pub fn find_by_id(id: Uuid, conn: &PgConnection) -> Result<DomainEvent, DatabaseError> {
+        domain_events::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain event")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        event_type: Option<DomainEventTypes>,
+        conn: &PgConnection,
+    ) -> Result<Vec<DomainEvent>, DatabaseError> {
+        let mut query = domain_events::table
+            .filter(domain_events::organization_id.eq(organization_id))
+            .into_boxed();
+
+        if let Some(event_type) = event_type {
+            query = query.filter(domain_events::event_type.eq(event_type.to_string()));
+        }
+
+        query
+            .order_by(domain_events::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain events")
+    }
+
     pub fn create(
         event_type: DomainEventTypes,
         display_text: String,
@@ -54,6 +81,7 @@ impl DomainEvent {
             event_data,
             main_table: main_table.to_string(),
             main_id,
+            organization_id: None,
         }
     }
 }
@@ -66,9 +94,16 @@ pub struct NewDomainEvent {
     pub event_data: Option<serde_json::Value>,
     pub main_table: String,
     pub main_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
 }
 
 impl NewDomainEvent {
+    /// Domain events that belong to an organization are delivered to its webhooks
+    pub fn for_organization(mut self, organization_id: Uuid) -> NewDomainEvent {
+        self.organization_id = Some(organization_id);
+        self
+    }
+
     pub fn commit(self, conn: &PgConnection) -> Result<DomainEvent, DatabaseError> {
         diesel::insert_into(domain_events::table)
             .values(self)
@@ -38,18 +38,30 @@ macro_rules! string_enum {
 }
 
 string_enum! { AssetStatus [Unsynced] }
-string_enum! { DomainEventTypes [PaymentCreated, PaymentCompleted, PaymentMethodCreated, PaymentMethodUpdated]}
+string_enum! { AuditActions [Create, Update, Delete, Publish, Cancel] }
+string_enum! { DomainEventTypes [PaymentCreated, PaymentCompleted, PaymentMethodCreated, PaymentMethodUpdated, EventPublished, OrderPaid, OrderRefunded, TicketRedeemed, TicketTransferred]}
+string_enum! { EmailTemplateTypes [TicketConfirmation, TicketTransfer, EventReminder] }
+string_enum! { EventPromoterPermissions [View, Edit] }
 string_enum! { EventStatus [Draft,Closed,Published,Offline]}
+string_enum! { FeeMode [Absorbed, PassedToBuyer] }
+string_enum! { LedgerAccount [Customer, Organization, Platform] }
+string_enum! { LedgerEntryType [TicketSale, PerUnitFee, EventFee, Refund, Chargeback] }
+string_enum! { LoginCodeChannel [Email, Sms] }
+string_enum! { LoginCodePurpose [Login, PhoneVerification] }
 string_enum! { OrderStatus [Draft, PartiallyPaid, Paid, Cancelled] }
 string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees]}
 string_enum! { OrderTypes [Cart, BackOffice] }
+string_enum! { OrganizationInviteStatus [Pending, Accepted, Declined, Expired, Revoked] }
 string_enum! { PaymentMethods [External, CreditCard] }
 string_enum! { PaymentStatus [Authorized, Completed] }
 string_enum! { Roles [Admin, OrgMember, OrgOwner, User] }
-string_enum! { Tables [Payments, PaymentMethods] }
+string_enum! { SettlementStatus [Open, PaidOut] }
+string_enum! { Tables [Payments, PaymentMethods, Events, Orders, TicketInstances, Artists, EventArtists, FeeSchedules, Holds, OrganizationInvites, OrganizationRoles, OrganizationUsers, Organizations, TicketPricing, TicketTypes, Venues, EventPromoters, OrganizationEmailTemplates, OrganizationApiKeys, WebhookSubscriptions, WebhookDeliveries, Settlements, Users, Regions] }
 string_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 string_enum! { TicketPricingStatus [Published, Deleted] }
-string_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut] }
+string_enum! { TicketTypeStatus [NoActivePricing, Presale, Published, SoldOut] }
+string_enum! { UserErasureRequestStatus [Pending, Completed, Rejected] }
+string_enum! { WebhookDeliveryStatus [Pending, Delivered, Failed] }
 
 #[test]
 fn display() {
@@ -100,6 +100,17 @@ impl EventInterest {
         Ok(!result.is_empty())
     }
 
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventInterest>, DatabaseError> {
+        event_interest::table
+            .filter(event_interest::user_id.eq(user_id))
+            .order_by(event_interest::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event interest")
+    }
+
     pub fn list_interested_users(
         event_id: Uuid,
         user_id: Uuid,
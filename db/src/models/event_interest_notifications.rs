@@ -0,0 +1,167 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::{Event, User};
+use schema::event_interest_notifications;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Notifications that failed this many times are no longer sent
+pub const MAX_INTEREST_NOTIFICATION_ATTEMPTS: i32 = 5;
+/// How long claimed notifications are skipped by other workers, failed sends are retried after
+const CLAIM_SECONDS: i64 = 60 * 5;
+
+/// Record of an on sale notification for a user interested in an event. The combination of
+/// event, user and on sale time is unique so a user is never notified twice for the same on sale
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[belongs_to(User)]
+#[belongs_to(Event)]
+#[table_name = "event_interest_notifications"]
+pub struct EventInterestNotification {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub on_sale_at: NaiveDateTime,
+    pub sent_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub attempts: i32,
+    pub claimed_until: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, QueryableByName, Serialize)]
+pub struct PendingEventInterestNotification {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub event_start: Option<NaiveDateTime>,
+    #[sql_type = "dUuid"]
+    pub organization_id: Uuid,
+    #[sql_type = "Text"]
+    pub organization_name: String,
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Text"]
+    pub first_name: String,
+    #[sql_type = "Text"]
+    pub last_name: String,
+    #[sql_type = "Text"]
+    pub email: String,
+    #[sql_type = "Timestamp"]
+    pub on_sale_at: NaiveDateTime,
+}
+
+impl EventInterestNotification {
+    /// Queues notifications for users interested in a newly published event. Nothing is queued
+    /// if no pricing tier is open yet, those users are notified once a tier opens instead
+    pub fn queue_for_published_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        diesel::sql_query(include_str!(
+            "../queries/queue_event_interest_notifications_for_publish.sql"
+        )).bind::<dUuid, _>(event_id)
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::InsertError,
+            "Could not queue event interest notifications",
+        )
+    }
+
+    /// Queues notifications for pricing tiers that opened after their event was published
+    pub fn queue_for_opened_pricing(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::sql_query(include_str!(
+            "../queries/queue_event_interest_notifications_for_opened_pricing.sql"
+        )).execute(conn)
+        .to_db_error(
+            ErrorCode::InsertError,
+            "Could not queue event interest notifications",
+        )
+    }
+
+    pub fn find_pending(
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<PendingEventInterestNotification>, DatabaseError> {
+        diesel::sql_query(include_str!(
+            "../queries/find_pending_event_interest_notifications.sql"
+        )).bind::<BigInt, _>(limit)
+        .bind::<Integer, _>(MAX_INTEREST_NOTIFICATION_ATTEMPTS)
+        .load(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not load pending event interest notifications",
+        )
+    }
+
+    /// Claims the next pending notifications to send. Notifications claimed by another worker
+    /// are skipped so none are sent twice
+    pub fn claim_pending(
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<PendingEventInterestNotification>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        diesel::sql_query(include_str!(
+            "../queries/claim_event_interest_notifications.sql"
+        )).bind::<BigInt, _>(limit)
+        .bind::<Timestamp, _>(now)
+        .bind::<Timestamp, _>(now + Duration::seconds(CLAIM_SECONDS))
+        .bind::<Integer, _>(MAX_INTEREST_NOTIFICATION_ATTEMPTS)
+        .load(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not claim event interest notifications",
+        )
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventInterestNotification>, DatabaseError> {
+        event_interest_notifications::table
+            .filter(event_interest_notifications::user_id.eq(user_id))
+            .order_by(event_interest_notifications::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event interest notifications",
+            )
+    }
+
+    pub fn mark_sent(ids: &[Uuid], conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(
+            event_interest_notifications::table
+                .filter(event_interest_notifications::id.eq_any(ids))
+                .filter(event_interest_notifications::sent_at.is_null()),
+        ).set((
+            event_interest_notifications::sent_at.eq(dsl::now.nullable()),
+            event_interest_notifications::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not mark event interest notifications as sent",
+        )
+    }
+
+    /// Records a failed send, the notification is retried once its claim expires
+    pub fn mark_failed(id: Uuid, error: &str, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(event_interest_notifications::table.find(id))
+            .set((
+                event_interest_notifications::attempts
+                    .eq(event_interest_notifications::attempts + 1),
+                event_interest_notifications::last_error.eq(error),
+                event_interest_notifications::updated_at.eq(dsl::now),
+            )).execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not record failed event interest notification",
+            )
+    }
+}
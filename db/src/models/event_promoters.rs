@@ -0,0 +1,190 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::event_promoters;
+use std::borrow::Cow;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+const ALL_REVENUE_IN_BASIS_POINTS: i64 = 10000;
+
+/// An organization co-promoting an event owned by another organization. Members of the
+/// co-promoter are granted the scopes allowed by the permission within the event, and the
+/// co-promoter receives its share of the event's ticket revenue
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[belongs_to(Event)]
+#[belongs_to(Organization)]
+#[table_name = "event_promoters"]
+pub struct EventPromoter {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub organization_id: Uuid,
+    pub permission: String,
+    pub revenue_split_in_basis_points: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "event_promoters"]
+pub struct NewEventPromoter {
+    pub event_id: Uuid,
+    pub organization_id: Uuid,
+    pub permission: String,
+    pub revenue_split_in_basis_points: i64,
+}
+
+/// Share of an event's revenue belonging to an organization
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RevenueSplit {
+    pub organization_id: Uuid,
+    pub revenue_split_in_basis_points: i64,
+}
+
+impl NewEventPromoter {
+    pub fn commit(&self, conn: &PgConnection) -> Result<EventPromoter, DatabaseError> {
+        self.validate_record(conn)?;
+
+        diesel::insert_into(event_promoters::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add event promoter")
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut errors = ValidationErrors::new();
+        let event = Event::find(self.event_id, conn)?;
+        if event.organization_id == self.organization_id {
+            let mut validation_error = ValidationError::new(&"invalid_organization");
+            validation_error.message = Some(Cow::from("Organization already owns this event"));
+            errors.add("organization_id", validation_error);
+        }
+
+        let assigned: i64 = EventPromoter::find_for_event(self.event_id, conn)?
+            .iter()
+            .map(|p| p.revenue_split_in_basis_points)
+            .sum();
+        if self.revenue_split_in_basis_points < 0
+            || assigned + self.revenue_split_in_basis_points > ALL_REVENUE_IN_BASIS_POINTS
+        {
+            let mut validation_error = ValidationError::new(&"invalid_revenue_split");
+            validation_error.message = Some(Cow::from(
+                "Revenue splits of all promoters cannot be more than 100%",
+            ));
+            errors.add("revenue_split_in_basis_points", validation_error);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        Ok(())
+    }
+}
+
+impl EventPromoter {
+    pub fn create(
+        event_id: Uuid,
+        organization_id: Uuid,
+        permission: EventPromoterPermissions,
+        revenue_split_in_basis_points: i64,
+    ) -> NewEventPromoter {
+        NewEventPromoter {
+            event_id,
+            organization_id,
+            permission: permission.to_string(),
+            revenue_split_in_basis_points,
+        }
+    }
+
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventPromoter>, DatabaseError> {
+        event_promoters::table
+            .filter(event_promoters::event_id.eq(event_id))
+            .order_by(event_promoters::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event promoters")
+    }
+
+    pub fn find_by_event_and_organization(
+        event_id: Uuid,
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<EventPromoter, DatabaseError> {
+        event_promoters::table
+            .filter(event_promoters::event_id.eq(event_id))
+            .filter(event_promoters::organization_id.eq(organization_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event promoter")
+    }
+
+    pub fn permission(&self) -> EventPromoterPermissions {
+        self.permission.parse::<EventPromoterPermissions>().unwrap()
+    }
+
+    /// Co-promoters can always view the event, only those with `Edit` permission can change it
+    pub fn allows(&self, scope: Scopes) -> bool {
+        match scope {
+            Scopes::EventViewGuests | Scopes::OrgRead | Scopes::OrgReports => true,
+            Scopes::EventWrite | Scopes::HoldWrite | Scopes::TicketAdmin => {
+                self.permission() == EventPromoterPermissions::Edit
+            }
+            _ => false,
+        }
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event promoter")
+    }
+}
+
+impl RevenueSplit {
+    /// Splits for the owning organization, which is always listed first and receives whatever
+    /// is not assigned to co-promoters, followed by each of the co-promoters
+    pub fn for_event(
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<Vec<RevenueSplit>, DatabaseError> {
+        let promoters = EventPromoter::find_for_event(event.id, conn)?;
+        let assigned: i64 = promoters
+            .iter()
+            .map(|p| p.revenue_split_in_basis_points)
+            .sum();
+
+        let mut splits = vec![RevenueSplit {
+            organization_id: event.organization_id,
+            revenue_split_in_basis_points: ALL_REVENUE_IN_BASIS_POINTS - assigned,
+        }];
+        splits.extend(promoters.into_iter().map(|p| RevenueSplit {
+            organization_id: p.organization_id,
+            revenue_split_in_basis_points: p.revenue_split_in_basis_points,
+        }));
+        Ok(splits)
+    }
+
+    /// Divides an amount between the splits. Co-promoter shares are rounded towards zero so the
+    /// owning organization receives any remainder
+    pub fn divide(splits: &[RevenueSplit], amount_in_cents: i64) -> Vec<(Uuid, i64)> {
+        let mut shares: Vec<(Uuid, i64)> = splits
+            .iter()
+            .skip(1)
+            .map(|s| {
+                (
+                    s.organization_id,
+                    amount_in_cents * s.revenue_split_in_basis_points
+                        / ALL_REVENUE_IN_BASIS_POINTS,
+                )
+            }).collect();
+        let remainder = amount_in_cents - shares.iter().map(|s| s.1).sum::<i64>();
+        if let Some(owner) = splits.first() {
+            shares.insert(0, (owner.organization_id, remainder));
+        }
+        shares
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::{Event, User};
+use schema::event_reminders;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Ticket holders are reminded this many hours before their event starts
+pub const EVENT_REMINDER_HOURS: i64 = 24;
+/// Reminders that failed this many times are no longer sent
+pub const MAX_EVENT_REMINDER_ATTEMPTS: i32 = 5;
+/// How long claimed reminders are skipped by other workers, failed sends are retried after
+const CLAIM_SECONDS: i64 = 60 * 5;
+
+/// Record of a reminder emailed to a ticket holder before an event. The combination of event,
+/// user and start time is unique so a user is reminded once unless the event is rescheduled
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[belongs_to(User)]
+#[belongs_to(Event)]
+#[table_name = "event_reminders"]
+pub struct EventReminder {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub event_start: NaiveDateTime,
+    pub sent_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub attempts: i32,
+    pub claimed_until: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, QueryableByName, Serialize)]
+pub struct PendingEventReminder {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "Timestamp"]
+    pub event_start: NaiveDateTime,
+    #[sql_type = "Nullable<Text>"]
+    pub venue_name: Option<String>,
+    #[sql_type = "dUuid"]
+    pub organization_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Text"]
+    pub first_name: String,
+    #[sql_type = "Text"]
+    pub last_name: String,
+    #[sql_type = "Text"]
+    pub email: String,
+}
+
+impl EventReminder {
+    /// Queues reminders for everyone holding tickets to a published event that starts within
+    /// the reminder period
+    pub fn queue_due(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        diesel::sql_query(include_str!("../queries/queue_event_reminders.sql"))
+            .bind::<Timestamp, _>(now)
+            .bind::<Timestamp, _>(now + Duration::hours(EVENT_REMINDER_HOURS))
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not queue event reminders")
+    }
+
+    /// Reminders that have not been sent yet for events that have not started
+    pub fn find_pending(
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<PendingEventReminder>, DatabaseError> {
+        diesel::sql_query(include_str!("../queries/find_pending_event_reminders.sql"))
+            .bind::<BigInt, _>(limit)
+            .bind::<Timestamp, _>(Utc::now().naive_utc())
+            .bind::<Integer, _>(MAX_EVENT_REMINDER_ATTEMPTS)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load pending event reminders")
+    }
+
+    /// Claims the next pending reminders to send. Reminders claimed by another worker are
+    /// skipped so none are sent twice
+    pub fn claim_pending(
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<PendingEventReminder>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        diesel::sql_query(include_str!("../queries/claim_event_reminders.sql"))
+            .bind::<BigInt, _>(limit)
+            .bind::<Timestamp, _>(now)
+            .bind::<Timestamp, _>(now + Duration::seconds(CLAIM_SECONDS))
+            .bind::<Integer, _>(MAX_EVENT_REMINDER_ATTEMPTS)
+            .load(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not claim event reminders")
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventReminder>, DatabaseError> {
+        event_reminders::table
+            .filter(event_reminders::user_id.eq(user_id))
+            .order_by(event_reminders::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event reminders")
+    }
+
+    pub fn mark_sent(ids: &[Uuid], conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(
+            event_reminders::table
+                .filter(event_reminders::id.eq_any(ids))
+                .filter(event_reminders::sent_at.is_null()),
+        ).set((
+            event_reminders::sent_at.eq(dsl::now.nullable()),
+            event_reminders::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not mark event reminders as sent")
+    }
+
+    pub fn mark_failed(id: Uuid, error: &str, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(event_reminders::table.find(id))
+            .set((
+                event_reminders::attempts.eq(event_reminders::attempts + 1),
+                event_reminders::last_error.eq(error),
+                event_reminders::updated_at.eq(dsl::now),
+            )).execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not record failed event reminder")
+    }
+}
@@ -5,12 +5,16 @@ use diesel::expression::dsl;
 use diesel::prelude::*;
 use diesel::sql_types;
 use models::*;
-use schema::{artists, event_artists, events, organization_users, organizations, venues};
+use schema::{
+    artists, event_artists, event_promoters, events, organization_users, organizations, venues,
+};
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
 use utils::errors::*;
+use utils::serializers;
 use uuid::Uuid;
 use validator::{Validate, ValidationError, ValidationErrors};
+use validators;
 
 #[derive(Associations, Identifiable, Queryable, AsChangeset)]
 #[belongs_to(Organization)]
@@ -35,6 +39,8 @@ pub struct Event {
     pub top_line_info: Option<String>,
     pub cancelled_at: Option<NaiveDateTime>,
     pub updated_at: NaiveDateTime,
+    pub fee_schedule_id: Option<Uuid>,
+    pub fee_mode: Option<String>,
 }
 
 #[derive(Default, Insertable, Serialize, Deserialize, Validate)]
@@ -56,6 +62,8 @@ pub struct NewEvent {
     pub age_limit: Option<i32>,
     #[validate(length(max = "100"))]
     pub top_line_info: Option<String>,
+    #[validate(custom = "validators::validate_fee_mode")]
+    pub fee_mode: Option<String>,
 }
 
 impl NewEvent {
@@ -90,6 +98,9 @@ pub struct EventEditableAttributes {
     pub cancelled_at: Option<NaiveDateTime>,
     #[validate(length(max = "100"))]
     pub top_line_info: Option<String>,
+    /// `Some(None)` removes the override so the organization's fee mode is used again
+    #[serde(default, deserialize_with = "serializers::deserialize_double_option")]
+    pub fee_mode: Option<Option<String>>,
 }
 
 impl Event {
@@ -122,7 +133,16 @@ impl Event {
         attributes: EventEditableAttributes,
         conn: &PgConnection,
     ) -> Result<Event, DatabaseError> {
-        attributes.validate()?;
+        let mut validation_errors = attributes.validate();
+        if let Some(Some(ref fee_mode)) = attributes.fee_mode {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "fee_mode",
+                validators::validate_fee_mode(fee_mode),
+            );
+        }
+        validation_errors?;
+
         DatabaseError::wrap(
             ErrorCode::UpdateError,
             "Could not update event",
@@ -161,6 +181,17 @@ impl Event {
             )).execute(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not publish record")?;
 
+        EventInterestNotification::queue_for_published_event(self.id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventPublished,
+            format!("Event {} was published", self.name),
+            Tables::Events,
+            Some(self.id),
+            Some(json!({ "event_id": self.id, "name": self.name })),
+        ).for_organization(self.organization_id)
+        .commit(conn)?;
+
         Event::find(self.id, conn)
     }
 
@@ -172,6 +203,37 @@ impl Event {
         )
     }
 
+    /// Whether fees are absorbed by the organization or passed to the buyer, events use the fee
+    /// mode of their organization unless it has been set for the event
+    pub fn fee_mode(&self, conn: &PgConnection) -> Result<FeeMode, DatabaseError> {
+        match self.fee_mode {
+            Some(ref fee_mode) => fee_mode.parse::<FeeMode>().map_err(|_| {
+                DatabaseError::new(
+                    ErrorCode::InternalError,
+                    Some(format!("Invalid fee mode {} for event {}", fee_mode, self.id)),
+                )
+            }),
+            None => self.organization(conn)?.fee_mode(),
+        }
+    }
+
+    /// Overrides the fee schedule of the organization for this event, `None` removes the override
+    pub fn set_fee_schedule(
+        &self,
+        fee_schedule: Option<&FeeSchedule>,
+        conn: &PgConnection,
+    ) -> Result<Event, DatabaseError> {
+        diesel::update(self)
+            .set((
+                events::fee_schedule_id.eq(fee_schedule.map(|f| f.id)),
+                events::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not set the fee schedule for this event",
+            )
+    }
+
     pub fn cancel(self, conn: &PgConnection) -> Result<Event, DatabaseError> {
         diesel::update(&self)
             .set(events::cancelled_at.eq(dsl::now.nullable()))
@@ -193,20 +255,44 @@ impl Event {
         )
     }
 
+    /// Events owned by the organization along with those it co-promotes
     pub fn find_all_events_from_organization(
         organization_id: &Uuid,
         conn: &PgConnection,
     ) -> Result<Vec<Event>, DatabaseError> {
+        let promoted_event_ids = event_promoters::table
+            .filter(event_promoters::organization_id.eq(organization_id))
+            .select(event_promoters::event_id);
         DatabaseError::wrap(
             ErrorCode::QueryError,
             "Error loading events via organization",
             events::table
-                .filter(events::organization_id.eq(organization_id))
-                .order_by(events::name)
+                .filter(
+                    events::organization_id
+                        .eq(organization_id)
+                        .or(events::id.eq_any(promoted_event_ids)),
+                ).order_by(events::name)
                 .load(conn),
         )
     }
 
+    pub fn find_published_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::organization_id.eq(organization_id))
+            .filter(events::status.eq(EventStatus::Published.to_string()))
+            .filter(events::cancelled_at.is_null())
+            .order_by(events::event_start.asc())
+            .then_order_by(events::name.asc())
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Error loading published events for organization",
+            )
+    }
+
     pub fn guest_list(
         &self,
         query: &str,
@@ -221,6 +307,17 @@ impl Event {
             .to_db_error(ErrorCode::QueryError, "Could not load guest list")
     }
 
+    /// Full guest list of purchased and redeemed tickets, ordered by guest last name
+    pub fn guest_list_export(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<GuestListEntry>, DatabaseError> {
+        diesel::sql_query(include_str!("../queries/retrieve_guest_list_export.sql"))
+            .bind::<sql_types::Uuid, _>(self.id)
+            .load::<GuestListEntry>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not export guest list")
+    }
+
     pub fn search(
         query_filter: Option<String>,
         region_id: Option<Uuid>,
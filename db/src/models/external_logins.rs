@@ -3,8 +3,7 @@ use diesel;
 use diesel::prelude::*;
 use models::User;
 use schema::external_logins;
-use utils::errors::DatabaseError;
-use utils::errors::ErrorCode;
+use utils::errors::*;
 use uuid::Uuid;
 
 #[derive(Identifiable, Associations, Queryable)]
@@ -16,6 +15,7 @@ pub struct ExternalLogin {
     pub user_id: Uuid,
     pub created_at: NaiveDateTime,
     pub site: String,
+    #[serde(skip_serializing)]
     pub access_token: String,
     pub external_user_id: String,
     pub updated_at: NaiveDateTime,
@@ -73,4 +73,29 @@ impl ExternalLogin {
                 .optional(),
         )
     }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ExternalLogin, DatabaseError> {
+        external_logins::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading external login")
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ExternalLogin>, DatabaseError> {
+        external_logins::table
+            .filter(external_logins::user_id.eq(user_id))
+            .order_by(external_logins::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading external logins")
+    }
+
+    /// Unlinks the external account so it can no longer be used to sign in
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove external login")
+    }
 }
@@ -16,12 +16,21 @@ pub struct FeeScheduleRange {
     pub fee_in_cents: i64,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub percentage_in_basis_points: i64,
+    pub min_fee_in_cents: Option<i64>,
+    pub max_fee_in_cents: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct NewFeeScheduleRange {
     pub min_price: i64,
     pub fee_in_cents: i64,
+    #[serde(default)]
+    pub percentage_in_basis_points: i64,
+    #[serde(default)]
+    pub min_fee_in_cents: Option<i64>,
+    #[serde(default)]
+    pub max_fee_in_cents: Option<i64>,
 }
 
 impl FeeScheduleRange {
@@ -31,4 +40,19 @@ impl FeeScheduleRange {
             .first::<FeeScheduleRange>(conn)
             .to_db_error(ErrorCode::QueryError, "Error loading fee schedule range")
     }
+
+    /// Fee for a single ticket, the flat fee plus the percentage of the price rounded to the
+    /// nearest cent and then limited to the minimum and maximum fee
+    pub fn calculate_fee(&self, price_in_cents: i64) -> i64 {
+        let percentage_fee_in_cents =
+            (price_in_cents * self.percentage_in_basis_points + 5_000) / 10_000;
+        let mut fee_in_cents = self.fee_in_cents + percentage_fee_in_cents;
+        if let Some(min_fee_in_cents) = self.min_fee_in_cents {
+            fee_in_cents = fee_in_cents.max(min_fee_in_cents);
+        }
+        if let Some(max_fee_in_cents) = self.max_fee_in_cents {
+            fee_in_cents = fee_in_cents.min(max_fee_in_cents);
+        }
+        fee_in_cents
+    }
 }
@@ -1,25 +1,34 @@
-use chrono::NaiveDateTime;
+use chrono::prelude::*;
 use diesel;
 use diesel::prelude::*;
-use models::{FeeScheduleRange, NewFeeScheduleRange};
+use models::{Event, FeeScheduleRange, NewFeeScheduleRange, Organization, TicketType};
 use schema::{fee_schedule_ranges, fee_schedules};
+use std::borrow::Cow;
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
 use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
 
-#[derive(Queryable, Identifiable, Clone, Debug)]
+#[derive(Queryable, Identifiable, Clone, Debug, PartialEq, Serialize)]
 pub struct FeeSchedule {
     pub id: Uuid,
     pub name: String,
     pub version: i16,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub effective_at: NaiveDateTime,
+    /// Id of the first version, shared by all versions of the fee schedule
+    pub root_fee_schedule_id: Uuid,
 }
 
 impl FeeSchedule {
     pub fn create(name: String, ranges: Vec<(NewFeeScheduleRange)>) -> NewFeeSchedule {
-        NewFeeSchedule { name, ranges }
+        NewFeeSchedule {
+            name,
+            effective_at: None,
+            ranges,
+        }
     }
 
     pub fn ranges(&self, conn: &PgConnection) -> Result<Vec<FeeScheduleRange>, DatabaseError> {
@@ -36,6 +45,69 @@ impl FeeSchedule {
             .to_db_error(ErrorCode::QueryError, "Error loading Fee Schedule")
     }
 
+    /// Fee schedule used when selling the ticket type. Ticket types and events can override
+    /// the fee schedule of the organization, overrides that are not yet effective are skipped
+    pub fn find_for_ticket_type(
+        ticket_type: &TicketType,
+        conn: &PgConnection,
+    ) -> Result<FeeSchedule, DatabaseError> {
+        let event = Event::find(ticket_type.event_id, conn)?;
+        let overrides = ticket_type
+            .fee_schedule_id
+            .iter()
+            .chain(event.fee_schedule_id.iter());
+        for &fee_schedule_id in overrides {
+            let fee_schedule = FeeSchedule::find(fee_schedule_id, conn)?;
+            if let Some(current_version) = fee_schedule.current_version(conn)? {
+                return Ok(current_version);
+            }
+        }
+
+        FeeSchedule::find_for_organization(&Organization::find(event.organization_id, conn)?, conn)
+    }
+
+    /// Fee schedule in effect for the organization. Until the organization's own fee schedule
+    /// takes effect the closest parent organization's fee schedule applies, if none is in effect
+    /// yet the organization's fee schedule is used as is
+    pub fn find_for_organization(
+        organization: &Organization,
+        conn: &PgConnection,
+    ) -> Result<FeeSchedule, DatabaseError> {
+        let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, conn)?;
+        if let Some(current_version) = fee_schedule.current_version(conn)? {
+            return Ok(current_version);
+        }
+
+        let mut parent_id = organization.parent_id;
+        while let Some(id) = parent_id {
+            let parent = Organization::find(id, conn)?;
+            if let Some(current_version) =
+                FeeSchedule::find(parent.fee_schedule_id, conn)?.current_version(conn)?
+            {
+                return Ok(current_version);
+            }
+            parent_id = parent.parent_id;
+        }
+
+        Ok(fee_schedule)
+    }
+
+    /// Latest version of this fee schedule that is in effect. Versions that are not yet
+    /// effective are ignored so they only apply to orders placed after they take effect, `None`
+    /// if no version is in effect yet
+    pub fn current_version(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Option<FeeSchedule>, DatabaseError> {
+        fee_schedules::table
+            .filter(fee_schedules::root_fee_schedule_id.eq(self.root_fee_schedule_id))
+            .filter(fee_schedules::effective_at.le(Utc::now().naive_utc()))
+            .order_by(fee_schedules::version.desc())
+            .first::<FeeSchedule>(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading Fee Schedule")
+    }
+
     pub fn get_range(
         &self,
         price: i64,
@@ -63,28 +135,55 @@ impl FeeSchedule {
 #[derive(Serialize, Deserialize)]
 pub struct NewFeeSchedule {
     pub name: String,
+    #[serde(default)]
+    pub effective_at: Option<NaiveDateTime>,
     pub ranges: Vec<NewFeeScheduleRange>,
 }
 
 impl NewFeeSchedule {
+    /// Creates the first version of a new fee schedule
     pub fn commit(self, conn: &PgConnection) -> Result<FeeSchedule, DatabaseError> {
+        let id = Uuid::new_v4();
+        self.insert(id, id, 0, conn)
+    }
+
+    /// Creates the next version of the fee schedule the given version belongs to
+    pub fn commit_as_version_of(
+        self,
+        fee_schedule: &FeeSchedule,
+        conn: &PgConnection,
+    ) -> Result<FeeSchedule, DatabaseError> {
         let previous_version = fee_schedules::table
-            .filter(fee_schedules::name.eq(&self.name))
-            .order_by(fee_schedules::id.desc())
+            .filter(fee_schedules::root_fee_schedule_id.eq(fee_schedule.root_fee_schedule_id))
+            .order_by(fee_schedules::version.desc())
             .first::<FeeSchedule>(conn)
-            .optional()
             .to_db_error(ErrorCode::QueryError, "Error loading Fee Schedule")?;
 
-        let next_version = match previous_version {
-            None => 0,
+        self.insert(
+            Uuid::new_v4(),
+            previous_version.root_fee_schedule_id,
+            previous_version.version + 1,
+            conn,
+        )
+    }
 
-            Some(pv) => pv.version + 1,
-        };
+    fn insert(
+        self,
+        id: Uuid,
+        root_fee_schedule_id: Uuid,
+        version: i16,
+        conn: &PgConnection,
+    ) -> Result<FeeSchedule, DatabaseError> {
+        self.validate_record()?;
 
         let result: FeeSchedule = diesel::insert_into(fee_schedules::table)
             .values((
+                fee_schedules::id.eq(id),
                 fee_schedules::name.eq(&self.name),
-                fee_schedules::version.eq(next_version),
+                fee_schedules::version.eq(version),
+                fee_schedules::effective_at
+                    .eq(self.effective_at.unwrap_or_else(|| Utc::now().naive_utc())),
+                fee_schedules::root_fee_schedule_id.eq(root_fee_schedule_id),
             )).get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create fee schedule")?;
 
@@ -94,6 +193,9 @@ impl NewFeeSchedule {
             fee_schedule_id: Uuid,
             min_price: i64,
             fee_in_cents: i64,
+            percentage_in_basis_points: i64,
+            min_fee_in_cents: Option<i64>,
+            max_fee_in_cents: Option<i64>,
         }
         let mut ranges = Vec::<I>::new();
         for range in &self.ranges {
@@ -101,6 +203,9 @@ impl NewFeeSchedule {
                 fee_schedule_id: result.id,
                 min_price: range.min_price,
                 fee_in_cents: range.fee_in_cents,
+                percentage_in_basis_points: range.percentage_in_basis_points,
+                min_fee_in_cents: range.min_fee_in_cents,
+                max_fee_in_cents: range.max_fee_in_cents,
             })
         }
         diesel::insert_into(fee_schedule_ranges::table)
@@ -113,4 +218,33 @@ impl NewFeeSchedule {
 
         Ok(result)
     }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut errors = ValidationErrors::new();
+        for range in &self.ranges {
+            if range.percentage_in_basis_points < 0 || range.percentage_in_basis_points > 10_000 {
+                let mut validation_error = ValidationError::new(&"invalid_percentage");
+                validation_error.message = Some(Cow::from(
+                    "Percentage must be between 0 and 10000 basis points",
+                ));
+                errors.add("ranges", validation_error);
+            }
+            if let (Some(min_fee_in_cents), Some(max_fee_in_cents)) =
+                (range.min_fee_in_cents, range.max_fee_in_cents)
+            {
+                if min_fee_in_cents > max_fee_in_cents {
+                    let mut validation_error = ValidationError::new(&"invalid_fee_cap");
+                    validation_error.message = Some(Cow::from(
+                        "Minimum fee must not be more than the maximum fee",
+                    ));
+                    errors.add("ranges", validation_error);
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,34 @@
+use diesel::sql_types::{Nullable, Text, Uuid as dUuid};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, QueryableByName, Serialize)]
+pub struct GuestListEntry {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "Text"]
+    pub ticket_type: String,
+    #[sql_type = "Text"]
+    pub status: String,
+    #[sql_type = "Nullable<Text>"]
+    pub redeem_key: Option<String>,
+    #[sql_type = "Nullable<dUuid>"]
+    pub order_id: Option<Uuid>,
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Text"]
+    pub first_name: String,
+    #[sql_type = "Text"]
+    pub last_name: String,
+    #[sql_type = "Nullable<Text>"]
+    pub email: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub phone: Option<String>,
+    #[sql_type = "Nullable<dUuid>"]
+    pub purchaser_id: Option<Uuid>,
+    #[sql_type = "Nullable<Text>"]
+    pub purchaser_first_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub purchaser_last_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub purchaser_email: Option<String>,
+}
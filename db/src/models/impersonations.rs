@@ -0,0 +1,157 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{Roles, Session, User};
+use schema::{impersonation_actions, impersonations};
+use std::borrow::Cow;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+const IMPERSONATION_DURATION_IN_MINUTES: i64 = 15;
+const IMPERSONATION_USER_AGENT: &str = "Support impersonation";
+
+/// An admin acting as another user, e.g. to see their cart or ticket wallet. The impersonation
+/// has its own session for the user so it shows up in their sessions and can be revoked
+#[derive(Identifiable, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[table_name = "impersonations"]
+pub struct Impersonation {
+    pub id: Uuid,
+    pub admin_user_id: Uuid,
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    pub reason: Option<String>,
+    pub expires_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "impersonations"]
+struct NewImpersonation {
+    admin_user_id: Uuid,
+    user_id: Uuid,
+    session_id: Uuid,
+    reason: Option<String>,
+    expires_at: NaiveDateTime,
+}
+
+/// Write request made while impersonating a user
+#[derive(Identifiable, Associations, Queryable)]
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[belongs_to(Impersonation)]
+#[table_name = "impersonation_actions"]
+pub struct ImpersonationAction {
+    pub id: Uuid,
+    pub impersonation_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "impersonation_actions"]
+struct NewImpersonationAction<'a> {
+    impersonation_id: Uuid,
+    method: &'a str,
+    path: &'a str,
+}
+
+impl Impersonation {
+    pub fn start(
+        admin_user: &User,
+        user: &User,
+        reason: Option<String>,
+        ip_address: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<Impersonation, DatabaseError> {
+        // Admins can not be impersonated, that would allow picking up another admin's access
+        if admin_user.id == user.id || user.role.contains(&Roles::Admin.to_string()) {
+            let mut errors = ValidationErrors::new();
+            let mut validation_error = ValidationError::new(&"invalid_user");
+            validation_error.message = Some(Cow::from("User can not be impersonated"));
+            errors.add("user_id", validation_error);
+            return Err(errors.into());
+        }
+
+        let session = Session::create(
+            user.id,
+            Some(IMPERSONATION_USER_AGENT.to_string()),
+            ip_address,
+        ).commit(conn)?;
+        diesel::insert_into(impersonations::table)
+            .values(&NewImpersonation {
+                admin_user_id: admin_user.id,
+                user_id: user.id,
+                session_id: session.id,
+                reason,
+                expires_at: Utc::now().naive_utc()
+                    + Duration::minutes(IMPERSONATION_DURATION_IN_MINUTES),
+            }).get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not start impersonation")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Impersonation, DatabaseError> {
+        impersonations::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load impersonation")
+    }
+
+    /// Impersonations of the user, newest first
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Impersonation>, DatabaseError> {
+        impersonations::table
+            .filter(impersonations::user_id.eq(user_id))
+            .order_by(impersonations::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load impersonations")
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none() && self.expires_at > Utc::now().naive_utc()
+    }
+
+    /// Ends the impersonation early and signs out its session
+    pub fn end(&self, conn: &PgConnection) -> Result<Impersonation, DatabaseError> {
+        Session::find(self.session_id, conn)?.revoke(conn)?;
+        diesel::update(self)
+            .set((
+                impersonations::ended_at.eq(dsl::now.nullable()),
+                impersonations::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not end impersonation")
+    }
+
+    pub fn record_action(
+        &self,
+        method: &str,
+        path: &str,
+        conn: &PgConnection,
+    ) -> Result<ImpersonationAction, DatabaseError> {
+        diesel::insert_into(impersonation_actions::table)
+            .values(&NewImpersonationAction {
+                impersonation_id: self.id,
+                method,
+                path,
+            }).get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not record impersonation action",
+            )
+    }
+
+    pub fn actions(&self, conn: &PgConnection) -> Result<Vec<ImpersonationAction>, DatabaseError> {
+        ImpersonationAction::belonging_to(self)
+            .order_by(impersonation_actions::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load impersonation actions",
+            )
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::{Organization, User};
+use schema::{event_interest_notifications, events, interest_notification_opt_outs};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(User)]
+#[belongs_to(Organization)]
+#[table_name = "interest_notification_opt_outs"]
+pub struct InterestNotificationOptOut {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "interest_notification_opt_outs"]
+pub struct NewInterestNotificationOptOut {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+}
+
+impl NewInterestNotificationOptOut {
+    /// Opts the user out and discards any of their notifications for the organization's events
+    /// that have not been sent yet
+    pub fn commit(&self, conn: &PgConnection) -> Result<InterestNotificationOptOut, DatabaseError> {
+        let organization_event_ids = events::table
+            .filter(events::organization_id.eq(self.organization_id))
+            .select(events::id);
+        diesel::delete(
+            event_interest_notifications::table
+                .filter(event_interest_notifications::user_id.eq(self.user_id))
+                .filter(event_interest_notifications::sent_at.is_null())
+                .filter(event_interest_notifications::event_id.eq_any(organization_event_ids)),
+        ).execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove pending event interest notifications",
+        )?;
+
+        if let Some(opt_out) = InterestNotificationOptOut::find_for_organization_and_user(
+            self.organization_id,
+            self.user_id,
+            conn,
+        ).optional()?
+        {
+            return Ok(opt_out);
+        }
+
+        diesel::insert_into(interest_notification_opt_outs::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create interest notification opt out",
+            )
+    }
+}
+
+impl InterestNotificationOptOut {
+    pub fn create(organization_id: Uuid, user_id: Uuid) -> NewInterestNotificationOptOut {
+        NewInterestNotificationOptOut {
+            organization_id,
+            user_id,
+        }
+    }
+
+    pub fn find_for_organization_and_user(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<InterestNotificationOptOut, DatabaseError> {
+        interest_notification_opt_outs::table
+            .filter(interest_notification_opt_outs::organization_id.eq(organization_id))
+            .filter(interest_notification_opt_outs::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load interest notification opt out",
+            )
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<InterestNotificationOptOut>, DatabaseError> {
+        interest_notification_opt_outs::table
+            .filter(interest_notification_opt_outs::user_id.eq(user_id))
+            .order_by(interest_notification_opt_outs::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load interest notification opt outs",
+            )
+    }
+
+    pub fn remove(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        diesel::delete(
+            interest_notification_opt_outs::table
+                .filter(interest_notification_opt_outs::organization_id.eq(organization_id))
+                .filter(interest_notification_opt_outs::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove interest notification opt out",
+        )
+    }
+}
@@ -0,0 +1,234 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl::exists;
+use diesel::prelude::*;
+use models::*;
+use schema::ledger_entries;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+/// One side of a money movement. Every movement is recorded as entries that sum to zero, money
+/// taken from customers is debited from the `Customer` account and credited to the
+/// `Organization` (ticket sales) or the `Platform` (per unit and event fees). Fees absorbed by
+/// the organization are debited from the `Organization` account instead. The `Organization`
+/// side of co-promoted events is divided between the promoters by their revenue splits
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[table_name = "ledger_entries"]
+pub struct LedgerEntry {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub order_id: Option<Uuid>,
+    pub order_item_id: Option<Uuid>,
+    pub settlement_id: Option<Uuid>,
+    pub entry_type: String,
+    pub account: String,
+    pub amount_in_cents: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "ledger_entries"]
+struct NewLedgerEntry {
+    organization_id: Uuid,
+    event_id: Option<Uuid>,
+    order_id: Option<Uuid>,
+    order_item_id: Option<Uuid>,
+    entry_type: String,
+    account: String,
+    amount_in_cents: i64,
+}
+
+impl LedgerEntry {
+    /// Records the sale of every item in a paid order, orders are only recorded once
+    pub fn create_for_order(
+        order: &Order,
+        conn: &PgConnection,
+    ) -> Result<Vec<LedgerEntry>, DatabaseError> {
+        if !LedgerEntry::find_for_order(order.id, conn)?.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut revenue_splits: HashMap<Uuid, Vec<RevenueSplit>> = HashMap::new();
+        let mut new_entries = Vec::new();
+
+        for item in order.items(conn)? {
+            let amount_in_cents = item.quantity * item.unit_price_in_cents;
+            let event_id = match item.event_id {
+                Some(event_id) if amount_in_cents != 0 => event_id,
+                _ => continue,
+            };
+            if !revenue_splits.contains_key(&event_id) {
+                let event = Event::find(event_id, conn)?;
+                revenue_splits.insert(event_id, RevenueSplit::for_event(&event, conn)?);
+            }
+            let splits = &revenue_splits[&event_id];
+
+            let (entry_type, account) = match item.item_type() {
+                OrderItemTypes::Tickets => {
+                    (LedgerEntryType::TicketSale, LedgerAccount::Organization)
+                }
+                OrderItemTypes::PerUnitFees => {
+                    (LedgerEntryType::PerUnitFee, LedgerAccount::Platform)
+                }
+                OrderItemTypes::EventFees => (LedgerEntryType::EventFee, LedgerAccount::Platform),
+            };
+
+            // Absorbed fees are paid out of the organization's share of the ticket price
+            let paid_by = if item.is_absorbed {
+                LedgerAccount::Organization
+            } else {
+                LedgerAccount::Customer
+            };
+
+            for (account, amount_in_cents) in vec![
+                (paid_by, -amount_in_cents),
+                (account, amount_in_cents),
+            ] {
+                let shares = if account == LedgerAccount::Organization {
+                    RevenueSplit::divide(splits, amount_in_cents)
+                } else {
+                    vec![(splits[0].organization_id, amount_in_cents)]
+                };
+                for (organization_id, amount_in_cents) in shares {
+                    if amount_in_cents == 0 {
+                        continue;
+                    }
+                    new_entries.push(NewLedgerEntry {
+                        organization_id,
+                        event_id: Some(event_id),
+                        order_id: Some(order.id),
+                        order_item_id: Some(item.id),
+                        entry_type: entry_type.to_string(),
+                        account: account.to_string(),
+                        amount_in_cents,
+                    });
+                }
+            }
+        }
+
+        if new_entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        diesel::insert_into(ledger_entries::table)
+            .values(&new_entries)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ledger entries")
+    }
+
+    /// Reverses the sale of an order after a refund or chargeback. Reversals are recorded
+    /// separately from the sale so settlements that were already paid out are not changed
+    pub fn create_reversal_for_order(
+        order: &Order,
+        entry_type: LedgerEntryType,
+        conn: &PgConnection,
+    ) -> Result<Vec<LedgerEntry>, DatabaseError> {
+        match entry_type {
+            LedgerEntryType::Refund | LedgerEntryType::Chargeback => (),
+            _ => {
+                return Err(LedgerEntry::reversal_error(
+                    "invalid_entry_type",
+                    "Only refunds and chargebacks can be recorded",
+                ))
+            }
+        }
+
+        let reversal_types = vec![
+            LedgerEntryType::Refund.to_string(),
+            LedgerEntryType::Chargeback.to_string(),
+        ];
+        let already_reversed = diesel::select(exists(
+            ledger_entries::table
+                .filter(ledger_entries::order_id.eq(order.id))
+                .filter(ledger_entries::entry_type.eq_any(&reversal_types)),
+        )).get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check for ledger reversals",
+        )?;
+        if already_reversed {
+            return Err(LedgerEntry::reversal_error(
+                "already_reversed",
+                "Order has already been refunded or charged back",
+            ));
+        }
+
+        let sale_entries = LedgerEntry::find_for_order(order.id, conn)?;
+        if sale_entries.is_empty() {
+            return Err(LedgerEntry::reversal_error(
+                "not_paid",
+                "Order has no recorded sales to reverse",
+            ));
+        }
+
+        let new_entries: Vec<NewLedgerEntry> = sale_entries
+            .into_iter()
+            .map(|entry| NewLedgerEntry {
+                organization_id: entry.organization_id,
+                event_id: entry.event_id,
+                order_id: entry.order_id,
+                order_item_id: entry.order_item_id,
+                entry_type: entry_type.to_string(),
+                account: entry.account,
+                amount_in_cents: -entry.amount_in_cents,
+            }).collect();
+
+        let entries: Vec<LedgerEntry> = diesel::insert_into(ledger_entries::table)
+            .values(&new_entries)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ledger entries")?;
+
+        if entry_type == LedgerEntryType::Refund {
+            for organization_id in order.organization_ids(conn)? {
+                DomainEvent::create(
+                    DomainEventTypes::OrderRefunded,
+                    "Order was refunded".to_string(),
+                    Tables::Orders,
+                    Some(order.id),
+                    Some(json!({ "order_id": order.id, "user_id": order.user_id })),
+                ).for_organization(organization_id)
+                .commit(conn)?;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn reversal_error(code: &'static str, message: &'static str) -> DatabaseError {
+        let mut errors = ValidationErrors::new();
+        let mut validation_error = ValidationError::new(code);
+        validation_error.message = Some(Cow::from(message));
+        errors.add("order_id", validation_error);
+        errors.into()
+    }
+
+    pub fn find_for_order(
+        order_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<LedgerEntry>, DatabaseError> {
+        ledger_entries::table
+            .filter(ledger_entries::order_id.eq(order_id))
+            .order_by(ledger_entries::created_at)
+            .then_order_by(ledger_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ledger entries")
+    }
+
+    pub fn find_for_settlement(
+        settlement_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<LedgerEntry>, DatabaseError> {
+        ledger_entries::table
+            .filter(ledger_entries::settlement_id.eq(settlement_id))
+            .order_by(ledger_entries::created_at)
+            .then_order_by(ledger_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ledger entries")
+    }
+}
@@ -0,0 +1,200 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{LoginCodeChannel, LoginCodePurpose, User};
+use rand::{thread_rng, Rng};
+use schema::login_codes;
+use utils::errors::*;
+use utils::passwords::PasswordHash;
+use uuid::Uuid;
+
+const LOGIN_CODE_EXPIRY_IN_MINUTES: i64 = 10;
+const LOGIN_CODE_MAX_ATTEMPTS: i32 = 5;
+const LOGIN_CODE_RESEND_INTERVAL_IN_SECONDS: i64 = 60;
+
+/// Six digit code sent by email or SMS that can be exchanged for tokens instead of a password,
+/// or that proves the user owns their phone number. Only the newest code for a user and purpose
+/// is accepted and it is hashed like a password
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "login_codes"]
+pub struct LoginCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub channel: String,
+    pub hashed_code: String,
+    pub attempts: i32,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub purpose: String,
+    pub phone: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "login_codes"]
+struct NewLoginCode {
+    user_id: Uuid,
+    channel: String,
+    hashed_code: String,
+    expires_at: NaiveDateTime,
+    purpose: String,
+    phone: Option<String>,
+}
+
+impl LoginCode {
+    /// Creates a login code for the user and returns it along with the plain text code to send
+    pub fn create_for_user(
+        user_id: Uuid,
+        channel: LoginCodeChannel,
+        conn: &PgConnection,
+    ) -> Result<(LoginCode, String), DatabaseError> {
+        LoginCode::create(user_id, channel, LoginCodePurpose::Login, None, conn)
+    }
+
+    /// Creates a code that verifies the phone number it is sent to by SMS
+    pub fn create_for_phone_verification(
+        user_id: Uuid,
+        phone: &str,
+        conn: &PgConnection,
+    ) -> Result<(LoginCode, String), DatabaseError> {
+        LoginCode::create(
+            user_id,
+            LoginCodeChannel::Sms,
+            LoginCodePurpose::PhoneVerification,
+            Some(phone.to_string()),
+            conn,
+        )
+    }
+
+    fn create(
+        user_id: Uuid,
+        channel: LoginCodeChannel,
+        purpose: LoginCodePurpose,
+        phone: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<(LoginCode, String), DatabaseError> {
+        let code = format!("{:06}", thread_rng().gen_range(0, 1_000_000));
+        let login_code = diesel::insert_into(login_codes::table)
+            .values(&NewLoginCode {
+                user_id,
+                channel: channel.to_string(),
+                hashed_code: PasswordHash::generate(&code, None).to_string(),
+                expires_at: Utc::now().naive_utc()
+                    + Duration::minutes(LOGIN_CODE_EXPIRY_IN_MINUTES),
+                purpose: purpose.to_string(),
+                phone,
+            }).get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create login code")?;
+
+        Ok((login_code, code))
+    }
+
+    pub fn find_latest_for_user(
+        user_id: Uuid,
+        purpose: LoginCodePurpose,
+        conn: &PgConnection,
+    ) -> Result<LoginCode, DatabaseError> {
+        login_codes::table
+            .filter(login_codes::user_id.eq(user_id))
+            .filter(login_codes::purpose.eq(purpose.to_string()))
+            .order_by(login_codes::created_at.desc())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load login code")
+    }
+
+    /// True if a code was sent to the user too recently for another one to be sent
+    pub fn recently_sent(
+        user_id: Uuid,
+        purpose: LoginCodePurpose,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let resend_after =
+            Utc::now().naive_utc() - Duration::seconds(LOGIN_CODE_RESEND_INTERVAL_IN_SECONDS);
+        match LoginCode::find_latest_for_user(user_id, purpose, conn).optional()? {
+            Some(login_code) => Ok(login_code.created_at > resend_after),
+            None => Ok(false),
+        }
+    }
+
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none()
+            && self.attempts < LOGIN_CODE_MAX_ATTEMPTS
+            && self.expires_at > Utc::now().naive_utc()
+    }
+
+    /// Marks the user's newest login code as used if it matches, otherwise counts the failed
+    /// attempt
+    pub fn redeem(user_id: Uuid, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let login_code =
+            LoginCode::find_latest_for_user(user_id, LoginCodePurpose::Login, conn).optional()?;
+        LoginCode::redeem_code(login_code, code, conn)
+    }
+
+    /// Like `redeem` but for the newest phone verification code, which must have been sent to
+    /// `phone`
+    pub fn redeem_phone_verification(
+        user_id: Uuid,
+        phone: &str,
+        code: &str,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let login_code =
+            LoginCode::find_latest_for_user(user_id, LoginCodePurpose::PhoneVerification, conn)
+                .optional()?
+                .filter(|login_code| login_code.phone.as_ref().map(|p| p.as_str()) == Some(phone));
+        LoginCode::redeem_code(login_code, code, conn)
+    }
+
+    /// Expires the user's unused phone verification codes, used when their phone number changes
+    pub fn expire_phone_verifications(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        diesel::update(
+            login_codes::table
+                .filter(login_codes::user_id.eq(user_id))
+                .filter(login_codes::purpose.eq(LoginCodePurpose::PhoneVerification.to_string()))
+                .filter(login_codes::used_at.is_null())
+                .filter(login_codes::expires_at.gt(dsl::now)),
+        ).set((
+            login_codes::expires_at.eq(dsl::now),
+            login_codes::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not expire phone verification codes")
+    }
+
+    fn redeem_code(
+        login_code: Option<LoginCode>,
+        code: &str,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let login_code = match login_code {
+            Some(login_code) if login_code.is_usable() => login_code,
+            _ => return Ok(false),
+        };
+
+        if !PasswordHash::from_str(&login_code.hashed_code)?.verify(code.trim()) {
+            diesel::update(&login_code)
+                .set((
+                    login_codes::attempts.eq(login_codes::attempts + 1),
+                    login_codes::updated_at.eq(dsl::now),
+                )).execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update login code")?;
+            return Ok(false);
+        }
+
+        let updated = diesel::update(
+            login_codes::table
+                .filter(login_codes::id.eq(login_code.id))
+                .filter(login_codes::used_at.is_null()),
+        ).set((
+            login_codes::used_at.eq(dsl::now.nullable()),
+            login_codes::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not use login code")?;
+        Ok(updated == 1)
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::login_throttles;
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Failed attempts allowed before each further attempt is delayed
+const FREE_FAILED_ATTEMPTS: i32 = 3;
+const MAXIMUM_BACKOFF_EXPONENT: i32 = 8;
+const USER_LOCKOUT_THRESHOLD: i32 = 10;
+/// Addresses are shared by many users behind NAT so they are allowed more attempts
+const IP_ADDRESS_LOCKOUT_THRESHOLD: i32 = 50;
+const LOCKOUT_PERIOD_IN_MINUTES: i64 = 30;
+/// Failed attempts older than this no longer count towards a lockout
+const FAILED_ATTEMPT_WINDOW_IN_HOURS: i64 = 24;
+
+/// Failed sign in attempts against a user or from an IP address. Each failure past
+/// `FREE_FAILED_ATTEMPTS` doubles the delay before the next attempt is allowed until the
+/// lockout threshold is reached, after which attempts are refused for `LOCKOUT_PERIOD_IN_MINUTES`
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(User)]
+#[table_name = "login_throttles"]
+pub struct LoginThrottle {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub failed_attempts: i32,
+    pub last_failed_at: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "login_throttles"]
+struct NewLoginThrottle {
+    user_id: Option<Uuid>,
+    ip_address: Option<String>,
+    failed_attempts: i32,
+    locked_until: Option<NaiveDateTime>,
+}
+
+impl LoginThrottle {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<LoginThrottle, DatabaseError> {
+        login_throttles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load login throttle")
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<LoginThrottle, DatabaseError> {
+        login_throttles::table
+            .filter(login_throttles::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load login throttle")
+    }
+
+    pub fn find_for_ip_address(
+        ip_address: &str,
+        conn: &PgConnection,
+    ) -> Result<LoginThrottle, DatabaseError> {
+        login_throttles::table
+            .filter(login_throttles::ip_address.eq(ip_address))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load login throttle")
+    }
+
+    /// Users and IP addresses that are currently not allowed to attempt to sign in
+    pub fn find_throttled(conn: &PgConnection) -> Result<Vec<LoginThrottle>, DatabaseError> {
+        login_throttles::table
+            .filter(login_throttles::locked_until.gt(dsl::now.nullable()))
+            .order_by(login_throttles::locked_until.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load login throttles")
+    }
+
+    pub fn record_failure_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<LoginThrottle, DatabaseError> {
+        let existing = LoginThrottle::find_for_user(user_id, conn).optional()?;
+        LoginThrottle::record_failure(existing, Some(user_id), None, USER_LOCKOUT_THRESHOLD, conn)
+    }
+
+    pub fn record_failure_for_ip_address(
+        ip_address: &str,
+        conn: &PgConnection,
+    ) -> Result<LoginThrottle, DatabaseError> {
+        let existing = LoginThrottle::find_for_ip_address(ip_address, conn).optional()?;
+        LoginThrottle::record_failure(
+            existing,
+            None,
+            Some(ip_address.to_string()),
+            IP_ADDRESS_LOCKOUT_THRESHOLD,
+            conn,
+        )
+    }
+
+    /// Forgets the failed attempts for a user, for example after they sign in successfully
+    pub fn clear_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(login_throttles::table.filter(login_throttles::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not clear login throttle")
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not clear login throttle")
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        match self.locked_until {
+            Some(locked_until) => locked_until > Utc::now().naive_utc(),
+            None => false,
+        }
+    }
+
+    pub fn is_locked_out(&self) -> bool {
+        self.failed_attempts >= self.lockout_threshold() && self.is_throttled()
+    }
+
+    /// True when the most recent failure is the one that caused the lockout
+    pub fn lockout_started(&self) -> bool {
+        self.failed_attempts == self.lockout_threshold()
+    }
+
+    fn lockout_threshold(&self) -> i32 {
+        if self.user_id.is_some() {
+            USER_LOCKOUT_THRESHOLD
+        } else {
+            IP_ADDRESS_LOCKOUT_THRESHOLD
+        }
+    }
+
+    fn record_failure(
+        existing: Option<LoginThrottle>,
+        user_id: Option<Uuid>,
+        ip_address: Option<String>,
+        lockout_threshold: i32,
+        conn: &PgConnection,
+    ) -> Result<LoginThrottle, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let previous_failed_attempts = match existing {
+            Some(ref throttle) => {
+                let lockout_expired =
+                    throttle.failed_attempts >= lockout_threshold && !throttle.is_throttled();
+                let window_expired = now.signed_duration_since(throttle.last_failed_at)
+                    > Duration::hours(FAILED_ATTEMPT_WINDOW_IN_HOURS);
+                if lockout_expired || window_expired {
+                    0
+                } else {
+                    throttle.failed_attempts
+                }
+            }
+            None => 0,
+        };
+        let failed_attempts = previous_failed_attempts + 1;
+
+        let locked_until = if failed_attempts >= lockout_threshold {
+            Some(now + Duration::minutes(LOCKOUT_PERIOD_IN_MINUTES))
+        } else if failed_attempts > FREE_FAILED_ATTEMPTS {
+            let exponent = cmp::min(
+                failed_attempts - FREE_FAILED_ATTEMPTS,
+                MAXIMUM_BACKOFF_EXPONENT,
+            );
+            Some(now + Duration::seconds(2i64.pow(exponent as u32)))
+        } else {
+            None
+        };
+
+        match existing {
+            Some(throttle) => diesel::update(&throttle)
+                .set((
+                    login_throttles::failed_attempts.eq(failed_attempts),
+                    login_throttles::last_failed_at.eq(now),
+                    login_throttles::locked_until.eq(locked_until),
+                    login_throttles::updated_at.eq(dsl::now),
+                )).get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update login throttle"),
+            None => diesel::insert_into(login_throttles::table)
+                .values(&NewLoginThrottle {
+                    user_id,
+                    ip_address,
+                    failed_attempts,
+                    locked_until,
+                }).get_result(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create login throttle"),
+        }
+    }
+}
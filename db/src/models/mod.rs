@@ -1,60 +1,110 @@
 pub use self::artists::*;
 pub use self::assets::*;
+pub use self::audit_entries::*;
+pub use self::calendar_tokens::*;
 pub use self::domain_events::*;
 pub use self::enums::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
+pub use self::event_interest_notifications::*;
+pub use self::event_promoters::*;
+pub use self::event_reminders::*;
 pub use self::events::*;
 pub use self::external_logins::*;
 pub use self::fee_schedule_ranges::*;
 pub use self::fee_schedules::*;
 pub use self::for_display::*;
+pub use self::guest_list_entry::*;
 pub use self::holds::*;
+pub use self::impersonations::*;
+pub use self::interest_notification_opt_outs::*;
+pub use self::ledger_entries::*;
+pub use self::login_codes::*;
+pub use self::login_throttles::*;
+pub use self::oidc_login_requests::*;
 pub use self::order_items::*;
 pub use self::orders::*;
+pub use self::organization_api_keys::*;
+pub use self::organization_customers::*;
+pub use self::organization_email_templates::*;
 pub use self::organization_invites::*;
+pub use self::organization_roles::*;
 pub use self::organization_users::*;
 pub use self::organizations::*;
 pub use self::payment_methods::*;
 pub use self::payments::*;
 pub use self::redeemable_ticket::*;
 pub use self::regions::*;
+pub use self::sales_reports::*;
 pub use self::scopes::*;
 pub use self::ticket_instances::RedeemResults;
+pub use self::sessions::*;
+pub use self::settlements::*;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
 pub use self::ticket_types::*;
+pub use self::two_factor_credentials::*;
+pub use self::two_factor_recovery_codes::*;
+pub use self::user_data_exports::*;
+pub use self::user_erasure_requests::*;
 pub use self::users::*;
 pub use self::venues::*;
 pub use self::wallets::*;
+pub use self::webhook_deliveries::*;
+pub use self::webhook_subscriptions::*;
 
+mod audit_entries;
 pub mod concerns;
 
 mod artists;
 mod assets;
+mod calendar_tokens;
 mod domain_events;
 mod enums;
 mod event_artists;
 mod event_interest;
+mod event_interest_notifications;
+mod event_promoters;
+mod event_reminders;
 mod events;
 mod external_logins;
 mod fee_schedule_ranges;
 mod fee_schedules;
 mod for_display;
+mod guest_list_entry;
 mod holds;
+mod impersonations;
+mod interest_notification_opt_outs;
+mod ledger_entries;
+mod login_codes;
+mod login_throttles;
+mod oidc_login_requests;
 mod order_items;
 mod orders;
+mod organization_api_keys;
+mod organization_customers;
+mod organization_email_templates;
 mod organization_invites;
+mod organization_roles;
 mod organization_users;
 mod organizations;
 mod payment_methods;
 mod payments;
 mod redeemable_ticket;
 mod regions;
+mod sales_reports;
 pub mod scopes;
+mod sessions;
+mod settlements;
 mod ticket_instances;
 mod ticket_pricing;
 mod ticket_types;
+mod two_factor_credentials;
+mod two_factor_recovery_codes;
+mod user_data_exports;
+mod user_erasure_requests;
 mod users;
 mod venues;
 mod wallets;
+mod webhook_deliveries;
+mod webhook_subscriptions;
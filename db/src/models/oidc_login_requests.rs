@@ -0,0 +1,85 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use schema::oidc_login_requests;
+use utils::errors::*;
+use uuid::Uuid;
+
+const OIDC_LOGIN_REQUEST_EXPIRY_IN_MINUTES: i64 = 10;
+const RANDOM_VALUE_LENGTH: usize = 32;
+
+/// State and nonce sent to the identity provider when redirecting a user to log in. The state
+/// is exchanged once for the nonce the ID token must contain, so a token cannot be replayed
+#[derive(Identifiable, Queryable, Clone, PartialEq, Debug)]
+#[table_name = "oidc_login_requests"]
+pub struct OidcLoginRequest {
+    pub id: Uuid,
+    pub state: String,
+    pub nonce: String,
+    pub redirect_uri: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "oidc_login_requests"]
+pub struct NewOidcLoginRequest {
+    pub state: String,
+    pub nonce: String,
+    pub redirect_uri: String,
+    pub expires_at: NaiveDateTime,
+}
+
+impl NewOidcLoginRequest {
+    pub fn commit(&self, conn: &PgConnection) -> Result<OidcLoginRequest, DatabaseError> {
+        diesel::insert_into(oidc_login_requests::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create login request")
+    }
+}
+
+impl OidcLoginRequest {
+    pub fn create(redirect_uri: &str) -> NewOidcLoginRequest {
+        NewOidcLoginRequest {
+            state: generate_random_string(RANDOM_VALUE_LENGTH),
+            nonce: generate_random_string(RANDOM_VALUE_LENGTH),
+            redirect_uri: redirect_uri.to_string(),
+            expires_at: Utc::now().naive_utc()
+                + Duration::minutes(OIDC_LOGIN_REQUEST_EXPIRY_IN_MINUTES),
+        }
+    }
+
+    /// Marks the unexpired request with this state and redirect as used and returns it, `None`
+    /// if there is no such request or it has already been used
+    pub fn consume(
+        state: &str,
+        redirect_uri: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<OidcLoginRequest>, DatabaseError> {
+        diesel::update(
+            oidc_login_requests::table
+                .filter(oidc_login_requests::state.eq(state))
+                .filter(oidc_login_requests::redirect_uri.eq(redirect_uri))
+                .filter(oidc_login_requests::used_at.is_null())
+                .filter(oidc_login_requests::expires_at.gt(dsl::now)),
+        ).set((
+            oidc_login_requests::used_at.eq(dsl::now.nullable()),
+            oidc_login_requests::updated_at.eq(dsl::now),
+        )).get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not use login request")
+    }
+}
+
+fn generate_random_string(length: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .collect()
+}
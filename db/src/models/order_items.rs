@@ -30,6 +30,8 @@ pub struct OrderItem {
     pub ticket_pricing_id: Option<Uuid>,
     pub fee_schedule_range_id: Option<Uuid>,
     pub parent_id: Option<Uuid>,
+    /// Absorbed fees are included in the ticket price, they are not charged to the buyer
+    pub is_absorbed: bool,
 }
 
 impl OrderItem {
@@ -59,25 +61,51 @@ impl OrderItem {
     pub(crate) fn update_fees(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         let fee_item = self.find_fee_item(conn)?;
         let fee_schedule_range = FeeScheduleRange::find(self.fee_schedule_range_id.unwrap(), conn)?;
+        let is_absorbed = match self.event_id {
+            Some(event_id) => Event::find(event_id, conn)?.fee_mode(conn)? == FeeMode::Absorbed,
+            None => false,
+        };
 
         match fee_item {
             Some(mut fee_item) => {
-                fee_item.unit_price_in_cents = fee_schedule_range.fee_in_cents * self.quantity;
-                fee_item.update(conn)
+                fee_item.unit_price_in_cents =
+                    fee_schedule_range.calculate_fee(self.unit_price_in_cents) * self.quantity;
+                fee_item.is_absorbed = is_absorbed;
+                fee_item.update(conn)?;
             }
             None => {
                 NewFeesOrderItem {
                     order_id: self.order_id,
                     item_type: OrderItemTypes::PerUnitFees.to_string(),
                     event_id: self.event_id,
-                    unit_price_in_cents: fee_schedule_range.fee_in_cents * self.quantity,
+                    unit_price_in_cents: fee_schedule_range.calculate_fee(self.unit_price_in_cents)
+                        * self.quantity,
                     quantity: 1,
                     parent_id: Some(self.id),
+                    is_absorbed,
                 }.commit(conn)?;
-
-                Ok(())
             }
         }
+
+        // The event fee follows the event's current fee mode as well
+        if let Some(event_id) = self.event_id {
+            diesel::update(
+                order_items::table
+                    .filter(order_items::order_id.eq(self.order_id))
+                    .filter(order_items::event_id.eq(event_id))
+                    .filter(order_items::item_type.eq(OrderItemTypes::EventFees.to_string()))
+                    .filter(order_items::is_absorbed.ne(is_absorbed)),
+            ).set((
+                order_items::is_absorbed.eq(is_absorbed),
+                order_items::updated_at.eq(dsl::now),
+            )).execute(conn)
+            .to_db_error(
+                errors::ErrorCode::UpdateError,
+                "Could not update event fee order item",
+            )?;
+        }
+
+        Ok(())
     }
 
     pub(crate) fn update(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
@@ -86,6 +114,7 @@ impl OrderItem {
             .set((
                 order_items::unit_price_in_cents.eq(self.unit_price_in_cents),
                 order_items::quantity.eq(self.quantity),
+                order_items::is_absorbed.eq(self.is_absorbed),
                 order_items::updated_at.eq(dsl::now),
             )).execute(conn)
             .map(|_| ())
@@ -182,6 +211,7 @@ impl OrderItem {
             ON tp.ticket_type_id = tt.id
             ON oi.ticket_pricing_id = tp.id
         WHERE oi.order_id = $1
+          AND NOT oi.is_absorbed
         ORDER BY oi.item_type DESC
         "#,
         ).bind::<sql_types::Uuid, _>(order_id)
@@ -281,6 +311,7 @@ pub(crate) struct NewFeesOrderItem {
     pub quantity: i64,
     pub unit_price_in_cents: i64,
     pub parent_id: Option<Uuid>,
+    pub is_absorbed: bool,
 }
 
 impl NewFeesOrderItem {
@@ -6,13 +6,15 @@ use diesel::prelude::*;
 use diesel::sql_types;
 use diesel::sql_types::{BigInt, Nullable, Uuid as dUuid};
 use models::*;
-use schema::{order_items, orders, users};
+use schema::{events, order_items, orders, users};
 use serde_json;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use time::Duration;
 use utils::errors;
 use utils::errors::*;
 use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
 
 #[derive(Associations, Debug, Identifiable, PartialEq, Queryable)]
 #[belongs_to(User)]
@@ -159,14 +161,36 @@ impl Order {
         ticket_type_id: Uuid,
         quantity: u32,
         conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        self.add_tickets_with_presale_code(ticket_type_id, quantity, None, conn)
+    }
+
+    pub fn add_tickets_with_presale_code(
+        &self,
+        ticket_type_id: Uuid,
+        quantity: u32,
+        presale_code: Option<&str>,
+        conn: &PgConnection,
     ) -> Result<Vec<TicketInstance>, DatabaseError> {
         let ticket_pricing = TicketPricing::get_current_ticket_pricing(ticket_type_id, conn)?;
         let ticket_type = TicketType::find(ticket_type_id, conn)?;
 
+        if ticket_type.is_in_presale()
+            && !ticket_type.is_presale_eligible(self.user_id, presale_code, conn)?
+        {
+            let mut errors = ValidationErrors::new();
+            let mut validation_error = ValidationError::new(&"presale_only");
+            validation_error.message = Some(Cow::from(
+                "Tickets are currently only available to presale customers",
+            ));
+            validation_error.add_param(Cow::from("ticket_type_id"), &ticket_type_id);
+            errors.add("ticket_type_id", validation_error);
+            return Err(errors.into());
+        }
+
         let event = Event::find(ticket_type.event_id, conn)?;
-        let organization = Organization::find(event.organization_id, conn)?;
 
-        let fee_schedule_range = FeeSchedule::find(organization.fee_schedule_id, conn)?
+        let fee_schedule_range = FeeSchedule::find_for_ticket_type(&ticket_type, conn)?
             .get_range(ticket_pricing.price_in_cents, conn)?
             .unwrap();
 
@@ -273,6 +297,7 @@ impl Order {
                     unit_price_in_cents: 0,
                     quantity: 1,
                     parent_id: None,
+                    is_absorbed: event.fee_mode(conn)? == FeeMode::Absorbed,
                 };
                 let organization = Organization::find(event.organization_id, conn)?;
                 if event.fee_in_cents.is_some() {
@@ -471,6 +496,17 @@ impl Order {
             for item in &order_items {
                 TicketInstance::mark_as_purchased(item, self.user_id, conn)?;
             }
+            LedgerEntry::create_for_order(self, conn)?;
+            for organization_id in self.organization_ids(conn)? {
+                DomainEvent::create(
+                    DomainEventTypes::OrderPaid,
+                    "Order was paid".to_string(),
+                    Tables::Orders,
+                    Some(self.id),
+                    Some(json!({ "order_id": self.id, "user_id": self.user_id })),
+                ).for_organization(organization_id)
+                .commit(conn)?;
+            }
             let cart_user: Option<User> = users::table
                 .filter(users::last_cart_id.eq(self.id))
                 .get_result(conn)
@@ -486,6 +522,17 @@ impl Order {
         Ok(())
     }
 
+    /// Organizations whose events have items in this order
+    pub fn organization_ids(&self, conn: &PgConnection) -> Result<Vec<Uuid>, DatabaseError> {
+        order_items::table
+            .inner_join(events::table)
+            .filter(order_items::order_id.eq(self.id))
+            .select(events::organization_id)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load order organizations")
+    }
+
     pub fn total_paid(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
         #[derive(QueryableByName)]
         struct ResultForSum {
@@ -523,7 +570,8 @@ impl Order {
         let order_items = self.items(conn)?;
         let mut total = 0;
 
-        for item in &order_items {
+        // Absorbed fees are already included in the ticket price
+        for item in order_items.iter().filter(|i| !i.is_absorbed) {
             total += item.unit_price_in_cents * item.quantity;
         }
 
@@ -0,0 +1,218 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{scopes, Organization, Roles, User};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use schema::{organization_api_key_requests, organization_api_keys};
+use std::borrow::Cow;
+use utils::errors::*;
+use utils::passwords::PasswordHash;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+const PREFIX_LENGTH: usize = 12;
+const SECRET_LENGTH: usize = 32;
+
+/// Credential for server to server integrations with an organization. The key is shown once as
+/// `<prefix>.<secret>`; the prefix is stored to look the key up and the secret is hashed like a
+/// password. Requests made with the key act as its service user and are limited to its scopes
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "organization_api_keys"]
+pub struct OrganizationApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub service_user_id: Uuid,
+    pub created_by: Uuid,
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub hashed_secret: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_keys"]
+struct NewOrganizationApiKey {
+    organization_id: Uuid,
+    service_user_id: Uuid,
+    created_by: Uuid,
+    name: String,
+    prefix: String,
+    hashed_secret: String,
+    scopes: Vec<String>,
+}
+
+/// Audit record of a request authenticated with an organization API key
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(OrganizationApiKey)]
+#[table_name = "organization_api_key_requests"]
+pub struct OrganizationApiKeyRequest {
+    pub id: Uuid,
+    pub organization_api_key_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_key_requests"]
+struct NewOrganizationApiKeyRequest<'a> {
+    organization_api_key_id: Uuid,
+    method: &'a str,
+    path: &'a str,
+    ip_address: Option<&'a str>,
+}
+
+impl OrganizationApiKey {
+    /// Scopes that can be granted to an API key
+    pub fn available_scopes() -> Vec<String> {
+        scopes::get_scopes(vec![Roles::OrgOwner.to_string()])
+    }
+
+    /// Creates the key and returns it along with the plain text key, which can not be
+    /// recovered later
+    pub fn create(
+        organization_id: Uuid,
+        name: &str,
+        scopes: Vec<String>,
+        created_by: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(OrganizationApiKey, String), DatabaseError> {
+        let available_scopes = OrganizationApiKey::available_scopes();
+        if scopes.is_empty() || scopes.iter().any(|s| !available_scopes.contains(s)) {
+            let mut errors = ValidationErrors::new();
+            let mut validation_error = ValidationError::new(&"invalid_scopes");
+            validation_error.message = Some(Cow::from(format!(
+                "Scopes must be one or more of: {}",
+                available_scopes.join(", ")
+            )));
+            errors.add("scopes", validation_error);
+            return Err(errors.into());
+        }
+
+        let service_user = User::create_service_user(name).commit(conn)?;
+        let prefix = generate_random_string(PREFIX_LENGTH);
+        let secret = generate_random_string(SECRET_LENGTH);
+        let api_key: OrganizationApiKey = diesel::insert_into(organization_api_keys::table)
+            .values(&NewOrganizationApiKey {
+                organization_id,
+                service_user_id: service_user.id,
+                created_by,
+                name: name.to_string(),
+                prefix: prefix.clone(),
+                hashed_secret: PasswordHash::generate(&secret, None).to_string(),
+                scopes,
+            }).get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create API key")?;
+
+        Ok((api_key, format!("{}.{}", prefix, secret)))
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        organization_api_keys::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load API key")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationApiKey>, DatabaseError> {
+        organization_api_keys::table
+            .filter(organization_api_keys::organization_id.eq(organization_id))
+            .order_by(organization_api_keys::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load API keys")
+    }
+
+    /// Finds the active key matching the plain text key
+    pub fn find_by_key(
+        key: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<OrganizationApiKey>, DatabaseError> {
+        let mut parts = key.splitn(2, '.');
+        let (prefix, secret) = match (parts.next(), parts.next()) {
+            (Some(prefix), Some(secret)) => (prefix, secret),
+            _ => return Ok(None),
+        };
+
+        let api_key: Option<OrganizationApiKey> = organization_api_keys::table
+            .filter(organization_api_keys::prefix.eq(prefix))
+            .filter(organization_api_keys::revoked_at.is_null())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load API key")?;
+
+        Ok(api_key.and_then(
+            |api_key| match PasswordHash::from_str(&api_key.hashed_secret) {
+                Ok(ref hash) if hash.verify(secret) => Some(api_key),
+                _ => None,
+            },
+        ))
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    pub fn revoke(&self, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        diesel::update(self)
+            .set((
+                organization_api_keys::revoked_at.eq(dsl::now.nullable()),
+                organization_api_keys::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke API key")
+    }
+
+    /// Updates the last used time and records the request in the key's audit log
+    pub fn record_use(
+        &self,
+        method: &str,
+        path: &str,
+        ip_address: Option<&str>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationApiKeyRequest, DatabaseError> {
+        diesel::update(self)
+            .set(organization_api_keys::last_used_at.eq(dsl::now.nullable()))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update API key")?;
+
+        diesel::insert_into(organization_api_key_requests::table)
+            .values(&NewOrganizationApiKeyRequest {
+                organization_api_key_id: self.id,
+                method,
+                path,
+                ip_address,
+            }).get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not record API key request")
+    }
+
+    pub fn requests(
+        &self,
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationApiKeyRequest>, DatabaseError> {
+        organization_api_key_requests::table
+            .filter(organization_api_key_requests::organization_api_key_id.eq(self.id))
+            .order_by(organization_api_key_requests::created_at.desc())
+            .limit(limit)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load API key requests")
+    }
+}
+
+fn generate_random_string(length: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .collect()
+}
@@ -0,0 +1,118 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A user who bought, was comped or was transferred a ticket for one of an organization's
+/// events, along with their totals across all of that organization's events
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct OrganizationCustomer {
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Text"]
+    pub first_name: String,
+    #[sql_type = "Text"]
+    pub last_name: String,
+    #[sql_type = "Nullable<Text>"]
+    pub email: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub phone: Option<String>,
+    #[sql_type = "BigInt"]
+    pub lifetime_spent_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub ticket_count: i64,
+    #[sql_type = "BigInt"]
+    pub events_attended: i64,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub first_purchase_at: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub last_purchase_at: Option<NaiveDateTime>,
+    /// Names of the organization's events the customer has marked as interesting
+    #[sql_type = "Array<Text>"]
+    pub interests: Vec<String>,
+}
+
+#[derive(QueryableByName)]
+struct OrganizationCustomerCount {
+    #[sql_type = "BigInt"]
+    total: i64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct OrganizationCustomerFilter {
+    /// Matched against the customer's name, email and phone
+    pub query: Option<String>,
+    /// Only customers with tickets for this event, totals still cover all events
+    pub event_id: Option<Uuid>,
+    pub min_spent_in_cents: Option<i64>,
+    pub min_events_attended: Option<i64>,
+    pub purchased_after: Option<NaiveDateTime>,
+    pub purchased_before: Option<NaiveDateTime>,
+}
+
+impl OrganizationCustomer {
+    /// Returns a page of the organization's customers ordered by name, along with the total
+    /// number of customers matching the filter. A limit of `None` returns every customer.
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        filter: &OrganizationCustomerFilter,
+        limit: Option<i64>,
+        offset: i64,
+        conn: &PgConnection,
+    ) -> Result<(Vec<OrganizationCustomer>, i64), DatabaseError> {
+        let customers_query = include_str!("../queries/organization_customers.sql");
+        // Wildcards typed by the user are matched literally
+        let query = filter
+            .query
+            .as_ref()
+            .filter(|q| !q.is_empty())
+            .map(|q| escape_like(q));
+
+        let count: OrganizationCustomerCount = diesel::sql_query(format!(
+            "SELECT COUNT(*) AS total FROM ({}) customers",
+            customers_query
+        )).bind::<dUuid, _>(organization_id)
+        .bind::<Nullable<Text>, _>(query.as_ref())
+        .bind::<Nullable<dUuid>, _>(filter.event_id)
+        .bind::<Nullable<BigInt>, _>(filter.min_spent_in_cents)
+        .bind::<Nullable<BigInt>, _>(filter.min_events_attended)
+        .bind::<Nullable<Timestamp>, _>(filter.purchased_after)
+        .bind::<Nullable<Timestamp>, _>(filter.purchased_before)
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not count organization customers",
+        )?;
+
+        let customers: Vec<OrganizationCustomer> = diesel::sql_query(format!(
+            "SELECT * FROM ({}) customers \
+             ORDER BY last_name, first_name, user_id LIMIT $8 OFFSET $9",
+            customers_query
+        )).bind::<dUuid, _>(organization_id)
+        .bind::<Nullable<Text>, _>(query.as_ref())
+        .bind::<Nullable<dUuid>, _>(filter.event_id)
+        .bind::<Nullable<BigInt>, _>(filter.min_spent_in_cents)
+        .bind::<Nullable<BigInt>, _>(filter.min_events_attended)
+        .bind::<Nullable<Timestamp>, _>(filter.purchased_after)
+        .bind::<Nullable<Timestamp>, _>(filter.purchased_before)
+        .bind::<Nullable<BigInt>, _>(limit)
+        .bind::<BigInt, _>(offset)
+        .load(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not load organization customers",
+        )?;
+
+        Ok((customers, count.total))
+    }
+}
+
+/// Escapes the LIKE wildcards and escape character so the value only matches itself
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
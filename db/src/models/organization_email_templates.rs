@@ -0,0 +1,278 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::organization_email_templates;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use utils::errors::*;
+use utils::templates;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+/// Organization specific replacement for one of the default emails sent to buyers
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[table_name = "organization_email_templates"]
+pub struct OrganizationEmailTemplate {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub template_type: String,
+    pub subject: String,
+    pub body: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_email_templates"]
+pub struct NewOrganizationEmailTemplate {
+    pub organization_id: Uuid,
+    pub template_type: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "organization_email_templates"]
+pub struct OrganizationEmailTemplateEditableAttributes {
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+/// Subject and body of an email with all placeholders replaced
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RenderedEmailTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailTemplateTypes {
+    /// Placeholders templates of this type can use, along with the value used for previews
+    pub fn placeholders(&self) -> Vec<(&'static str, &'static str)> {
+        let mut placeholders = vec![
+            ("recipient_name", "Jane Doe"),
+            ("organization_name", "The Venue"),
+        ];
+        placeholders.extend(match self {
+            EmailTemplateTypes::TicketConfirmation => vec![
+                ("order_id", "a1b2c3d4"),
+                ("event_names", "The Band Live"),
+                ("ticket_count", "2"),
+                ("tickets_link", "https://example.com/orders/a1b2c3d4"),
+            ],
+            EmailTemplateTypes::TicketTransfer => vec![
+                ("sender_name", "John Doe"),
+                ("ticket_count", "2"),
+                ("receive_tickets_link", "https://example.com/tickets/receive"),
+            ],
+            EmailTemplateTypes::EventReminder => vec![
+                ("event_name", "The Band Live"),
+                ("event_start", "2019-01-01 20:00"),
+                ("venue_name", "The Venue"),
+                ("tickets_link", "https://example.com/events/a1b2c3d4"),
+            ],
+        });
+        placeholders
+    }
+
+    pub fn sample_values(&self) -> HashMap<&'static str, String> {
+        self.placeholders()
+            .into_iter()
+            .map(|(name, sample)| (name, sample.to_string()))
+            .collect()
+    }
+
+    pub fn default_subject(&self) -> &'static str {
+        match self {
+            EmailTemplateTypes::TicketConfirmation => "Your tickets for {{event_names}}",
+            EmailTemplateTypes::TicketTransfer => "{{sender_name}} has sent you some tickets",
+            EmailTemplateTypes::EventReminder => "Reminder: {{event_name}} is coming up",
+        }
+    }
+
+    pub fn default_body(&self) -> &'static str {
+        match self {
+            EmailTemplateTypes::TicketConfirmation => {
+                "Hi {{recipient_name}}\nThank you for your order of {{ticket_count}} ticket(s) for {{event_names}}.\nYou can view your tickets here: {{tickets_link}}"
+            }
+            EmailTemplateTypes::TicketTransfer => {
+                "This link to receive the tickets is valid for 7 days: {{receive_tickets_link}}\nIf you did not request it please ignore this message."
+            }
+            EmailTemplateTypes::EventReminder => {
+                "Hi {{recipient_name}}\n{{event_name}} at {{venue_name}} starts at {{event_start}}.\nYou can view your tickets here: {{tickets_link}}"
+            }
+        }
+    }
+}
+
+impl NewOrganizationEmailTemplate {
+    pub fn commit(&self, conn: &PgConnection) -> Result<OrganizationEmailTemplate, DatabaseError> {
+        let template_type = self.template_type.parse::<EmailTemplateTypes>().map_err(|_| {
+            DatabaseError::new(
+                ErrorCode::InvalidInput,
+                Some("Invalid email template type".to_string()),
+            )
+        })?;
+        OrganizationEmailTemplate::render(
+            template_type,
+            &self.subject,
+            &self.body,
+            &template_type.sample_values(),
+        )?;
+
+        diesel::insert_into(organization_email_templates::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create email template")
+    }
+}
+
+impl OrganizationEmailTemplate {
+    pub fn create(
+        organization_id: Uuid,
+        template_type: EmailTemplateTypes,
+        subject: String,
+        body: String,
+    ) -> NewOrganizationEmailTemplate {
+        NewOrganizationEmailTemplate {
+            organization_id,
+            template_type: template_type.to_string(),
+            subject,
+            body,
+        }
+    }
+
+    pub fn update(
+        &self,
+        attributes: OrganizationEmailTemplateEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<OrganizationEmailTemplate, DatabaseError> {
+        let template_type = self.template_type();
+        OrganizationEmailTemplate::render(
+            template_type,
+            attributes.subject.as_ref().unwrap_or(&self.subject),
+            attributes.body.as_ref().unwrap_or(&self.body),
+            &template_type.sample_values(),
+        )?;
+
+        diesel::update(self)
+            .set((
+                attributes,
+                organization_email_templates::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update email template")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationEmailTemplate>, DatabaseError> {
+        organization_email_templates::table
+            .filter(organization_email_templates::organization_id.eq(organization_id))
+            .order_by(organization_email_templates::template_type)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load email templates")
+    }
+
+    pub fn find_by_type(
+        organization_id: Uuid,
+        template_type: EmailTemplateTypes,
+        conn: &PgConnection,
+    ) -> Result<OrganizationEmailTemplate, DatabaseError> {
+        organization_email_templates::table
+            .filter(organization_email_templates::organization_id.eq(organization_id))
+            .filter(organization_email_templates::template_type.eq(template_type.to_string()))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load email template")
+    }
+
+    pub fn template_type(&self) -> EmailTemplateTypes {
+        self.template_type.parse::<EmailTemplateTypes>().unwrap()
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete email template")
+    }
+
+    /// Renders the organization's template, falling back to the default template when the
+    /// organization has not replaced it
+    pub fn render_for_organization(
+        organization_id: Uuid,
+        template_type: EmailTemplateTypes,
+        values: &HashMap<&str, String>,
+        conn: &PgConnection,
+    ) -> Result<RenderedEmailTemplate, DatabaseError> {
+        match OrganizationEmailTemplate::find_by_type(organization_id, template_type, conn)
+            .optional()?
+        {
+            Some(template) => OrganizationEmailTemplate::render(
+                template_type,
+                &template.subject,
+                &template.body,
+                values,
+            ),
+            None => OrganizationEmailTemplate::render(
+                template_type,
+                template_type.default_subject(),
+                template_type.default_body(),
+                values,
+            ),
+        }
+    }
+
+    /// Renders a subject and body, only the placeholders of the template type may be used
+    pub fn render(
+        template_type: EmailTemplateTypes,
+        subject: &str,
+        body: &str,
+        values: &HashMap<&str, String>,
+    ) -> Result<RenderedEmailTemplate, DatabaseError> {
+        let values: HashMap<&str, String> = template_type
+            .placeholders()
+            .into_iter()
+            .map(|(name, _)| (name, values.get(name).cloned().unwrap_or_default()))
+            .collect();
+
+        // The subject is sent as a header so line breaks in values, such as event names, would
+        // allow injecting extra headers
+        let subject_values: HashMap<&str, String> = values
+            .iter()
+            .map(|(name, value)| (*name, value.replace(|c: char| c == '\r' || c == '\n', " ")))
+            .collect();
+
+        let mut errors = ValidationErrors::new();
+        let rendered_subject = templates::render(subject, &subject_values);
+        if let Err(ref message) = rendered_subject {
+            let mut validation_error = ValidationError::new(&"invalid_template");
+            validation_error.message = Some(Cow::from(message.clone()));
+            errors.add("subject", validation_error);
+        }
+        // Only line breaks in the template itself are left
+        if let Ok(ref rendered) = rendered_subject {
+            if rendered.contains('\r') || rendered.contains('\n') {
+                let mut validation_error = ValidationError::new(&"invalid_template");
+                validation_error.message = Some(Cow::from("Subject cannot contain line breaks"));
+                errors.add("subject", validation_error);
+            }
+        }
+        let rendered_body = templates::render(body, &values);
+        if let Err(ref message) = rendered_body {
+            let mut validation_error = ValidationError::new(&"invalid_template");
+            validation_error.message = Some(Cow::from(message.clone()));
+            errors.add("body", validation_error);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        Ok(RenderedEmailTemplate {
+            subject: rendered_subject.unwrap(),
+            body: rendered_body.unwrap(),
+        })
+    }
+}
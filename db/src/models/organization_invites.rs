@@ -5,10 +5,11 @@ use diesel::prelude::*;
 use diesel::sql_types::{Text, Timestamp};
 use models::*;
 use schema::organization_invites;
+use std::borrow::Cow;
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::{DatabaseError, ErrorCode};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError, ValidationErrors};
 
 const INVITE_EXPIRATION_PERIOD_IN_DAYS: i64 = 7;
 
@@ -39,6 +40,9 @@ pub struct OrganizationInvite {
     pub accepted: Option<i16>,
     pub updated_at: NaiveDateTime,
     pub sent_invite: bool,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub organization_role_id: Option<Uuid>,
 }
 
 #[derive(Insertable, PartialEq, Debug, Deserialize, Validate)]
@@ -50,6 +54,8 @@ pub struct NewOrganizationInvite {
     pub user_email: String,
     pub security_token: Option<Uuid>,
     pub user_id: Option<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub organization_role_id: Option<Uuid>,
 }
 
 #[derive(Debug, Queryable, Serialize, QueryableByName)]
@@ -65,7 +71,19 @@ pub struct DisplayInvite {
 impl NewOrganizationInvite {
     pub fn commit(&mut self, conn: &PgConnection) -> Result<OrganizationInvite, DatabaseError> {
         self.security_token = Some(Uuid::new_v4());
+        self.expires_at = Some(OrganizationInvite::new_expiry_date());
         self.validate()?;
+        if let Some(organization_role_id) = self.organization_role_id {
+            let role = OrganizationRole::find(organization_role_id, conn)?;
+            if role.organization_id != self.organization_id {
+                let mut errors = ValidationErrors::new();
+                let mut validation_error = ValidationError::new(&"invalid_role");
+                validation_error.message =
+                    Some(Cow::from("Role does not belong to this organization"));
+                errors.add("organization_role_id", validation_error);
+                return Err(errors.into());
+            }
+        }
         let res = diesel::insert_into(organization_invites::table)
             .values(&*self)
             .get_result(conn);
@@ -86,7 +104,122 @@ impl OrganizationInvite {
             user_email: email.into(),
             security_token: None,
             user_id,
+            expires_at: None,
+            organization_role_id: None,
+        }
+    }
+
+    fn new_expiry_date() -> NaiveDateTime {
+        Utc::now().naive_utc() + Duration::days(INVITE_EXPIRATION_PERIOD_IN_DAYS)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationInvite, DatabaseError> {
+        organization_invites::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Cannot find organization invite")
+    }
+
+    /// Invites for the organization, newest first, optionally only those in the given status
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        status: Option<OrganizationInviteStatus>,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationInvite>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let mut query = organization_invites::table
+            .filter(organization_invites::organization_id.eq(organization_id))
+            .into_boxed();
+
+        query = match status {
+            Some(OrganizationInviteStatus::Pending) => query
+                .filter(organization_invites::accepted.is_null())
+                .filter(organization_invites::revoked_at.is_null())
+                .filter(organization_invites::expires_at.gt(now)),
+            Some(OrganizationInviteStatus::Accepted) => {
+                query.filter(organization_invites::accepted.eq(1))
+            }
+            Some(OrganizationInviteStatus::Declined) => query
+                .filter(organization_invites::accepted.eq(0))
+                .filter(organization_invites::revoked_at.is_null()),
+            Some(OrganizationInviteStatus::Expired) => query
+                .filter(organization_invites::accepted.is_null())
+                .filter(organization_invites::revoked_at.is_null())
+                .filter(organization_invites::expires_at.le(now)),
+            Some(OrganizationInviteStatus::Revoked) => {
+                query.filter(organization_invites::revoked_at.is_not_null())
+            }
+            None => query,
+        };
+
+        query
+            .order_by(organization_invites::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Cannot find organization invites")
+    }
+
+    pub fn status(&self) -> OrganizationInviteStatus {
+        if self.revoked_at.is_some() {
+            OrganizationInviteStatus::Revoked
+        } else if self.accepted == Some(1) {
+            OrganizationInviteStatus::Accepted
+        } else if self.accepted.is_some() {
+            OrganizationInviteStatus::Declined
+        } else if self.expires_at <= Utc::now().naive_utc() {
+            OrganizationInviteStatus::Expired
+        } else {
+            OrganizationInviteStatus::Pending
+        }
+    }
+
+    /// Issues a new security token and expiry date so the invite can be sent again. Links in
+    /// previously sent emails stop working
+    pub fn resend(&self, conn: &PgConnection) -> Result<OrganizationInvite, DatabaseError> {
+        match self.status() {
+            OrganizationInviteStatus::Pending | OrganizationInviteStatus::Expired => (),
+            _ => return Err(OrganizationInvite::status_error("resend")),
         }
+
+        diesel::update(self)
+            .set((
+                organization_invites::security_token.eq(Some(Uuid::new_v4())),
+                organization_invites::expires_at.eq(OrganizationInvite::new_expiry_date()),
+                organization_invites::sent_invite.eq(false),
+                organization_invites::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not resend organization invite",
+            )
+    }
+
+    pub fn revoke(&self, conn: &PgConnection) -> Result<OrganizationInvite, DatabaseError> {
+        match self.status() {
+            OrganizationInviteStatus::Pending | OrganizationInviteStatus::Expired => (),
+            _ => return Err(OrganizationInvite::status_error("revoke")),
+        }
+
+        diesel::update(self)
+            .set((
+                organization_invites::security_token.eq(None::<Uuid>),
+                organization_invites::revoked_at.eq(dsl::now.nullable()),
+                organization_invites::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not revoke organization invite",
+            )
+    }
+
+    fn status_error(action: &str) -> DatabaseError {
+        let mut errors = ValidationErrors::new();
+        let mut validation_error = ValidationError::new(&"invalid_status");
+        validation_error.message = Some(Cow::from(format!(
+            "Only pending or expired invites can be {}d",
+            action
+        )));
+        errors.add("status", validation_error);
+        errors.into()
     }
 
     pub fn change_invite_status(
@@ -119,24 +252,22 @@ impl OrganizationInvite {
         token: &Uuid,
         conn: &PgConnection,
     ) -> Result<DisplayInvite, DatabaseError> {
-        let expiry_date = Utc::now().naive_utc() - Duration::days(INVITE_EXPIRATION_PERIOD_IN_DAYS);
         let query = r#"
                 SELECT
                     CONCAT(users.first_name, ' ',  users.last_name) AS inviter_name,
                     organizations.name AS organization_name,
-                    organization_invites.created_at + INTERVAL '$1' day AS expires_at
+                    organization_invites.expires_at AS expires_at
                 FROM organization_invites
                 LEFT JOIN users ON (users.id = organization_invites.inviter_id)
                 LEFT JOIN organizations ON (organizations.id = organization_invites.organization_id)
                 WHERE
-                    organization_invites.security_token = $2
-                    AND organization_invites.created_at > $3
+                    organization_invites.security_token = $1
+                    AND organization_invites.expires_at > $2
                     AND organization_invites.accepted is NULL;"#;
 
         diesel::sql_query(query)
-            .bind::<diesel::sql_types::BigInt, _>(INVITE_EXPIRATION_PERIOD_IN_DAYS)
             .bind::<diesel::sql_types::Uuid, _>(token)
-            .bind::<diesel::sql_types::Timestamp, _>(expiry_date)
+            .bind::<diesel::sql_types::Timestamp, _>(Utc::now().naive_utc())
             .get_result(conn)
             .to_db_error(ErrorCode::QueryError, "Cannot find organization invite")
     }
@@ -145,25 +276,27 @@ impl OrganizationInvite {
         token: &Uuid,
         conn: &PgConnection,
     ) -> Result<OrganizationInvite, DatabaseError> {
-        let expiry_date = Utc::now().naive_utc() - Duration::days(INVITE_EXPIRATION_PERIOD_IN_DAYS);
         DatabaseError::wrap(
             ErrorCode::AccessError,
             "No valid token found",
             organization_invites::table
                 .filter(organization_invites::accepted.is_null())
                 .filter(organization_invites::security_token.eq(token))
-                .filter(organization_invites::created_at.gt(expiry_date))
+                .filter(organization_invites::expires_at.gt(Utc::now().naive_utc()))
                 .get_result(conn),
         )
     }
 
     pub fn find_active_invite_by_email(
+        organization_id: Uuid,
         email: &String,
         conn: &PgConnection,
     ) -> Result<Option<OrganizationInvite>, DatabaseError> {
         organization_invites::table
+            .filter(organization_invites::organization_id.eq(organization_id))
             .filter(organization_invites::user_email.eq(email))
             .filter(organization_invites::security_token.is_not_null())
+            .filter(organization_invites::expires_at.gt(Utc::now().naive_utc()))
             .first::<OrganizationInvite>(conn)
             .optional()
             .to_db_error(ErrorCode::QueryError, "Cannot find organization invite")
@@ -0,0 +1,156 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{scopes, Organization, Roles};
+use schema::{organization_invites, organization_roles, organization_users};
+use std::borrow::Cow;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+/// Role defined by an organization, e.g. "Door Staff" or "Finance". Members assigned a role only
+/// receive its scopes within the organization instead of the default member scopes
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "organization_roles"]
+pub struct OrganizationRole {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_roles"]
+pub struct NewOrganizationRole {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "organization_roles"]
+pub struct OrganizationRoleEditableAttributes {
+    pub name: Option<String>,
+    pub scopes: Option<Vec<String>>,
+}
+
+impl NewOrganizationRole {
+    pub fn commit(&self, conn: &PgConnection) -> Result<OrganizationRole, DatabaseError> {
+        OrganizationRole::validate_scopes(&self.scopes)?;
+
+        diesel::insert_into(organization_roles::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create organization role")
+    }
+}
+
+impl OrganizationRole {
+    /// Scopes that can be granted to an organization role
+    pub fn available_scopes() -> Vec<String> {
+        scopes::get_scopes(vec![Roles::OrgOwner.to_string()])
+    }
+
+    pub fn create(organization_id: Uuid, name: &str, scopes: Vec<String>) -> NewOrganizationRole {
+        NewOrganizationRole {
+            organization_id,
+            name: name.to_string(),
+            scopes,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationRole, DatabaseError> {
+        organization_roles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization role")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationRole>, DatabaseError> {
+        organization_roles::table
+            .filter(organization_roles::organization_id.eq(organization_id))
+            .order_by(organization_roles::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization roles")
+    }
+
+    pub fn update(
+        &self,
+        attributes: OrganizationRoleEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<OrganizationRole, DatabaseError> {
+        if let Some(ref scopes) = attributes.scopes {
+            OrganizationRole::validate_scopes(scopes)?;
+        }
+
+        diesel::update(self)
+            .set((attributes, organization_roles::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update organization role")
+    }
+
+    /// Roles still assigned to members or to invites that can still be accepted can not be
+    /// removed, otherwise those members would fall back to the broader default member scopes
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let assigned: bool = select(exists(
+            organization_users::table.filter(organization_users::organization_role_id.eq(self.id)),
+        )).get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check role assignments")?;
+        if assigned {
+            return Err(OrganizationRole::in_use_error(
+                "role_assigned",
+                "Role must be unassigned from all members before it can be removed",
+            ));
+        }
+
+        // Expired invites can still be resent so they keep the role too
+        let invited: bool = select(exists(
+            organization_invites::table
+                .filter(organization_invites::organization_role_id.eq(self.id))
+                .filter(organization_invites::accepted.is_null())
+                .filter(organization_invites::revoked_at.is_null()),
+        )).get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check role invites")?;
+        if invited {
+            return Err(OrganizationRole::in_use_error(
+                "role_invited",
+                "Invites for the role must be revoked before it can be removed",
+            ));
+        }
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove organization role")
+    }
+
+    fn in_use_error(code: &'static str, message: &'static str) -> DatabaseError {
+        let mut errors = ValidationErrors::new();
+        let mut validation_error = ValidationError::new(code);
+        validation_error.message = Some(Cow::from(message));
+        errors.add("id", validation_error);
+        errors.into()
+    }
+
+    fn validate_scopes(scopes: &[String]) -> Result<(), DatabaseError> {
+        let available_scopes = OrganizationRole::available_scopes();
+        if scopes.is_empty() || scopes.iter().any(|s| !available_scopes.contains(s)) {
+            let mut errors = ValidationErrors::new();
+            let mut validation_error = ValidationError::new(&"invalid_scopes");
+            validation_error.message = Some(Cow::from(format!(
+                "Scopes must be one or more of: {}",
+                available_scopes.join(", ")
+            )));
+            errors.add("scopes", validation_error);
+            return Err(errors.into());
+        }
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@ pub struct OrganizationUser {
     pub user_id: Uuid,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub organization_role_id: Option<Uuid>,
 }
 
 #[derive(Insertable)]
@@ -47,6 +48,18 @@ impl OrganizationUser {
         }
     }
 
+    pub fn find_for_user(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OrganizationUser, DatabaseError> {
+        organization_users::table
+            .filter(organization_users::organization_id.eq(organization_id))
+            .filter(organization_users::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization user")
+    }
+
     pub fn find_users_by_organization(
         organization_id: Uuid,
         conn: &PgConnection,
@@ -3,13 +3,17 @@ use diesel;
 use diesel::dsl::{exists, select};
 use diesel::expression::dsl;
 use diesel::prelude::*;
+use diesel::sql_types::{Array, Uuid as dUuid};
 use models::scopes;
 use models::*;
-use schema::{organization_users, organizations, users, venues};
+use schema::{organization_roles, organization_users, organizations, users, venues};
+use std::borrow::Cow;
 use utils::errors::*;
 use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
+use validators;
 
-#[derive(Identifiable, Associations, Queryable, AsChangeset)]
+#[derive(Identifiable, Associations, Queryable, QueryableByName, AsChangeset)]
 #[belongs_to(User, foreign_key = "owner_user_id")]
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "organizations"]
@@ -27,6 +31,16 @@ pub struct Organization {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub fee_schedule_id: Uuid,
+    pub require_two_factor: bool,
+    pub fee_mode: String,
+    /// Members of the parent organization have the same roles within its children
+    pub parent_id: Option<Uuid>,
+    pub logo_url: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    /// Name buyers see as the sender of emails about this organization's events
+    pub email_sender_name: Option<String>,
+    pub email_reply_to: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -49,6 +63,7 @@ pub struct NewOrganization {
     pub country: Option<String>,
     pub postal_code: Option<String>,
     pub phone: Option<String>,
+    pub parent_id: Option<Uuid>,
 }
 
 impl NewOrganization {
@@ -62,7 +77,7 @@ impl NewOrganization {
     }
 }
 
-#[derive(AsChangeset, Default, Deserialize)]
+#[derive(AsChangeset, Default, Deserialize, Validate)]
 #[table_name = "organizations"]
 pub struct OrganizationEditableAttributes {
     pub name: Option<String>,
@@ -74,6 +89,19 @@ pub struct OrganizationEditableAttributes {
     pub phone: Option<String>,
     pub fee_schedule_id: Option<Uuid>,
     pub event_fee_in_cents: Option<i64>,
+    pub require_two_factor: Option<bool>,
+    #[validate(custom = "validators::validate_fee_mode")]
+    pub fee_mode: Option<String>,
+    #[validate(url)]
+    pub logo_url: Option<String>,
+    #[validate(custom = "validators::validate_color")]
+    pub primary_color: Option<String>,
+    #[validate(custom = "validators::validate_color")]
+    pub secondary_color: Option<String>,
+    #[validate(custom = "validators::validate_email_sender_name")]
+    pub email_sender_name: Option<String>,
+    #[validate(email)]
+    pub email_reply_to: Option<String>,
 }
 
 impl Organization {
@@ -91,6 +119,7 @@ impl Organization {
         attributes: OrganizationEditableAttributes,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        attributes.validate()?;
         diesel::update(self)
             .set((attributes, organizations::updated_at.eq(dsl::now)))
             .get_result(conn)
@@ -113,6 +142,68 @@ impl Organization {
             )
     }
 
+    /// Moves the organization below another organization, passing `None` makes it a top level
+    /// organization again
+    pub fn set_parent(
+        &self,
+        parent_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Organization, DatabaseError> {
+        if let Some(parent_id) = parent_id {
+            let parent = Organization::find(parent_id, conn)?;
+            if parent.id == self.id || parent.ancestors(conn)?.iter().any(|o| o.id == self.id) {
+                let mut errors = ValidationErrors::new();
+                let mut validation_error = ValidationError::new(&"invalid_parent");
+                validation_error.message = Some(Cow::from(
+                    "Organization cannot be a child of itself or of one of its children",
+                ));
+                errors.add("parent_id", validation_error);
+                return Err(errors.into());
+            }
+        }
+
+        diesel::update(self)
+            .set((
+                organizations::parent_id.eq(parent_id),
+                organizations::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update organization parent",
+            )
+    }
+
+    pub fn children(&self, conn: &PgConnection) -> Result<Vec<Organization>, DatabaseError> {
+        organizations::table
+            .filter(organizations::parent_id.eq(self.id))
+            .order_by(organizations::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child organizations")
+    }
+
+    /// The organization's parent, its parent's parent and so on
+    pub fn ancestors(&self, conn: &PgConnection) -> Result<Vec<Organization>, DatabaseError> {
+        diesel::sql_query(include_str!("../queries/organization_ancestors.sql"))
+            .bind::<dUuid, _>(self.id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load parent organizations")
+    }
+
+    /// Children of the organization and all of their children, ordered by name
+    pub fn descendants(&self, conn: &PgConnection) -> Result<Vec<Organization>, DatabaseError> {
+        Organization::descendants_of(&[self.id], conn)
+    }
+
+    fn descendants_of(
+        organization_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<Organization>, DatabaseError> {
+        diesel::sql_query(include_str!("../queries/organization_descendants.sql"))
+            .bind::<Array<dUuid>, _>(organization_ids)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child organizations")
+    }
+
     pub fn users(&self, conn: &PgConnection) -> Result<Vec<User>, DatabaseError> {
         let organization_users = OrganizationUser::belonging_to(self);
         let organization_owner = users::table
@@ -146,18 +237,137 @@ impl Organization {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve venues")
     }
 
+    /// Scopes the user has within this organization, including those inherited from the roles
+    /// they hold in any of its parent organizations
     pub fn get_scopes_for_user(
         &self,
         user: &User,
         conn: &PgConnection,
     ) -> Result<Vec<String>, DatabaseError> {
-        Ok(scopes::get_scopes(self.get_roles_for_user(user, conn)?))
+        let mut scopes = self.get_direct_scopes_for_user(user, conn)?;
+        for ancestor in self.ancestors(conn)? {
+            scopes.extend(ancestor.get_direct_scopes_for_user(user, conn)?);
+        }
+        scopes.sort();
+        scopes.dedup();
+        Ok(scopes)
     }
 
+    /// Whether the user needs to have signed in with a second factor to use `scope` within this
+    /// organization, either because the organization requires it or because a parent
+    /// organization the scope is inherited from does
+    pub fn requires_two_factor_for_scope(
+        &self,
+        user: &User,
+        scope: Scopes,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        if self.require_two_factor {
+            return Ok(true);
+        }
+        let scope = scope.to_string();
+        for ancestor in self.ancestors(conn)? {
+            if ancestor.require_two_factor
+                && ancestor
+                    .get_direct_scopes_for_user(user, conn)?
+                    .contains(&scope)
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn get_direct_scopes_for_user(
+        &self,
+        user: &User,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        if user.id != self.owner_user_id {
+            if let Some(role) = self.get_organization_role_for_user(user, conn)? {
+                let mut scopes = scopes::get_scopes(vec![Roles::User.to_string()]);
+                scopes.extend(role.scopes);
+                scopes.sort();
+                scopes.dedup();
+                return Ok(scopes);
+            }
+        }
+        Ok(scopes::get_scopes(self.get_direct_roles_for_user(user, conn)?))
+    }
+
+    /// The custom role assigned to the user within this organization, if any
+    pub fn get_organization_role_for_user(
+        &self,
+        user: &User,
+        conn: &PgConnection,
+    ) -> Result<Option<OrganizationRole>, DatabaseError> {
+        organization_users::table
+            .inner_join(organization_roles::table)
+            .filter(organization_users::user_id.eq(user.id))
+            .filter(organization_users::organization_id.eq(self.id))
+            .select(organization_roles::all_columns)
+            .first::<OrganizationRole>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization role")
+            .optional()
+    }
+
+    /// Assigns a custom role to a member, passing `None` restores the default member scopes
+    pub fn set_user_role(
+        &self,
+        user_id: Uuid,
+        organization_role_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationUser, DatabaseError> {
+        if let Some(organization_role_id) = organization_role_id {
+            let role = OrganizationRole::find(organization_role_id, conn)?;
+            if role.organization_id != self.id {
+                let mut errors = ValidationErrors::new();
+                let mut validation_error = ValidationError::new(&"invalid_role");
+                validation_error.message =
+                    Some(Cow::from("Role does not belong to this organization"));
+                errors.add("organization_role_id", validation_error);
+                return Err(errors.into());
+            }
+        }
+
+        diesel::update(
+            organization_users::table
+                .filter(organization_users::user_id.eq(user_id))
+                .filter(organization_users::organization_id.eq(self.id)),
+        ).set((
+            organization_users::organization_role_id.eq(organization_role_id),
+            organization_users::updated_at.eq(dsl::now),
+        )).get_result(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not update organization member role",
+        )
+    }
+
+    /// Roles the user has within this organization, owners and members of a parent organization
+    /// are owners and members of its children
     pub fn get_roles_for_user(
         &self,
         user: &User,
         conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let mut roles = self.get_direct_roles_for_user(user, conn)?;
+        for ancestor in self.ancestors(conn)? {
+            for role in ancestor.get_direct_roles_for_user(user, conn)? {
+                if !roles.contains(&role) {
+                    roles.push(role);
+                }
+            }
+        }
+        // Keep owners listed first
+        roles.sort_by_key(|role| *role != Roles::OrgOwner.to_string());
+        Ok(roles)
+    }
+
+    fn get_direct_roles_for_user(
+        &self,
+        user: &User,
+        conn: &PgConnection,
     ) -> Result<Vec<String>, DatabaseError> {
         let mut roles = Vec::new();
         if user.id == self.owner_user_id {
@@ -179,6 +389,18 @@ impl Organization {
             .to_db_error(ErrorCode::QueryError, "Error loading organization")
     }
 
+    pub fn fee_mode(&self) -> Result<FeeMode, DatabaseError> {
+        self.fee_mode.parse::<FeeMode>().map_err(|_| {
+            DatabaseError::new(
+                ErrorCode::InternalError,
+                Some(format!(
+                    "Invalid fee mode {} for organization {}",
+                    self.fee_mode, self.id
+                )),
+            )
+        })
+    }
+
     pub fn all(conn: &PgConnection) -> Result<Vec<Organization>, DatabaseError> {
         DatabaseError::wrap(
             ErrorCode::QueryError,
@@ -219,7 +441,14 @@ impl Organization {
             .to_db_error(ErrorCode::QueryError, "Unable to load all organizations")?;
 
         orgs.append(&mut org_members);
-        orgs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Users can also access every organization below the ones they are linked to
+        let ids: Vec<Uuid> = orgs.iter().map(|o| o.id).collect();
+        let mut children = Organization::descendants_of(&ids, conn)?;
+        orgs.append(&mut children);
+
+        orgs.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        orgs.dedup_by_key(|o| o.id);
         Ok(orgs)
     }
 
@@ -263,7 +492,10 @@ impl Organization {
         Ok(result_list)
     }
 
+    /// Removes the user from the organization and revokes their sessions so tokens issued
+    /// while they were a member stop working
     pub fn remove_user(&self, user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        Session::revoke_all_for_user(user_id, conn)?;
         diesel::delete(
             organization_users::table
                 .filter(organization_users::user_id.eq(user_id))
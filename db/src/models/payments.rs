@@ -5,13 +5,13 @@ use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use models::orders::Order;
 use models::*;
-use schema::payments;
+use schema::{orders, payments};
 use serde_json;
 use utils::errors::*;
 use uuid::Uuid;
 
 #[allow(dead_code)]
-#[derive(Identifiable, Queryable)]
+#[derive(Identifiable, Queryable, Serialize)]
 pub struct Payment {
     pub id: Uuid,
     order_id: Uuid,
@@ -21,6 +21,7 @@ pub struct Payment {
     amount: i64,
     provider: String,
     external_reference: String,
+    #[serde(skip_serializing)]
     raw_data: Option<serde_json::Value>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
@@ -49,6 +50,17 @@ impl Payment {
         }
     }
 
+    /// Payments for orders placed by the user
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<Payment>, DatabaseError> {
+        payments::table
+            .inner_join(orders::table)
+            .filter(orders::user_id.eq(user_id))
+            .select(payments::all_columns)
+            .order_by(payments::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payments")
+    }
+
     pub fn mark_complete(
         &self,
         raw_data: serde_json::Value,
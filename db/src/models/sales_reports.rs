@@ -0,0 +1,258 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Date, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::*;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct TicketSalesReportRow {
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "Text"]
+    pub ticket_type_name: String,
+    #[sql_type = "dUuid"]
+    pub ticket_pricing_id: Uuid,
+    #[sql_type = "Text"]
+    pub ticket_pricing_name: String,
+    #[sql_type = "Date"]
+    pub sales_date: NaiveDate,
+    #[sql_type = "BigInt"]
+    pub sold_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub comped_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub refunded_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub gross_revenue_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub per_unit_fees_in_cents: i64,
+    /// Per unit fees paid by the organization rather than the buyer
+    #[sql_type = "BigInt"]
+    pub absorbed_per_unit_fees_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct TicketInventoryReportRow {
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "Text"]
+    pub ticket_type_name: String,
+    #[sql_type = "BigInt"]
+    pub sold_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub reserved_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub held_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub available_quantity: i64,
+    #[sql_type = "BigInt"]
+    pub redeemed_quantity: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct EventFeesReportRow {
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "BigInt"]
+    pub event_fees_in_cents: i64,
+    /// Event fees paid by the organization rather than the buyer
+    #[sql_type = "BigInt"]
+    pub absorbed_event_fees_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct PaymentsReportRow {
+    #[sql_type = "Text"]
+    pub payment_method: String,
+    #[sql_type = "BigInt"]
+    pub payment_count: i64,
+    #[sql_type = "BigInt"]
+    pub amount_in_cents: i64,
+}
+
+/// Share of a co-promoted event's ticket revenue recorded for one of its promoters
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct RevenueSplitReportRow {
+    #[sql_type = "dUuid"]
+    pub organization_id: Uuid,
+    #[sql_type = "BigInt"]
+    pub ticket_revenue_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SalesReportTotals {
+    pub sold_quantity: i64,
+    pub comped_quantity: i64,
+    pub refunded_quantity: i64,
+    pub gross_revenue_in_cents: i64,
+    pub per_unit_fees_in_cents: i64,
+    pub absorbed_per_unit_fees_in_cents: i64,
+    pub event_fees_in_cents: i64,
+    pub absorbed_event_fees_in_cents: i64,
+    pub payments_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SalesReport {
+    pub organization_id: Uuid,
+    /// Child organizations whose sales are rolled up into this report
+    pub child_organization_ids: Vec<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+    pub ticket_sales: Vec<TicketSalesReportRow>,
+    pub inventory: Vec<TicketInventoryReportRow>,
+    pub event_fees: Vec<EventFeesReportRow>,
+    /// Payments are recorded against whole orders, so an order containing tickets for
+    /// several events is included in full in each of those events' reports
+    pub payments: Vec<PaymentsReportRow>,
+    /// Only included in event reports, the owning organization is listed first
+    pub revenue_splits: Vec<RevenueSplitReportRow>,
+    pub totals: SalesReportTotals,
+}
+
+impl SalesReport {
+    pub fn for_event(
+        event: &Event,
+        start_utc: Option<NaiveDateTime>,
+        end_utc: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<SalesReport, DatabaseError> {
+        let mut report = SalesReport::build(
+            event.organization_id,
+            Vec::new(),
+            Some(event.id),
+            start_utc,
+            end_utc,
+            conn,
+        )?;
+
+        report.revenue_splits =
+            diesel::sql_query(include_str!("../queries/sales_report_revenue_splits.sql"))
+                .bind::<dUuid, _>(event.id)
+                .bind::<Nullable<Timestamp>, _>(start_utc)
+                .bind::<Nullable<Timestamp>, _>(end_utc)
+                .bind::<dUuid, _>(event.organization_id)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load revenue splits report")?;
+        Ok(report)
+    }
+
+    pub fn for_organization(
+        organization: &Organization,
+        start_utc: Option<NaiveDateTime>,
+        end_utc: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<SalesReport, DatabaseError> {
+        SalesReport::build(organization.id, Vec::new(), None, start_utc, end_utc, conn)
+    }
+
+    /// Sales of the organization and of all of its child organizations
+    pub fn for_organization_with_children(
+        organization: &Organization,
+        start_utc: Option<NaiveDateTime>,
+        end_utc: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<SalesReport, DatabaseError> {
+        let child_organization_ids = organization
+            .descendants(conn)?
+            .iter()
+            .map(|o| o.id)
+            .collect();
+        SalesReport::build(
+            organization.id,
+            child_organization_ids,
+            None,
+            start_utc,
+            end_utc,
+            conn,
+        )
+    }
+
+    fn build(
+        organization_id: Uuid,
+        child_organization_ids: Vec<Uuid>,
+        event_id: Option<Uuid>,
+        start_utc: Option<NaiveDateTime>,
+        end_utc: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<SalesReport, DatabaseError> {
+        let mut organization_ids = vec![organization_id];
+        organization_ids.extend(child_organization_ids.iter());
+
+        let ticket_sales: Vec<TicketSalesReportRow> =
+            diesel::sql_query(include_str!("../queries/sales_report_ticket_sales.sql"))
+                .bind::<Array<dUuid>, _>(&organization_ids)
+                .bind::<Nullable<dUuid>, _>(event_id)
+                .bind::<Nullable<Timestamp>, _>(start_utc)
+                .bind::<Nullable<Timestamp>, _>(end_utc)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load ticket sales report")?;
+
+        let inventory: Vec<TicketInventoryReportRow> =
+            diesel::sql_query(include_str!("../queries/sales_report_inventory.sql"))
+                .bind::<Array<dUuid>, _>(&organization_ids)
+                .bind::<Nullable<dUuid>, _>(event_id)
+                .load(conn)
+                .to_db_error(
+                    ErrorCode::QueryError,
+                    "Could not load ticket inventory report",
+                )?;
+
+        let event_fees: Vec<EventFeesReportRow> =
+            diesel::sql_query(include_str!("../queries/sales_report_event_fees.sql"))
+                .bind::<Array<dUuid>, _>(&organization_ids)
+                .bind::<Nullable<dUuid>, _>(event_id)
+                .bind::<Nullable<Timestamp>, _>(start_utc)
+                .bind::<Nullable<Timestamp>, _>(end_utc)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load event fees report")?;
+
+        let payments: Vec<PaymentsReportRow> =
+            diesel::sql_query(include_str!("../queries/sales_report_payments.sql"))
+                .bind::<Array<dUuid>, _>(&organization_ids)
+                .bind::<Nullable<dUuid>, _>(event_id)
+                .bind::<Nullable<Timestamp>, _>(start_utc)
+                .bind::<Nullable<Timestamp>, _>(end_utc)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load payments report")?;
+
+        let mut totals = SalesReportTotals::default();
+        for row in &ticket_sales {
+            totals.sold_quantity += row.sold_quantity;
+            totals.comped_quantity += row.comped_quantity;
+            totals.refunded_quantity += row.refunded_quantity;
+            totals.gross_revenue_in_cents += row.gross_revenue_in_cents;
+            totals.per_unit_fees_in_cents += row.per_unit_fees_in_cents;
+            totals.absorbed_per_unit_fees_in_cents += row.absorbed_per_unit_fees_in_cents;
+        }
+        totals.event_fees_in_cents = event_fees.iter().map(|r| r.event_fees_in_cents).sum();
+        totals.absorbed_event_fees_in_cents = event_fees
+            .iter()
+            .map(|r| r.absorbed_event_fees_in_cents)
+            .sum();
+        totals.payments_in_cents = payments.iter().map(|r| r.amount_in_cents).sum();
+
+        Ok(SalesReport {
+            organization_id,
+            child_organization_ids,
+            event_id,
+            start_utc,
+            end_utc,
+            ticket_sales,
+            inventory,
+            event_fees,
+            payments,
+            revenue_splits: Vec::new(),
+            totals,
+        })
+    }
+}
@@ -10,9 +10,12 @@ pub enum Scopes {
     OrderMakeExternalPayment,
     OrderRead,
     OrgAdmin,
+    OrgCustomers,
     OrgRead,
+    OrgReports,
     OrgWrite,
     RegionWrite,
+    UserAdmin,
     UserRead,
     TicketAdmin,
     TicketTransfer,
@@ -30,9 +33,12 @@ impl fmt::Display for Scopes {
             Scopes::OrderRead => "order:read",
             Scopes::OrderMakeExternalPayment => "order::make-external-payment",
             Scopes::OrgAdmin => "org:admin",
+            Scopes::OrgCustomers => "org:customers",
             Scopes::OrgRead => "org:read",
+            Scopes::OrgReports => "org:reports",
             Scopes::OrgWrite => "org:write",
             Scopes::RegionWrite => "region:write",
+            Scopes::UserAdmin => "user:admin",
             Scopes::UserRead => "user:read",
             Scopes::VenueWrite => "venue:write",
             Scopes::TicketAdmin => "ticket:admin",
@@ -75,7 +81,12 @@ fn get_scopes_for_role(role: &str) -> Vec<Scopes> {
             roles
         }
         "OrgOwner" => {
-            let mut roles = vec![Scopes::OrgWrite, Scopes::UserRead];
+            let mut roles = vec![
+                Scopes::OrgCustomers,
+                Scopes::OrgReports,
+                Scopes::OrgWrite,
+                Scopes::UserRead,
+            ];
             roles.extend(get_scopes_for_role("OrgMember"));
             roles
         }
@@ -85,6 +96,7 @@ fn get_scopes_for_role(role: &str) -> Vec<Scopes> {
                 Scopes::OrderMakeExternalPayment,
                 Scopes::OrgAdmin,
                 Scopes::RegionWrite,
+                Scopes::UserAdmin,
             ];
             roles.extend(get_scopes_for_role("OrgOwner"));
             roles
@@ -98,6 +110,8 @@ fn get_scopes_for_role_test() {
     let res = get_scopes_for_role("OrgOwner");
     assert_eq!(
         vec![
+            Scopes::OrgCustomers,
+            Scopes::OrgReports,
             Scopes::OrgWrite,
             Scopes::UserRead,
             Scopes::ArtistWrite,
@@ -132,7 +146,9 @@ fn get_scopes_test() {
             "event:write",
             "hold:write",
             "order:read",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "ticket:admin",
             "ticket:transfer",
@@ -153,11 +169,14 @@ fn get_scopes_test() {
             "order::make-external-payment",
             "order:read",
             "org:admin",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "region:write",
             "ticket:admin",
             "ticket:transfer",
+            "user:admin",
             "user:read",
             "venue:write",
         ],
@@ -175,11 +194,14 @@ fn get_scopes_test() {
             "order::make-external-payment",
             "order:read",
             "org:admin",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "region:write",
             "ticket:admin",
             "ticket:transfer",
+            "user:admin",
             "user:read",
             "venue:write",
         ],
@@ -0,0 +1,108 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::sessions;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A signed in device. Refresh and access tokens carry the session id so revoking the
+/// session signs the device out
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(User)]
+#[table_name = "sessions"]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "sessions"]
+pub struct NewSession {
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl NewSession {
+    pub fn commit(&self, conn: &PgConnection) -> Result<Session, DatabaseError> {
+        diesel::insert_into(sessions::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create session")
+    }
+}
+
+impl Session {
+    pub fn create(
+        user_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> NewSession {
+        NewSession {
+            user_id,
+            user_agent,
+            ip_address,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Session, DatabaseError> {
+        sessions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load session")
+    }
+
+    pub fn find_active_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Session>, DatabaseError> {
+        sessions::table
+            .filter(sessions::user_id.eq(user_id))
+            .filter(sessions::revoked_at.is_null())
+            .order_by(sessions::last_used_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sessions")
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    pub fn record_use(&self, conn: &PgConnection) -> Result<Session, DatabaseError> {
+        diesel::update(self)
+            .set((
+                sessions::last_used_at.eq(dsl::now),
+                sessions::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update session")
+    }
+
+    pub fn revoke(&self, conn: &PgConnection) -> Result<Session, DatabaseError> {
+        diesel::update(self)
+            .set((
+                sessions::revoked_at.eq(dsl::now.nullable()),
+                sessions::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke session")
+    }
+
+    pub fn revoke_all_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(
+            sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::revoked_at.is_null()),
+        ).set((
+            sessions::revoked_at.eq(dsl::now.nullable()),
+            sessions::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not revoke sessions")
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::*;
+use schema::{ledger_entries, settlements};
+use std::borrow::Cow;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+/// Statement of what is owed to an organization. Creating a settlement claims the unsettled
+/// ledger entries for the organization, optionally limited to an event and period, so each
+/// entry is only ever paid out once. Settlements are locked once they have been paid out
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[table_name = "settlements"]
+pub struct Settlement {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+    pub status: String,
+    pub created_by: Uuid,
+    pub paid_out_by: Option<Uuid>,
+    pub paid_out_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "settlements"]
+pub struct NewSettlement {
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+    status: String,
+    pub created_by: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct SettlementStatementLine {
+    #[sql_type = "Nullable<dUuid>"]
+    pub event_id: Option<Uuid>,
+    #[sql_type = "Nullable<Text>"]
+    pub event_name: Option<String>,
+    #[sql_type = "Text"]
+    pub entry_type: String,
+    #[sql_type = "Text"]
+    pub account: String,
+    #[sql_type = "BigInt"]
+    pub entry_count: i64,
+    #[sql_type = "BigInt"]
+    pub amount_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SettlementStatement {
+    pub settlement: Settlement,
+    pub lines: Vec<SettlementStatementLine>,
+    /// Amount owed to the organization
+    pub organization_total_in_cents: i64,
+    pub platform_total_in_cents: i64,
+}
+
+impl NewSettlement {
+    pub fn commit(&self, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
+        self.validate_record(conn)?;
+
+        let settlement: Settlement = diesel::insert_into(settlements::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create settlement")?;
+
+        let query = r#"
+                UPDATE ledger_entries
+                SET settlement_id = $1
+                WHERE organization_id = $2
+                  AND settlement_id IS NULL
+                  AND ($3 IS NULL OR event_id = $3)
+                  AND ($4 IS NULL OR created_at >= $4)
+                  AND ($5 IS NULL OR created_at <= $5);"#;
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(settlement.id)
+            .bind::<dUuid, _>(self.organization_id)
+            .bind::<Nullable<dUuid>, _>(self.event_id)
+            .bind::<Nullable<Timestamp>, _>(self.start_utc)
+            .bind::<Nullable<Timestamp>, _>(self.end_utc)
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not settle ledger entries")?;
+
+        Ok(settlement)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut errors = ValidationErrors::new();
+        if let Some(event_id) = self.event_id {
+            // Co-promoters settle their share of the event separately
+            let event = Event::find(event_id, conn)?;
+            if !RevenueSplit::for_event(&event, conn)?
+                .iter()
+                .any(|s| s.organization_id == self.organization_id)
+            {
+                let mut validation_error = ValidationError::new(&"invalid_event");
+                validation_error.message =
+                    Some(Cow::from("Event does not belong to this organization"));
+                errors.add("event_id", validation_error);
+            }
+        }
+        if let (Some(start_utc), Some(end_utc)) = (self.start_utc, self.end_utc) {
+            if start_utc > end_utc {
+                let mut validation_error = ValidationError::new(&"invalid_period");
+                validation_error.message = Some(Cow::from("Start must be before end"));
+                errors.add("start_utc", validation_error);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        Ok(())
+    }
+}
+
+impl Settlement {
+    pub fn create(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        start_utc: Option<NaiveDateTime>,
+        end_utc: Option<NaiveDateTime>,
+        created_by: Uuid,
+    ) -> NewSettlement {
+        NewSettlement {
+            organization_id,
+            event_id,
+            start_utc,
+            end_utc,
+            status: SettlementStatus::Open.to_string(),
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
+        settlements::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load settlement")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization_id))
+            .order_by(settlements::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load settlements")
+    }
+
+    pub fn status(&self) -> SettlementStatus {
+        self.status.parse::<SettlementStatus>().unwrap()
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    /// Totals of the settled entries per event, entry type and account. The customer side of
+    /// each entry is left out as it only balances the ledger
+    pub fn statement(&self, conn: &PgConnection) -> Result<SettlementStatement, DatabaseError> {
+        let query = r#"
+                SELECT le.event_id,
+                       e.name                                   AS event_name,
+                       le.entry_type,
+                       le.account,
+                       CAST(COUNT(*) AS BIGINT)                 AS entry_count,
+                       CAST(SUM(le.amount_in_cents) AS BIGINT)  AS amount_in_cents
+                FROM ledger_entries le
+                       LEFT JOIN events e ON le.event_id = e.id
+                WHERE le.settlement_id = $1
+                  AND le.account <> $2
+                GROUP BY le.event_id, e.name, le.entry_type, le.account
+                ORDER BY e.name, le.entry_type, le.account;"#;
+        let lines: Vec<SettlementStatementLine> = diesel::sql_query(query)
+            .bind::<dUuid, _>(self.id)
+            .bind::<Text, _>(LedgerAccount::Customer.to_string())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load settlement statement")?;
+
+        let total_for = |account: LedgerAccount| -> i64 {
+            lines
+                .iter()
+                .filter(|l| l.account == account.to_string())
+                .map(|l| l.amount_in_cents)
+                .sum()
+        };
+        let organization_total_in_cents = total_for(LedgerAccount::Organization);
+        let platform_total_in_cents = total_for(LedgerAccount::Platform);
+
+        Ok(SettlementStatement {
+            settlement: self.clone(),
+            lines,
+            organization_total_in_cents,
+            platform_total_in_cents,
+        })
+    }
+
+    /// Locks the settlement, it can no longer be removed after it has been paid out
+    pub fn mark_paid_out(
+        &self,
+        paid_out_by: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Settlement, DatabaseError> {
+        self.validate_open()?;
+
+        diesel::update(self)
+            .set((
+                settlements::status.eq(SettlementStatus::PaidOut.to_string()),
+                settlements::paid_out_by.eq(Some(paid_out_by)),
+                settlements::paid_out_at.eq(dsl::now.nullable()),
+                settlements::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update settlement")
+    }
+
+    /// Removes an open settlement, its entries are released to be included in a new one
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        self.validate_open()?;
+
+        diesel::update(ledger_entries::table.filter(ledger_entries::settlement_id.eq(self.id)))
+            .set(ledger_entries::settlement_id.eq(None::<Uuid>))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not release ledger entries")?;
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove settlement")
+    }
+
+    fn validate_open(&self) -> Result<(), DatabaseError> {
+        if self.status() != SettlementStatus::Open {
+            let mut errors = ValidationErrors::new();
+            let mut validation_error = ValidationError::new(&"already_paid_out");
+            validation_error.message = Some(Cow::from("Settlement has already been paid out"));
+            errors.add("status", validation_error);
+            return Err(errors.into());
+        }
+        Ok(())
+    }
+}
@@ -331,6 +331,22 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Could not load Ticket Instances")
     }
 
+    /// Organizations owning the events the tickets are for
+    pub fn organization_ids(
+        ticket_instance_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<Uuid>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+            .inner_join(events::table.on(ticket_types::event_id.eq(events::id)))
+            .filter(ticket_instances::id.eq_any(ticket_instance_ids))
+            .select(events::organization_id)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket organizations")
+    }
+
     pub fn update_reserved_time(
         order_item: &OrderItem,
         reserved_time: NaiveDateTime,
@@ -405,6 +421,19 @@ impl TicketInstance {
                 .set(ticket_instances::status.eq(TicketInstanceStatus::Redeemed.to_string()))
                 .execute(conn)
                 .to_db_error(ErrorCode::UpdateError, "Could not set ticket to Redeemed")?;
+
+            for (ticket_id, organization_id) in
+                TicketInstance::organization_ids_by_ticket(&[ticket_id], conn)?
+            {
+                DomainEvent::create(
+                    DomainEventTypes::TicketRedeemed,
+                    "Ticket was redeemed".to_string(),
+                    Tables::TicketInstances,
+                    Some(ticket_id),
+                    Some(json!({ "ticket_instance_id": ticket_id })),
+                ).for_organization(organization_id)
+                .commit(conn)?;
+            }
         } else if ticket.status == TicketInstanceStatus::Redeemed.to_string() {
             return Ok(RedeemResults::TicketAlreadyRedeemed);
         } else {
@@ -600,9 +629,42 @@ impl TicketInstance {
             ));
         }
 
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+        for (ticket_id, organization_id) in
+            TicketInstance::organization_ids_by_ticket(&ticket_ids, conn)?
+        {
+            DomainEvent::create(
+                DomainEventTypes::TicketTransferred,
+                "Ticket was transferred".to_string(),
+                Tables::TicketInstances,
+                Some(ticket_id),
+                Some(json!({
+                    "ticket_instance_id": ticket_id,
+                    "sender_wallet_id": sender_wallet.id,
+                    "receiver_wallet_id": receiver_wallet_id
+                })),
+            ).for_organization(organization_id)
+            .commit(conn)?;
+        }
+
         Ok(tickets)
     }
 
+    /// Pairs each ticket with the organization of its event
+    fn organization_ids_by_ticket(
+        ticket_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<(Uuid, Uuid)>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+            .inner_join(events::table.on(ticket_types::event_id.eq(events::id)))
+            .filter(ticket_instances::id.eq_any(ticket_ids))
+            .select((ticket_instances::id, events::organization_id))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket organizations")
+    }
+
     pub fn nullify_tickets(
         asset_id: Uuid,
         quantity: u32,
@@ -12,7 +12,7 @@ use validator::*;
 
 sql_function!(fn ticket_pricing_no_overlapping_periods(id: dUuid, ticket_type_id: dUuid, start_date: Timestamp, end_date: Timestamp) -> Bool);
 
-#[derive(Identifiable, Associations, Queryable, PartialEq, Debug)]
+#[derive(Identifiable, Associations, Queryable, PartialEq, Debug, Serialize)]
 #[belongs_to(TicketType)]
 #[table_name = "ticket_pricing"]
 pub struct TicketPricing {
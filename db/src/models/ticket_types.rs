@@ -1,9 +1,11 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use diesel;
 use diesel::dsl;
 use diesel::prelude::*;
-use models::{Event, TicketInstanceStatus, TicketPricing, TicketPricingStatus, TicketTypeStatus};
-use schema::{assets, ticket_instances, ticket_pricing, ticket_types};
+use models::{
+    Event, FeeSchedule, TicketInstanceStatus, TicketPricing, TicketPricingStatus, TicketTypeStatus,
+};
+use schema::{assets, event_interest, ticket_instances, ticket_pricing, ticket_types};
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -11,7 +13,7 @@ use uuid::Uuid;
 use validator::*;
 use validators;
 
-#[derive(Identifiable, Associations, Queryable, PartialEq, Debug)]
+#[derive(Identifiable, Associations, Queryable, PartialEq, Debug, Serialize)]
 #[table_name = "ticket_types"]
 #[belongs_to(Event)]
 pub struct TicketType {
@@ -24,6 +26,10 @@ pub struct TicketType {
     pub increment: i32,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    pub presale_start_date: Option<NaiveDateTime>,
+    pub presale_interest_cutoff: Option<NaiveDateTime>,
+    pub presale_code: Option<String>,
+    pub fee_schedule_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
@@ -33,6 +39,9 @@ pub struct TicketTypeEditableAttributes {
     pub start_date: Option<NaiveDateTime>,
     pub end_date: Option<NaiveDateTime>,
     pub increment: Option<i32>,
+    pub presale_start_date: Option<NaiveDateTime>,
+    pub presale_interest_cutoff: Option<NaiveDateTime>,
+    pub presale_code: Option<String>,
 }
 
 impl TicketType {
@@ -64,6 +73,24 @@ impl TicketType {
             .to_db_error(ErrorCode::UpdateError, "Could not update ticket_types")
     }
 
+    /// Overrides the fee schedule of the event and organization for this ticket type, `None`
+    /// removes the override
+    pub fn set_fee_schedule(
+        &self,
+        fee_schedule: Option<&FeeSchedule>,
+        conn: &PgConnection,
+    ) -> Result<TicketType, DatabaseError> {
+        diesel::update(self)
+            .set((
+                ticket_types::fee_schedule_id.eq(fee_schedule.map(|f| f.id)),
+                ticket_types::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not set the fee schedule for this ticket type",
+            )
+    }
+
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
         ticket_types::table
             .filter(ticket_types::id.eq(id))
@@ -208,6 +235,48 @@ impl TicketType {
     pub fn status(&self) -> TicketTypeStatus {
         self.status.parse::<TicketTypeStatus>().unwrap()
     }
+
+    /// The presale runs from the presale start date until the general on sale (start date)
+    pub fn is_in_presale(&self) -> bool {
+        let now = Utc::now().naive_utc();
+        match self.presale_start_date {
+            Some(presale_start_date) => presale_start_date <= now && now < self.start_date,
+            None => false,
+        }
+    }
+
+    /// Users may purchase during the presale if they present the presale code or registered
+    /// interest in the event before the interest cutoff, which defaults to the presale start
+    pub fn is_presale_eligible(
+        &self,
+        user_id: Uuid,
+        presale_code: Option<&str>,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        if let (Some(expected_code), Some(presale_code)) = (self.presale_code.as_ref(), presale_code)
+        {
+            let presale_code = presale_code.trim();
+            if !presale_code.is_empty() && expected_code.trim().eq_ignore_ascii_case(presale_code)
+            {
+                return Ok(true);
+            }
+        }
+
+        let interest_cutoff = match self.presale_interest_cutoff.or(self.presale_start_date) {
+            Some(interest_cutoff) => interest_cutoff,
+            None => return Ok(false),
+        };
+        dsl::select(dsl::exists(
+            event_interest::table
+                .filter(event_interest::event_id.eq(self.event_id))
+                .filter(event_interest::user_id.eq(user_id))
+                .filter(event_interest::created_at.lt(interest_cutoff)),
+        )).get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check presale eligibility for ticket type",
+        )
+    }
 }
 
 #[derive(Insertable)]
@@ -0,0 +1,159 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{TwoFactorRecoveryCode, User};
+use rand::{thread_rng, Rng};
+use schema::two_factor_credentials;
+use std::borrow::Cow;
+use utils::base32;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+const SECRET_LENGTH_IN_BYTES: usize = 20;
+
+/// TOTP secret for a user. The credential only protects logins once `enabled_at` is set,
+/// which happens when the user proves their authenticator app produces valid codes
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "two_factor_credentials"]
+pub struct TwoFactorCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub secret: String,
+    pub enabled_at: Option<NaiveDateTime>,
+    pub last_used_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "two_factor_credentials"]
+pub struct NewTwoFactorCredential {
+    pub user_id: Uuid,
+    pub secret: String,
+}
+
+impl NewTwoFactorCredential {
+    /// Starts a new enrolment, replacing any earlier enrolment that was never enabled
+    pub fn commit(&self, conn: &PgConnection) -> Result<TwoFactorCredential, DatabaseError> {
+        if let Some(credential) =
+            TwoFactorCredential::find_for_user(self.user_id, conn).optional()?
+        {
+            if credential.is_enabled() {
+                let mut errors = ValidationErrors::new();
+                let mut validation_error = ValidationError::new(&"two_factor_enabled");
+                validation_error.message =
+                    Some(Cow::from("Two factor authentication is already enabled"));
+                errors.add("user_id", validation_error);
+                return Err(errors.into());
+            }
+
+            diesel::delete(&credential).execute(conn).to_db_error(
+                ErrorCode::DeleteError,
+                "Could not remove two factor enrolment",
+            )?;
+        }
+
+        diesel::insert_into(two_factor_credentials::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create two factor enrolment",
+            )
+    }
+}
+
+impl TwoFactorCredential {
+    pub fn create(user_id: Uuid) -> NewTwoFactorCredential {
+        let mut secret = [0u8; SECRET_LENGTH_IN_BYTES];
+        thread_rng().fill(&mut secret);
+
+        NewTwoFactorCredential {
+            user_id,
+            secret: base32::encode(&secret),
+        }
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<TwoFactorCredential, DatabaseError> {
+        two_factor_credentials::table
+            .filter(two_factor_credentials::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load two factor credential",
+            )
+    }
+
+    pub fn is_enabled_for_user(user_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            two_factor_credentials::table
+                .filter(two_factor_credentials::user_id.eq(user_id))
+                .filter(two_factor_credentials::enabled_at.is_not_null()),
+        )).get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check two factor authentication status",
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Enables the credential after the user has verified a code for the given time step
+    pub fn enable(
+        &self,
+        step: i64,
+        conn: &PgConnection,
+    ) -> Result<TwoFactorCredential, DatabaseError> {
+        diesel::update(self)
+            .set((
+                two_factor_credentials::enabled_at.eq(dsl::now.nullable()),
+                two_factor_credentials::last_used_step.eq(Some(step)),
+                two_factor_credentials::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not enable two factor authentication",
+            )
+    }
+
+    /// Records that a code for the given time step has been used. Returns false if a code for
+    /// this or a later step was already used, so codes cannot be replayed
+    pub fn use_step(&self, step: i64, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let updated = diesel::update(
+            two_factor_credentials::table
+                .filter(two_factor_credentials::id.eq(self.id))
+                .filter(
+                    two_factor_credentials::last_used_step
+                        .is_null()
+                        .or(two_factor_credentials::last_used_step.lt(step)),
+                ),
+        ).set((
+            two_factor_credentials::last_used_step.eq(Some(step)),
+            two_factor_credentials::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not record two factor code use",
+        )?;
+
+        Ok(updated == 1)
+    }
+
+    /// Removes the credential along with the user's recovery codes
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        TwoFactorRecoveryCode::destroy_for_user(self.user_id, conn)?;
+        diesel::delete(self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove two factor credential",
+        )
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use schema::two_factor_recovery_codes;
+use utils::errors::*;
+use utils::passwords::PasswordHash;
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// Single use code allowing a user to complete two factor authentication without their
+/// authenticator app. Codes are hashed like passwords so they can only be shown once
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "two_factor_recovery_codes"]
+pub struct TwoFactorRecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub hashed_code: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "two_factor_recovery_codes"]
+struct NewTwoFactorRecoveryCode {
+    user_id: Uuid,
+    hashed_code: String,
+}
+
+impl TwoFactorRecoveryCode {
+    /// Replaces any existing recovery codes for the user and returns the new codes
+    pub fn regenerate_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        TwoFactorRecoveryCode::destroy_for_user(user_id, conn)?;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_code()).collect();
+        let new_codes: Vec<NewTwoFactorRecoveryCode> = codes
+            .iter()
+            .map(|code| NewTwoFactorRecoveryCode {
+                user_id,
+                hashed_code: PasswordHash::generate(&normalize(code), None).to_string(),
+            }).collect();
+        diesel::insert_into(two_factor_recovery_codes::table)
+            .values(&new_codes)
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create two factor recovery codes",
+            )?;
+
+        Ok(codes)
+    }
+
+    /// Marks the matching unused recovery code as used, returning false if there is no match
+    pub fn redeem(user_id: Uuid, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let code = normalize(code);
+        let unused_codes: Vec<TwoFactorRecoveryCode> = two_factor_recovery_codes::table
+            .filter(two_factor_recovery_codes::user_id.eq(user_id))
+            .filter(two_factor_recovery_codes::used_at.is_null())
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load two factor recovery codes",
+            )?;
+
+        for recovery_code in unused_codes {
+            if PasswordHash::from_str(&recovery_code.hashed_code)?.verify(&code) {
+                let updated = diesel::update(
+                    two_factor_recovery_codes::table
+                        .filter(two_factor_recovery_codes::id.eq(recovery_code.id))
+                        .filter(two_factor_recovery_codes::used_at.is_null()),
+                ).set((
+                    two_factor_recovery_codes::used_at.eq(dsl::now.nullable()),
+                    two_factor_recovery_codes::updated_at.eq(dsl::now),
+                )).execute(conn)
+                .to_db_error(
+                    ErrorCode::UpdateError,
+                    "Could not use two factor recovery code",
+                )?;
+                return Ok(updated == 1);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn remaining_for_user(user_id: Uuid, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        two_factor_recovery_codes::table
+            .filter(two_factor_recovery_codes::user_id.eq(user_id))
+            .filter(two_factor_recovery_codes::used_at.is_null())
+            .count()
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not count two factor recovery codes",
+            )
+    }
+
+    pub fn destroy_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(
+            two_factor_recovery_codes::table.filter(two_factor_recovery_codes::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove two factor recovery codes",
+        )
+    }
+}
+
+// Codes are shown as two groups of five characters, users may enter them without the
+// separator or in a different case
+fn generate_code() -> String {
+    let code: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RECOVERY_CODE_LENGTH)
+        .collect::<String>()
+        .to_lowercase();
+    format!(
+        "{}-{}",
+        &code[..RECOVERY_CODE_LENGTH / 2],
+        &code[RECOVERY_CODE_LENGTH / 2..]
+    )
+}
+
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
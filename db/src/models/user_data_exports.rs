@@ -0,0 +1,112 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use models::*;
+use schema::sessions;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Everything held about a user, assembled for a self-service data export
+#[derive(Serialize)]
+pub struct UserDataExport {
+    pub exported_at: NaiveDateTime,
+    pub profile: UserDataExportProfile,
+    pub orders: Vec<DisplayOrder>,
+    pub payments: Vec<Payment>,
+    pub payment_methods: Vec<DisplayPaymentMethod>,
+    pub tickets: Vec<UserDataExportEventTickets>,
+    pub ticket_transfers: Vec<UserDataExportTicketTransfer>,
+    pub event_interests: Vec<EventInterest>,
+    pub external_logins: Vec<ExternalLogin>,
+    pub sessions: Vec<Session>,
+    pub erasure_requests: Vec<UserErasureRequest>,
+    pub impersonations: Vec<Impersonation>,
+}
+
+/// Profile fields of the user, secrets such as password hashes and tokens are left out
+#[derive(Serialize)]
+pub struct UserDataExportProfile {
+    pub id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub profile_pic_url: Option<String>,
+    pub thumb_profile_pic_url: Option<String>,
+    pub cover_photo_url: Option<String>,
+    pub role: Vec<String>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub last_used: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+pub struct UserDataExportEventTickets {
+    pub event: DisplayEvent,
+    pub tickets: Vec<DisplayTicket>,
+}
+
+/// Transfer of a ticket that has been authorized but not yet received
+#[derive(Serialize)]
+pub struct UserDataExportTicketTransfer {
+    pub ticket_instance_id: Uuid,
+    pub transfer_expiry_date: Option<NaiveDateTime>,
+}
+
+impl From<User> for UserDataExportProfile {
+    fn from(user: User) -> Self {
+        UserDataExportProfile {
+            id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            email: user.email,
+            phone: user.phone,
+            profile_pic_url: user.profile_pic_url,
+            thumb_profile_pic_url: user.thumb_profile_pic_url,
+            cover_photo_url: user.cover_photo_url,
+            role: user.role,
+            email_verified_at: user.email_verified_at,
+            last_used: user.last_used,
+            created_at: user.created_at,
+        }
+    }
+}
+
+impl UserDataExport {
+    pub fn for_user(user: &User, conn: &PgConnection) -> Result<UserDataExport, DatabaseError> {
+        let payment_methods = PaymentMethod::find_for_user(user.id, None, conn)?
+            .into_iter()
+            .map(|payment_method| payment_method.into())
+            .collect();
+        let tickets = TicketInstance::find_for_user_for_display(user.id, None, None, None, conn)?
+            .into_iter()
+            .map(|(event, tickets)| UserDataExportEventTickets { event, tickets })
+            .collect();
+        let ticket_transfers = TicketInstance::find_for_user(user.id, conn)?
+            .into_iter()
+            .filter(|ticket| ticket.transfer_key.is_some())
+            .map(|ticket| UserDataExportTicketTransfer {
+                ticket_instance_id: ticket.id,
+                transfer_expiry_date: ticket.transfer_expiry_date,
+            }).collect();
+        let sessions = sessions::table
+            .filter(sessions::user_id.eq(user.id))
+            .order_by(sessions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sessions")?;
+
+        Ok(UserDataExport {
+            exported_at: Utc::now().naive_utc(),
+            profile: user.clone().into(),
+            orders: Order::find_for_user_for_display(user.id, conn)?,
+            payments: Payment::find_for_user(user.id, conn)?,
+            payment_methods,
+            tickets,
+            ticket_transfers,
+            event_interests: EventInterest::find_for_user(user.id, conn)?,
+            external_logins: ExternalLogin::find_for_user(user.id, conn)?,
+            sessions,
+            erasure_requests: UserErasureRequest::find_for_user(user.id, conn)?,
+            impersonations: Impersonation::find_for_user(user.id, conn)?,
+        })
+    }
+}
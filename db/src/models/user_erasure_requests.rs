@@ -0,0 +1,425 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl::{self, exists};
+use diesel::prelude::*;
+use diesel::sql_types::{Nullable, Text, Uuid as dUuid};
+use models::*;
+use schema::{
+    assets, calendar_tokens, event_interest, event_interest_notifications, event_reminders,
+    events, external_logins, impersonations, interest_notification_opt_outs, login_codes,
+    login_throttles, orders, organization_api_key_requests, organization_api_keys,
+    organization_invites, organization_users, organizations, payment_methods, payments, sessions,
+    ticket_instances, ticket_types, two_factor_credentials, two_factor_recovery_codes,
+    user_erasure_requests, users, wallets,
+};
+use serde_json;
+use std::borrow::Cow;
+use utils::errors::*;
+use utils::passwords::PasswordHash;
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+/// Every column referencing `users`, rows are deleted on erasure unless noted otherwise. A
+/// table added with a reference to users must be listed here once `erase_user` handles it
+pub const ERASED_USER_REFERENCES: &[(&str, &str)] = &[
+    // Kept, erasure is refused while the user owns an organization
+    ("organizations", "owner_user_id"),
+    ("organization_users", "user_id"),
+    // Kept as the organization's record of who sent the invite
+    ("organization_invites", "inviter_id"),
+    ("organization_invites", "user_id"),
+    ("external_logins", "user_id"),
+    ("event_interest", "user_id"),
+    // Kept for accounting, payment provider data is removed
+    ("orders", "user_id"),
+    ("payments", "created_by"),
+    // Kept so ticket history stays intact, pending transfers are cancelled
+    ("wallets", "user_id"),
+    ("payment_methods", "user_id"),
+    ("calendar_tokens", "user_id"),
+    ("event_interest_notifications", "user_id"),
+    ("interest_notification_opt_outs", "user_id"),
+    ("event_reminders", "user_id"),
+    ("two_factor_credentials", "user_id"),
+    ("two_factor_recovery_codes", "user_id"),
+    ("sessions", "user_id"),
+    ("login_throttles", "user_id"),
+    ("login_codes", "user_id"),
+    // Kept, addresses requests were made from are removed
+    ("organization_api_keys", "service_user_id"),
+    ("organization_api_keys", "created_by"),
+    // Kept as the record of the erasure
+    ("user_erasure_requests", "user_id"),
+    ("user_erasure_requests", "reviewed_by"),
+    // Kept for accountability of the admin, the reason is removed. Impersonations of the user
+    // are removed along with their sessions
+    ("impersonations", "admin_user_id"),
+    ("impersonations", "user_id"),
+    // Kept as the record of who did what, changed values about the user are removed
+    ("settlements", "created_by"),
+    ("settlements", "paid_out_by"),
+    ("webhook_subscriptions", "created_by"),
+    ("audit_entries", "user_id"),
+    ("audit_entries", "impersonated_by"),
+];
+
+/// Request from a user to have their personal data erased. Requests are reviewed by an admin
+/// before the account is anonymised, orders and payments are kept for accounting.
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(User)]
+#[table_name = "user_erasure_requests"]
+pub struct UserErasureRequest {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    pub reason: Option<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub review_notes: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(QueryableByName)]
+struct UserReference {
+    #[sql_type = "Text"]
+    table_name: String,
+    #[sql_type = "Text"]
+    column_name: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_erasure_requests"]
+pub struct NewUserErasureRequest {
+    pub user_id: Uuid,
+    pub reason: Option<String>,
+}
+
+impl NewUserErasureRequest {
+    pub fn commit(&self, conn: &PgConnection) -> Result<UserErasureRequest, DatabaseError> {
+        UserErasureRequest::validate_erasable(self.user_id, conn)?;
+
+        diesel::insert_into(user_erasure_requests::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create erasure request")
+    }
+}
+
+impl UserErasureRequest {
+    pub fn create(user_id: Uuid, reason: Option<String>) -> NewUserErasureRequest {
+        NewUserErasureRequest { user_id, reason }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<UserErasureRequest, DatabaseError> {
+        user_erasure_requests::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load erasure request")
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<UserErasureRequest>, DatabaseError> {
+        user_erasure_requests::table
+            .filter(user_erasure_requests::user_id.eq(user_id))
+            .order_by(user_erasure_requests::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load erasure requests")
+    }
+
+    /// Requests waiting for review, oldest first
+    pub fn find_pending(conn: &PgConnection) -> Result<Vec<UserErasureRequest>, DatabaseError> {
+        user_erasure_requests::table
+            .filter(user_erasure_requests::status.eq(UserErasureRequestStatus::Pending.to_string()))
+            .order_by(user_erasure_requests::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load erasure requests")
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == UserErasureRequestStatus::Pending.to_string()
+    }
+
+    /// Anonymises the user and marks the request as completed
+    pub fn approve(
+        &self,
+        reviewed_by: Uuid,
+        review_notes: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<UserErasureRequest, DatabaseError> {
+        self.validate_pending()?;
+        // Organization ownership or tickets may have changed since the request was made
+        UserErasureRequest::validate_erasable(self.user_id, conn)?;
+        UserErasureRequest::erase_user(self.user_id, conn)?;
+        self.review(
+            UserErasureRequestStatus::Completed,
+            reviewed_by,
+            review_notes,
+            conn,
+        )
+    }
+
+    pub fn reject(
+        &self,
+        reviewed_by: Uuid,
+        review_notes: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<UserErasureRequest, DatabaseError> {
+        self.validate_pending()?;
+        self.review(
+            UserErasureRequestStatus::Rejected,
+            reviewed_by,
+            review_notes,
+            conn,
+        )
+    }
+
+    fn review(
+        &self,
+        status: UserErasureRequestStatus,
+        reviewed_by: Uuid,
+        review_notes: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<UserErasureRequest, DatabaseError> {
+        diesel::update(self)
+            .set((
+                user_erasure_requests::status.eq(status.to_string()),
+                user_erasure_requests::reviewed_by.eq(reviewed_by),
+                user_erasure_requests::review_notes.eq(review_notes),
+                user_erasure_requests::reviewed_at.eq(dsl::now.nullable()),
+                user_erasure_requests::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update erasure request")
+    }
+
+    fn validate_pending(&self) -> Result<(), DatabaseError> {
+        if self.is_pending() {
+            return Ok(());
+        }
+        let mut errors = ValidationErrors::new();
+        let mut validation_error = ValidationError::new(&"already_reviewed");
+        validation_error.message = Some(Cow::from("Erasure request has already been reviewed"));
+        errors.add("status", validation_error);
+        Err(errors.into())
+    }
+
+    /// Users that own an organization or hold tickets for upcoming events can not be erased
+    pub fn validate_erasable(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut errors = ValidationErrors::new();
+
+        let owns_organization: bool = diesel::select(exists(
+            organizations::table.filter(organizations::owner_user_id.eq(user_id)),
+        )).get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check organization ownership",
+        )?;
+        if owns_organization {
+            let mut validation_error = ValidationError::new(&"organization_owner");
+            validation_error.message = Some(Cow::from(
+                "Ownership of organizations must be transferred before erasing the account",
+            ));
+            errors.add("user_id", validation_error);
+        }
+
+        let has_upcoming_tickets: bool = diesel::select(exists(
+            ticket_instances::table
+                .inner_join(wallets::table)
+                .inner_join(assets::table.inner_join(ticket_types::table.inner_join(events::table)))
+                .filter(wallets::user_id.eq(user_id))
+                .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased.to_string()))
+                .filter(
+                    events::event_start
+                        .is_null()
+                        .or(events::event_start.gt(dsl::now.nullable())),
+                ),
+        )).get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check upcoming tickets")?;
+        if has_upcoming_tickets {
+            let mut validation_error = ValidationError::new(&"upcoming_tickets");
+            validation_error.message = Some(Cow::from(
+                "Tickets for upcoming events must be transferred before erasing the account",
+            ));
+            errors.add("user_id", validation_error);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        Ok(())
+    }
+
+    /// Columns referencing users that are missing from `ERASED_USER_REFERENCES`, as
+    /// `table.column`
+    pub fn user_references_without_erasure_rule(
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let references: Vec<UserReference> =
+            diesel::sql_query(include_str!("../queries/user_references.sql"))
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load user references")?;
+        Ok(references
+            .into_iter()
+            .filter(|r| {
+                !ERASED_USER_REFERENCES.contains(&(r.table_name.as_str(), r.column_name.as_str()))
+            }).map(|r| format!("{}.{}", r.table_name, r.column_name))
+            .collect())
+    }
+
+    /// Removes personal data while keeping the user row so orders and payments stay intact
+    fn erase_user(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let user = User::find(user_id, conn)?;
+        let hash = PasswordHash::generate(&PasswordHash::generate_salt(), None);
+        diesel::update(&user)
+            .set((
+                users::first_name.eq("Deleted"),
+                users::last_name.eq("User"),
+                users::email.eq(None::<String>),
+                users::phone.eq(None::<String>),
+                users::phone_verified_at.eq(None::<NaiveDateTime>),
+                users::profile_pic_url.eq(None::<String>),
+                users::thumb_profile_pic_url.eq(None::<String>),
+                users::cover_photo_url.eq(None::<String>),
+                users::hashed_pw.eq(hash.to_string()),
+                users::password_set.eq(false),
+                users::active.eq(false),
+                users::role.eq(Vec::<String>::new()),
+                users::password_reset_token.eq(None::<Uuid>),
+                users::password_reset_requested_at.eq(None::<NaiveDateTime>),
+                users::email_verified_at.eq(None::<NaiveDateTime>),
+                users::email_verification_token.eq(None::<Uuid>),
+                users::email_verification_sent_at.eq(None::<NaiveDateTime>),
+                users::updated_at.eq(dsl::now),
+            )).execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not anonymise user")?;
+
+        // Copies of personal data kept in logs and payloads, removed before the rows they refer
+        // to are deleted below
+        diesel::sql_query(include_str!("../queries/erase_user_domain_events.sql"))
+            .bind::<dUuid, _>(user_id)
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not remove domain event data")?;
+        diesel::sql_query(include_str!("../queries/erase_user_audit_entries.sql"))
+            .bind::<dUuid, _>(user_id)
+            .bind::<Nullable<Text>, _>(user.email.clone())
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not remove audited changes")?;
+        let order_ids = orders::table
+            .filter(orders::user_id.eq(user_id))
+            .select(orders::id);
+        diesel::update(payments::table.filter(payments::order_id.eq_any(order_ids)))
+            .set((
+                payments::raw_data.eq(None::<serde_json::Value>),
+                payments::updated_at.eq(dsl::now),
+            )).execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not remove payment data")?;
+        let api_key_ids = organization_api_keys::table
+            .filter(
+                organization_api_keys::service_user_id
+                    .eq(user_id)
+                    .or(organization_api_keys::created_by.eq(user_id)),
+            ).select(organization_api_keys::id);
+        diesel::update(
+            organization_api_key_requests::table
+                .filter(organization_api_key_requests::organization_api_key_id.eq_any(api_key_ids)),
+        ).set(organization_api_key_requests::ip_address.eq(None::<String>))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not remove API key request addresses")?;
+        diesel::update(
+            impersonations::table.filter(
+                impersonations::user_id
+                    .eq(user_id)
+                    .or(impersonations::admin_user_id.eq(user_id)),
+            ),
+        ).set((
+            impersonations::reason.eq(None::<String>),
+            impersonations::updated_at.eq(dsl::now),
+        )).execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not remove impersonation reasons")?;
+
+        if let Some(email) = user.email {
+            diesel::delete(
+                organization_invites::table.filter(organization_invites::user_email.eq(email)),
+            ).execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove invites")?;
+        }
+        diesel::delete(
+            organization_invites::table.filter(organization_invites::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove invites")?;
+
+        // Outstanding transfers would otherwise still reveal the user as the sender
+        let wallet_ids = wallets::table
+            .filter(wallets::user_id.eq(user_id))
+            .select(wallets::id);
+        diesel::update(
+            ticket_instances::table.filter(ticket_instances::wallet_id.eq_any(wallet_ids)),
+        ).set((
+            ticket_instances::transfer_key.eq(None::<Uuid>),
+            ticket_instances::transfer_expiry_date.eq(None::<NaiveDateTime>),
+        )).execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not cancel ticket transfers")?;
+
+        diesel::delete(external_logins::table.filter(external_logins::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove external logins")?;
+        diesel::delete(sessions::table.filter(sessions::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove sessions")?;
+        diesel::delete(
+            two_factor_recovery_codes::table.filter(two_factor_recovery_codes::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove recovery codes")?;
+        diesel::delete(
+            two_factor_credentials::table.filter(two_factor_credentials::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove two factor credentials",
+        )?;
+        diesel::delete(payment_methods::table.filter(payment_methods::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove payment methods")?;
+        diesel::delete(calendar_tokens::table.filter(calendar_tokens::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove calendar tokens")?;
+        diesel::delete(
+            event_interest_notifications::table
+                .filter(event_interest_notifications::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove interest notifications",
+        )?;
+        diesel::delete(event_reminders::table.filter(event_reminders::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event reminders")?;
+        diesel::delete(event_interest::table.filter(event_interest::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event interest")?;
+        diesel::delete(
+            interest_notification_opt_outs::table
+                .filter(interest_notification_opt_outs::user_id.eq(user_id)),
+        ).execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove notification opt outs",
+        )?;
+        diesel::delete(organization_users::table.filter(organization_users::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::DeleteError,
+                "Could not remove organization memberships",
+            )?;
+        diesel::delete(login_codes::table.filter(login_codes::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove login codes")?;
+        diesel::delete(login_throttles::table.filter(login_throttles::user_id.eq(user_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove login throttles")?;
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use chrono::NaiveDateTime;
 use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
+use models::concerns::users::email_verifiable::EmailVerification;
 use models::*;
 use schema::{organization_users, organizations, users};
 use std::collections::HashMap;
@@ -20,6 +21,7 @@ pub struct NewUser {
     pub phone: Option<String>,
     pub hashed_pw: String,
     role: Vec<String>,
+    password_set: bool,
 }
 
 #[derive(Queryable, Identifiable, PartialEq, Debug, Clone, QueryableByName)]
@@ -43,6 +45,12 @@ pub struct User {
     pub password_reset_requested_at: Option<NaiveDateTime>,
     pub updated_at: NaiveDateTime,
     pub last_cart_id: Option<Uuid>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_token: Option<Uuid>,
+    pub email_verification_sent_at: Option<NaiveDateTime>,
+    /// False until a user created from an external login chooses a password
+    pub password_set: bool,
+    pub phone_verified_at: Option<NaiveDateTime>,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -106,6 +114,22 @@ impl User {
             phone: Some(String::from(phone)),
             hashed_pw: hash.to_string(),
             role: vec![Roles::User.to_string()],
+            password_set: true,
+        }
+    }
+
+    /// A user without an email address or usable password that acts on behalf of an
+    /// organization API key
+    pub fn create_service_user(name: &str) -> NewUser {
+        let hash = PasswordHash::generate(&PasswordHash::generate_salt(), None);
+        NewUser {
+            first_name: String::from(name),
+            last_name: String::from("API key"),
+            email: None,
+            phone: None,
+            hashed_pw: hash.to_string(),
+            role: Vec::new(),
+            password_set: false,
         }
     }
 
@@ -127,22 +151,78 @@ impl User {
         )
     }
 
+    /// Only verified phone numbers identify a user, unverified numbers are not unique
+    pub fn find_by_verified_phone(phone: &str, conn: &PgConnection) -> Result<User, DatabaseError> {
+        users::table
+            .filter(users::phone.eq(phone))
+            .filter(users::phone_verified_at.is_not_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading user")
+    }
+
+    pub fn is_phone_verified(&self) -> bool {
+        self.phone.is_some() && self.phone_verified_at.is_some()
+    }
+
+    /// Fails with a duplicate key error if another user has already verified the phone number
+    pub fn mark_phone_verified(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        diesel::update(self)
+            .set((
+                users::phone_verified_at.eq(dsl::now.nullable()),
+                users::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not verify phone number")
+    }
+
     pub fn update(
         &self,
         attributes: &UserEditableAttributes,
         conn: &PgConnection,
     ) -> Result<User, DatabaseError> {
         attributes.validate()?;
+        // Deactivated users are signed out of all of their devices
+        if attributes.active == Some(false) {
+            Session::revoke_all_for_user(self.id, conn)?;
+        }
         let query = diesel::update(self).set((attributes, users::updated_at.eq(dsl::now)));
 
-        DatabaseError::wrap(
+        let mut user: User = DatabaseError::wrap(
             ErrorCode::UpdateError,
             "Error updating user",
             query.get_result(conn),
-        )
+        )?;
+
+        // A changed phone number has to be verified again before it can be used to sign in, codes
+        // sent to the previous number no longer verify it
+        if attributes.phone.is_some() && attributes.phone != self.phone {
+            LoginCode::expire_phone_verifications(self.id, conn)?;
+            user = diesel::update(&user)
+                .set(users::phone_verified_at.eq(None::<NaiveDateTime>))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Error updating user")?;
+        }
+
+        // A changed email address has to be verified again
+        if attributes.email.is_some() && attributes.email != self.email {
+            return DatabaseError::wrap(
+                ErrorCode::UpdateError,
+                "Error updating user",
+                diesel::update(&user)
+                    .set(EmailVerification {
+                        email_verified_at: None,
+                        email_verification_token: None,
+                        email_verification_sent_at: None,
+                    }).get_result(conn),
+            );
+        }
+
+        Ok(user)
     }
 
     pub fn check_password(&self, password: &str) -> bool {
+        if !self.password_set {
+            return false;
+        }
         let hash = match PasswordHash::from_str(&self.hashed_pw) {
             Ok(h) => h,
             Err(_) => return false,
@@ -176,7 +256,7 @@ impl User {
         conn: &PgConnection,
     ) -> Result<HashMap<Uuid, Vec<String>>, DatabaseError> {
         let mut roles_by_organization = HashMap::new();
-        for organization in self.organizations(conn)? {
+        for organization in Organization::all_linked_to_user(self.id, conn)? {
             roles_by_organization.insert(
                 organization.id.clone(),
                 organization.get_roles_for_user(self, conn)?,
@@ -190,7 +270,7 @@ impl User {
         conn: &PgConnection,
     ) -> Result<HashMap<Uuid, Vec<String>>, DatabaseError> {
         let mut scopes_by_organization = HashMap::new();
-        for organization in self.organizations(conn)? {
+        for organization in Organization::all_linked_to_user(self.id, conn)? {
             scopes_by_organization.insert(
                 organization.id.clone(),
                 organization.get_scopes_for_user(self, conn)?,
@@ -217,6 +297,13 @@ impl User {
             )
     }
 
+    /// True if any organization the user owns or belongs to requires two factor authentication
+    pub fn two_factor_required(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(Organization::all_linked_to_user(self.id, conn)?
+            .iter()
+            .any(|organization| organization.require_two_factor))
+    }
+
     pub fn payment_methods(
         &self,
         conn: &PgConnection,
@@ -284,7 +371,7 @@ impl User {
         access_token: String,
         conn: &PgConnection,
     ) -> Result<User, DatabaseError> {
-        let hash = PasswordHash::generate("random", None);
+        let hash = PasswordHash::generate(&PasswordHash::generate_salt(), None);
         let new_user = NewUser {
             first_name: first_name.to_string(),
             last_name: last_name.to_string(),
@@ -292,6 +379,7 @@ impl User {
             phone: None,
             hashed_pw: hash.to_string(),
             role: vec![Roles::User.to_string()],
+            password_set: false,
         };
         new_user.commit(&*conn).and_then(|user| {
             user.add_external_login(external_user_id, site, access_token, conn)?;
@@ -0,0 +1,183 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Text, Timestamp, Uuid as dUuid};
+use models::{DomainEvent, WebhookDeliveryStatus, WebhookSubscription};
+use schema::webhook_deliveries;
+use serde_json;
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Deliveries are given up on after this many failed attempts
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+const RETRY_BASE_SECONDS: i64 = 60;
+const MAX_RETRY_SECONDS: i64 = 60 * 60 * 6;
+/// How long claimed deliveries are skipped by other workers while they are being sent
+const CLAIM_SECONDS: i64 = 60 * 5;
+
+/// A domain event queued for delivery to a webhook. Pending deliveries are retried with
+/// exponential backoff until they succeed or run out of attempts, the last response is kept as
+/// the delivery log
+#[derive(Identifiable, Associations, Queryable, QueryableByName, Clone, PartialEq, Debug)]
+#[derive(Serialize)]
+#[belongs_to(WebhookSubscription)]
+#[belongs_to(DomainEvent)]
+#[table_name = "webhook_deliveries"]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_subscription_id: Uuid,
+    pub domain_event_id: Uuid,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub last_attempt_at: Option<NaiveDateTime>,
+    pub response_status: Option<i32>,
+    /// Kept for support only, the subscriber's response is not shown to the organization
+    #[serde(skip_serializing)]
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl WebhookDelivery {
+    /// Queues deliveries for domain events raised since each active subscription was created.
+    /// Events are read from the domain events table so none are lost if the queue is not
+    /// processed for a while
+    pub fn queue_pending(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let mut queued = 0;
+        for subscription in WebhookSubscription::find_active(conn)? {
+            queued += diesel::sql_query(include_str!("../queries/queue_webhook_deliveries.sql"))
+                .bind::<dUuid, _>(subscription.id)
+                .bind::<Array<Text>, _>(subscription.domain_event_types())
+                .execute(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not queue webhook deliveries")?;
+        }
+        Ok(queued)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        webhook_deliveries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook delivery")
+    }
+
+    /// Claims the oldest pending deliveries of active subscriptions that are due to be
+    /// attempted. Deliveries claimed by another worker are skipped so none are sent twice
+    pub fn claim_due(
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        diesel::sql_query(include_str!("../queries/claim_webhook_deliveries.sql"))
+            .bind::<BigInt, _>(limit)
+            .bind::<Timestamp, _>(now)
+            .bind::<Timestamp, _>(now + Duration::seconds(CLAIM_SECONDS))
+            .load(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not claim webhook deliveries")
+    }
+
+    pub fn status(&self) -> WebhookDeliveryStatus {
+        self.status.parse::<WebhookDeliveryStatus>().unwrap()
+    }
+
+    pub fn subscription(&self, conn: &PgConnection) -> Result<WebhookSubscription, DatabaseError> {
+        WebhookSubscription::find(self.webhook_subscription_id, conn)
+    }
+
+    /// Webhook name of the domain event and the JSON body that is sent to the subscriber
+    pub fn payload(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<(String, serde_json::Value), DatabaseError> {
+        let domain_event = DomainEvent::find_by_id(self.domain_event_id, conn)?;
+        let event_type = WebhookSubscription::event_type_name(&domain_event.event_type)
+            .unwrap_or_else(|| domain_event.event_type.clone());
+        let payload = json!({
+            "id": domain_event.id,
+            "type": event_type,
+            "created_at": domain_event.created_at,
+            "organization_id": domain_event.organization_id,
+            "data": domain_event.event_data,
+        });
+        Ok((event_type, payload))
+    }
+
+    pub fn mark_delivered(
+        &self,
+        response_status: i32,
+        response_body: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<WebhookDelivery, DatabaseError> {
+        diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(WebhookDeliveryStatus::Delivered.to_string()),
+                webhook_deliveries::attempts.eq(self.attempts + 1),
+                webhook_deliveries::next_attempt_at.eq(None::<NaiveDateTime>),
+                webhook_deliveries::last_attempt_at.eq(dsl::now.nullable()),
+                webhook_deliveries::response_status.eq(Some(response_status)),
+                webhook_deliveries::response_body.eq(response_body),
+                webhook_deliveries::error.eq(None::<String>),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")
+    }
+
+    /// Records a failed attempt and schedules the next one, the delivery is marked as failed
+    /// once it runs out of attempts
+    pub fn mark_attempt_failed(
+        &self,
+        response_status: Option<i32>,
+        response_body: Option<String>,
+        error: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<WebhookDelivery, DatabaseError> {
+        let attempts = self.attempts + 1;
+        let (status, next_attempt_at) = if attempts >= MAX_DELIVERY_ATTEMPTS {
+            (WebhookDeliveryStatus::Failed, None)
+        } else {
+            (
+                WebhookDeliveryStatus::Pending,
+                Some(Utc::now().naive_utc() + WebhookDelivery::retry_delay(attempts)),
+            )
+        };
+
+        diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(status.to_string()),
+                webhook_deliveries::attempts.eq(attempts),
+                webhook_deliveries::next_attempt_at.eq(next_attempt_at),
+                webhook_deliveries::last_attempt_at.eq(dsl::now.nullable()),
+                webhook_deliveries::response_status.eq(response_status),
+                webhook_deliveries::response_body.eq(response_body),
+                webhook_deliveries::error.eq(error),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")
+    }
+
+    /// Delay before the next attempt, doubling after every failed attempt
+    pub fn retry_delay(attempts: i32) -> Duration {
+        let exponent = cmp::min(cmp::max(attempts - 1, 0), 16) as u32;
+        Duration::seconds(cmp::min(
+            RETRY_BASE_SECONDS * 2i64.pow(exponent),
+            MAX_RETRY_SECONDS,
+        ))
+    }
+
+    /// Queues the domain event to be sent again with a fresh set of attempts, the result of the
+    /// last attempt is kept until the next one is made
+    pub fn redeliver(&self, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(WebhookDeliveryStatus::Pending.to_string()),
+                webhook_deliveries::attempts.eq(0),
+                webhook_deliveries::next_attempt_at.eq(Some(Utc::now().naive_utc())),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            )).get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not queue webhook delivery")
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::{DomainEventTypes, Organization, WebhookDelivery};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use schema::{webhook_deliveries, webhook_subscriptions};
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+use validators;
+
+const SECRET_LENGTH: usize = 32;
+
+/// Domain events that can be delivered to webhooks along with the name subscribers use for them
+const WEBHOOK_EVENT_TYPES: [(&str, DomainEventTypes); 5] = [
+    ("event.published", DomainEventTypes::EventPublished),
+    ("order.paid", DomainEventTypes::OrderPaid),
+    ("order.refunded", DomainEventTypes::OrderRefunded),
+    ("ticket.redeemed", DomainEventTypes::TicketRedeemed),
+    ("ticket.transferred", DomainEventTypes::TicketTransferred),
+];
+
+/// URL of an organization's system that is sent the domain events it subscribed to. Payloads
+/// are signed with the secret so the receiver can verify they were sent by us
+#[derive(Identifiable, Associations, Queryable, Clone, PartialEq, Debug, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "webhook_subscriptions"]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub created_by: Uuid,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Validate)]
+#[table_name = "webhook_subscriptions"]
+pub struct NewWebhookSubscription {
+    pub organization_id: Uuid,
+    pub created_by: Uuid,
+    #[validate(custom = "validators::validate_webhook_url")]
+    pub url: String,
+    pub secret: String,
+    #[validate(custom = "validators::validate_webhook_event_types")]
+    pub event_types: Vec<String>,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Validate)]
+#[table_name = "webhook_subscriptions"]
+pub struct WebhookSubscriptionEditableAttributes {
+    #[validate(custom = "validators::validate_webhook_url")]
+    pub url: Option<String>,
+    #[validate(custom = "validators::validate_webhook_event_types")]
+    pub event_types: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+impl NewWebhookSubscription {
+    pub fn commit(self, conn: &PgConnection) -> Result<WebhookSubscription, DatabaseError> {
+        self.validate()?;
+        diesel::insert_into(webhook_subscriptions::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create webhook subscription")
+    }
+}
+
+impl WebhookSubscription {
+    pub fn create(
+        organization_id: Uuid,
+        url: String,
+        event_types: Vec<String>,
+        created_by: Uuid,
+    ) -> NewWebhookSubscription {
+        NewWebhookSubscription {
+            organization_id,
+            created_by,
+            url,
+            secret: thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(SECRET_LENGTH)
+                .collect(),
+            event_types,
+        }
+    }
+
+    /// Names of the events that can be subscribed to
+    pub fn available_event_types() -> Vec<String> {
+        WEBHOOK_EVENT_TYPES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Name subscribers use for a domain event type, if it can be delivered to webhooks
+    pub fn event_type_name(domain_event_type: &str) -> Option<String> {
+        WEBHOOK_EVENT_TYPES
+            .iter()
+            .find(|(_, event_type)| event_type.to_string() == domain_event_type)
+            .map(|(name, _)| name.to_string())
+    }
+
+    /// Domain event types matching the event types this webhook is subscribed to
+    pub fn domain_event_types(&self) -> Vec<String> {
+        WEBHOOK_EVENT_TYPES
+            .iter()
+            .filter(|(name, _)| self.event_types.iter().any(|e| e == name))
+            .map(|(_, event_type)| event_type.to_string())
+            .collect()
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WebhookSubscription, DatabaseError> {
+        webhook_subscriptions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook subscription")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<WebhookSubscription>, DatabaseError> {
+        webhook_subscriptions::table
+            .filter(webhook_subscriptions::organization_id.eq(organization_id))
+            .order_by(webhook_subscriptions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook subscriptions")
+    }
+
+    pub fn find_active(conn: &PgConnection) -> Result<Vec<WebhookSubscription>, DatabaseError> {
+        webhook_subscriptions::table
+            .filter(webhook_subscriptions::active.eq(true))
+            .order_by(webhook_subscriptions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook subscriptions")
+    }
+
+    pub fn update(
+        &self,
+        attributes: WebhookSubscriptionEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<WebhookSubscription, DatabaseError> {
+        attributes.validate()?;
+        diesel::update(self)
+            .set((attributes, webhook_subscriptions::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook subscription")
+    }
+
+    /// Removes the subscription along with its delivery log
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete webhook subscription")
+    }
+
+    pub fn deliveries(
+        &self,
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        webhook_deliveries::table
+            .filter(webhook_deliveries::webhook_subscription_id.eq(self.id))
+            .order_by(webhook_deliveries::created_at.desc())
+            .limit(limit)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook deliveries")
+    }
+}
@@ -0,0 +1,36 @@
+-- Claimed notifications are skipped by other workers until the claim expires, so each is sent
+-- once even when several workers run. Failed sends become due again when their claim expires
+WITH claimed AS (
+  UPDATE event_interest_notifications
+  SET claimed_until = $3,
+      updated_at    = now()
+  WHERE id IN (SELECT ein.id
+               FROM event_interest_notifications ein
+                      INNER JOIN events e ON ein.event_id = e.id
+                      INNER JOIN users u ON ein.user_id = u.id
+               WHERE ein.sent_at IS NULL
+                 AND ein.attempts < $4
+                 AND (ein.claimed_until IS NULL OR ein.claimed_until <= $2)
+                 AND u.email IS NOT NULL
+                 AND e.cancelled_at IS NULL
+               ORDER BY ein.created_at, ein.id
+               LIMIT $1
+               FOR UPDATE OF ein SKIP LOCKED)
+  RETURNING *
+)
+SELECT ein.id,
+       ein.event_id,
+       e.name            AS event_name,
+       e.event_start,
+       e.organization_id,
+       o.name            AS organization_name,
+       ein.user_id,
+       u.first_name,
+       u.last_name,
+       u.email,
+       ein.on_sale_at
+FROM claimed ein
+       INNER JOIN events e ON ein.event_id = e.id
+       INNER JOIN organizations o ON e.organization_id = o.id
+       INNER JOIN users u ON ein.user_id = u.id
+ORDER BY ein.created_at, ein.id;
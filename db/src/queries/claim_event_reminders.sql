@@ -0,0 +1,38 @@
+-- Claimed reminders are skipped by other workers until the claim expires, so each is sent once
+-- even when several workers run. Failed sends become due again when their claim expires
+WITH claimed AS (
+  UPDATE event_reminders
+  SET claimed_until = $3,
+      updated_at    = now()
+  WHERE id IN (SELECT er.id
+               FROM event_reminders er
+                      INNER JOIN events e ON er.event_id = e.id
+                      INNER JOIN users u ON er.user_id = u.id
+               WHERE er.sent_at IS NULL
+                 AND er.attempts < $4
+                 AND (er.claimed_until IS NULL OR er.claimed_until <= $2)
+                 AND u.email IS NOT NULL
+                 AND e.cancelled_at IS NULL
+                 -- Reminders for an earlier start time of a rescheduled event are not sent
+                 AND e.event_start = er.event_start
+                 AND e.event_start > $2
+               ORDER BY er.created_at, er.id
+               LIMIT $1
+               FOR UPDATE OF er SKIP LOCKED)
+  RETURNING *
+)
+SELECT er.id,
+       er.event_id,
+       e.name            AS event_name,
+       e.event_start,
+       v.name            AS venue_name,
+       e.organization_id,
+       er.user_id,
+       u.first_name,
+       u.last_name,
+       u.email
+FROM claimed er
+       INNER JOIN events e ON er.event_id = e.id
+       INNER JOIN users u ON er.user_id = u.id
+       LEFT JOIN venues v ON e.venue_id = v.id
+ORDER BY er.created_at, er.id;
@@ -0,0 +1,15 @@
+-- Pushes the next attempt of the claimed deliveries back so other workers skip them while they
+-- are being sent, they become due again if the worker stops before recording the attempt
+UPDATE webhook_deliveries
+SET next_attempt_at = $3,
+    updated_at      = now()
+WHERE id IN (SELECT wd.id
+             FROM webhook_deliveries wd
+                    INNER JOIN webhook_subscriptions ws ON wd.webhook_subscription_id = ws.id
+             WHERE wd.status = 'Pending'
+               AND ws.active
+               AND wd.next_attempt_at <= $2
+             ORDER BY wd.next_attempt_at
+             LIMIT $1
+             FOR UPDATE OF wd SKIP LOCKED)
+RETURNING *;
@@ -0,0 +1,10 @@
+-- The before and after values of changes to the user or to invites sent to them are removed,
+-- the entries themselves are kept as the record of who did what
+UPDATE audit_entries
+SET changes    = NULL,
+    updated_at = now()
+WHERE target_id = $1
+   OR (table_name = 'OrganizationInvites' AND target_id IN (SELECT oi.id
+                                                            FROM organization_invites oi
+                                                            WHERE oi.user_id = $1
+                                                               OR oi.user_email = $2));
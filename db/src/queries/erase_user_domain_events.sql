@@ -0,0 +1,16 @@
+-- Domain event data is delivered to webhooks and can include payment provider responses, the
+-- data of events about the user's orders, payments, payment methods and tickets is removed
+UPDATE domain_events
+SET event_data = NULL,
+    updated_at = now()
+WHERE (main_table = 'Orders' AND main_id IN (SELECT o.id FROM orders o WHERE o.user_id = $1))
+   OR (main_table = 'Payments' AND main_id IN (SELECT p.id
+                                               FROM payments p
+                                                      INNER JOIN orders o ON p.order_id = o.id
+                                               WHERE o.user_id = $1))
+   OR (main_table = 'PaymentMethods'
+         AND main_id IN (SELECT pm.id FROM payment_methods pm WHERE pm.user_id = $1))
+   OR (main_table = 'TicketInstances' AND main_id IN (SELECT ti.id
+                                                      FROM ticket_instances ti
+                                                             INNER JOIN wallets w ON ti.wallet_id = w.id
+                                                      WHERE w.user_id = $1));
@@ -0,0 +1,21 @@
+SELECT ein.id,
+       ein.event_id,
+       e.name            AS event_name,
+       e.event_start,
+       e.organization_id,
+       o.name            AS organization_name,
+       ein.user_id,
+       u.first_name,
+       u.last_name,
+       u.email,
+       ein.on_sale_at
+FROM event_interest_notifications ein
+       INNER JOIN events e ON ein.event_id = e.id
+       INNER JOIN organizations o ON e.organization_id = o.id
+       INNER JOIN users u ON ein.user_id = u.id
+WHERE ein.sent_at IS NULL
+  AND ein.attempts < $2
+  AND u.email IS NOT NULL
+  AND e.cancelled_at IS NULL
+ORDER BY ein.created_at, ein.id
+LIMIT $1;
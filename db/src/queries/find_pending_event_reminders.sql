@@ -0,0 +1,23 @@
+SELECT er.id,
+       er.event_id,
+       e.name            AS event_name,
+       e.event_start,
+       v.name            AS venue_name,
+       e.organization_id,
+       er.user_id,
+       u.first_name,
+       u.last_name,
+       u.email
+FROM event_reminders er
+       INNER JOIN events e ON er.event_id = e.id
+       INNER JOIN users u ON er.user_id = u.id
+       LEFT JOIN venues v ON e.venue_id = v.id
+WHERE er.sent_at IS NULL
+  AND er.attempts < $3
+  AND u.email IS NOT NULL
+  AND e.cancelled_at IS NULL
+  -- Reminders for an earlier start time of a rescheduled event are not sent
+  AND e.event_start = er.event_start
+  AND e.event_start > $2
+ORDER BY er.created_at, er.id
+LIMIT $1;
@@ -0,0 +1,12 @@
+WITH RECURSIVE ancestors AS (
+  SELECT p.*
+  FROM organizations o
+         INNER JOIN organizations p ON o.parent_id = p.id
+  WHERE o.id = $1
+  UNION
+  SELECT p.*
+  FROM ancestors a
+         INNER JOIN organizations p ON a.parent_id = p.id
+)
+SELECT *
+FROM ancestors;
@@ -0,0 +1,84 @@
+-- Used as a subquery, ordering and paging are added by OrganizationCustomer::find_for_organization
+-- Everyone who bought, was comped or was transferred a ticket for one of the organization's events
+-- Spend is taken from the ledger's Customer account so refunds and chargebacks are subtracted and
+-- fees absorbed by the organization, which were never charged to the customer, are left out
+WITH purchases AS (
+    SELECT o.user_id,
+           CAST(-SUM(le.amount_in_cents) AS BIGINT) AS spent_in_cents,
+           MIN(o.order_date)                        AS first_purchase_at,
+           MAX(o.order_date)                        AS last_purchase_at
+    FROM ledger_entries le
+           INNER JOIN orders o ON le.order_id = o.id
+           INNER JOIN events e ON le.event_id = e.id
+    WHERE le.account = 'Customer'
+      AND e.organization_id = $1
+    GROUP BY o.user_id
+),
+     tickets AS (
+    SELECT w.user_id,
+           COUNT(ti.id)                                                         AS ticket_count,
+           COUNT(DISTINCT CASE WHEN ti.status = 'Redeemed' THEN tt.event_id END) AS events_attended
+    FROM ticket_instances ti
+           INNER JOIN wallets w ON ti.wallet_id = w.id
+           INNER JOIN assets a ON ti.asset_id = a.id
+           INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+           INNER JOIN events e ON tt.event_id = e.id
+    WHERE e.organization_id = $1
+      AND ti.status IN ('Purchased', 'Redeemed')
+      AND w.user_id IS NOT NULL
+    GROUP BY w.user_id
+),
+     interests AS (
+    SELECT ei.user_id,
+           array_agg(e.name ORDER BY e.name) AS interests
+    FROM event_interest ei
+           INNER JOIN events e ON ei.event_id = e.id
+    WHERE e.organization_id = $1
+    GROUP BY ei.user_id
+),
+     customers AS (
+    SELECT user_id FROM purchases
+    UNION
+    SELECT user_id FROM tickets
+)
+SELECT u.id                                             AS user_id,
+       u.first_name,
+       u.last_name,
+       u.email,
+       u.phone,
+       COALESCE(p.spent_in_cents, 0)                    AS lifetime_spent_in_cents,
+       COALESCE(t.ticket_count, 0)                      AS ticket_count,
+       COALESCE(t.events_attended, 0)                   AS events_attended,
+       p.first_purchase_at,
+       p.last_purchase_at,
+       COALESCE(i.interests, CAST(ARRAY[] AS TEXT[]))   AS interests
+FROM customers c
+       INNER JOIN users u ON c.user_id = u.id
+       LEFT JOIN purchases p ON p.user_id = u.id
+       LEFT JOIN tickets t ON t.user_id = u.id
+       LEFT JOIN interests i ON i.user_id = u.id
+WHERE ($2 IS NULL
+         OR u.first_name ILIKE '%' || $2 || '%'
+         OR u.last_name ILIKE '%' || $2 || '%'
+         OR u.email ILIKE '%' || $2 || '%'
+         OR u.phone ILIKE '%' || $2 || '%')
+  -- Filtering by event keeps the lifetime totals across all of the organization's events
+  AND ($3 IS NULL
+         OR EXISTS(SELECT 1
+                   FROM ticket_instances ti
+                          INNER JOIN wallets w ON ti.wallet_id = w.id
+                          INNER JOIN assets a ON ti.asset_id = a.id
+                          INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+                   WHERE w.user_id = u.id
+                     AND tt.event_id = $3
+                     AND ti.status IN ('Purchased', 'Redeemed'))
+         OR EXISTS(SELECT 1
+                   FROM order_items oi
+                          INNER JOIN orders o ON oi.order_id = o.id
+                   WHERE o.user_id = u.id
+                     AND o.status = 'Paid'
+                     AND oi.event_id = $3))
+  AND ($4 IS NULL OR COALESCE(p.spent_in_cents, 0) >= $4)
+  AND ($5 IS NULL OR COALESCE(t.events_attended, 0) >= $5)
+  AND ($6 IS NULL OR p.last_purchase_at >= $6)
+  AND ($7 IS NULL OR p.first_purchase_at <= $7)
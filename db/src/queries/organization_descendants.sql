@@ -0,0 +1,12 @@
+WITH RECURSIVE descendants AS (
+  SELECT o.*
+  FROM organizations o
+  WHERE o.parent_id = ANY($1)
+  UNION
+  SELECT o.*
+  FROM descendants d
+         INNER JOIN organizations o ON o.parent_id = d.id
+)
+SELECT *
+FROM descendants
+ORDER BY name;
@@ -0,0 +1,18 @@
+INSERT INTO event_interest_notifications (event_id, user_id, on_sale_at)
+SELECT DISTINCT e.id, ei.user_id, tp.start_date
+FROM ticket_pricing tp
+       INNER JOIN ticket_types tt ON tp.ticket_type_id = tt.id
+       INNER JOIN events e ON tt.event_id = e.id
+       INNER JOIN event_interest ei ON ei.event_id = e.id
+WHERE e.status = 'Published'
+  AND e.publish_date IS NOT NULL
+  AND e.cancelled_at IS NULL
+  AND tp.status = 'Published'
+  AND tp.start_date > e.publish_date
+  AND tp.start_date <= now()
+  AND tp.end_date > now()
+  AND NOT EXISTS(SELECT 1
+                 FROM interest_notification_opt_outs oo
+                 WHERE oo.organization_id = e.organization_id
+                   AND oo.user_id = ei.user_id)
+ON CONFLICT DO NOTHING;
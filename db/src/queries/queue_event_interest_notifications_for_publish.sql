@@ -0,0 +1,20 @@
+INSERT INTO event_interest_notifications (event_id, user_id, on_sale_at)
+SELECT e.id, ei.user_id, e.publish_date
+FROM events e
+       INNER JOIN event_interest ei ON ei.event_id = e.id
+WHERE e.id = $1
+  AND e.status = 'Published'
+  AND e.publish_date IS NOT NULL
+  AND e.cancelled_at IS NULL
+  AND EXISTS(SELECT 1
+             FROM ticket_pricing tp
+                    INNER JOIN ticket_types tt ON tp.ticket_type_id = tt.id
+             WHERE tt.event_id = e.id
+               AND tp.status = 'Published'
+               AND tp.start_date <= now()
+               AND tp.end_date > now())
+  AND NOT EXISTS(SELECT 1
+                 FROM interest_notification_opt_outs oo
+                 WHERE oo.organization_id = e.organization_id
+                   AND oo.user_id = ei.user_id)
+ON CONFLICT DO NOTHING;
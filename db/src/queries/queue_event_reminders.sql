@@ -0,0 +1,15 @@
+-- Ticket holders of published events starting within the reminder period
+INSERT INTO event_reminders (event_id, user_id, event_start)
+SELECT DISTINCT e.id, w.user_id, e.event_start
+FROM ticket_instances ti
+       INNER JOIN wallets w ON ti.wallet_id = w.id
+       INNER JOIN assets a ON ti.asset_id = a.id
+       INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+       INNER JOIN events e ON tt.event_id = e.id
+WHERE e.status = 'Published'
+  AND e.cancelled_at IS NULL
+  AND e.event_start > $1
+  AND e.event_start <= $2
+  AND ti.status = 'Purchased'
+  AND w.user_id IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,10 @@
+INSERT INTO webhook_deliveries (webhook_subscription_id, domain_event_id, status, next_attempt_at)
+SELECT ws.id, de.id, 'Pending', de.created_at
+FROM webhook_subscriptions ws
+       INNER JOIN domain_events de ON de.organization_id = ws.organization_id
+WHERE ws.id = $1
+  AND ws.active
+  AND de.event_type = ANY($2)
+  AND de.created_at >= ws.created_at
+-- Another worker may have queued the same events in the meantime
+ON CONFLICT (webhook_subscription_id, domain_event_id) DO NOTHING;
@@ -0,0 +1,26 @@
+SELECT ti.id,
+       t2.name        AS ticket_type,
+       ti.status,
+       case when e.redeem_date is null or e.redeem_date < now() then ti.redeem_key else null end as redeem_key,
+       o.id           AS order_id,
+       u.id           AS user_id,
+       u.first_name   AS first_name,
+       u.last_name    AS last_name,
+       u.email        AS email,
+       u.phone        AS phone,
+       pu.id          AS purchaser_id,
+       pu.first_name  AS purchaser_first_name,
+       pu.last_name   AS purchaser_last_name,
+       pu.email       AS purchaser_email
+FROM ticket_instances ti
+       INNER JOIN assets a ON ti.asset_id = a.id
+       INNER JOIN ticket_types t2 ON a.ticket_type_id = t2.id
+       INNER JOIN wallets w ON ti.wallet_id = w.id
+       INNER JOIN users u ON w.user_id = u.id
+       INNER JOIN events e ON t2.event_id = e.id
+       LEFT JOIN order_items oi ON ti.order_item_id = oi.id
+       LEFT JOIN orders o ON oi.order_id = o.id
+       LEFT JOIN users pu ON o.user_id = pu.id
+WHERE t2.event_id = $1
+  AND ti.status IN ('Purchased', 'Redeemed')
+ORDER BY lower(u.last_name), lower(u.first_name), u.id, t2.name, ti.id;
@@ -0,0 +1,17 @@
+SELECT e.id                                                             AS event_id,
+       e.name                                                           AS event_name,
+       CAST(COALESCE(SUM(CASE WHEN NOT oi.is_absorbed THEN oi.quantity * oi.unit_price_in_cents END), 0) AS BIGINT)
+                                                                        AS event_fees_in_cents,
+       CAST(COALESCE(SUM(CASE WHEN oi.is_absorbed THEN oi.quantity * oi.unit_price_in_cents END), 0) AS BIGINT)
+                                                                        AS absorbed_event_fees_in_cents
+FROM order_items oi
+       INNER JOIN orders o ON oi.order_id = o.id
+       INNER JOIN events e ON oi.event_id = e.id
+WHERE oi.item_type = 'EventFees'
+  AND o.status = 'Paid'
+  AND e.organization_id = ANY($1)
+  AND ($2 IS NULL OR e.id = $2)
+  AND ($3 IS NULL OR o.order_date >= $3)
+  AND ($4 IS NULL OR o.order_date <= $4)
+GROUP BY e.id, e.name
+ORDER BY e.name;
@@ -0,0 +1,20 @@
+SELECT e.id                                                                                 AS event_id,
+       tt.id                                                                                AS ticket_type_id,
+       tt.name                                                                              AS ticket_type_name,
+       COUNT(ti.id) FILTER (WHERE ti.status IN ('Purchased', 'Redeemed'))                   AS sold_quantity,
+       COUNT(ti.id) FILTER (WHERE ti.status = 'Reserved' AND ti.reserved_until >= now())    AS reserved_quantity,
+       COUNT(ti.id) FILTER (WHERE ti.hold_id IS NOT NULL
+                                  AND (ti.status = 'Available'
+                                         OR (ti.status = 'Reserved' AND ti.reserved_until < now()))) AS held_quantity,
+       COUNT(ti.id) FILTER (WHERE ti.hold_id IS NULL
+                                  AND (ti.status = 'Available'
+                                         OR (ti.status = 'Reserved' AND ti.reserved_until < now()))) AS available_quantity,
+       COUNT(ti.id) FILTER (WHERE ti.status = 'Redeemed')                                   AS redeemed_quantity
+FROM ticket_types tt
+       INNER JOIN events e ON tt.event_id = e.id
+       INNER JOIN assets a ON a.ticket_type_id = tt.id
+       INNER JOIN ticket_instances ti ON ti.asset_id = a.id
+WHERE e.organization_id = ANY($1)
+  AND ($2 IS NULL OR e.id = $2)
+GROUP BY e.id, tt.id, tt.name
+ORDER BY e.id, tt.name;
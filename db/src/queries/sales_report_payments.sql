@@ -0,0 +1,14 @@
+SELECT p.payment_method,
+       COUNT(p.id)                                   AS payment_count,
+       CAST(COALESCE(SUM(p.amount), 0) AS BIGINT)    AS amount_in_cents
+FROM payments p
+WHERE p.status = 'Completed'
+  AND p.order_id IN (SELECT oi.order_id
+                     FROM order_items oi
+                            INNER JOIN events e ON oi.event_id = e.id
+                     WHERE e.organization_id = ANY($1)
+                       AND ($2 IS NULL OR e.id = $2))
+  AND ($3 IS NULL OR p.created_at >= $3)
+  AND ($4 IS NULL OR p.created_at <= $4)
+GROUP BY p.payment_method
+ORDER BY p.payment_method;
@@ -0,0 +1,13 @@
+-- Ticket revenue of each promoter as recorded in the ledger, net of refunds and chargebacks, so
+-- sales are divided the way they were when recorded rather than by the current revenue splits
+SELECT le.organization_id,
+       CAST(COALESCE(SUM(le.amount_in_cents), 0) AS BIGINT) AS ticket_revenue_in_cents
+FROM ledger_entries le
+       INNER JOIN order_items oi ON le.order_item_id = oi.id
+WHERE le.event_id = $1
+  AND le.account = 'Organization'
+  AND oi.item_type = 'Tickets'
+  AND ($2 IS NULL OR le.created_at >= $2)
+  AND ($3 IS NULL OR le.created_at <= $3)
+GROUP BY le.organization_id
+ORDER BY le.organization_id = $4 DESC, le.organization_id;
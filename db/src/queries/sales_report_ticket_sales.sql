@@ -0,0 +1,36 @@
+SELECT e.id                                AS event_id,
+       tt.id                               AS ticket_type_id,
+       tt.name                             AS ticket_type_name,
+       tp.id                               AS ticket_pricing_id,
+       tp.name                             AS ticket_pricing_name,
+       CAST(o.order_date AS DATE)          AS sales_date,
+       CAST(COALESCE(SUM(CASE WHEN o.status = 'Paid' AND oi.unit_price_in_cents > 0 THEN oi.quantity END), 0) AS BIGINT)
+                                           AS sold_quantity,
+       CAST(COALESCE(SUM(CASE WHEN o.status = 'Paid' AND oi.unit_price_in_cents = 0 THEN oi.quantity END), 0) AS BIGINT)
+                                           AS comped_quantity,
+       CAST(COALESCE(SUM(CASE WHEN o.status = 'Cancelled' THEN oi.quantity END), 0) AS BIGINT)
+                                           AS refunded_quantity,
+       CAST(COALESCE(SUM(CASE WHEN o.status = 'Paid' THEN oi.quantity * oi.unit_price_in_cents END), 0) AS BIGINT)
+                                           AS gross_revenue_in_cents,
+       -- Absorbed fees are paid by the organization rather than the buyer so are reported apart
+       CAST(COALESCE(SUM(CASE WHEN o.status = 'Paid' AND NOT f.is_absorbed THEN f.quantity * f.unit_price_in_cents END), 0) AS BIGINT)
+                                           AS per_unit_fees_in_cents,
+       CAST(COALESCE(SUM(CASE WHEN o.status = 'Paid' AND f.is_absorbed THEN f.quantity * f.unit_price_in_cents END), 0) AS BIGINT)
+                                           AS absorbed_per_unit_fees_in_cents
+FROM order_items oi
+       INNER JOIN orders o ON oi.order_id = o.id
+       INNER JOIN ticket_pricing tp ON oi.ticket_pricing_id = tp.id
+       INNER JOIN ticket_types tt ON tp.ticket_type_id = tt.id
+       INNER JOIN events e ON tt.event_id = e.id
+       LEFT JOIN order_items f ON f.parent_id = oi.id AND f.item_type = 'PerUnitFees'
+WHERE oi.item_type = 'Tickets'
+  AND e.organization_id = ANY($1)
+  AND ($2 IS NULL OR e.id = $2)
+  AND ($3 IS NULL OR o.order_date >= $3)
+  AND ($4 IS NULL OR o.order_date <= $4)
+  -- Cancelled orders only count as refunds when money had been taken
+  AND (o.status = 'Paid'
+         OR (o.status = 'Cancelled'
+               AND EXISTS(SELECT 1 FROM payments p WHERE p.order_id = o.id AND p.status = 'Completed')))
+GROUP BY e.id, tt.id, tt.name, tp.id, tp.name, CAST(o.order_date AS DATE)
+ORDER BY sales_date, tt.name, tp.name;
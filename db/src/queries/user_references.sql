@@ -0,0 +1,9 @@
+-- Columns with a foreign key to users
+SELECT cl.relname::text  AS table_name,
+       att.attname::text AS column_name
+FROM pg_constraint con
+       INNER JOIN pg_class cl ON con.conrelid = cl.oid
+       INNER JOIN pg_attribute att ON att.attrelid = con.conrelid AND att.attnum = ANY (con.conkey)
+WHERE con.contype = 'f'
+  AND con.confrelid = 'users'::regclass
+ORDER BY cl.relname, att.attname;
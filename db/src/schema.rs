@@ -31,6 +31,33 @@ table! {
     }
 }
 
+table! {
+    audit_entries (id) {
+        id -> Uuid,
+        organization_id -> Nullable<Uuid>,
+        user_id -> Nullable<Uuid>,
+        request_id -> Nullable<Text>,
+        action -> Text,
+        table_name -> Text,
+        target_id -> Nullable<Uuid>,
+        changes -> Nullable<Json>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        impersonated_by -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    calendar_tokens (id) {
+        id -> Uuid,
+        token -> Uuid,
+        user_id -> Nullable<Uuid>,
+        organization_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     domain_events (id) {
         id -> Uuid,
@@ -41,6 +68,7 @@ table! {
         main_id -> Nullable<Uuid>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        organization_id -> Nullable<Uuid>,
     }
 }
 
@@ -66,6 +94,48 @@ table! {
     }
 }
 
+table! {
+    event_interest_notifications (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        user_id -> Uuid,
+        on_sale_at -> Timestamp,
+        sent_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        attempts -> Int4,
+        claimed_until -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
+table! {
+    event_promoters (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        organization_id -> Uuid,
+        permission -> Text,
+        revenue_split_in_basis_points -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_reminders (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        user_id -> Uuid,
+        event_start -> Timestamp,
+        sent_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        attempts -> Int4,
+        claimed_until -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
 table! {
     events (id) {
         id -> Uuid,
@@ -85,6 +155,8 @@ table! {
         top_line_info -> Nullable<Varchar>,
         cancelled_at -> Nullable<Timestamp>,
         updated_at -> Timestamp,
+        fee_schedule_id -> Nullable<Uuid>,
+        fee_mode -> Nullable<Text>,
     }
 }
 
@@ -108,6 +180,9 @@ table! {
         fee_in_cents -> Int8,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        percentage_in_basis_points -> Int8,
+        min_fee_in_cents -> Nullable<Int8>,
+        max_fee_in_cents -> Nullable<Int8>,
     }
 }
 
@@ -118,6 +193,8 @@ table! {
         version -> Int2,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        effective_at -> Timestamp,
+        root_fee_schedule_id -> Uuid,
     }
 }
 
@@ -135,6 +212,97 @@ table! {
     }
 }
 
+table! {
+    impersonation_actions (id) {
+        id -> Uuid,
+        impersonation_id -> Uuid,
+        method -> Text,
+        path -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    impersonations (id) {
+        id -> Uuid,
+        admin_user_id -> Uuid,
+        user_id -> Uuid,
+        session_id -> Uuid,
+        reason -> Nullable<Text>,
+        expires_at -> Timestamp,
+        ended_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    interest_notification_opt_outs (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    ledger_entries (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        order_id -> Nullable<Uuid>,
+        order_item_id -> Nullable<Uuid>,
+        settlement_id -> Nullable<Uuid>,
+        entry_type -> Text,
+        account -> Text,
+        amount_in_cents -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    login_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        channel -> Text,
+        hashed_code -> Text,
+        attempts -> Int4,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        purpose -> Text,
+        phone -> Nullable<Text>,
+    }
+}
+
+table! {
+    login_throttles (id) {
+        id -> Uuid,
+        user_id -> Nullable<Uuid>,
+        ip_address -> Nullable<Text>,
+        failed_attempts -> Int4,
+        last_failed_at -> Timestamp,
+        locked_until -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    oidc_login_requests (id) {
+        id -> Uuid,
+        state -> Text,
+        nonce -> Text,
+        redirect_uri -> Text,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     order_items (id) {
         id -> Uuid,
@@ -148,6 +316,7 @@ table! {
         ticket_pricing_id -> Nullable<Uuid>,
         fee_schedule_range_id -> Nullable<Uuid>,
         parent_id -> Nullable<Uuid>,
+        is_absorbed -> Bool,
     }
 }
 
@@ -165,6 +334,46 @@ table! {
     }
 }
 
+table! {
+    organization_api_key_requests (id) {
+        id -> Uuid,
+        organization_api_key_id -> Uuid,
+        method -> Text,
+        path -> Text,
+        ip_address -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    organization_api_keys (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        service_user_id -> Uuid,
+        created_by -> Uuid,
+        name -> Text,
+        prefix -> Text,
+        hashed_secret -> Text,
+        scopes -> Array<Text>,
+        last_used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    organization_email_templates (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        template_type -> Text,
+        subject -> Text,
+        body -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     organization_invites (id) {
         id -> Uuid,
@@ -177,6 +386,20 @@ table! {
         accepted -> Nullable<Int2>,
         updated_at -> Timestamp,
         sent_invite -> Bool,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        organization_role_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    organization_roles (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        scopes -> Array<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -195,6 +418,14 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         fee_schedule_id -> Uuid,
+        require_two_factor -> Bool,
+        fee_mode -> Text,
+        parent_id -> Nullable<Uuid>,
+        logo_url -> Nullable<Text>,
+        primary_color -> Nullable<Text>,
+        secondary_color -> Nullable<Text>,
+        email_sender_name -> Nullable<Text>,
+        email_reply_to -> Nullable<Text>,
     }
 }
 
@@ -205,6 +436,7 @@ table! {
         user_id -> Uuid,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        organization_role_id -> Nullable<Uuid>,
     }
 }
 
@@ -246,6 +478,35 @@ table! {
     }
 }
 
+table! {
+    sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        user_agent -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+        last_used_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    settlements (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        start_utc -> Nullable<Timestamp>,
+        end_utc -> Nullable<Timestamp>,
+        status -> Text,
+        created_by -> Uuid,
+        paid_out_by -> Nullable<Uuid>,
+        paid_out_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     ticket_instances (id) {
         id -> Uuid,
@@ -289,6 +550,47 @@ table! {
         increment -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        presale_start_date -> Nullable<Timestamp>,
+        presale_interest_cutoff -> Nullable<Timestamp>,
+        presale_code -> Nullable<Text>,
+        fee_schedule_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    two_factor_credentials (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        secret -> Text,
+        enabled_at -> Nullable<Timestamp>,
+        last_used_step -> Nullable<Int8>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    two_factor_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        hashed_code -> Text,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    user_erasure_requests (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        status -> Text,
+        reason -> Nullable<Text>,
+        reviewed_by -> Nullable<Uuid>,
+        review_notes -> Nullable<Text>,
+        reviewed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -312,6 +614,11 @@ table! {
         password_reset_requested_at -> Nullable<Timestamp>,
         updated_at -> Timestamp,
         last_cart_id -> Nullable<Uuid>,
+        email_verified_at -> Nullable<Timestamp>,
+        email_verification_token -> Nullable<Uuid>,
+        email_verification_sent_at -> Nullable<Timestamp>,
+        password_set -> Bool,
+        phone_verified_at -> Nullable<Timestamp>,
     }
 }
 
@@ -347,22 +654,82 @@ table! {
     }
 }
 
+table! {
+    webhook_deliveries (id) {
+        id -> Uuid,
+        webhook_subscription_id -> Uuid,
+        domain_event_id -> Uuid,
+        status -> Text,
+        attempts -> Int4,
+        next_attempt_at -> Nullable<Timestamp>,
+        last_attempt_at -> Nullable<Timestamp>,
+        response_status -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    webhook_subscriptions (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        created_by -> Uuid,
+        url -> Text,
+        secret -> Text,
+        event_types -> Array<Text>,
+        active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
+joinable!(audit_entries -> organizations (organization_id));
+joinable!(audit_entries -> users (user_id));
+joinable!(calendar_tokens -> organizations (organization_id));
+joinable!(calendar_tokens -> users (user_id));
+joinable!(domain_events -> organizations (organization_id));
 joinable!(event_artists -> artists (artist_id));
 joinable!(event_artists -> events (event_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
+joinable!(event_interest_notifications -> events (event_id));
+joinable!(event_interest_notifications -> users (user_id));
+joinable!(event_promoters -> events (event_id));
+joinable!(event_promoters -> organizations (organization_id));
+joinable!(event_reminders -> events (event_id));
+joinable!(event_reminders -> users (user_id));
+joinable!(events -> fee_schedules (fee_schedule_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
 joinable!(fee_schedule_ranges -> fee_schedules (fee_schedule_id));
 joinable!(holds -> events (event_id));
+joinable!(impersonation_actions -> impersonations (impersonation_id));
+joinable!(impersonations -> sessions (session_id));
+joinable!(interest_notification_opt_outs -> organizations (organization_id));
+joinable!(interest_notification_opt_outs -> users (user_id));
+joinable!(ledger_entries -> events (event_id));
+joinable!(ledger_entries -> order_items (order_item_id));
+joinable!(ledger_entries -> orders (order_id));
+joinable!(ledger_entries -> organizations (organization_id));
+joinable!(ledger_entries -> settlements (settlement_id));
+joinable!(login_codes -> users (user_id));
+joinable!(login_throttles -> users (user_id));
 joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> orders (order_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
+joinable!(organization_api_key_requests -> organization_api_keys (organization_api_key_id));
+joinable!(organization_api_keys -> organizations (organization_id));
+joinable!(organization_email_templates -> organizations (organization_id));
+joinable!(organization_invites -> organization_roles (organization_role_id));
 joinable!(organization_invites -> organizations (organization_id));
+joinable!(organization_roles -> organizations (organization_id));
+joinable!(organization_users -> organization_roles (organization_role_id));
 joinable!(organization_users -> organizations (organization_id));
 joinable!(organization_users -> users (user_id));
 joinable!(organizations -> fee_schedules (fee_schedule_id));
@@ -370,40 +737,73 @@ joinable!(organizations -> users (owner_user_id));
 joinable!(payment_methods -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> users (created_by));
+joinable!(sessions -> users (user_id));
+joinable!(settlements -> events (event_id));
+joinable!(settlements -> organizations (organization_id));
 joinable!(ticket_instances -> assets (asset_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> order_items (order_item_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
 joinable!(ticket_types -> events (event_id));
+joinable!(ticket_types -> fee_schedules (fee_schedule_id));
+joinable!(two_factor_credentials -> users (user_id));
+joinable!(two_factor_recovery_codes -> users (user_id));
 joinable!(venues -> organizations (organization_id));
 joinable!(venues -> regions (region_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
+joinable!(webhook_deliveries -> domain_events (domain_event_id));
+joinable!(webhook_deliveries -> webhook_subscriptions (webhook_subscription_id));
+joinable!(webhook_subscriptions -> organizations (organization_id));
+joinable!(webhook_subscriptions -> users (created_by));
 
 allow_tables_to_appear_in_same_query!(
     artists,
     assets,
+    audit_entries,
+    calendar_tokens,
     domain_events,
     event_artists,
     event_interest,
+    event_interest_notifications,
+    event_promoters,
+    event_reminders,
     events,
     external_logins,
     fee_schedule_ranges,
     fee_schedules,
     holds,
+    impersonation_actions,
+    impersonations,
+    interest_notification_opt_outs,
+    ledger_entries,
+    login_codes,
+    login_throttles,
+    oidc_login_requests,
     order_items,
     orders,
+    organization_api_key_requests,
+    organization_api_keys,
+    organization_email_templates,
     organization_invites,
+    organization_roles,
     organizations,
     organization_users,
     payment_methods,
     payments,
     regions,
+    sessions,
+    settlements,
     ticket_instances,
     ticket_pricing,
     ticket_types,
+    two_factor_credentials,
+    two_factor_recovery_codes,
+    user_erasure_requests,
     users,
     venues,
     wallets,
+    webhook_deliveries,
+    webhook_subscriptions,
 );
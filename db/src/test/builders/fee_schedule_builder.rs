@@ -21,10 +21,12 @@ impl<'a> FeeScheduleBuilder<'a> {
                 NewFeeScheduleRange {
                     min_price: 50,
                     fee_in_cents: 10,
+                    ..Default::default()
                 },
                 NewFeeScheduleRange {
                     min_price: 100,
                     fee_in_cents: 20,
+                    ..Default::default()
                 },
             ],
         ).commit(self.connection)
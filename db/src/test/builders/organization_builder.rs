@@ -15,6 +15,7 @@ pub struct OrganizationBuilder<'a> {
     fee_schedule: Option<FeeSchedule>,
     event_fee_in_cents: Option<i64>,
     use_address: bool,
+    parent_id: Option<Uuid>,
 }
 
 impl<'a> OrganizationBuilder<'a> {
@@ -28,6 +29,7 @@ impl<'a> OrganizationBuilder<'a> {
             connection,
             use_address: false,
             event_fee_in_cents: None,
+            parent_id: None,
         }
     }
 
@@ -56,6 +58,11 @@ impl<'a> OrganizationBuilder<'a> {
         self
     }
 
+    pub fn with_parent(mut self, parent: &Organization) -> OrganizationBuilder<'a> {
+        self.parent_id = Some(parent.id);
+        self
+    }
+
     pub fn with_event_fee(mut self) -> Self {
         self.event_fee_in_cents = Some(250);
         self
@@ -69,19 +76,21 @@ impl<'a> OrganizationBuilder<'a> {
                 vec![NewFeeScheduleRange {
                     min_price: 0,
                     fee_in_cents: 50,
+                    ..Default::default()
                 }],
             ).commit(self.connection);
             self.fee_schedule = Some(fee_schedule.unwrap());
         }
 
-        let mut organization = Organization::create(
+        let mut new_organization = Organization::create(
             self.owner_user_id
                 .or_else(|| Some(UserBuilder::new(self.connection).finish().id))
                 .unwrap(),
             &self.name,
             self.fee_schedule.unwrap().id,
-        ).commit(self.connection)
-        .unwrap();
+        );
+        new_organization.parent_id = self.parent_id;
+        let mut organization = new_organization.commit(self.connection).unwrap();
 
         let event_fee_update = OrganizationEditableAttributes {
             event_fee_in_cents: self.event_fee_in_cents,
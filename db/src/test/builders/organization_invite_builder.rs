@@ -1,7 +1,7 @@
 use chrono::prelude::*;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
-use models::{Organization, OrganizationInvite, User};
+use models::{Organization, OrganizationInvite, OrganizationRole, User};
 use uuid::Uuid;
 
 #[allow(dead_code)]
@@ -15,6 +15,7 @@ pub struct OrgInviteBuilder<'a> {
     user_id: Option<Uuid>,
     status_change_at: Option<NaiveDateTime>,
     accepted: Option<i16>,
+    organization_role_id: Option<Uuid>,
     connection: &'a PgConnection,
 }
 
@@ -31,6 +32,7 @@ impl<'a> OrgInviteBuilder<'a> {
             user_id: None,
             status_change_at: None,
             accepted: None,
+            organization_role_id: None,
         }
     }
 
@@ -64,14 +66,19 @@ impl<'a> OrgInviteBuilder<'a> {
         self
     }
 
+    pub fn with_role(mut self, role: &OrganizationRole) -> Self {
+        self.organization_role_id = Some(role.id);
+        self
+    }
+
     pub fn finish(&self) -> OrganizationInvite {
-        let orginvite = OrganizationInvite::create(
+        let mut orginvite = OrganizationInvite::create(
             self.organization_id.unwrap(),
             self.invitee_id.unwrap(),
             &self.user_email,
             self.user_id,
-        ).commit(self.connection)
-        .unwrap();
-        orginvite
+        );
+        orginvite.organization_role_id = self.organization_role_id;
+        orginvite.commit(self.connection).unwrap()
     }
 }
@@ -1,4 +1,5 @@
 use diesel::prelude::*;
+use models::concerns::users::email_verifiable::EmailVerifiable;
 use models::User;
 use uuid::Uuid;
 
@@ -8,6 +9,8 @@ pub struct UserBuilder<'a> {
     email: String,
     phone: String,
     password: String,
+    email_verified: bool,
+    phone_verified: bool,
     connection: &'a PgConnection,
 }
 
@@ -20,6 +23,8 @@ impl<'a> UserBuilder<'a> {
             email: format!("jeff{}@tari.com", x).into(),
             phone: "555-555-5555".into(),
             password: "examplePassword".into(),
+            email_verified: true,
+            phone_verified: false,
             connection,
         }
     }
@@ -44,14 +49,41 @@ impl<'a> UserBuilder<'a> {
         self
     }
 
+    pub fn with_phone(mut self, phone: String) -> Self {
+        self.phone = phone;
+        self
+    }
+
+    pub fn with_verified_phone(mut self) -> Self {
+        self.phone_verified = true;
+        self
+    }
+
+    pub fn with_unverified_email(mut self) -> Self {
+        self.email_verified = false;
+        self
+    }
+
     pub fn finish(&self) -> User {
-        User::create(
+        let user = User::create(
             &self.first_name,
             &self.last_name,
             &self.email,
             &self.phone,
             &self.password,
         ).commit(self.connection)
-        .unwrap()
+        .unwrap();
+
+        let user = if self.email_verified {
+            user.mark_email_verified(self.connection).unwrap()
+        } else {
+            user
+        };
+
+        if self.phone_verified {
+            user.mark_phone_verified(self.connection).unwrap()
+        } else {
+            user
+        }
     }
 }
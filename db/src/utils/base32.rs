@@ -0,0 +1,62 @@
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encodes bytes using the RFC 4648 base32 alphabet without padding, the format
+/// authenticator apps expect for TOTP secrets
+pub fn encode(data: &[u8]) -> String {
+    let mut result = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        result.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    result
+}
+
+/// Decodes RFC 4648 base32, ignoring case, whitespace and padding
+pub fn decode(data: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in data.chars() {
+        if c.is_whitespace() || c == '=' {
+            continue;
+        }
+        let value = ALPHABET
+            .iter()
+            .position(|a| *a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push(((buffer >> bits) & 255) as u8);
+        }
+    }
+    Some(result)
+}
+
+#[test]
+fn encode_test() {
+    assert_eq!(encode(b""), "");
+    assert_eq!(encode(b"f"), "MY");
+    assert_eq!(encode(b"foobar"), "MZXW6YTBOI");
+    assert_eq!(
+        encode(b"12345678901234567890"),
+        "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+    );
+}
+
+#[test]
+fn decode_test() {
+    assert_eq!(decode("MZXW6YTBOI").unwrap(), b"foobar".to_vec());
+    assert_eq!(decode("mzxw 6ytb oi======").unwrap(), b"foobar".to_vec());
+    assert_eq!(decode("MY").unwrap(), b"f".to_vec());
+    assert!(decode("M1").is_none());
+}
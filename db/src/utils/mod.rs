@@ -1,5 +1,8 @@
+pub mod base32;
 pub mod errors;
 mod math;
 pub mod passwords;
+pub mod serializers;
+pub mod templates;
 
 pub use self::math::*;
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Deserializer};
+
+/// Distinguishes a missing field from an explicit `null`, use together with `#[serde(default)]`
+/// so a missing field stays `None` while `null` becomes `Some(None)` and clears the value
+pub fn deserialize_double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
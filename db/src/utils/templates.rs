@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+/// Replaces `{{name}}` placeholders with their values. Templates have no expressions or
+/// control flow, so templates written by organizations can only output the values given to
+/// them. Unknown placeholders are an error rather than being left in the output
+pub fn render(template: &str, values: &HashMap<&str, String>) -> Result<String, String> {
+    let mut result = String::new();
+    let mut remaining = template;
+    while let Some(start) = remaining.find("{{") {
+        result.push_str(&remaining[..start]);
+        let placeholder = &remaining[start + 2..];
+        let end = match placeholder.find("}}") {
+            Some(end) => end,
+            None => return Err("Placeholder is missing its closing }}".to_string()),
+        };
+        let name = placeholder[..end].trim();
+        match values.get(name) {
+            Some(value) => result.push_str(value),
+            None => return Err(format!("Unknown placeholder {{{{{}}}}}", name)),
+        }
+        remaining = &placeholder[end + 2..];
+    }
+    result.push_str(remaining);
+    Ok(result)
+}
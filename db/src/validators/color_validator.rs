@@ -0,0 +1,12 @@
+use validator::ValidationError;
+
+/// Colors are stored as hex values, e.g. `#1A2B3C`
+pub fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let valid = color.starts_with('#')
+        && (color.len() == 4 || color.len() == 7)
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ValidationError::new(&"color"));
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use validator::ValidationError;
+
+const MAX_LENGTH: usize = 100;
+
+/// The sender name is sent in the From header, so line breaks are rejected to prevent header
+/// injection and names that could be mistaken for the platform itself are not allowed
+pub fn validate_email_sender_name(name: &str) -> Result<(), ValidationError> {
+    let compact: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    let valid = !name.trim().is_empty()
+        && name.chars().count() <= MAX_LENGTH
+        && !name.chars().any(|c| c.is_control())
+        && !compact.contains("bigneon");
+    if !valid {
+        return Err(ValidationError::new(&"email_sender_name"));
+    }
+    Ok(())
+}
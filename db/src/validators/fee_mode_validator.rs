@@ -0,0 +1,9 @@
+use models::FeeMode;
+use validator::ValidationError;
+
+pub fn validate_fee_mode(fee_mode: &str) -> Result<(), ValidationError> {
+    if fee_mode.parse::<FeeMode>().is_err() {
+        return Err(ValidationError::new(&"fee_mode"));
+    }
+    Ok(())
+}
@@ -1,6 +1,16 @@
+mod color_validator;
+mod email_sender_name_validator;
+mod fee_mode_validator;
 mod url_array_validator;
+mod webhook_event_types_validator;
+mod webhook_url_validator;
 
+pub use self::color_validator::validate_color;
+pub use self::email_sender_name_validator::validate_email_sender_name;
+pub use self::fee_mode_validator::validate_fee_mode;
 pub use self::url_array_validator::validate_urls;
+pub use self::webhook_event_types_validator::validate_webhook_event_types;
+pub use self::webhook_url_validator::validate_webhook_url;
 use validator::*;
 
 pub fn append_validation_error(
@@ -0,0 +1,14 @@
+use models::WebhookSubscription;
+use validator::ValidationError;
+
+pub fn validate_webhook_event_types(event_types: &Vec<String>) -> Result<(), ValidationError> {
+    let available_event_types = WebhookSubscription::available_event_types();
+    if event_types.is_empty()
+        || event_types
+            .iter()
+            .any(|event_type| !available_event_types.contains(event_type))
+    {
+        return Err(ValidationError::new(&"webhook_event_types"));
+    }
+    Ok(())
+}
@@ -0,0 +1,9 @@
+use validator::{validate_url, ValidationError};
+
+/// Payloads contain customer details so are only sent over https
+pub fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if !validate_url(url) || !url.to_lowercase().starts_with("https://") {
+        return Err(ValidationError::new(&"webhook_url"));
+    }
+    Ok(())
+}
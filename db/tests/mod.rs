@@ -13,6 +13,8 @@ extern crate bigneon_db;
 extern crate chrono;
 extern crate diesel;
 extern crate rand;
+#[macro_use]
+extern crate serde_json;
 extern crate time;
 extern crate uuid;
 extern crate validator;
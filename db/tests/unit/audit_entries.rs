@@ -0,0 +1,164 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let entry = AuditEntry::create(
+        Some(user.id),
+        AuditActions::Update,
+        Tables::Events,
+        Some(event.id),
+    ).for_organization(organization.id)
+    .with_request_id(Some("request-1".to_string()))
+    .with_change("name", &"Old name", &"New name")
+    .with_change("capacity", &10, &10)
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(entry.organization_id, Some(organization.id));
+    assert_eq!(entry.user_id, Some(user.id));
+    assert_eq!(entry.request_id, Some("request-1".to_string()));
+    assert_eq!(entry.action, "Update");
+    assert_eq!(entry.table_name, "Events");
+    assert_eq!(entry.target_id, Some(event.id));
+    // Unchanged values are not recorded
+    assert_eq!(
+        entry.changes,
+        Some(json!({"name": {"before": "Old name", "after": "New name"}}))
+    );
+
+    assert_eq!(
+        AuditEntry::find_for_target(Tables::Events, event.id, connection).unwrap(),
+        vec![entry]
+    );
+}
+
+#[test]
+fn diff() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let mut updated_venue = venue.clone();
+    updated_venue.name = "New name".to_string();
+
+    assert_eq!(
+        AuditEntry::diff(Some(&venue), Some(&updated_venue)),
+        json!({"name": {"before": venue.name, "after": "New name"}})
+    );
+    assert_eq!(AuditEntry::diff(Some(&venue), Some(&venue)), json!({}));
+
+    let created = AuditEntry::diff(None, Some(&venue));
+    assert_eq!(created["name"], json!({"before": null, "after": venue.name}));
+    assert_eq!(created["id"], json!({"before": null, "after": venue.id}));
+
+    let deleted = AuditEntry::diff(Some(&venue), None);
+    assert_eq!(deleted["name"], json!({"before": venue.name, "after": null}));
+
+    // Records are unaffected
+    assert_eq!(Venue::find(venue.id, connection).unwrap(), venue);
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+
+    let entry = AuditEntry::create(
+        Some(user.id),
+        AuditActions::Create,
+        Tables::Events,
+        None,
+    ).for_organization(organization.id)
+    .commit(connection)
+    .unwrap();
+    let entry2 = AuditEntry::create(
+        Some(user2.id),
+        AuditActions::Update,
+        Tables::Venues,
+        None,
+    ).for_organization(organization.id)
+    .commit(connection)
+    .unwrap();
+    // Entries for other organizations are not included
+    AuditEntry::create(Some(user.id), AuditActions::Create, Tables::Events, None)
+        .for_organization(organization2.id)
+        .commit(connection)
+        .unwrap();
+
+    let (entries, total) = AuditEntry::find_for_organization(
+        organization.id,
+        &AuditEntryFilter::default(),
+        100,
+        0,
+        connection,
+    ).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(entries.len(), 2);
+    assert!(entries.contains(&entry));
+    assert!(entries.contains(&entry2));
+
+    // Paging
+    let (entries, total) = AuditEntry::find_for_organization(
+        organization.id,
+        &AuditEntryFilter::default(),
+        1,
+        1,
+        connection,
+    ).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(entries.len(), 1);
+
+    // Filter by actor
+    let filter = AuditEntryFilter {
+        user_id: Some(user2.id),
+        ..Default::default()
+    };
+    let (entries, total) =
+        AuditEntry::find_for_organization(organization.id, &filter, 100, 0, connection).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(entries, vec![entry2.clone()]);
+
+    // Filter by table
+    let filter = AuditEntryFilter {
+        table_name: Some(Tables::Events),
+        ..Default::default()
+    };
+    let (entries, total) =
+        AuditEntry::find_for_organization(organization.id, &filter, 100, 0, connection).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(entries, vec![entry.clone()]);
+
+    // Filter by date
+    let now = Utc::now().naive_utc();
+    let filter = AuditEntryFilter {
+        start_utc: Some(now - Duration::days(1)),
+        end_utc: Some(now + Duration::days(1)),
+        ..Default::default()
+    };
+    let (_, total) =
+        AuditEntry::find_for_organization(organization.id, &filter, 100, 0, connection).unwrap();
+    assert_eq!(total, 2);
+    let filter = AuditEntryFilter {
+        start_utc: Some(now + Duration::days(1)),
+        ..Default::default()
+    };
+    let (entries, total) =
+        AuditEntry::find_for_organization(organization.id, &filter, 100, 0, connection).unwrap();
+    assert_eq!(total, 0);
+    assert!(entries.is_empty());
+}
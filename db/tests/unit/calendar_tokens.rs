@@ -0,0 +1,60 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::CalendarToken;
+
+#[test]
+fn find_or_create_for_user() {
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+
+    let calendar_token =
+        CalendarToken::find_or_create_for_user(user.id, project.get_connection()).unwrap();
+    assert_eq!(calendar_token.user_id, Some(user.id));
+    assert_eq!(calendar_token.organization_id, None);
+
+    // Subsequent calls return the existing token
+    let found_calendar_token =
+        CalendarToken::find_or_create_for_user(user.id, project.get_connection()).unwrap();
+    assert_eq!(calendar_token, found_calendar_token);
+}
+
+#[test]
+fn find_or_create_for_organization() {
+    let project = TestProject::new();
+    let organization = project.create_organization().finish();
+
+    let calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, project.get_connection())
+            .unwrap();
+    assert_eq!(calendar_token.organization_id, Some(organization.id));
+    assert_eq!(calendar_token.user_id, None);
+
+    let found_calendar_token =
+        CalendarToken::find_or_create_for_organization(organization.id, project.get_connection())
+            .unwrap();
+    assert_eq!(calendar_token, found_calendar_token);
+}
+
+#[test]
+fn find_by_token() {
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+    let calendar_token =
+        CalendarToken::find_or_create_for_user(user.id, project.get_connection()).unwrap();
+
+    let found_calendar_token =
+        CalendarToken::find_by_token(calendar_token.token, project.get_connection()).unwrap();
+    assert_eq!(calendar_token, found_calendar_token);
+}
+
+#[test]
+fn regenerate() {
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+    let calendar_token =
+        CalendarToken::find_or_create_for_user(user.id, project.get_connection()).unwrap();
+
+    let regenerated_calendar_token = calendar_token.regenerate(project.get_connection()).unwrap();
+    assert_eq!(calendar_token.id, regenerated_calendar_token.id);
+    assert_ne!(calendar_token.token, regenerated_calendar_token.token);
+    assert!(CalendarToken::find_by_token(calendar_token.token, project.get_connection()).is_err());
+}
@@ -0,0 +1,117 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::concerns::users::email_verifiable::{EmailVerifiable, EmailVerification};
+use bigneon_db::models::User;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::{Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn find_by_email_verification_token() {
+    let project = TestProject::new();
+    let user = project.create_user().with_unverified_email().finish();
+    let user = user
+        .create_email_verification_token(project.get_connection())
+        .unwrap();
+
+    let found_user = User::find_by_email_verification_token(
+        &user.email_verification_token.unwrap(),
+        project.get_connection(),
+    ).expect("User was not found");
+    assert_eq!(found_user.id, user.id);
+
+    assert!(
+        User::find_by_email_verification_token(&Uuid::new_v4(), project.get_connection()).is_err()
+    );
+}
+
+#[test]
+fn create_email_verification_token() {
+    let project = TestProject::new();
+    let user = project.create_user().with_unverified_email().finish();
+    assert!(user.email_verification_token.is_none());
+    assert!(user.email_verification_sent_at.is_none());
+
+    let user = user
+        .create_email_verification_token(project.get_connection())
+        .unwrap();
+    assert!(user.email_verification_token.is_some());
+    assert!(user.email_verification_sent_at.is_some());
+    assert!(!user.is_email_verified());
+}
+
+#[test]
+fn consume_email_verification_token() {
+    use bigneon_db::schema::users::dsl::*;
+    let project = TestProject::new();
+    let user = project.create_user().with_unverified_email().finish();
+    let user = user
+        .create_email_verification_token(project.get_connection())
+        .unwrap();
+
+    let user = User::consume_email_verification_token(
+        &user.email_verification_token.unwrap(),
+        project.get_connection(),
+    ).unwrap();
+    assert!(user.is_email_verified());
+    assert!(user.email_verification_token.is_none());
+    assert!(user.email_verification_sent_at.is_none());
+
+    // Expired token
+    let token = Uuid::new_v4();
+    let user: User = diesel::update(users.filter(id.eq(user.id)))
+        .set(EmailVerification {
+            email_verified_at: None,
+            email_verification_token: Some(token),
+            email_verification_sent_at: Some(Utc::now().naive_utc() - Duration::days(8)),
+        }).get_result(project.get_connection())
+        .unwrap();
+    match User::consume_email_verification_token(&token, project.get_connection()) {
+        Ok(_v) => panic!("Expected failure to consume expired email verification token"),
+        Err(e) => match &e.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["email_verification_token"][0].code, "expired");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+    assert!(!User::find(user.id, project.get_connection())
+        .unwrap()
+        .is_email_verified());
+}
+
+#[test]
+fn can_resend_email_verification() {
+    let project = TestProject::new();
+    let mut user = project.create_user().with_unverified_email().finish();
+    assert!(user.can_resend_email_verification());
+
+    user.email_verification_sent_at = Some(Utc::now().naive_utc() - Duration::minutes(1));
+    assert!(!user.can_resend_email_verification());
+
+    user.email_verification_sent_at = Some(Utc::now().naive_utc() - Duration::minutes(6));
+    assert!(user.can_resend_email_verification());
+}
+
+#[test]
+fn has_valid_email_verification_token() {
+    let project = TestProject::new();
+    let mut user = project.create_user().with_unverified_email().finish();
+
+    // Expired token
+    user.email_verification_token = Some(Uuid::new_v4());
+    user.email_verification_sent_at =
+        Some(Utc::now().naive_utc() - Duration::days(7) - Duration::seconds(10));
+    assert!(!user.has_valid_email_verification_token());
+
+    // Token not yet expired
+    user.email_verification_sent_at =
+        Some(Utc::now().naive_utc() - Duration::days(7) + Duration::seconds(10));
+    assert!(user.has_valid_email_verification_token());
+
+    // Token does not exist
+    user.email_verification_token = None;
+    user.email_verification_sent_at = None;
+    assert!(!user.has_valid_email_verification_token());
+}
@@ -73,3 +73,56 @@ fn find() {
         [domain_event2]
     );
 }
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let id = Uuid::new_v4();
+
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::OrderPaid,
+        "Order was paid".to_string(),
+        Tables::Orders,
+        Some(id),
+        None,
+    ).for_organization(organization.id)
+    .commit(connection)
+    .unwrap();
+    let domain_event2 = DomainEvent::create(
+        DomainEventTypes::OrderRefunded,
+        "Order was refunded".to_string(),
+        Tables::Orders,
+        Some(id),
+        None,
+    ).for_organization(organization.id)
+    .commit(connection)
+    .unwrap();
+    // Not attached to an organization
+    DomainEvent::create(
+        DomainEventTypes::PaymentCreated,
+        "Payment was created".to_string(),
+        Tables::Payments,
+        Some(id),
+        None,
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        DomainEvent::find_for_organization(organization.id, None, connection).unwrap(),
+        [domain_event.clone(), domain_event2]
+    );
+    assert_eq!(
+        DomainEvent::find_for_organization(
+            organization.id,
+            Some(DomainEventTypes::OrderPaid),
+            connection,
+        ).unwrap(),
+        [domain_event.clone()]
+    );
+    assert_eq!(
+        DomainEvent::find_by_id(domain_event.id, connection).unwrap(),
+        domain_event
+    );
+}
@@ -0,0 +1,282 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::event_interest_notifications;
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use time::Duration;
+use uuid::Uuid;
+
+fn create_publishable_event(project: &TestProject, organization: &Organization) -> Event {
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    venue
+        .update(
+            VenueEditableAttributes {
+                address: Some("address".to_string()),
+                city: Some("city".to_string()),
+                state: Some("state".to_string()),
+                country: Some("country".to_string()),
+                postal_code: Some("333".to_string()),
+                phone: Some("33333".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    project
+        .create_event()
+        .with_status(EventStatus::Draft)
+        .with_organization(organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish()
+}
+
+fn pending_user_ids(connection: &PgConnection) -> Vec<Uuid> {
+    EventInterestNotification::find_pending(100, connection)
+        .unwrap()
+        .iter()
+        .map(|n| n.user_id)
+        .collect()
+}
+
+#[test]
+fn queue_for_published_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = create_publishable_event(&project, &organization);
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let opted_out_user = project.create_user().finish();
+    for u in &[&user, &user2, &opted_out_user] {
+        EventInterest::create(event.id, u.id)
+            .commit(connection)
+            .unwrap();
+    }
+    InterestNotificationOptOut::create(organization.id, opted_out_user.id)
+        .commit(connection)
+        .unwrap();
+
+    let event = event.publish(connection).unwrap();
+    let pending = EventInterestNotification::find_pending(100, connection).unwrap();
+    assert_eq!(pending.len(), 2);
+    assert!(pending.iter().all(|n| n.event_id == event.id));
+    assert!(pending
+        .iter()
+        .all(|n| Some(n.on_sale_at) == event.publish_date));
+    let user_ids = pending_user_ids(connection);
+    assert!(user_ids.contains(&user.id));
+    assert!(user_ids.contains(&user2.id));
+
+    // Queuing again for the same on sale does not duplicate notifications
+    assert_eq!(
+        EventInterestNotification::queue_for_published_event(event.id, connection).unwrap(),
+        0
+    );
+    assert_eq!(
+        EventInterestNotification::find_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn queue_for_published_event_without_open_pricing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+    event
+        .update(
+            EventEditableAttributes {
+                publish_date: Some(Utc::now().naive_utc()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    assert_eq!(
+        EventInterestNotification::queue_for_published_event(event.id, connection).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn queue_for_opened_pricing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+
+    // The current tier opened before the event was published so it is not a new on sale
+    let event = event
+        .update(
+            EventEditableAttributes {
+                publish_date: Some(Utc::now().naive_utc()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        EventInterestNotification::queue_for_opened_pricing(connection).unwrap(),
+        0
+    );
+
+    // Published before the current tier opened
+    let event = event
+        .update(
+            EventEditableAttributes {
+                publish_date: Some(Utc::now().naive_utc() - Duration::days(3)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        EventInterestNotification::queue_for_opened_pricing(connection).unwrap(),
+        1
+    );
+    assert_eq!(
+        EventInterestNotification::queue_for_opened_pricing(connection).unwrap(),
+        0
+    );
+
+    let notifications = EventInterestNotification::find_for_user(user.id, connection).unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].event_id, event.id);
+    assert_eq!(notifications[0].sent_at, None);
+}
+
+#[test]
+fn mark_sent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = create_publishable_event(&project, &organization);
+    let user = project.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+    event.publish(connection).unwrap();
+
+    let pending = EventInterestNotification::find_pending(100, connection).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        EventInterestNotification::mark_sent(&[pending[0].id], connection).unwrap(),
+        1
+    );
+    assert!(EventInterestNotification::find_pending(100, connection)
+        .unwrap()
+        .is_empty());
+    let notifications = EventInterestNotification::find_for_user(user.id, connection).unwrap();
+    assert!(notifications[0].sent_at.is_some());
+}
+
+#[test]
+fn claim_pending() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = create_publishable_event(&project, &organization);
+    let user = project.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+    event.publish(connection).unwrap();
+
+    let claimed = EventInterestNotification::claim_pending(100, connection).unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].user_id, user.id);
+    // Claimed notifications are not claimed again by other workers
+    assert!(EventInterestNotification::claim_pending(100, connection)
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(
+        EventInterestNotification::mark_failed(claimed[0].id, "Connection refused", connection)
+            .unwrap(),
+        1
+    );
+    let notification = &EventInterestNotification::find_for_user(user.id, connection).unwrap()[0];
+    assert_eq!(notification.attempts, 1);
+    assert_eq!(notification.last_error, Some("Connection refused".to_string()));
+    assert_eq!(notification.sent_at, None);
+
+    // Failed notifications are retried once the claim expires, until they run out of attempts
+    let expire_claim = || {
+        diesel::update(event_interest_notifications::table.find(claimed[0].id))
+            .set(
+                event_interest_notifications::claimed_until
+                    .eq(Some(Utc::now().naive_utc() - Duration::minutes(1))),
+            ).execute(connection)
+            .unwrap();
+    };
+    expire_claim();
+    assert_eq!(
+        EventInterestNotification::claim_pending(100, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+    for _ in 1..MAX_INTEREST_NOTIFICATION_ATTEMPTS {
+        EventInterestNotification::mark_failed(claimed[0].id, "Connection refused", connection)
+            .unwrap();
+    }
+    expire_claim();
+    assert!(EventInterestNotification::claim_pending(100, connection)
+        .unwrap()
+        .is_empty());
+    assert!(EventInterestNotification::find_pending(100, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn opt_out_removes_pending_notifications() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = create_publishable_event(&project, &organization);
+    let user = project.create_user().finish();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+    event.publish(connection).unwrap();
+    assert_eq!(pending_user_ids(connection), vec![user.id]);
+
+    let opt_out = InterestNotificationOptOut::create(organization.id, user.id)
+        .commit(connection)
+        .unwrap();
+    assert!(pending_user_ids(connection).is_empty());
+
+    // Opting out twice returns the existing opt out
+    let opt_out2 = InterestNotificationOptOut::create(organization.id, user.id)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(opt_out, opt_out2);
+    assert_eq!(
+        InterestNotificationOptOut::find_for_user(user.id, connection).unwrap(),
+        vec![opt_out]
+    );
+
+    assert_eq!(
+        InterestNotificationOptOut::remove(organization.id, user.id, connection).unwrap(),
+        1
+    );
+    assert!(
+        InterestNotificationOptOut::find_for_user(user.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,196 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let organization = project.create_organization().finish();
+
+    let promoter = EventPromoter::create(
+        event.id,
+        organization.id,
+        EventPromoterPermissions::Edit,
+        2500,
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(promoter.event_id, event.id);
+    assert_eq!(promoter.organization_id, organization.id);
+    assert_eq!(promoter.permission(), EventPromoterPermissions::Edit);
+    assert_eq!(promoter.revenue_split_in_basis_points, 2500);
+    assert_eq!(
+        EventPromoter::find_for_event(event.id, connection).unwrap(),
+        vec![promoter.clone()]
+    );
+    assert_eq!(
+        EventPromoter::find_by_event_and_organization(event.id, organization.id, connection)
+            .unwrap(),
+        promoter
+    );
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    EventPromoter::create(
+        event.id,
+        organization.id,
+        EventPromoterPermissions::View,
+        6000,
+    ).commit(connection)
+    .unwrap();
+
+    let result = EventPromoter::create(
+        event.id,
+        event.organization_id,
+        EventPromoterPermissions::View,
+        0,
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["organization_id"][0].code, "invalid_organization");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Splits of all co-promoters cannot add up to more than the whole revenue
+    let result = EventPromoter::create(
+        event.id,
+        organization2.id,
+        EventPromoterPermissions::View,
+        5000,
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(
+                    errors["revenue_split_in_basis_points"][0].code,
+                    "invalid_revenue_split"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn allows() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let viewer = EventPromoter::create(
+        event.id,
+        project.create_organization().finish().id,
+        EventPromoterPermissions::View,
+        0,
+    ).commit(connection)
+    .unwrap();
+    let editor = EventPromoter::create(
+        event.id,
+        project.create_organization().finish().id,
+        EventPromoterPermissions::Edit,
+        0,
+    ).commit(connection)
+    .unwrap();
+
+    for promoter in vec![&viewer, &editor] {
+        assert!(promoter.allows(Scopes::OrgRead));
+        assert!(promoter.allows(Scopes::OrgReports));
+        assert!(promoter.allows(Scopes::EventViewGuests));
+        assert!(!promoter.allows(Scopes::OrgWrite));
+        assert!(!promoter.allows(Scopes::OrgAdmin));
+    }
+    assert!(!viewer.allows(Scopes::EventWrite));
+    assert!(!viewer.allows(Scopes::TicketAdmin));
+    assert!(editor.allows(Scopes::EventWrite));
+    assert!(editor.allows(Scopes::HoldWrite));
+    assert!(editor.allows(Scopes::TicketAdmin));
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let organization = project.create_organization().finish();
+    let promoter = EventPromoter::create(
+        event.id,
+        organization.id,
+        EventPromoterPermissions::View,
+        0,
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(promoter.destroy(connection).unwrap(), 1);
+    assert!(
+        EventPromoter::find_for_event(event.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn revenue_splits() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+
+    assert_eq!(
+        RevenueSplit::for_event(&event, connection).unwrap(),
+        vec![RevenueSplit {
+            organization_id: event.organization_id,
+            revenue_split_in_basis_points: 10000,
+        }]
+    );
+
+    EventPromoter::create(
+        event.id,
+        organization.id,
+        EventPromoterPermissions::View,
+        2500,
+    ).commit(connection)
+    .unwrap();
+    EventPromoter::create(
+        event.id,
+        organization2.id,
+        EventPromoterPermissions::View,
+        3333,
+    ).commit(connection)
+    .unwrap();
+
+    let splits = RevenueSplit::for_event(&event, connection).unwrap();
+    assert_eq!(splits.len(), 3);
+    assert_eq!(splits[0].organization_id, event.organization_id);
+    assert_eq!(splits[0].revenue_split_in_basis_points, 4167);
+
+    // The owning organization receives the remainder after rounding
+    assert_eq!(
+        RevenueSplit::divide(&splits, 1000),
+        vec![
+            (event.organization_id, 417),
+            (organization.id, 250),
+            (organization2.id, 333),
+        ]
+    );
+    assert_eq!(
+        RevenueSplit::divide(&splits, -1000),
+        vec![
+            (event.organization_id, -417),
+            (organization.id, -250),
+            (organization2.id, -333),
+        ]
+    );
+}
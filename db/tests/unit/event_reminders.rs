@@ -0,0 +1,152 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::event_reminders;
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use time::Duration;
+
+fn purchase_tickets(project: &TestProject, event: &Event, user: &User) {
+    let connection = project.get_connection();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+}
+
+fn create_event_starting_in(project: &TestProject, hours: i64) -> Event {
+    let event_start = Utc::now().naive_utc() + Duration::hours(hours);
+    project
+        .create_event()
+        .with_event_start(&event_start)
+        .with_ticket_pricing()
+        .finish()
+}
+
+fn pending(connection: &PgConnection) -> Vec<PendingEventReminder> {
+    EventReminder::find_pending(100, connection).unwrap()
+}
+
+#[test]
+fn queue_due() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_event_starting_in(&project, 12);
+    let later_event = create_event_starting_in(&project, EVENT_REMINDER_HOURS + 24);
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    purchase_tickets(&project, &event, &user);
+    purchase_tickets(&project, &later_event, &user2);
+
+    // Users holding several tickets are reminded once and later events are not included yet
+    assert_eq!(EventReminder::queue_due(connection).unwrap(), 1);
+    let reminders = pending(connection);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].event_id, event.id);
+    assert_eq!(reminders[0].user_id, user.id);
+    assert_eq!(reminders[0].email, user.email.clone().unwrap());
+    assert_eq!(Some(reminders[0].event_start), event.event_start);
+
+    // Queuing again does not duplicate reminders
+    assert_eq!(EventReminder::queue_due(connection).unwrap(), 0);
+    assert_eq!(
+        EventReminder::find_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(EventReminder::find_for_user(user2.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn queue_due_skips_cancelled_events() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_event_starting_in(&project, 12);
+    let user = project.create_user().finish();
+    purchase_tickets(&project, &event, &user);
+    event.cancel(connection).unwrap();
+
+    assert_eq!(EventReminder::queue_due(connection).unwrap(), 0);
+    assert!(pending(connection).is_empty());
+}
+
+#[test]
+fn mark_sent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_event_starting_in(&project, 12);
+    let user = project.create_user().finish();
+    purchase_tickets(&project, &event, &user);
+    EventReminder::queue_due(connection).unwrap();
+    let reminders = pending(connection);
+    assert_eq!(reminders.len(), 1);
+
+    assert_eq!(
+        EventReminder::mark_sent(&[reminders[0].id], connection).unwrap(),
+        1
+    );
+    assert!(pending(connection).is_empty());
+    let reminders = EventReminder::find_for_user(user.id, connection).unwrap();
+    assert!(reminders[0].sent_at.is_some());
+    // Already sent reminders are left as is
+    assert_eq!(
+        EventReminder::mark_sent(&[reminders[0].id], connection).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn claim_pending() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_event_starting_in(&project, 12);
+    let user = project.create_user().finish();
+    purchase_tickets(&project, &event, &user);
+    EventReminder::queue_due(connection).unwrap();
+
+    let claimed = EventReminder::claim_pending(100, connection).unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].user_id, user.id);
+    // Claimed reminders are not claimed again by other workers
+    assert!(EventReminder::claim_pending(100, connection)
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(
+        EventReminder::mark_failed(claimed[0].id, "Connection refused", connection).unwrap(),
+        1
+    );
+    let reminder = &EventReminder::find_for_user(user.id, connection).unwrap()[0];
+    assert_eq!(reminder.attempts, 1);
+    assert_eq!(reminder.last_error, Some("Connection refused".to_string()));
+    assert_eq!(reminder.sent_at, None);
+
+    // Failed reminders are retried once the claim expires, until they run out of attempts
+    let expire_claim = || {
+        diesel::update(event_reminders::table.find(claimed[0].id))
+            .set(
+                event_reminders::claimed_until
+                    .eq(Some(Utc::now().naive_utc() - Duration::minutes(1))),
+            ).execute(connection)
+            .unwrap();
+    };
+    expire_claim();
+    assert_eq!(
+        EventReminder::claim_pending(100, connection).unwrap().len(),
+        1
+    );
+    for _ in 1..MAX_EVENT_REMINDER_ATTEMPTS {
+        EventReminder::mark_failed(claimed[0].id, "Connection refused", connection).unwrap();
+    }
+    expire_claim();
+    assert!(EventReminder::claim_pending(100, connection)
+        .unwrap()
+        .is_empty());
+    assert!(pending(connection).is_empty());
+}
@@ -1,5 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
 use chrono::prelude::*;
 
 #[test]
@@ -46,6 +47,68 @@ fn update() {
     );
 }
 
+#[test]
+fn fee_mode() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    assert_eq!(organization.fee_mode().unwrap(), FeeMode::PassedToBuyer);
+    assert_eq!(event.fee_mode(connection).unwrap(), FeeMode::PassedToBuyer);
+
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                fee_mode: Some(FeeMode::Absorbed.to_string()),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert_eq!(event.fee_mode(connection).unwrap(), FeeMode::Absorbed);
+
+    let event = event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(Some(FeeMode::PassedToBuyer.to_string())),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert_eq!(event.fee_mode(connection).unwrap(), FeeMode::PassedToBuyer);
+
+    // Removing the override uses the organization's fee mode again
+    let event = event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(None),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert_eq!(event.fee_mode, None);
+    assert_eq!(event.fee_mode(connection).unwrap(), FeeMode::Absorbed);
+
+    let result = event.update(
+        EventEditableAttributes {
+            fee_mode: Some(Some("Split".to_string())),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["fee_mode"][0].code, "fee_mode");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn publish() {
     //create event
@@ -90,6 +153,15 @@ fn publish() {
 
     assert_eq!(event.status(), EventStatus::Published);
     assert!(event.publish_date.is_some());
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::EventPublished),
+        project.get_connection(),
+    ).unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].organization_id, Some(organization.id));
 }
 
 #[test]
@@ -530,6 +602,108 @@ fn find_for_organization_and_venue() {
     assert_eq!(found_event_via_venues[0], all_events[0]);
 }
 
+#[test]
+fn find_all_events_from_organization_includes_co_promoted_events() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let other_event = project.create_event().finish();
+    assert_eq!(
+        Event::find_all_events_from_organization(&organization.id, connection).unwrap(),
+        vec![event.clone()]
+    );
+
+    EventPromoter::create(
+        other_event.id,
+        organization.id,
+        EventPromoterPermissions::View,
+        0,
+    ).commit(connection)
+    .unwrap();
+    let found_events =
+        Event::find_all_events_from_organization(&organization.id, connection).unwrap();
+    assert_eq!(found_events.len(), 2);
+    assert!(found_events.contains(&event));
+    assert!(found_events.contains(&other_event));
+}
+
+#[test]
+fn find_published_for_organization() {
+    let project = TestProject::new();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(&NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11))
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(&NaiveDate::from_ymd(2016, 7, 9).and_hms(9, 10, 11))
+        .finish();
+    project
+        .create_event()
+        .with_organization(&organization)
+        .with_status(EventStatus::Draft)
+        .finish();
+    let cancelled_event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    cancelled_event.cancel(project.get_connection()).unwrap();
+    project.create_event().finish();
+
+    let events =
+        Event::find_published_for_organization(organization.id, project.get_connection())
+            .unwrap();
+    assert_eq!(events, vec![event, event2]);
+}
+
+#[test]
+fn guest_list_export() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project
+        .create_user()
+        .with_first_name("Zed".to_string())
+        .with_last_name("Zimmerman".to_string())
+        .finish();
+    let user2 = project
+        .create_user()
+        .with_first_name("Anne".to_string())
+        .with_last_name("Adams".to_string())
+        .finish();
+    project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_user(&user2)
+        .for_event(&event)
+        .is_paid()
+        .finish();
+    // Unpaid orders are not included
+    project.create_order().for_event(&event).finish();
+
+    let guests = event.guest_list_export(connection).unwrap();
+    assert_eq!(guests.len(), 20);
+    assert!(guests[..10].iter().all(|g| g.user_id == user2.id));
+    assert!(guests[10..].iter().all(|g| g.user_id == user.id));
+    assert_eq!(guests[0].last_name, "Adams".to_string());
+    assert_eq!(guests[0].purchaser_id, Some(user2.id));
+    assert_eq!(guests[0].purchaser_email, Some(user2.email.clone()));
+    assert_eq!(guests[0].status, "Purchased".to_string());
+    assert!(guests[0].order_id.is_some());
+}
+
 #[test]
 fn organization() {
     let project = TestProject::new();
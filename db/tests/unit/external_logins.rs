@@ -0,0 +1,59 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn find_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let facebook_login = user
+        .add_external_login(
+            "123".to_string(),
+            "facebook.com".to_string(),
+            "token".to_string(),
+            connection,
+        ).unwrap();
+    let google_login = user
+        .add_external_login(
+            "456".to_string(),
+            "https://accounts.google.com".to_string(),
+            "token".to_string(),
+            connection,
+        ).unwrap();
+    project
+        .create_user()
+        .finish()
+        .add_external_login(
+            "789".to_string(),
+            "facebook.com".to_string(),
+            "token".to_string(),
+            connection,
+        ).unwrap();
+
+    assert_eq!(
+        ExternalLogin::find_for_user(user.id, connection).unwrap(),
+        vec![facebook_login, google_login]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let external_login = user
+        .add_external_login(
+            "123".to_string(),
+            "facebook.com".to_string(),
+            "token".to_string(),
+            connection,
+        ).unwrap();
+
+    assert_eq!(external_login.destroy(connection).unwrap(), 1);
+    assert!(
+        ExternalLogin::find_user("123", "facebook.com", connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(ExternalLogin::find(external_login.id, connection).is_err());
+}
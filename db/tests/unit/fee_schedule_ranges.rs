@@ -10,10 +10,12 @@ fn find() {
             NewFeeScheduleRange {
                 min_price: 20,
                 fee_in_cents: 10,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price: 100,
                 fee_in_cents: 20,
+                ..Default::default()
             },
         ],
     ).commit(project.get_connection())
@@ -28,3 +30,43 @@ fn find() {
         FeeScheduleRange::find(fee_schedule_range.id, project.get_connection()).unwrap();
     assert_eq!(found_fee_schedule_range, fee_schedule_range);
 }
+
+#[test]
+fn calculate_fee() {
+    let project = TestProject::new();
+    let fee_schedule = FeeSchedule::create(
+        "default".to_string(),
+        vec![
+            NewFeeScheduleRange {
+                min_price: 0,
+                fee_in_cents: 50,
+                percentage_in_basis_points: 250,
+                ..Default::default()
+            },
+            NewFeeScheduleRange {
+                min_price: 1000,
+                fee_in_cents: 0,
+                percentage_in_basis_points: 1000,
+                min_fee_in_cents: Some(150),
+                max_fee_in_cents: Some(500),
+            },
+        ],
+    ).commit(project.get_connection())
+    .unwrap();
+
+    let range = fee_schedule
+        .get_range(500, project.get_connection())
+        .unwrap()
+        .unwrap();
+    // 50 + 2.5% of 500 rounded to the nearest cent
+    assert_eq!(range.calculate_fee(500), 63);
+    assert_eq!(range.calculate_fee(0), 50);
+
+    let range = fee_schedule
+        .get_range(1000, project.get_connection())
+        .unwrap()
+        .unwrap();
+    assert_eq!(range.calculate_fee(1000), 150);
+    assert_eq!(range.calculate_fee(2500), 250);
+    assert_eq!(range.calculate_fee(10000), 500);
+}
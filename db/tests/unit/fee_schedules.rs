@@ -1,5 +1,8 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::{FeeSchedule, NewFeeScheduleRange};
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use time::Duration;
 
 #[test]
 fn fee_schedule_create() {
@@ -10,10 +13,12 @@ fn fee_schedule_create() {
             NewFeeScheduleRange {
                 min_price: 20,
                 fee_in_cents: 10,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price: 1000,
                 fee_in_cents: 100,
+                ..Default::default()
             },
         ],
     ).commit(project.get_connection())
@@ -35,16 +40,19 @@ fn fee_schedule_create() {
             NewFeeScheduleRange {
                 min_price: 20,
                 fee_in_cents: 10,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price: 1000,
                 fee_in_cents: 100,
+                ..Default::default()
             },
         ],
-    ).commit(project.get_connection())
+    ).commit_as_version_of(&fee_schedule, project.get_connection())
     .unwrap();
 
     assert_eq!(fee_schedule2.version, 1);
+    assert_eq!(fee_schedule2.root_fee_schedule_id, fee_schedule.id);
 }
 
 #[test]
@@ -56,10 +64,12 @@ fn get_fee_schedule_range() {
             NewFeeScheduleRange {
                 min_price: 20,
                 fee_in_cents: 10,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price: 100,
                 fee_in_cents: 20,
+                ..Default::default()
             },
         ],
     ).commit(project.get_connection())
@@ -81,3 +91,220 @@ fn get_fee_schedule_range() {
     assert_eq!(fee_schedule_range2.fee_in_cents, 20);
     assert!(fee_schedule_range3.is_none());
 }
+
+#[test]
+fn fee_schedule_create_with_validation_errors() {
+    let project = TestProject::new();
+    let result = FeeSchedule::create(
+        "default".to_string(),
+        vec![
+            NewFeeScheduleRange {
+                min_price: 0,
+                fee_in_cents: 10,
+                percentage_in_basis_points: 10_001,
+                ..Default::default()
+            },
+            NewFeeScheduleRange {
+                min_price: 100,
+                fee_in_cents: 10,
+                min_fee_in_cents: Some(200),
+                max_fee_in_cents: Some(100),
+                ..Default::default()
+            },
+        ],
+    ).commit(project.get_connection());
+
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["ranges"].len(), 2);
+                assert_eq!(errors["ranges"][0].code, "invalid_percentage");
+                assert_eq!(errors["ranges"][1].code, "invalid_fee_cap");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn current_version() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let ranges = || {
+        vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 10,
+            ..Default::default()
+        }]
+    };
+    let fee_schedule = FeeSchedule::create("default".to_string(), ranges())
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        fee_schedule.current_version(connection).unwrap(),
+        Some(fee_schedule.clone())
+    );
+
+    let fee_schedule2 = FeeSchedule::create("default".to_string(), ranges())
+        .commit_as_version_of(&fee_schedule, connection)
+        .unwrap();
+    assert_eq!(
+        fee_schedule.current_version(connection).unwrap(),
+        Some(fee_schedule2.clone())
+    );
+
+    // Versions that are not yet effective only apply to later orders
+    let mut new_fee_schedule = FeeSchedule::create("default".to_string(), ranges());
+    new_fee_schedule.effective_at = Some(Utc::now().naive_utc() + Duration::days(1));
+    let fee_schedule3 = new_fee_schedule
+        .commit_as_version_of(&fee_schedule2, connection)
+        .unwrap();
+    assert_eq!(fee_schedule3.version, 2);
+    assert_eq!(
+        fee_schedule.current_version(connection).unwrap(),
+        Some(fee_schedule2.clone())
+    );
+    assert_eq!(
+        fee_schedule3.current_version(connection).unwrap(),
+        Some(fee_schedule2.clone())
+    );
+
+    // Fee schedules with the same name are not versions of each other
+    let other_fee_schedule = FeeSchedule::create("default".to_string(), ranges())
+        .commit(connection)
+        .unwrap();
+    assert_eq!(other_fee_schedule.version, 0);
+    assert_eq!(
+        other_fee_schedule.current_version(connection).unwrap(),
+        Some(other_fee_schedule.clone())
+    );
+    assert_eq!(
+        fee_schedule.current_version(connection).unwrap(),
+        Some(fee_schedule2.clone())
+    );
+
+    // No version is in effect until the first one takes effect
+    let mut new_fee_schedule = FeeSchedule::create("future".to_string(), ranges());
+    new_fee_schedule.effective_at = Some(Utc::now().naive_utc() + Duration::days(1));
+    let future_fee_schedule = new_fee_schedule.commit(connection).unwrap();
+    assert_eq!(
+        future_fee_schedule.current_version(connection).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn find_for_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(connection).unwrap().remove(0);
+    assert_eq!(
+        FeeSchedule::find_for_ticket_type(&ticket_type, connection)
+            .unwrap()
+            .id,
+        organization.fee_schedule_id
+    );
+
+    let event_fee_schedule = project.create_fee_schedule().finish();
+    event
+        .set_fee_schedule(Some(&event_fee_schedule), connection)
+        .unwrap();
+    assert_eq!(
+        FeeSchedule::find_for_ticket_type(&ticket_type, connection).unwrap(),
+        event_fee_schedule
+    );
+
+    let ticket_type_fee_schedule = project.create_fee_schedule().finish();
+    let ticket_type = ticket_type
+        .set_fee_schedule(Some(&ticket_type_fee_schedule), connection)
+        .unwrap();
+    assert_eq!(
+        ticket_type.fee_schedule_id,
+        Some(ticket_type_fee_schedule.id)
+    );
+    assert_eq!(
+        FeeSchedule::find_for_ticket_type(&ticket_type, connection).unwrap(),
+        ticket_type_fee_schedule
+    );
+
+    let ticket_type = ticket_type.set_fee_schedule(None, connection).unwrap();
+    assert_eq!(
+        FeeSchedule::find_for_ticket_type(&ticket_type, connection).unwrap(),
+        event_fee_schedule
+    );
+
+    // Overrides that are not yet effective do not apply
+    let mut new_fee_schedule = FeeSchedule::create(
+        "future".to_string(),
+        vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 10,
+            ..Default::default()
+        }],
+    );
+    new_fee_schedule.effective_at = Some(Utc::now().naive_utc() + Duration::days(1));
+    let future_fee_schedule = new_fee_schedule.commit(connection).unwrap();
+    let ticket_type = ticket_type
+        .set_fee_schedule(Some(&future_fee_schedule), connection)
+        .unwrap();
+    assert_eq!(
+        FeeSchedule::find_for_ticket_type(&ticket_type, connection).unwrap(),
+        event_fee_schedule
+    );
+    event
+        .set_fee_schedule(Some(&future_fee_schedule), connection)
+        .unwrap();
+    assert_eq!(
+        FeeSchedule::find_for_ticket_type(&ticket_type, connection)
+            .unwrap()
+            .id,
+        organization.fee_schedule_id
+    );
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let parent = project.create_organization().finish();
+    let mut new_fee_schedule = FeeSchedule::create(
+        "future".to_string(),
+        vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 10,
+            ..Default::default()
+        }],
+    );
+    new_fee_schedule.effective_at = Some(Utc::now().naive_utc() + Duration::days(1));
+    let future_fee_schedule = new_fee_schedule.commit(connection).unwrap();
+    let organization = project
+        .create_organization()
+        .with_parent(&parent)
+        .with_fee_schedule(&future_fee_schedule)
+        .finish();
+
+    // The parent's fee schedule applies until the organization's takes effect
+    assert_eq!(
+        FeeSchedule::find_for_organization(&organization, connection)
+            .unwrap()
+            .id,
+        parent.fee_schedule_id
+    );
+
+    // Without a parent the organization's fee schedule is used as is
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&future_fee_schedule)
+        .finish();
+    assert_eq!(
+        FeeSchedule::find_for_organization(&organization, connection).unwrap(),
+        future_fee_schedule
+    );
+}
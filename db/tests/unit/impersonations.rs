@@ -0,0 +1,114 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::Utc;
+
+#[test]
+fn start() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project
+        .create_user()
+        .finish()
+        .add_role(Roles::Admin, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+
+    let impersonation = Impersonation::start(
+        &admin,
+        &user,
+        Some("Cart issue".to_string()),
+        Some("127.0.0.1".to_string()),
+        connection,
+    ).unwrap();
+
+    assert_eq!(impersonation.admin_user_id, admin.id);
+    assert_eq!(impersonation.user_id, user.id);
+    assert_eq!(impersonation.reason, Some("Cart issue".to_string()));
+    assert!(impersonation.expires_at > Utc::now().naive_utc());
+    assert!(impersonation.is_active());
+
+    // The impersonation has its own session for the user
+    let session = Session::find(impersonation.session_id, connection).unwrap();
+    assert_eq!(session.user_id, user.id);
+    assert_eq!(session.ip_address, Some("127.0.0.1".to_string()));
+    assert!(session.is_active());
+}
+
+#[test]
+fn start_for_admin() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project
+        .create_user()
+        .finish()
+        .add_role(Roles::Admin, connection)
+        .unwrap();
+    let admin2 = project
+        .create_user()
+        .finish()
+        .add_role(Roles::Admin, connection)
+        .unwrap();
+
+    for user in vec![&admin, &admin2] {
+        let result = Impersonation::start(&admin, user, None, None, connection);
+        match result {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert_eq!(errors["user_id"][0].code, "invalid_user");
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+}
+
+#[test]
+fn end() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let impersonation = Impersonation::start(&admin, &user, None, None, connection).unwrap();
+
+    let impersonation = impersonation.end(connection).unwrap();
+    assert!(impersonation.ended_at.is_some());
+    assert!(!impersonation.is_active());
+    assert!(!Session::find(impersonation.session_id, connection)
+        .unwrap()
+        .is_active());
+}
+
+#[test]
+fn find_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let impersonation = Impersonation::start(&admin, &user, None, None, connection).unwrap();
+    Impersonation::start(&admin, &user2, None, None, connection).unwrap();
+
+    assert_eq!(
+        Impersonation::find_for_user(user.id, connection).unwrap(),
+        vec![impersonation]
+    );
+}
+
+#[test]
+fn record_action() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let impersonation = Impersonation::start(&admin, &user, None, None, connection).unwrap();
+
+    let action = impersonation
+        .record_action("POST", "/cart", connection)
+        .unwrap();
+    assert_eq!(action.impersonation_id, impersonation.id);
+    assert_eq!(action.method, "POST");
+    assert_eq!(action.path, "/cart");
+    assert_eq!(impersonation.actions(connection).unwrap(), vec![action]);
+}
@@ -0,0 +1,223 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use uuid::Uuid;
+
+#[test]
+fn create_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    assert!(LedgerEntry::find_for_order(cart.id, connection)
+        .unwrap()
+        .is_empty());
+
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    let entries = LedgerEntry::find_for_order(cart.id, connection).unwrap();
+    assert_eq!(entries.len(), 6);
+    assert_eq!(entries.iter().map(|e| e.amount_in_cents).sum::<i64>(), 0);
+    assert!(entries.iter().all(|e| e.organization_id == organization.id));
+    let total_for = |account: LedgerAccount| -> i64 {
+        entries
+            .iter()
+            .filter(|e| e.account == account.to_string())
+            .map(|e| e.amount_in_cents)
+            .sum()
+    };
+    assert_eq!(total_for(LedgerAccount::Customer), -total);
+    assert_eq!(total_for(LedgerAccount::Organization), 300);
+    assert_eq!(total_for(LedgerAccount::Platform), total - 300);
+
+    // Orders are only recorded once
+    assert!(LedgerEntry::create_for_order(&cart, connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        LedgerEntry::find_for_order(cart.id, connection)
+            .unwrap()
+            .len(),
+        6
+    );
+}
+
+#[test]
+fn create_for_order_with_absorbed_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                fee_mode: Some(FeeMode::Absorbed.to_string()),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    assert_eq!(total, 300);
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    let entries = LedgerEntry::find_for_order(cart.id, connection).unwrap();
+    assert_eq!(entries.iter().map(|e| e.amount_in_cents).sum::<i64>(), 0);
+    let total_for = |account: LedgerAccount| -> i64 {
+        entries
+            .iter()
+            .filter(|e| e.account == account.to_string())
+            .map(|e| e.amount_in_cents)
+            .sum()
+    };
+    let platform_total = total_for(LedgerAccount::Platform);
+    assert!(platform_total > 0);
+    assert_eq!(total_for(LedgerAccount::Customer), -total);
+    assert_eq!(
+        total_for(LedgerAccount::Organization),
+        total - platform_total
+    );
+}
+
+#[test]
+fn create_for_order_with_co_promoter() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    let co_promoter = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    EventPromoter::create(
+        event.id,
+        co_promoter.id,
+        EventPromoterPermissions::View,
+        2500,
+    ).commit(connection)
+    .unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    let entries = LedgerEntry::find_for_order(cart.id, connection).unwrap();
+    assert_eq!(entries.iter().map(|e| e.amount_in_cents).sum::<i64>(), 0);
+    let total_for = |account: LedgerAccount, organization_id: Uuid| -> i64 {
+        entries
+            .iter()
+            .filter(|e| e.account == account.to_string())
+            .filter(|e| e.organization_id == organization_id)
+            .map(|e| e.amount_in_cents)
+            .sum()
+    };
+    assert_eq!(total_for(LedgerAccount::Organization, organization.id), 225);
+    assert_eq!(total_for(LedgerAccount::Organization, co_promoter.id), 75);
+    // Platform fees are recorded against the owning organization only
+    assert_eq!(total_for(LedgerAccount::Platform, co_promoter.id), 0);
+    assert_eq!(total_for(LedgerAccount::Platform, organization.id), total - 300);
+}
+
+#[test]
+fn create_reversal_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let order = project.create_order().is_paid().finish();
+    let sale_entries = LedgerEntry::find_for_order(order.id, connection).unwrap();
+    assert!(!sale_entries.is_empty());
+
+    let reversal_entries =
+        LedgerEntry::create_reversal_for_order(&order, LedgerEntryType::Refund, connection)
+            .unwrap();
+    assert_eq!(reversal_entries.len(), sale_entries.len());
+    assert!(reversal_entries
+        .iter()
+        .all(|e| e.entry_type == LedgerEntryType::Refund.to_string()));
+    let organization_total: i64 = LedgerEntry::find_for_order(order.id, connection)
+        .unwrap()
+        .iter()
+        .filter(|e| e.account == LedgerAccount::Organization.to_string())
+        .map(|e| e.amount_in_cents)
+        .sum();
+    assert_eq!(organization_total, 0);
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Orders,
+            Some(order.id),
+            Some(DomainEventTypes::OrderRefunded),
+            connection,
+        ).unwrap()
+        .len(),
+        1
+    );
+
+    // Orders can only be reversed once
+    let result =
+        LedgerEntry::create_reversal_for_order(&order, LedgerEntryType::Chargeback, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("order_id"));
+                assert_eq!(errors["order_id"][0].code, "already_reversed");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn create_reversal_for_order_with_invalid_state() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let paid_order = project.create_order().is_paid().finish();
+    let result = LedgerEntry::create_reversal_for_order(
+        &paid_order,
+        LedgerEntryType::TicketSale,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["order_id"][0].code, "invalid_entry_type");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let unpaid_order = project.create_order().finish();
+    let result =
+        LedgerEntry::create_reversal_for_order(&unpaid_order, LedgerEntryType::Refund, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["order_id"][0].code, "not_paid");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
@@ -0,0 +1,183 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::login_codes;
+use chrono::{Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn create_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let (login_code, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Sms, connection).unwrap();
+    assert_eq!(code.len(), 6);
+    assert!(code.chars().all(|c| c.is_digit(10)));
+    assert_eq!(login_code.user_id, user.id);
+    assert_eq!(login_code.channel, LoginCodeChannel::Sms.to_string());
+    assert_ne!(login_code.hashed_code, code);
+    assert!(login_code.is_usable());
+    assert_eq!(login_code.purpose, LoginCodePurpose::Login.to_string());
+    assert_eq!(
+        LoginCode::find_latest_for_user(user.id, LoginCodePurpose::Login, connection).unwrap(),
+        login_code
+    );
+}
+
+#[test]
+fn recently_sent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    assert!(!LoginCode::recently_sent(user.id, LoginCodePurpose::Login, connection).unwrap());
+
+    let (login_code, _) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection).unwrap();
+    assert!(LoginCode::recently_sent(user.id, LoginCodePurpose::Login, connection).unwrap());
+
+    diesel::update(&login_code)
+        .set(login_codes::created_at.eq(Utc::now().naive_utc() - Duration::minutes(2)))
+        .execute(connection)
+        .unwrap();
+    assert!(!LoginCode::recently_sent(user.id, LoginCodePurpose::Login, connection).unwrap());
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection).unwrap();
+
+    assert!(LoginCode::redeem(user.id, &code, connection).unwrap());
+    let login_code =
+        LoginCode::find_latest_for_user(user.id, LoginCodePurpose::Login, connection).unwrap();
+    assert!(login_code.used_at.is_some());
+    assert!(!login_code.is_usable());
+
+    // Codes can only be used once
+    assert!(!LoginCode::redeem(user.id, &code, connection).unwrap());
+}
+
+#[test]
+fn redeem_incorrect_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection).unwrap();
+    let incorrect_code = if code == "000000" { "111111" } else { "000000" };
+
+    for _ in 0..5 {
+        assert!(!LoginCode::redeem(user.id, incorrect_code, connection).unwrap());
+    }
+    let login_code =
+        LoginCode::find_latest_for_user(user.id, LoginCodePurpose::Login, connection).unwrap();
+    assert_eq!(login_code.attempts, 5);
+
+    // Too many attempts invalidates the code
+    assert!(!LoginCode::redeem(user.id, &code, connection).unwrap());
+}
+
+#[test]
+fn redeem_expired_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (login_code, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection).unwrap();
+
+    diesel::update(&login_code)
+        .set(login_codes::expires_at.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+        .execute(connection)
+        .unwrap();
+    assert!(!LoginCode::redeem(user.id, &code, connection).unwrap());
+}
+
+#[test]
+fn redeem_superseded_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (login_code, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection).unwrap();
+    diesel::update(&login_code)
+        .set(login_codes::created_at.eq(Utc::now().naive_utc() - Duration::minutes(2)))
+        .execute(connection)
+        .unwrap();
+    LoginCode::create_for_user(user.id, LoginCodeChannel::Email, connection).unwrap();
+
+    // Only the newest code is accepted
+    assert!(!LoginCode::redeem(user.id, &code, connection).unwrap());
+}
+
+#[test]
+fn redeem_phone_verification() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (login_code, code) =
+        LoginCode::create_for_phone_verification(user.id, "555-555-1234", connection).unwrap();
+    assert_eq!(login_code.channel, LoginCodeChannel::Sms.to_string());
+    assert_eq!(
+        login_code.purpose,
+        LoginCodePurpose::PhoneVerification.to_string()
+    );
+    assert_eq!(login_code.phone, Some("555-555-1234".to_string()));
+
+    // Verification codes can not be used to sign in or to verify another number
+    assert!(!LoginCode::redeem(user.id, &code, connection).unwrap());
+    assert!(
+        !LoginCode::redeem_phone_verification(user.id, "555-555-9876", &code, connection)
+            .unwrap()
+    );
+    assert!(
+        LoginCode::redeem_phone_verification(user.id, "555-555-1234", &code, connection)
+            .unwrap()
+    );
+}
+
+#[test]
+fn redeem_login_code_for_phone_verification() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (_, code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Sms, connection).unwrap();
+
+    assert!(
+        !LoginCode::redeem_phone_verification(user.id, "555-555-1234", &code, connection)
+            .unwrap()
+    );
+    assert!(LoginCode::redeem(user.id, &code, connection).unwrap());
+}
+
+#[test]
+fn expire_phone_verifications() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (_, sign_in_code) =
+        LoginCode::create_for_user(user.id, LoginCodeChannel::Sms, connection).unwrap();
+    let (_, code) =
+        LoginCode::create_for_phone_verification(user.id, "555-555-1234", connection).unwrap();
+
+    assert_eq!(
+        LoginCode::expire_phone_verifications(user.id, connection).unwrap(),
+        1
+    );
+    assert!(
+        !LoginCode::find_latest_for_user(user.id, LoginCodePurpose::PhoneVerification, connection)
+            .unwrap()
+            .is_usable()
+    );
+    assert!(
+        !LoginCode::redeem_phone_verification(user.id, "555-555-1234", &code, connection)
+            .unwrap()
+    );
+    // Login codes are unaffected
+    assert!(LoginCode::redeem(user.id, &sign_in_code, connection).unwrap());
+}
@@ -0,0 +1,116 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::login_throttles;
+use chrono::{Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn record_failure_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    // The first few failures are not delayed
+    for attempt in 1..4 {
+        let throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+        assert_eq!(throttle.user_id, Some(user.id));
+        assert_eq!(throttle.failed_attempts, attempt);
+        assert!(!throttle.is_throttled());
+    }
+
+    let throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+    assert_eq!(throttle.failed_attempts, 4);
+    assert!(throttle.is_throttled());
+    assert!(!throttle.is_locked_out());
+    assert_eq!(
+        LoginThrottle::find_for_user(user.id, connection).unwrap(),
+        throttle
+    );
+}
+
+#[test]
+fn record_failure_for_user_lockout() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let mut throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+    while !throttle.is_locked_out() {
+        assert!(!throttle.lockout_started());
+        throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+    }
+    assert_eq!(throttle.failed_attempts, 10);
+    assert!(throttle.lockout_started());
+    assert!(throttle.locked_until.unwrap() > Utc::now().naive_utc() + Duration::minutes(29));
+
+    // Counting restarts once the lockout has expired
+    diesel::update(login_throttles::table.find(throttle.id))
+        .set(login_throttles::locked_until.eq(Some(Utc::now().naive_utc() - Duration::minutes(1))))
+        .execute(connection)
+        .unwrap();
+    let throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+    assert_eq!(throttle.failed_attempts, 1);
+    assert!(!throttle.is_throttled());
+}
+
+#[test]
+fn record_failure_for_ip_address() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    let throttle = LoginThrottle::record_failure_for_ip_address("10.0.0.1", connection).unwrap();
+    assert_eq!(throttle.ip_address, Some("10.0.0.1".to_string()));
+    assert_eq!(throttle.user_id, None);
+    assert_eq!(throttle.failed_attempts, 1);
+
+    let throttle = LoginThrottle::record_failure_for_ip_address("10.0.0.1", connection).unwrap();
+    assert_eq!(throttle.failed_attempts, 2);
+    assert_eq!(
+        LoginThrottle::find_for_ip_address("10.0.0.1", connection).unwrap(),
+        throttle
+    );
+    assert!(LoginThrottle::find_for_ip_address("10.0.0.2", connection).is_err());
+}
+
+#[test]
+fn find_throttled() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    LoginThrottle::record_failure_for_user(user2.id, connection).unwrap();
+    let mut throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+    while !throttle.is_throttled() {
+        throttle = LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+    }
+
+    assert_eq!(
+        LoginThrottle::find_throttled(connection).unwrap(),
+        vec![throttle]
+    );
+}
+
+#[test]
+fn clear_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    LoginThrottle::record_failure_for_user(user.id, connection).unwrap();
+
+    assert_eq!(
+        LoginThrottle::clear_for_user(user.id, connection).unwrap(),
+        1
+    );
+    assert!(LoginThrottle::find_for_user(user.id, connection).is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let throttle = LoginThrottle::record_failure_for_ip_address("10.0.0.1", connection).unwrap();
+
+    assert_eq!(throttle.destroy(connection).unwrap(), 1);
+    assert!(LoginThrottle::find(throttle.id, connection).is_err());
+}
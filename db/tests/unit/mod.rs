@@ -1,22 +1,46 @@
 pub mod artists;
 pub mod assets;
+pub mod audit_entries;
+pub mod calendar_tokens;
 pub mod concerns;
 pub mod domain_events;
 pub mod event_artists;
 pub mod event_interest;
+pub mod event_interest_notifications;
+pub mod event_promoters;
+pub mod event_reminders;
 pub mod events;
+pub mod external_logins;
 pub mod fee_schedule_ranges;
 pub mod fee_schedules;
 pub mod holds;
+pub mod impersonations;
+pub mod ledger_entries;
+pub mod login_codes;
+pub mod login_throttles;
+pub mod oidc_login_requests;
 pub mod order_items;
 pub mod orders;
+pub mod organization_api_keys;
+pub mod organization_customers;
+pub mod organization_email_templates;
 pub mod organization_invites;
+pub mod organization_roles;
 pub mod organization_users;
 pub mod organizations;
 pub mod payment_methods;
 pub mod regions;
+pub mod sales_reports;
+pub mod sessions;
+pub mod settlements;
 pub mod ticket_instances;
 pub mod ticket_pricing;
 pub mod ticket_types;
+pub mod two_factor_credentials;
+pub mod two_factor_recovery_codes;
+pub mod user_data_exports;
+pub mod user_erasure_requests;
 pub mod users;
 pub mod venues;
+pub mod webhook_deliveries;
+pub mod webhook_subscriptions;
@@ -0,0 +1,68 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::oidc_login_requests;
+use chrono::{Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+
+const REDIRECT_URI: &str = "https://example.com/oidc/callback";
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let login_request = OidcLoginRequest::create(REDIRECT_URI)
+        .commit(project.get_connection())
+        .unwrap();
+    assert_eq!(login_request.state.len(), 32);
+    assert_eq!(login_request.nonce.len(), 32);
+    assert_ne!(login_request.state, login_request.nonce);
+    assert!(login_request.expires_at > Utc::now().naive_utc());
+    assert_eq!(login_request.used_at, None);
+}
+
+#[test]
+fn consume() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let login_request = OidcLoginRequest::create(REDIRECT_URI)
+        .commit(connection)
+        .unwrap();
+
+    // The redirect must match the one the login was started with
+    assert!(
+        OidcLoginRequest::consume(&login_request.state, "https://evil.com", connection)
+            .unwrap()
+            .is_none()
+    );
+    let consumed = OidcLoginRequest::consume(&login_request.state, REDIRECT_URI, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(consumed.nonce, login_request.nonce);
+    assert!(consumed.used_at.is_some());
+
+    // Each state can only be used once
+    assert!(
+        OidcLoginRequest::consume(&login_request.state, REDIRECT_URI, connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn consume_expired() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let login_request = OidcLoginRequest::create(REDIRECT_URI)
+        .commit(connection)
+        .unwrap();
+    diesel::update(oidc_login_requests::table.find(login_request.id))
+        .set(oidc_login_requests::expires_at.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+        .execute(connection)
+        .unwrap();
+
+    assert!(
+        OidcLoginRequest::consume(&login_request.state, REDIRECT_URI, connection)
+            .unwrap()
+            .is_none()
+    );
+}
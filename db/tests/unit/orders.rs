@@ -71,6 +71,184 @@ fn add_tickets() {
     assert_eq!(items[0].calculate_quantity(connection), Ok(15));
 }
 
+#[test]
+fn add_tickets_with_ticket_type_fee_schedule() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let fee_schedule = FeeSchedule::create(
+        "Ticket type fees".to_string(),
+        vec![NewFeeScheduleRange {
+            min_price: 0,
+            fee_in_cents: 5,
+            percentage_in_basis_points: 1_000,
+            max_fee_in_cents: Some(18),
+            ..Default::default()
+        }],
+    ).commit(connection)
+    .unwrap();
+    let ticket_type = event.ticket_types(connection).unwrap().remove(0);
+    ticket_type
+        .set_fee_schedule(Some(&fee_schedule), connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    assert_eq!(
+        order_item.fee_schedule_range_id,
+        Some(fee_schedule.ranges(connection).unwrap()[0].id)
+    );
+
+    // 5 cents plus 10% of 150 cents, capped at 18 cents per ticket
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.unit_price_in_cents, 36);
+}
+
+#[test]
+fn add_tickets_with_absorbed_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event = event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(Some(FeeMode::Absorbed.to_string())),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+
+    let user = project.create_user().finish();
+    let cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+
+    // Fees are still recorded but the buyer only pays the ticket price
+    let items = cart.items(connection).unwrap();
+    assert_eq!(items.len(), 3);
+    for item in &items {
+        assert_eq!(
+            item.is_absorbed,
+            item.item_type() != OrderItemTypes::Tickets
+        );
+    }
+    assert_eq!(cart.calculate_total(connection).unwrap(), 300);
+
+    let display_order = cart.for_display(connection).unwrap();
+    assert_eq!(display_order.total_in_cents, 300);
+    assert_eq!(display_order.items.len(), 1);
+    assert_eq!(display_order.items[0].unit_price_in_cents, 150);
+}
+
+#[test]
+fn add_tickets_after_fee_mode_changed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+
+    let user = project.create_user().finish();
+    let cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    assert!(cart.items(connection).unwrap().iter().all(|i| !i.is_absorbed));
+
+    event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(Some(FeeMode::Absorbed.to_string())),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    cart.add_tickets(ticket_type.id, 1, connection).unwrap();
+
+    // Both the per ticket fees and the event fee are absorbed now
+    let items = cart.items(connection).unwrap();
+    assert_eq!(items.len(), 3);
+    for item in &items {
+        assert_eq!(
+            item.is_absorbed,
+            item.item_type() != OrderItemTypes::Tickets
+        );
+    }
+    assert_eq!(cart.calculate_total(connection).unwrap(), 450);
+}
+
+#[test]
+fn add_tickets_during_presale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                presale_start_date: Some(Utc::now().naive_utc() - Duration::days(1)),
+                presale_code: Some("FANCLUB".to_string()),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(ticket_type.is_in_presale());
+
+    let user = project.create_user().finish();
+    let cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let error = cart
+        .add_tickets(ticket_type.id, 2, connection)
+        .unwrap_err();
+    match &error.error_code {
+        ValidationError { errors } => {
+            assert!(errors.contains_key("ticket_type_id"));
+            assert_eq!(errors["ticket_type_id"][0].code, "presale_only");
+        }
+        _ => panic!("Expected validation error"),
+    }
+
+    let tickets = cart
+        .add_tickets_with_presale_code(ticket_type.id, 2, Some("FANCLUB"), connection)
+        .unwrap();
+    assert_eq!(tickets.len(), 2);
+
+    // Users who registered interest before the presale do not need a code
+    let interested_user = project.create_user().finish();
+    EventInterest::create(event.id, interested_user.id)
+        .commit(connection)
+        .unwrap();
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                presale_interest_cutoff: Some(Utc::now().naive_utc() + Duration::hours(1)),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    let cart = Order::find_or_create_cart(&interested_user, connection).unwrap();
+    let tickets = cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+}
+
 #[test]
 fn add_tickets_with_increment() {
     let project = TestProject::new();
@@ -381,6 +559,18 @@ fn add_external_payment() {
     cart.add_external_payment("test2".to_string(), user.id, 500, conn)
         .unwrap();
     assert_eq!(cart.status(), OrderStatus::Paid);
+
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(cart.id),
+        Some(DomainEventTypes::OrderPaid),
+        conn,
+    ).unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(
+        domain_events[0].organization_id,
+        Some(event.organization_id)
+    );
 }
 
 #[test]
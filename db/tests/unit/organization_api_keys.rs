@@ -0,0 +1,147 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        "Box office",
+        vec!["event:write".to_string(), "org:read".to_string()],
+        user.id,
+        connection,
+    ).unwrap();
+
+    assert_eq!(api_key.organization_id, organization.id);
+    assert_eq!(api_key.created_by, user.id);
+    assert_eq!(api_key.name, "Box office");
+    assert!(key.starts_with(&format!("{}.", api_key.prefix)));
+    assert!(!api_key.hashed_secret.contains(&key));
+    assert!(api_key.is_active());
+
+    // The key acts as a service user that can not sign in
+    let service_user = User::find(api_key.service_user_id, connection).unwrap();
+    assert_eq!(service_user.email, None);
+    assert!(service_user.role.is_empty());
+}
+
+#[test]
+fn create_with_invalid_scopes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+
+    for scopes in vec![vec![], vec!["org:admin".to_string()]] {
+        let result =
+            OrganizationApiKey::create(organization.id, "Key", scopes, user.id, connection);
+        match result {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert!(errors.contains_key("scopes"));
+                    assert_eq!(errors["scopes"].len(), 1);
+                    assert_eq!(errors["scopes"][0].code, "invalid_scopes");
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+}
+
+#[test]
+fn find_by_key() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        "Box office",
+        vec!["org:read".to_string()],
+        user.id,
+        connection,
+    ).unwrap();
+
+    assert_eq!(
+        OrganizationApiKey::find_by_key(&key, connection).unwrap(),
+        Some(api_key.clone())
+    );
+    assert_eq!(
+        OrganizationApiKey::find_by_key(&format!("{}.wrong", api_key.prefix), connection).unwrap(),
+        None
+    );
+    assert_eq!(
+        OrganizationApiKey::find_by_key(&api_key.prefix, connection).unwrap(),
+        None
+    );
+
+    // Revoked keys no longer authenticate
+    let api_key = api_key.revoke(connection).unwrap();
+    assert!(!api_key.is_active());
+    assert_eq!(
+        OrganizationApiKey::find_by_key(&key, connection).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let organization2 = project.create_organization().with_owner(&user).finish();
+    let (api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Box office",
+        vec!["org:read".to_string()],
+        user.id,
+        connection,
+    ).unwrap();
+    OrganizationApiKey::create(
+        organization2.id,
+        "Website",
+        vec!["org:read".to_string()],
+        user.id,
+        connection,
+    ).unwrap();
+
+    assert_eq!(
+        OrganizationApiKey::find_for_organization(organization.id, connection).unwrap(),
+        vec![api_key]
+    );
+}
+
+#[test]
+fn record_use() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let (api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Box office",
+        vec!["org:read".to_string()],
+        user.id,
+        connection,
+    ).unwrap();
+    assert!(api_key.last_used_at.is_none());
+
+    let request = api_key
+        .record_use("GET", "/events", Some("10.0.0.1"), connection)
+        .unwrap();
+    assert_eq!(request.organization_api_key_id, api_key.id);
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/events");
+    assert_eq!(request.ip_address, Some("10.0.0.1".to_string()));
+
+    let api_key = OrganizationApiKey::find(api_key.id, connection).unwrap();
+    assert!(api_key.last_used_at.is_some());
+    assert_eq!(api_key.requests(10, connection).unwrap(), vec![request]);
+}
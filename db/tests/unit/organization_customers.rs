@@ -0,0 +1,237 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use uuid::Uuid;
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+
+    // Customers of other organizations are not included
+    project.create_order().is_paid().finish();
+
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = TicketInstance::find(tickets[0].id, connection).unwrap();
+    TicketInstance::redeem_ticket(ticket.id, ticket.redeem_key.unwrap(), connection).unwrap();
+    let transfer_auth =
+        TicketInstance::authorize_ticket_transfer(user.id, vec![tickets[1].id], 3600, connection)
+            .unwrap();
+    let sender_wallet = Wallet::find_default_for_user(user.id, connection).unwrap();
+    let receiver_wallet = Wallet::find_default_for_user(user2.id, connection).unwrap();
+    TicketInstance::receive_ticket_transfer(
+        transfer_auth,
+        &sender_wallet,
+        &receiver_wallet.id,
+        connection,
+    ).unwrap();
+
+    let (customers, total_customers) = OrganizationCustomer::find_for_organization(
+        organization.id,
+        &OrganizationCustomerFilter::default(),
+        None,
+        0,
+        connection,
+    ).unwrap();
+    assert_eq!(total_customers, 2);
+    assert_eq!(customers.len(), 2);
+
+    let customer = customers.iter().find(|c| c.user_id == user.id).unwrap();
+    assert_eq!(customer.email, user.email);
+    assert_eq!(customer.lifetime_spent_in_cents, total);
+    assert_eq!(customer.ticket_count, 1);
+    assert_eq!(customer.events_attended, 1);
+    assert_eq!(customer.first_purchase_at, customer.last_purchase_at);
+    assert!(customer.first_purchase_at.is_some());
+    assert_eq!(customer.interests, vec![event.name.clone()]);
+
+    // Transferred tickets make the receiver a customer without any spend
+    let customer = customers.iter().find(|c| c.user_id == user2.id).unwrap();
+    assert_eq!(customer.lifetime_spent_in_cents, 0);
+    assert_eq!(customer.ticket_count, 1);
+    assert_eq!(customer.events_attended, 0);
+    assert!(customer.first_purchase_at.is_none());
+    assert!(customer.interests.is_empty());
+}
+
+#[test]
+fn find_for_organization_after_refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let order2 = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let find_spent = || -> i64 {
+        OrganizationCustomer::find_for_organization(
+            organization.id,
+            &OrganizationCustomerFilter::default(),
+            None,
+            0,
+            connection,
+        ).unwrap()
+        .0
+        .iter()
+        .find(|c| c.user_id == user.id)
+        .unwrap()
+        .lifetime_spent_in_cents
+    };
+    let spent = find_spent();
+    let order_spent = order.calculate_total(connection).unwrap();
+    assert_eq!(spent, order_spent + order2.calculate_total(connection).unwrap());
+
+    // Refunds and chargebacks are not counted as spend
+    LedgerEntry::create_reversal_for_order(&order, LedgerEntryType::Refund, connection).unwrap();
+    assert_eq!(find_spent(), spent - order_spent);
+    LedgerEntry::create_reversal_for_order(&order2, LedgerEntryType::Chargeback, connection)
+        .unwrap();
+    assert_eq!(find_spent(), 0);
+}
+
+#[test]
+fn find_for_organization_with_filters() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project
+        .create_user()
+        .with_last_name("Aardvark".to_string())
+        .finish();
+    let user2 = project
+        .create_user()
+        .with_last_name("Zebra".to_string())
+        .finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event2)
+        .for_user(&user2)
+        .is_paid()
+        .finish();
+
+    let find = |filter: OrganizationCustomerFilter| -> Vec<Uuid> {
+        OrganizationCustomer::find_for_organization(organization.id, &filter, None, 0, connection)
+            .unwrap()
+            .0
+            .iter()
+            .map(|c| c.user_id)
+            .collect()
+    };
+
+    assert_eq!(
+        find(OrganizationCustomerFilter::default()),
+        vec![user.id, user2.id]
+    );
+    assert_eq!(
+        find(OrganizationCustomerFilter {
+            query: Some("zeb".to_string()),
+            ..Default::default()
+        }),
+        vec![user2.id]
+    );
+    // Wildcards are matched literally
+    assert_eq!(
+        find(OrganizationCustomerFilter {
+            query: Some("%".to_string()),
+            ..Default::default()
+        }),
+        Vec::<Uuid>::new()
+    );
+    assert_eq!(
+        find(OrganizationCustomerFilter {
+            query: Some("z_b".to_string()),
+            ..Default::default()
+        }),
+        Vec::<Uuid>::new()
+    );
+    assert_eq!(
+        find(OrganizationCustomerFilter {
+            event_id: Some(event.id),
+            ..Default::default()
+        }),
+        vec![user.id]
+    );
+    assert_eq!(
+        find(OrganizationCustomerFilter {
+            min_events_attended: Some(1),
+            ..Default::default()
+        }),
+        Vec::<Uuid>::new()
+    );
+    assert_eq!(
+        find(OrganizationCustomerFilter {
+            min_spent_in_cents: Some(1_000_000),
+            ..Default::default()
+        }),
+        Vec::<Uuid>::new()
+    );
+
+    // Paging keeps the total number of matching customers
+    let (customers, total) = OrganizationCustomer::find_for_organization(
+        organization.id,
+        &OrganizationCustomerFilter::default(),
+        Some(1),
+        1,
+        connection,
+    ).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(customers.len(), 1);
+    assert_eq!(customers[0].user_id, user2.id);
+
+    // Including for pages past the last customer
+    let (customers, total) = OrganizationCustomer::find_for_organization(
+        organization.id,
+        &OrganizationCustomerFilter::default(),
+        Some(1),
+        5,
+        connection,
+    ).unwrap();
+    assert_eq!(total, 2);
+    assert!(customers.is_empty());
+}
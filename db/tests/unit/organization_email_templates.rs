@@ -0,0 +1,193 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use std::collections::HashMap;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketConfirmation,
+        "Your {{event_names}} tickets".to_string(),
+        "Hi {{ recipient_name }}, see you there".to_string(),
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(template.organization_id, organization.id);
+    assert_eq!(
+        template.template_type(),
+        EmailTemplateTypes::TicketConfirmation
+    );
+    assert_eq!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, connection).unwrap(),
+        vec![template.clone()]
+    );
+    assert_eq!(
+        OrganizationEmailTemplate::find_by_type(
+            organization.id,
+            EmailTemplateTypes::TicketConfirmation,
+            connection
+        ).unwrap(),
+        template
+    );
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    // Placeholders of other template types cannot be used
+    let result = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        "Your {{event_names}} tickets".to_string(),
+        "Hi {{recipient_name".to_string(),
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["subject"][0].code, "invalid_template");
+                assert_eq!(errors["body"][0].code, "invalid_template");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Subjects are sent as a header so cannot contain line breaks
+    let result = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketConfirmation,
+        "Your tickets\r\nBcc: victim@example.com".to_string(),
+        "Hi {{recipient_name}}".to_string(),
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["subject"][0].code, "invalid_template");
+                assert!(!errors.contains_key("body"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::EventReminder,
+        "{{event_name}} is soon".to_string(),
+        "See you at {{venue_name}}".to_string(),
+    ).commit(connection)
+    .unwrap();
+
+    let attributes = OrganizationEmailTemplateEditableAttributes {
+        body: Some("See you at {{venue_name}} on {{event_start}}".to_string()),
+        ..Default::default()
+    };
+    let updated_template = template.update(attributes, connection).unwrap();
+    assert_eq!(updated_template.subject, template.subject);
+    assert_eq!(
+        updated_template.body,
+        "See you at {{venue_name}} on {{event_start}}"
+    );
+
+    let attributes = OrganizationEmailTemplateEditableAttributes {
+        body: Some("{{password}}".to_string()),
+        ..Default::default()
+    };
+    assert!(updated_template.update(attributes, connection).is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        "Tickets from {{sender_name}}".to_string(),
+        "{{receive_tickets_link}}".to_string(),
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(template.destroy(connection).unwrap(), 1);
+    assert!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn render_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let mut values = HashMap::new();
+    values.insert("sender_name", "John".to_string());
+    values.insert("receive_tickets_link", "http://localhost/receive".to_string());
+
+    // Default template
+    let rendered = OrganizationEmailTemplate::render_for_organization(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        &values,
+        connection,
+    ).unwrap();
+    assert_eq!(rendered.subject, "John has sent you some tickets");
+    assert!(rendered.body.contains("http://localhost/receive"));
+
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        "Tickets from {{sender_name}}".to_string(),
+        "Claim them here {{receive_tickets_link}} {{recipient_name}}".to_string(),
+    ).commit(connection)
+    .unwrap();
+    let rendered = OrganizationEmailTemplate::render_for_organization(
+        organization.id,
+        EmailTemplateTypes::TicketTransfer,
+        &values,
+        connection,
+    ).unwrap();
+    // Values that are not provided are left empty
+    assert_eq!(
+        rendered,
+        RenderedEmailTemplate {
+            subject: "Tickets from John".to_string(),
+            body: "Claim them here http://localhost/receive ".to_string(),
+        }
+    );
+}
+
+#[test]
+fn render_with_line_breaks_in_values() {
+    let mut values = HashMap::new();
+    values.insert("sender_name", "John\r\nBcc: victim@example.com".to_string());
+
+    // Line breaks are removed from the subject but kept in the body
+    let rendered = OrganizationEmailTemplate::render(
+        EmailTemplateTypes::TicketTransfer,
+        "Tickets from {{sender_name}}",
+        "Sent by {{sender_name}}",
+        &values,
+    ).unwrap();
+    assert_eq!(
+        rendered.subject,
+        "Tickets from John  Bcc: victim@example.com"
+    );
+    assert_eq!(rendered.body, "Sent by John\r\nBcc: victim@example.com");
+}
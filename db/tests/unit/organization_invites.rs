@@ -1,11 +1,12 @@
 extern crate chrono;
 use bigneon_db::dev::TestProject;
-use bigneon_db::models::OrganizationInvite;
+use bigneon_db::models::{OrganizationInvite, OrganizationInviteStatus, OrganizationRole};
 use bigneon_db::utils::errors::ErrorCode::ValidationError;
 use bigneon_db::utils::errors::{DatabaseError, ErrorCode};
 use diesel;
 use diesel::prelude::*;
 use unit::organization_invites::chrono::prelude::*;
+use unit::organization_invites::chrono::Duration;
 
 #[test]
 fn create() {
@@ -49,6 +50,191 @@ fn create_with_validation_errors() {
     }
 }
 
+#[test]
+fn create_with_role() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let role = OrganizationRole::create(organization.id, "Door Staff", vec![])
+        .commit(connection)
+        .unwrap();
+    let org_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .with_role(&role)
+        .finish();
+    assert_eq!(org_invite.organization_role_id, Some(role.id));
+
+    // Roles belonging to other organizations are rejected
+    let organization2 = project.create_organization().with_owner(&user).finish();
+    let mut new_invite =
+        OrganizationInvite::create(organization2.id, user.id, "invitee@tari.com", None);
+    new_invite.organization_role_id = Some(role.id);
+    match new_invite.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("organization_role_id"));
+                assert_eq!(errors["organization_role_id"][0].code, "invalid_role");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let pending_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+    let accepted_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish()
+        .accept_invite(connection)
+        .unwrap();
+    let declined_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish()
+        .decline_invite(connection)
+        .unwrap();
+    let revoked_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish()
+        .revoke(connection)
+        .unwrap();
+    let mut expired_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+    expired_invite.expires_at = Utc::now().naive_utc() - Duration::days(1);
+    let expired_invite = update(&expired_invite, connection).unwrap();
+
+    assert_eq!(pending_invite.status(), OrganizationInviteStatus::Pending);
+    assert_eq!(accepted_invite.status(), OrganizationInviteStatus::Accepted);
+    assert_eq!(declined_invite.status(), OrganizationInviteStatus::Declined);
+    assert_eq!(revoked_invite.status(), OrganizationInviteStatus::Revoked);
+    assert_eq!(expired_invite.status(), OrganizationInviteStatus::Expired);
+
+    let all_invites =
+        OrganizationInvite::find_for_organization(organization.id, None, connection).unwrap();
+    assert_eq!(all_invites.len(), 5);
+    for (status, invite) in vec![
+        (OrganizationInviteStatus::Pending, pending_invite),
+        (OrganizationInviteStatus::Accepted, accepted_invite),
+        (OrganizationInviteStatus::Declined, declined_invite),
+        (OrganizationInviteStatus::Revoked, revoked_invite),
+        (OrganizationInviteStatus::Expired, expired_invite),
+    ] {
+        assert_eq!(
+            OrganizationInvite::find_for_organization(organization.id, Some(status), connection)
+                .unwrap(),
+            vec![invite]
+        );
+    }
+}
+
+#[test]
+fn resend() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let mut org_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+    org_invite.expires_at = Utc::now().naive_utc() - Duration::days(1);
+    org_invite.sent_invite = true;
+    let org_invite = update(&org_invite, connection).unwrap();
+    assert_eq!(org_invite.status(), OrganizationInviteStatus::Expired);
+
+    let resent_invite = org_invite.resend(connection).unwrap();
+    assert_eq!(resent_invite.status(), OrganizationInviteStatus::Pending);
+    assert!(!resent_invite.sent_invite);
+    assert!(resent_invite.security_token.is_some());
+    assert_ne!(resent_invite.security_token, org_invite.security_token);
+    assert!(resent_invite.expires_at > Utc::now().naive_utc() + Duration::days(6));
+
+    // The old link no longer works
+    assert!(OrganizationInvite::get_invite_details(
+        &org_invite.security_token.unwrap(),
+        connection
+    ).is_err());
+
+    // Answered invites cannot be resent
+    let accepted_invite = resent_invite.accept_invite(connection).unwrap();
+    assert!(accepted_invite.resend(connection).is_err());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let org_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .finish();
+
+    let revoked_invite = org_invite.revoke(connection).unwrap();
+    assert!(revoked_invite.revoked_at.is_some());
+    assert!(revoked_invite.security_token.is_none());
+    assert!(OrganizationInvite::get_invite_details(
+        &org_invite.security_token.unwrap(),
+        connection
+    ).is_err());
+
+    // Already revoked invites cannot be revoked again or resent
+    assert!(revoked_invite.revoke(connection).is_err());
+    assert!(revoked_invite.resend(connection).is_err());
+}
+
+#[test]
+fn find_active_invite_by_email() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let other_organization = project.create_organization().with_owner(&user).finish();
+    let email = "invitee@localhost".to_string();
+    let org_invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .with_email(&email)
+        .finish();
+
+    let found = OrganizationInvite::find_active_invite_by_email(organization.id, &email, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, org_invite.id);
+
+    // Invites to other organizations are not found
+    assert!(
+        OrganizationInvite::find_active_invite_by_email(other_organization.id, &email, connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
 #[test]
 fn change_invite_status_of_invite() {
     let project = TestProject::new();
@@ -121,7 +307,7 @@ fn test_token_validity() {
         project.get_connection(),
     ).unwrap();
     assert_eq!(org_invite, recovered_invite);
-    org_invite.created_at = NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11);
+    org_invite.expires_at = NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11);
     org_invite = update(&org_invite, &project.get_connection()).unwrap();
     let recovered_invite2 = OrganizationInvite::get_invite_details(
         &org_invite.security_token.unwrap(),
@@ -0,0 +1,185 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let role = OrganizationRole::create(
+        organization.id,
+        "Door Staff",
+        vec!["ticket:admin".to_string()],
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(role.organization_id, organization.id);
+    assert_eq!(role.name, "Door Staff");
+    assert_eq!(role.scopes, vec!["ticket:admin".to_string()]);
+}
+
+#[test]
+fn create_with_invalid_scopes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    for scopes in vec![vec![], vec!["org:admin".to_string()]] {
+        let result =
+            OrganizationRole::create(organization.id, "Door Staff", scopes).commit(connection);
+        match result {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert!(errors.contains_key("scopes"));
+                    assert_eq!(errors["scopes"][0].code, "invalid_scopes");
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+}
+
+#[test]
+fn create_with_duplicate_name() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    OrganizationRole::create(organization.id, "Finance", vec!["org:reports".to_string()])
+        .commit(connection)
+        .unwrap();
+
+    let result =
+        OrganizationRole::create(organization.id, "Finance", vec!["org:reports".to_string()])
+            .commit(connection);
+    assert_eq!(result.unwrap_err().error_code, ErrorCode::DuplicateKeyError);
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let role =
+        OrganizationRole::create(organization.id, "Finance", vec!["org:reports".to_string()])
+            .commit(connection)
+            .unwrap();
+    let role2 = OrganizationRole::create(
+        organization.id,
+        "Box Office",
+        vec!["order:read".to_string()],
+    ).commit(connection)
+    .unwrap();
+    OrganizationRole::create(organization2.id, "Finance", vec!["org:reports".to_string()])
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        OrganizationRole::find_for_organization(organization.id, connection).unwrap(),
+        vec![role2, role]
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let role =
+        OrganizationRole::create(organization.id, "Finance", vec!["org:reports".to_string()])
+            .commit(connection)
+            .unwrap();
+
+    let role = role
+        .update(
+            OrganizationRoleEditableAttributes {
+                name: Some("Accounts".to_string()),
+                scopes: Some(vec!["org:reports".to_string(), "order:read".to_string()]),
+            },
+            connection,
+        ).unwrap();
+    assert_eq!(role.name, "Accounts");
+    assert_eq!(
+        role.scopes,
+        vec!["org:reports".to_string(), "order:read".to_string()]
+    );
+
+    let result = role.update(
+        OrganizationRoleEditableAttributes {
+            scopes: Some(vec!["user:admin".to_string()]),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_user(&user).finish();
+    let role =
+        OrganizationRole::create(organization.id, "Finance", vec!["org:reports".to_string()])
+            .commit(connection)
+            .unwrap();
+    organization
+        .set_user_role(user.id, Some(role.id), connection)
+        .unwrap();
+
+    // Assigned roles can not be removed
+    let result = role.destroy(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["id"][0].code, "role_assigned");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    organization
+        .set_user_role(user.id, None, connection)
+        .unwrap();
+    assert_eq!(role.destroy(connection).unwrap(), 1);
+    assert!(OrganizationRole::find(role.id, connection).is_err());
+}
+
+#[test]
+fn destroy_with_pending_invite() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let role =
+        OrganizationRole::create(organization.id, "Finance", vec!["org:reports".to_string()])
+            .commit(connection)
+            .unwrap();
+    let invite = project
+        .create_organization_invite()
+        .with_org(&organization)
+        .with_invitee(&user)
+        .with_role(&role)
+        .finish();
+
+    // Roles of invites that can still be accepted can not be removed
+    let result = role.destroy(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["id"][0].code, "role_invited");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    invite.revoke(connection).unwrap();
+    assert_eq!(role.destroy(connection).unwrap(), 1);
+}
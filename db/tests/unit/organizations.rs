@@ -1,8 +1,9 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::{
     FeeSchedule, NewFeeScheduleRange, Organization, OrganizationEditableAttributes,
-    OrganizationUser, Roles, User,
+    OrganizationRole, OrganizationUser, Roles, Scopes, User,
 };
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
 use uuid::Uuid;
 
 #[test]
@@ -15,6 +16,7 @@ fn create() {
         vec![NewFeeScheduleRange {
             min_price: 0,
             fee_in_cents: 0,
+            ..Default::default()
         }],
     ).commit(connection)
     .unwrap();
@@ -65,6 +67,82 @@ fn update() {
     assert_eq!(edited_organization, updated_organization);
 }
 
+#[test]
+fn update_branding() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let attributes = OrganizationEditableAttributes {
+        logo_url: Some("https://example.com/logo.png".to_string()),
+        primary_color: Some("#1A2B3C".to_string()),
+        secondary_color: Some("#fff".to_string()),
+        email_sender_name: Some("The Venue".to_string()),
+        email_reply_to: Some("tickets@example.com".to_string()),
+        ..Default::default()
+    };
+    let organization = organization.update(attributes, connection).unwrap();
+    assert_eq!(
+        organization.logo_url,
+        Some("https://example.com/logo.png".to_string())
+    );
+    assert_eq!(organization.primary_color, Some("#1A2B3C".to_string()));
+    assert_eq!(organization.secondary_color, Some("#fff".to_string()));
+    assert_eq!(organization.email_sender_name, Some("The Venue".to_string()));
+    assert_eq!(
+        organization.email_reply_to,
+        Some("tickets@example.com".to_string())
+    );
+
+    let attributes = OrganizationEditableAttributes {
+        logo_url: Some("invalid".to_string()),
+        primary_color: Some("red".to_string()),
+        secondary_color: Some("#12345".to_string()),
+        email_reply_to: Some("invalid".to_string()),
+        ..Default::default()
+    };
+    let result = organization.update(attributes, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["logo_url"][0].code, "url");
+                assert_eq!(errors["primary_color"][0].code, "color");
+                assert_eq!(errors["secondary_color"][0].code, "color");
+                assert_eq!(errors["email_reply_to"][0].code, "email");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update_email_sender_name_validation() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    for name in vec![
+        "The Venue\r\nBcc: victim@example.com".to_string(),
+        "Big Neon Support".to_string(),
+        "a".repeat(101),
+    ] {
+        let attributes = OrganizationEditableAttributes {
+            email_sender_name: Some(name),
+            ..Default::default()
+        };
+        match organization.update(attributes, connection) {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert_eq!(errors["email_sender_name"][0].code, "email_sender_name");
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+}
+
 #[test]
 fn update_owner() {
     let project = TestProject::new();
@@ -341,7 +419,9 @@ pub fn get_scopes_for_user() {
             "event:write",
             "hold:write",
             "order:read",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "ticket:admin",
             "ticket:transfer",
@@ -374,6 +454,76 @@ pub fn get_scopes_for_user() {
     );
 }
 
+#[test]
+pub fn get_scopes_for_user_with_organization_role() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_owner(&user)
+        .with_user(&user2)
+        .finish();
+    let role = OrganizationRole::create(
+        organization.id,
+        "Door Staff",
+        vec!["ticket:admin".to_string()],
+    ).commit(connection)
+    .unwrap();
+    organization
+        .set_user_role(user2.id, Some(role.id), connection)
+        .unwrap();
+
+    assert_eq!(
+        organization
+            .get_organization_role_for_user(&user2, connection)
+            .unwrap(),
+        Some(role)
+    );
+    assert_eq!(
+        organization
+            .get_scopes_for_user(&user2, connection)
+            .unwrap(),
+        vec![
+            "event:interest",
+            "order:read",
+            "ticket:admin",
+            "ticket:transfer",
+        ]
+    );
+
+    // Removing the role restores the default member scopes
+    organization.set_user_role(user2.id, None, connection).unwrap();
+    assert!(
+        organization
+            .get_scopes_for_user(&user2, connection)
+            .unwrap()
+            .contains(&"event:write".to_string())
+    );
+}
+
+#[test]
+fn set_user_role_for_other_organization_role() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_user(&user).finish();
+    let organization2 = project.create_organization().finish();
+    let role = OrganizationRole::create(
+        organization2.id,
+        "Finance",
+        vec!["org:reports".to_string()],
+    ).commit(connection)
+    .unwrap();
+
+    assert!(
+        organization
+            .set_user_role(user.id, Some(role.id), connection)
+            .is_err()
+    );
+}
+
 #[test]
 fn change_owner() {
     let project = TestProject::new();
@@ -384,6 +534,7 @@ fn change_owner() {
         vec![NewFeeScheduleRange {
             min_price: 0,
             fee_in_cents: 0,
+            ..Default::default()
         }],
     ).commit(connection)
     .unwrap();
@@ -450,3 +601,193 @@ fn owner() {
 
     assert_eq!(owner.id, organization_owner.id);
 }
+
+#[test]
+fn set_parent() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let parent = project.create_organization().finish();
+    let organization = project.create_organization().finish();
+    let child = project
+        .create_organization()
+        .with_parent(&organization)
+        .finish();
+    assert_eq!(child.parent_id, Some(organization.id));
+
+    let organization = organization.set_parent(Some(parent.id), connection).unwrap();
+    assert_eq!(organization.parent_id, Some(parent.id));
+
+    // Organizations cannot be moved below themselves or their own children
+    assert!(organization.set_parent(Some(organization.id), connection).is_err());
+    assert!(parent.set_parent(Some(child.id), connection).is_err());
+
+    let organization = organization.set_parent(None, connection).unwrap();
+    assert_eq!(organization.parent_id, None);
+}
+
+#[test]
+fn children_ancestors_and_descendants() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let parent = project
+        .create_organization()
+        .with_name("Parent".into())
+        .finish();
+    let child = project
+        .create_organization()
+        .with_name("Child".into())
+        .with_parent(&parent)
+        .finish();
+    let grandchild = project
+        .create_organization()
+        .with_name("Grandchild".into())
+        .with_parent(&child)
+        .finish();
+    let _other = project.create_organization().finish();
+
+    assert_eq!(parent.children(connection).unwrap(), vec![child.clone()]);
+    assert_eq!(
+        parent.descendants(connection).unwrap(),
+        vec![child.clone(), grandchild.clone()]
+    );
+    assert!(grandchild.descendants(connection).unwrap().is_empty());
+
+    let mut ancestors = grandchild.ancestors(connection).unwrap();
+    ancestors.sort_by_key(|o| o.name.clone());
+    assert_eq!(ancestors, vec![child, parent.clone()]);
+    assert!(parent.ancestors(connection).unwrap().is_empty());
+}
+
+#[test]
+fn get_roles_and_scopes_for_user_in_child_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let owner = project.create_user().finish();
+    let member = project.create_user().finish();
+    let child_member = project.create_user().finish();
+    let parent = project
+        .create_organization()
+        .with_owner(&owner)
+        .with_user(&member)
+        .finish();
+    let child = project
+        .create_organization()
+        .with_parent(&parent)
+        .with_user(&child_member)
+        .finish();
+    let grandchild = project.create_organization().with_parent(&child).finish();
+
+    // Roles are inherited downward
+    assert_eq!(
+        grandchild.get_roles_for_user(&owner, connection).unwrap(),
+        vec!["OrgOwner", "OrgMember"]
+    );
+    assert_eq!(
+        grandchild.get_roles_for_user(&member, connection).unwrap(),
+        vec!["OrgMember"]
+    );
+    assert_eq!(
+        grandchild.get_roles_for_user(&child_member, connection).unwrap(),
+        vec!["OrgMember"]
+    );
+    assert_eq!(
+        grandchild.get_scopes_for_user(&owner, connection).unwrap(),
+        parent.get_scopes_for_user(&owner, connection).unwrap()
+    );
+
+    // But not upward
+    assert!(
+        parent
+            .get_roles_for_user(&child_member, connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        parent
+            .get_scopes_for_user(&child_member, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn requires_two_factor_for_scope() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let member = project.create_user().finish();
+    let child_member = project.create_user().finish();
+    let parent = project.create_organization().with_user(&member).finish();
+    let child = project
+        .create_organization()
+        .with_parent(&parent)
+        .with_user(&child_member)
+        .finish();
+    assert!(
+        !child
+            .requires_two_factor_for_scope(&member, Scopes::EventWrite, connection)
+            .unwrap()
+    );
+
+    // Scopes inherited from an organization requiring a second factor need one in its children
+    parent
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(
+        child
+            .requires_two_factor_for_scope(&member, Scopes::EventWrite, connection)
+            .unwrap()
+    );
+    assert!(
+        !child
+            .requires_two_factor_for_scope(&child_member, Scopes::EventWrite, connection)
+            .unwrap()
+    );
+
+    let child = child
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(
+        child
+            .requires_two_factor_for_scope(&child_member, Scopes::EventWrite, connection)
+            .unwrap()
+    );
+}
+
+#[test]
+fn all_linked_to_user_includes_child_organizations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let parent = project
+        .create_organization()
+        .with_name("Parent".into())
+        .with_owner(&user)
+        .finish();
+    let child = project
+        .create_organization()
+        .with_name("Child".into())
+        .with_parent(&parent)
+        .with_user(&user)
+        .finish();
+    let grandchild = project
+        .create_organization()
+        .with_name("Grandchild".into())
+        .with_parent(&child)
+        .finish();
+    let _other = project.create_organization().finish();
+
+    assert_eq!(
+        Organization::all_linked_to_user(user.id, connection).unwrap(),
+        vec![child, grandchild, parent]
+    );
+}
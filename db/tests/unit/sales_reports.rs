@@ -0,0 +1,267 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use time::Duration;
+
+#[test]
+fn for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    let report = SalesReport::for_event(&event, None, None, connection).unwrap();
+    assert_eq!(report.event_id, Some(event.id));
+    assert_eq!(report.ticket_sales.len(), 1);
+    let ticket_sales = &report.ticket_sales[0];
+    assert_eq!(ticket_sales.ticket_type_id, ticket_type.id);
+    assert_eq!(ticket_sales.ticket_pricing_name, "Standard".to_string());
+    assert_eq!(ticket_sales.sales_date, Utc::now().naive_utc().date());
+    assert_eq!(ticket_sales.sold_quantity, 2);
+    assert_eq!(ticket_sales.comped_quantity, 0);
+    assert_eq!(ticket_sales.refunded_quantity, 0);
+    assert_eq!(ticket_sales.gross_revenue_in_cents, 300);
+    assert_eq!(ticket_sales.per_unit_fees_in_cents, 100);
+    assert_eq!(ticket_sales.absorbed_per_unit_fees_in_cents, 0);
+
+    assert_eq!(report.inventory.len(), 1);
+    assert_eq!(report.inventory[0].sold_quantity, 2);
+    assert_eq!(report.inventory[0].reserved_quantity, 0);
+    assert_eq!(report.inventory[0].available_quantity, 98);
+
+    assert_eq!(
+        report.event_fees,
+        vec![EventFeesReportRow {
+            event_id: event.id,
+            event_name: event.name.clone(),
+            event_fees_in_cents: 250,
+            absorbed_event_fees_in_cents: 0,
+        }]
+    );
+    assert_eq!(
+        report.payments,
+        vec![PaymentsReportRow {
+            payment_method: PaymentMethods::External.to_string(),
+            payment_count: 1,
+            amount_in_cents: total,
+        }]
+    );
+    assert_eq!(
+        report.totals,
+        SalesReportTotals {
+            sold_quantity: 2,
+            comped_quantity: 0,
+            refunded_quantity: 0,
+            gross_revenue_in_cents: 300,
+            per_unit_fees_in_cents: 100,
+            absorbed_per_unit_fees_in_cents: 0,
+            event_fees_in_cents: 250,
+            absorbed_event_fees_in_cents: 0,
+            payments_in_cents: total,
+        }
+    );
+}
+
+#[test]
+fn for_event_with_absorbed_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event = event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(Some(FeeMode::Absorbed.to_string())),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    // Absorbed fees are not paid by the buyer so are kept apart from the fees they paid
+    let report = SalesReport::for_event(&event, None, None, connection).unwrap();
+    assert_eq!(report.ticket_sales[0].per_unit_fees_in_cents, 0);
+    assert_eq!(report.ticket_sales[0].absorbed_per_unit_fees_in_cents, 100);
+    assert_eq!(report.event_fees[0].event_fees_in_cents, 0);
+    assert_eq!(report.event_fees[0].absorbed_event_fees_in_cents, 250);
+    assert_eq!(report.totals.gross_revenue_in_cents, 300);
+    assert_eq!(report.totals.per_unit_fees_in_cents, 0);
+    assert_eq!(report.totals.absorbed_per_unit_fees_in_cents, 100);
+    assert_eq!(report.totals.event_fees_in_cents, 0);
+    assert_eq!(report.totals.absorbed_event_fees_in_cents, 250);
+    assert_eq!(report.totals.payments_in_cents, 300);
+}
+
+#[test]
+fn for_event_revenue_splits() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let co_promoter = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let promoter = EventPromoter::create(
+        event.id,
+        co_promoter.id,
+        EventPromoterPermissions::View,
+        2500,
+    ).commit(connection)
+    .unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    // Sales keep the split they were recorded with after the promoters change
+    promoter.destroy(connection).unwrap();
+    let report = SalesReport::for_event(&event, None, None, connection).unwrap();
+    assert_eq!(
+        report.revenue_splits,
+        vec![
+            RevenueSplitReportRow {
+                organization_id: organization.id,
+                ticket_revenue_in_cents: 225,
+            },
+            RevenueSplitReportRow {
+                organization_id: co_promoter.id,
+                ticket_revenue_in_cents: 75,
+            },
+        ]
+    );
+
+    LedgerEntry::create_reversal_for_order(&cart, LedgerEntryType::Refund, connection).unwrap();
+    let report = SalesReport::for_event(&event, None, None, connection).unwrap();
+    assert!(
+        report
+            .revenue_splits
+            .iter()
+            .all(|split| split.ticket_revenue_in_cents == 0)
+    );
+}
+
+#[test]
+fn for_event_excludes_unpaid_orders() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.add_tickets(ticket_type.id, 3, connection).unwrap();
+
+    let report = SalesReport::for_event(&event, None, None, connection).unwrap();
+    assert!(report.ticket_sales.is_empty());
+    assert!(report.payments.is_empty());
+    assert_eq!(report.inventory[0].reserved_quantity, 3);
+    assert_eq!(report.inventory[0].sold_quantity, 0);
+}
+
+#[test]
+fn for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 1, connection).unwrap();
+    cart.add_tickets(ticket_type2.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    let report = SalesReport::for_organization(&organization, None, None, connection).unwrap();
+    assert_eq!(report.event_id, None);
+    assert_eq!(report.ticket_sales.len(), 2);
+    assert_eq!(report.inventory.len(), 2);
+    assert_eq!(report.totals.sold_quantity, 3);
+    assert_eq!(report.totals.payments_in_cents, total);
+
+    // Filtering to a window before the order was placed excludes it
+    let end_utc = Utc::now().naive_utc() - Duration::days(1);
+    let report =
+        SalesReport::for_organization(&organization, None, Some(end_utc), connection).unwrap();
+    assert!(report.ticket_sales.is_empty());
+    assert_eq!(report.totals.sold_quantity, 0);
+}
+
+#[test]
+fn for_organization_with_children() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let child_organization = project
+        .create_organization()
+        .with_parent(&organization)
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&child_organization)
+        .with_ticket_pricing()
+        .finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 1, connection).unwrap();
+    cart.add_tickets(ticket_type2.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+
+    let report =
+        SalesReport::for_organization_with_children(&organization, None, None, connection)
+            .unwrap();
+    assert_eq!(report.child_organization_ids, vec![child_organization.id]);
+    assert_eq!(report.ticket_sales.len(), 2);
+    assert_eq!(report.inventory.len(), 2);
+    assert_eq!(report.totals.sold_quantity, 3);
+
+    // Without children only the organization's own events are included
+    let report = SalesReport::for_organization(&organization, None, None, connection).unwrap();
+    assert!(report.child_organization_ids.is_empty());
+    assert_eq!(report.totals.sold_quantity, 1);
+}
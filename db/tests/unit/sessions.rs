@@ -0,0 +1,134 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = Session::create(
+        user.id,
+        Some("Mozilla/5.0".to_string()),
+        Some("127.0.0.1".to_string()),
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(session.user_id, user.id);
+    assert_eq!(session.user_agent, Some("Mozilla/5.0".to_string()));
+    assert_eq!(session.ip_address, Some("127.0.0.1".to_string()));
+    assert!(session.is_active());
+    assert_eq!(Session::find(session.id, connection).unwrap(), session);
+}
+
+#[test]
+fn find_active_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let session = Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+    let session2 = Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+    Session::create(user2.id, None, None)
+        .commit(connection)
+        .unwrap();
+    session2.revoke(connection).unwrap();
+
+    assert_eq!(
+        Session::find_active_for_user(user.id, connection).unwrap(),
+        vec![session]
+    );
+}
+
+#[test]
+fn record_use() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let used_session = session.record_use(connection).unwrap();
+    assert!(used_session.last_used_at >= session.last_used_at);
+    assert!(used_session.is_active());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let session = session.revoke(connection).unwrap();
+    assert!(session.revoked_at.is_some());
+    assert!(!session.is_active());
+}
+
+#[test]
+fn revoke_all_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+    Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+    let other_session = Session::create(user2.id, None, None)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        Session::revoke_all_for_user(user.id, connection).unwrap(),
+        2
+    );
+    assert!(Session::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(Session::find(other_session.id, connection)
+        .unwrap()
+        .is_active());
+}
+
+#[test]
+fn deactivating_user_revokes_sessions() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+
+    user.update(
+        &UserEditableAttributes {
+            active: Some(false),
+            ..Default::default()
+        },
+        connection,
+    ).unwrap();
+    assert!(!Session::find(session.id, connection).unwrap().is_active());
+}
+
+#[test]
+fn removing_user_from_organization_revokes_sessions() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    organization.add_user(user.id, connection).unwrap();
+    let session = Session::create(user.id, None, None)
+        .commit(connection)
+        .unwrap();
+
+    organization.remove_user(user.id, connection).unwrap();
+    assert!(!Session::find(session.id, connection).unwrap().is_active());
+}
@@ -0,0 +1,217 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use time::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(settlement.organization_id, organization.id);
+    assert_eq!(settlement.status(), SettlementStatus::Open);
+    assert_eq!(settlement.created_by, user.id);
+
+    let entries = LedgerEntry::find_for_settlement(settlement.id, connection).unwrap();
+    assert_eq!(
+        entries.len(),
+        LedgerEntry::find_for_order(order.id, connection)
+            .unwrap()
+            .len()
+    );
+
+    // Entries are only claimed by a single settlement
+    let settlement2 = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    assert!(LedgerEntry::find_for_settlement(settlement2.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn commit_for_event_and_period() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    project.create_order().for_event(&event2).is_paid().finish();
+
+    let settlement = Settlement::create(organization.id, Some(event.id), None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    let entries = LedgerEntry::find_for_settlement(settlement.id, connection).unwrap();
+    assert!(!entries.is_empty());
+    assert!(entries.iter().all(|e| e.order_id == Some(order.id)));
+
+    let end_utc = Utc::now().naive_utc() - Duration::days(1);
+    let settlement = Settlement::create(organization.id, None, None, Some(end_utc), user.id)
+        .commit(connection)
+        .unwrap();
+    assert!(LedgerEntry::find_for_settlement(settlement.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+    let start_utc = Utc::now().naive_utc();
+    let end_utc = start_utc - Duration::days(1);
+
+    let result = Settlement::create(
+        organization.id,
+        Some(other_event.id),
+        Some(start_utc),
+        Some(end_utc),
+        user.id,
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["event_id"][0].code, "invalid_event");
+                assert_eq!(errors["start_utc"][0].code, "invalid_period");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    let other_organization = project.create_organization().finish();
+    Settlement::create(other_organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        Settlement::find_for_organization(organization.id, connection).unwrap(),
+        vec![settlement.clone()]
+    );
+    assert_eq!(
+        Settlement::find(settlement.id, connection).unwrap(),
+        settlement
+    );
+}
+
+#[test]
+fn statement() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let total = order.calculate_total(connection).unwrap();
+
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    let statement = settlement.statement(connection).unwrap();
+    assert_eq!(statement.settlement, settlement);
+    assert_eq!(statement.lines.len(), 3);
+    assert!(statement
+        .lines
+        .iter()
+        .all(|l| l.event_id == Some(event.id) && l.account != "Customer"));
+    assert_eq!(
+        statement.organization_total_in_cents + statement.platform_total_in_cents,
+        total
+    );
+    assert_eq!(statement.organization_total_in_cents, 1500);
+}
+
+#[test]
+fn mark_paid_out() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+
+    let settlement = settlement.mark_paid_out(user.id, connection).unwrap();
+    assert_eq!(settlement.status(), SettlementStatus::PaidOut);
+    assert_eq!(settlement.paid_out_by, Some(user.id));
+    assert!(settlement.paid_out_at.is_some());
+
+    // Paid out settlements are locked
+    for result in vec![
+        settlement.mark_paid_out(user.id, connection).map(|_| ()),
+        settlement.destroy(connection).map(|_| ()),
+    ] {
+        match result {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert_eq!(errors["status"][0].code, "already_paid_out");
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(settlement.destroy(connection).unwrap(), 1);
+    assert!(Settlement::find(settlement.id, connection).is_err());
+    assert!(LedgerEntry::find_for_order(order.id, connection)
+        .unwrap()
+        .iter()
+        .all(|e| e.settlement_id.is_none()));
+}
@@ -1,6 +1,7 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::{
-    DisplayTicket, EventEditableAttributes, Order, RedeemResults, TicketInstance, Wallet,
+    DisplayTicket, DomainEvent, DomainEventTypes, EventEditableAttributes, Order, RedeemResults,
+    Tables, TicketInstance, Wallet,
 };
 use chrono::prelude::*;
 use chrono::NaiveDateTime;
@@ -357,6 +358,15 @@ fn redeem_ticket() {
     let result2 =
         TicketInstance::redeem_ticket(ticket.id, ticket.redeem_key.unwrap(), connection).unwrap();
     assert_eq!(result2, RedeemResults::TicketRedeemSuccess);
+
+    let domain_events = DomainEvent::find(
+        Tables::TicketInstances,
+        Some(ticket.id),
+        Some(DomainEventTypes::TicketRedeemed),
+        connection,
+    ).unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].organization_id, Some(organization.id));
 }
 
 #[test]
@@ -552,4 +562,15 @@ pub fn receive_ticket_transfer() {
     let received_ticket = TicketInstance::find(ticket_ids[0], connection).unwrap();
 
     assert_eq!(receive_wallet.id, received_ticket.wallet_id);
+
+    for ticket_id in ticket_ids {
+        let domain_events = DomainEvent::find(
+            Tables::TicketInstances,
+            Some(ticket_id),
+            Some(DomainEventTypes::TicketTransferred),
+            connection,
+        ).unwrap();
+        assert_eq!(domain_events.len(), 1);
+        assert_eq!(domain_events[0].organization_id, Some(organization.id));
+    }
 }
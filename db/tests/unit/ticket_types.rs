@@ -1,11 +1,13 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::{
-    TicketPricing, TicketPricingEditableAttributes, TicketType, TicketTypeEditableAttributes,
+    EventInterest, TicketPricing, TicketPricingEditableAttributes, TicketType,
+    TicketTypeEditableAttributes,
 };
 use bigneon_db::utils::errors::ErrorCode::ValidationError;
-use chrono::NaiveDate;
+use chrono::prelude::*;
 use diesel::result::Error;
 use diesel::Connection;
+use time::Duration;
 
 #[test]
 fn create() {
@@ -164,3 +166,106 @@ fn find() {
     let found_ticket_type = TicketType::find(ticket_type.id, &db.get_connection()).unwrap();
     assert_eq!(&found_ticket_type, ticket_type);
 }
+
+#[test]
+fn is_in_presale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let ticket_type = event.ticket_types(connection).unwrap().remove(0);
+    assert!(!ticket_type.is_in_presale());
+
+    let now = Utc::now().naive_utc();
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                start_date: Some(now + Duration::days(1)),
+                presale_start_date: Some(now - Duration::days(1)),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(ticket_type.is_in_presale());
+
+    // General on sale has started
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                start_date: Some(now - Duration::hours(1)),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(!ticket_type.is_in_presale());
+
+    // Presale has not started
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                start_date: Some(now + Duration::days(2)),
+                presale_start_date: Some(now + Duration::days(1)),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(!ticket_type.is_in_presale());
+}
+
+#[test]
+fn is_presale_eligible() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let user = project.create_user().finish();
+    let interested_user = project.create_user().finish();
+    EventInterest::create(event.id, interested_user.id)
+        .commit(connection)
+        .unwrap();
+
+    let now = Utc::now().naive_utc();
+    let ticket_type = event
+        .ticket_types(connection)
+        .unwrap()
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                presale_start_date: Some(now - Duration::days(1)),
+                presale_interest_cutoff: Some(now + Duration::hours(1)),
+                presale_code: Some("FANCLUB".to_string()),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+
+    assert!(!ticket_type.is_presale_eligible(user.id, None, connection).unwrap());
+    assert!(
+        !ticket_type
+            .is_presale_eligible(user.id, Some("WRONG"), connection)
+            .unwrap()
+    );
+    assert!(
+        ticket_type
+            .is_presale_eligible(user.id, Some(" fanclub "), connection)
+            .unwrap()
+    );
+    assert!(
+        ticket_type
+            .is_presale_eligible(interested_user.id, None, connection)
+            .unwrap()
+    );
+
+    // Interest registered after the cutoff does not count
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                presale_interest_cutoff: Some(now - Duration::hours(1)),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(
+        !ticket_type
+            .is_presale_eligible(interested_user.id, None, connection)
+            .unwrap()
+    );
+}
@@ -0,0 +1,105 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::{TwoFactorCredential, TwoFactorRecoveryCode};
+use bigneon_db::utils::base32;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let credential = TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(credential.user_id, user.id);
+    assert!(!credential.is_enabled());
+    assert_eq!(base32::decode(&credential.secret).unwrap().len(), 20);
+    assert!(!TwoFactorCredential::is_enabled_for_user(user.id, connection).unwrap());
+
+    // Enrolling again replaces an enrolment that was never enabled
+    let credential2 = TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap();
+    assert_ne!(credential.secret, credential2.secret);
+    assert_eq!(
+        TwoFactorCredential::find_for_user(user.id, connection).unwrap(),
+        credential2
+    );
+}
+
+#[test]
+fn create_when_enabled() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap()
+        .enable(100, connection)
+        .unwrap();
+
+    let error = TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap_err();
+    match &error.error_code {
+        ValidationError { errors } => {
+            assert!(errors.contains_key("user_id"));
+            assert_eq!(errors["user_id"][0].code, "two_factor_enabled");
+        }
+        _ => panic!("Expected validation error"),
+    }
+}
+
+#[test]
+fn enable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let credential = TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap();
+
+    let credential = credential.enable(100, connection).unwrap();
+    assert!(credential.is_enabled());
+    assert_eq!(credential.last_used_step, Some(100));
+    assert!(TwoFactorCredential::is_enabled_for_user(user.id, connection).unwrap());
+}
+
+#[test]
+fn use_step() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let credential = TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap()
+        .enable(100, connection)
+        .unwrap();
+
+    // Codes for a step that was already used are rejected
+    assert!(!credential.use_step(100, connection).unwrap());
+    assert!(!credential.use_step(99, connection).unwrap());
+    assert!(credential.use_step(101, connection).unwrap());
+    assert!(!credential.use_step(101, connection).unwrap());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let credential = TwoFactorCredential::create(user.id)
+        .commit(connection)
+        .unwrap()
+        .enable(100, connection)
+        .unwrap();
+    TwoFactorRecoveryCode::regenerate_for_user(user.id, connection).unwrap();
+
+    assert_eq!(credential.destroy(connection).unwrap(), 1);
+    assert!(!TwoFactorCredential::is_enabled_for_user(user.id, connection).unwrap());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        0
+    );
+}
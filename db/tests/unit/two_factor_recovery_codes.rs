@@ -0,0 +1,49 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::TwoFactorRecoveryCode;
+
+#[test]
+fn regenerate_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let codes = TwoFactorRecoveryCode::regenerate_for_user(user.id, connection).unwrap();
+    assert_eq!(codes.len(), 10);
+    assert!(codes.iter().all(|code| code.len() == 11));
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        10
+    );
+
+    // Previous codes stop working once new ones are generated
+    let new_codes = TwoFactorRecoveryCode::regenerate_for_user(user.id, connection).unwrap();
+    assert_ne!(codes, new_codes);
+    assert!(!TwoFactorRecoveryCode::redeem(user.id, &codes[0], connection).unwrap());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        10
+    );
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let other_user = project.create_user().finish();
+    let codes = TwoFactorRecoveryCode::regenerate_for_user(user.id, connection).unwrap();
+
+    assert!(!TwoFactorRecoveryCode::redeem(other_user.id, &codes[0], connection).unwrap());
+    assert!(!TwoFactorRecoveryCode::redeem(user.id, "not-a-code", connection).unwrap());
+
+    // Codes are accepted without the separator and in upper case
+    let entered_code = codes[0].replace("-", "").to_uppercase();
+    assert!(TwoFactorRecoveryCode::redeem(user.id, &entered_code, connection).unwrap());
+    assert_eq!(
+        TwoFactorRecoveryCode::remaining_for_user(user.id, connection).unwrap(),
+        9
+    );
+
+    // Codes can only be used once
+    assert!(!TwoFactorRecoveryCode::redeem(user.id, &codes[0], connection).unwrap());
+}
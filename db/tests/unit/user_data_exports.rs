@@ -0,0 +1,58 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use serde_json;
+
+#[test]
+fn for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish())
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+    user.add_external_login(
+        "123".to_string(),
+        "facebook.com".to_string(),
+        "secret-token".to_string(),
+        connection,
+    ).unwrap();
+    let other_user = project.create_user().finish();
+    EventInterest::create(event.id, other_user.id)
+        .commit(connection)
+        .unwrap();
+
+    let export = UserDataExport::for_user(&user, connection).unwrap();
+    assert_eq!(export.profile.id, user.id);
+    assert_eq!(export.profile.email, user.email);
+    assert_eq!(export.orders.len(), 1);
+    assert_eq!(export.orders[0].id, cart.id);
+    assert_eq!(export.payments.len(), 1);
+    assert_eq!(export.tickets.len(), 1);
+    assert_eq!(export.tickets[0].event.id, event.id);
+    assert_eq!(export.tickets[0].tickets.len(), 2);
+    assert!(export.ticket_transfers.is_empty());
+    assert_eq!(export.event_interests.len(), 1);
+    assert_eq!(export.event_interests[0].user_id, user.id);
+    assert_eq!(export.external_logins.len(), 1);
+
+    // Secrets are never part of the export
+    let json = serde_json::to_string(&export).unwrap();
+    assert!(!json.contains(&user.hashed_pw));
+    assert!(!json.contains("secret-token"));
+}
@@ -0,0 +1,223 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::DatabaseError;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use time::Duration;
+
+fn purchase_tickets(project: &TestProject, user: &User, event_start: NaiveDateTime) -> Order {
+    let connection = project.get_connection();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish())
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(&event_start)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let mut cart = Order::find_or_create_cart(user, connection).unwrap();
+    let ticket_type = &event.ticket_types(connection).unwrap()[0];
+    cart.add_tickets(ticket_type.id, 2, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment("test".to_string(), user.id, total, connection)
+        .unwrap();
+    cart
+}
+
+fn assert_validation_error(error: DatabaseError, field: &str, code: &str) {
+    match &error.error_code {
+        ValidationError { errors } => {
+            assert!(errors.contains_key(field));
+            assert_eq!(errors[field][0].code, code);
+        }
+        _ => panic!("Expected validation error"),
+    }
+}
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let erasure_request =
+        UserErasureRequest::create(user.id, Some("No longer attending events".to_string()))
+            .commit(connection)
+            .unwrap();
+
+    assert_eq!(erasure_request.user_id, user.id);
+    assert!(erasure_request.is_pending());
+    assert_eq!(
+        UserErasureRequest::find_pending(connection).unwrap(),
+        vec![erasure_request.clone()]
+    );
+    assert_eq!(
+        UserErasureRequest::find_for_user(user.id, connection).unwrap(),
+        vec![erasure_request]
+    );
+
+    // Only one request can be waiting for review at a time
+    assert!(UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .is_err());
+}
+
+#[test]
+fn create_for_organization_owner() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    project.create_organization().with_owner(&user).finish();
+
+    let error = UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .unwrap_err();
+    assert_validation_error(error, "user_id", "organization_owner");
+}
+
+#[test]
+fn create_with_tickets_for_upcoming_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    purchase_tickets(&project, &user, Utc::now().naive_utc() + Duration::days(30));
+
+    let error = UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .unwrap_err();
+    assert_validation_error(error, "user_id", "upcoming_tickets");
+}
+
+#[test]
+fn approve() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let email = user.email.clone().unwrap();
+    let order = purchase_tickets(&project, &user, Utc::now().naive_utc() - Duration::days(30));
+    user.add_external_login(
+        "123".to_string(),
+        "facebook.com".to_string(),
+        "token".to_string(),
+        connection,
+    ).unwrap();
+    let organization = project.create_organization().finish();
+    organization.add_user(user.id, connection).unwrap();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .unwrap();
+
+    let erasure_request = erasure_request
+        .approve(admin.id, Some("Verified by phone".to_string()), connection)
+        .unwrap();
+    assert_eq!(
+        erasure_request.status,
+        UserErasureRequestStatus::Completed.to_string()
+    );
+    assert_eq!(erasure_request.reviewed_by, Some(admin.id));
+    assert!(erasure_request.reviewed_at.is_some());
+    assert!(UserErasureRequest::find_pending(connection)
+        .unwrap()
+        .is_empty());
+
+    let user = User::find(user.id, connection).unwrap();
+    assert_eq!(user.first_name, "Deleted");
+    assert_eq!(user.last_name, "User");
+    assert_eq!(user.email, None);
+    assert_eq!(user.phone, None);
+    assert!(!user.active);
+    assert!(user.role.is_empty());
+    assert!(User::find_by_email(&email, connection).is_err());
+    assert!(ExternalLogin::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(!organization.is_member(&user, connection).unwrap());
+
+    // Financial records are kept for accounting
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.user_id, user.id);
+    assert_eq!(
+        Payment::find_for_user(user.id, connection).unwrap().len(),
+        1
+    );
+    assert_eq!(
+        TicketInstance::find_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        2
+    );
+    // Copies of the user's data sent in order payloads are removed
+    let order_events = DomainEvent::find(Tables::Orders, Some(order.id), None, connection).unwrap();
+    assert!(!order_events.is_empty());
+    assert!(order_events.iter().all(|e| e.event_data.is_none()));
+}
+
+#[test]
+fn user_references_have_erasure_rules() {
+    let project = TestProject::new();
+    // Fails when a table referencing users is added without deciding how erasure handles it
+    assert_eq!(
+        UserErasureRequest::user_references_without_erasure_rule(project.get_connection())
+            .unwrap(),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn approve_after_becoming_organization_owner() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .unwrap();
+    project.create_organization().with_owner(&user).finish();
+
+    let error = erasure_request
+        .approve(admin.id, None, connection)
+        .unwrap_err();
+    assert_validation_error(error, "user_id", "organization_owner");
+    assert!(UserErasureRequest::find(erasure_request.id, connection)
+        .unwrap()
+        .is_pending());
+}
+
+#[test]
+fn reject() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let erasure_request = UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .unwrap();
+
+    let erasure_request = erasure_request
+        .reject(admin.id, Some("Duplicate request".to_string()), connection)
+        .unwrap();
+    assert_eq!(
+        erasure_request.status,
+        UserErasureRequestStatus::Rejected.to_string()
+    );
+    assert_eq!(
+        erasure_request.review_notes,
+        Some("Duplicate request".to_string())
+    );
+    assert_eq!(User::find(user.id, connection).unwrap().email, user.email);
+
+    // Reviewed requests can not be reviewed again
+    let error = erasure_request
+        .approve(admin.id, None, connection)
+        .unwrap_err();
+    assert_validation_error(error, "status", "already_reviewed");
+
+    // A new request can be made once the previous one is reviewed
+    assert!(UserErasureRequest::create(user.id, None)
+        .commit(connection)
+        .is_ok());
+}
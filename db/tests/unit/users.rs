@@ -1,5 +1,9 @@
 use bigneon_db::dev::TestProject;
-use bigneon_db::models::{ExternalLogin, ForDisplay, Roles, User, UserEditableAttributes};
+use bigneon_db::models::concerns::users::email_verifiable::EmailVerifiable;
+use bigneon_db::models::{
+    ExternalLogin, ForDisplay, OrganizationEditableAttributes, Roles, User,
+    UserEditableAttributes,
+};
 use bigneon_db::utils::errors;
 use bigneon_db::utils::errors::ErrorCode;
 use std::collections::HashMap;
@@ -187,6 +191,57 @@ fn update() {
 
     let updated_user = user.update(&attributes.into(), connection).unwrap();
     assert_eq!(updated_user.email, Some(email.into()));
+    // Changing the email address requires it to be verified again
+    assert!(!updated_user.is_email_verified());
+}
+
+#[test]
+fn update_phone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().with_verified_phone().finish();
+    assert!(user.is_phone_verified());
+    let mut attributes: UserEditableAttributes = Default::default();
+    attributes.phone = Some("555-555-1234".to_string());
+
+    // Changing the phone number requires it to be verified again
+    let updated_user = user.update(&attributes.into(), connection).unwrap();
+    assert!(!updated_user.is_phone_verified());
+}
+
+#[test]
+fn find_by_verified_phone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let phone = "555-555-1234".to_string();
+    project.create_user().with_phone(phone.clone()).finish();
+    assert!(User::find_by_verified_phone(&phone, connection).is_err());
+
+    let user = project
+        .create_user()
+        .with_phone(phone.clone())
+        .with_verified_phone()
+        .finish();
+    assert_eq!(
+        User::find_by_verified_phone(&phone, connection).unwrap().id,
+        user.id
+    );
+}
+
+#[test]
+fn mark_phone_verified_already_verified_by_another_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let phone = "555-555-1234".to_string();
+    project
+        .create_user()
+        .with_phone(phone.clone())
+        .with_verified_phone()
+        .finish();
+    let user = project.create_user().with_phone(phone).finish();
+
+    let error = user.mark_phone_verified(connection).unwrap_err();
+    assert_eq!(error.error_code, ErrorCode::DuplicateKeyError);
 }
 
 #[test]
@@ -254,6 +309,8 @@ fn create_from_external_login() {
     assert_eq!(Some(email.to_string()), user.email);
     assert_eq!(first_name, user.first_name);
     assert_eq!(last_name, user.last_name);
+    assert!(!user.password_set);
+    assert!(!user.check_password("random"));
 }
 
 #[test]
@@ -370,6 +427,27 @@ pub fn get_roles_by_organization() {
     );
 }
 
+#[test]
+pub fn get_roles_by_organization_with_child_organizations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_user(&user).finish();
+    let child_organization = project
+        .create_organization()
+        .with_parent(&organization)
+        .finish();
+
+    let mut expected_results = HashMap::new();
+    expected_results.insert(organization.id, vec!["OrgMember".to_string()]);
+    expected_results.insert(child_organization.id, vec!["OrgMember".to_string()]);
+
+    assert_eq!(
+        user.get_roles_by_organization(connection).unwrap(),
+        expected_results
+    );
+}
+
 #[test]
 pub fn get_scopes_by_organization() {
     let project = TestProject::new();
@@ -401,7 +479,9 @@ pub fn get_scopes_by_organization() {
             "event:write",
             "hold:write",
             "order:read",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "ticket:admin",
             "ticket:transfer",
@@ -468,11 +548,14 @@ pub fn get_global_scopes() {
             "order::make-external-payment",
             "order:read",
             "org:admin",
+            "org:customers",
             "org:read",
+            "org:reports",
             "org:write",
             "region:write",
             "ticket:admin",
             "ticket:transfer",
+            "user:admin",
             "user:read",
             "venue:write"
         ]
@@ -493,3 +576,25 @@ fn add_role() {
     let user2 = User::find(user.id, project.get_connection()).unwrap();
     assert_eq!(user2.role, vec!["User", "Admin"]);
 }
+
+#[test]
+fn two_factor_required() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    organization.add_user(user.id, connection).unwrap();
+    assert!(!user.two_factor_required(connection).unwrap());
+
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert!(user.two_factor_required(connection).unwrap());
+    let other_user = project.create_user().finish();
+    assert!(!other_user.two_factor_required(connection).unwrap());
+}
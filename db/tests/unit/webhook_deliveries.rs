@@ -0,0 +1,232 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+fn create_subscription(
+    project: &TestProject,
+    organization: &Organization,
+    event_types: Vec<&str>,
+) -> WebhookSubscription {
+    let user = project.create_user().finish();
+    WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        event_types.into_iter().map(|e| e.to_string()).collect(),
+        user.id,
+    ).commit(project.get_connection())
+    .unwrap()
+}
+
+fn create_domain_event(
+    event_type: DomainEventTypes,
+    organization: &Organization,
+    conn: &PgConnection,
+) -> DomainEvent {
+    let order_id = Uuid::new_v4();
+    DomainEvent::create(
+        event_type,
+        "Order was updated".to_string(),
+        Tables::Orders,
+        Some(order_id),
+        Some(json!({ "order_id": order_id })),
+    ).for_organization(organization.id)
+    .commit(conn)
+    .unwrap()
+}
+
+#[test]
+fn queue_pending() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let subscription = create_subscription(&project, &organization, vec!["order.paid"]);
+    let inactive_subscription = create_subscription(&project, &organization, vec!["order.paid"])
+        .update(
+            WebhookSubscriptionEditableAttributes {
+                active: Some(false),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+
+    let domain_event = create_domain_event(DomainEventTypes::OrderPaid, &organization, connection);
+    // Not subscribed to refunds or to events from other organizations
+    create_domain_event(DomainEventTypes::OrderRefunded, &organization, connection);
+    create_domain_event(DomainEventTypes::OrderPaid, &organization2, connection);
+
+    assert_eq!(WebhookDelivery::queue_pending(connection).unwrap(), 1);
+    let deliveries = subscription.deliveries(100, connection).unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].domain_event_id, domain_event.id);
+    assert_eq!(deliveries[0].status(), WebhookDeliveryStatus::Pending);
+    assert_eq!(deliveries[0].attempts, 0);
+    assert!(
+        inactive_subscription
+            .deliveries(100, connection)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Events are only queued once
+    assert_eq!(WebhookDelivery::queue_pending(connection).unwrap(), 0);
+
+    let due = WebhookDelivery::claim_due(100, connection).unwrap();
+    assert!(due.iter().any(|d| d.id == deliveries[0].id));
+}
+
+#[test]
+fn claim_due() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let subscription = create_subscription(&project, &organization, vec!["order.paid"]);
+    let subscription2 = create_subscription(&project, &organization, vec!["order.paid"]);
+    create_domain_event(DomainEventTypes::OrderPaid, &organization, connection);
+    WebhookDelivery::queue_pending(connection).unwrap();
+    let delivery = subscription.deliveries(100, connection).unwrap().remove(0);
+    let delivery2 = subscription2.deliveries(100, connection).unwrap().remove(0);
+
+    // Deliveries of deactivated subscriptions are not sent
+    subscription2
+        .update(
+            WebhookSubscriptionEditableAttributes {
+                active: Some(false),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    let claimed = WebhookDelivery::claim_due(100, connection).unwrap();
+    assert!(claimed.iter().any(|d| d.id == delivery.id));
+    assert!(claimed.iter().all(|d| d.id != delivery2.id));
+
+    // Claimed deliveries are not due again until the claim runs out
+    let claimed_delivery = WebhookDelivery::find(delivery.id, connection).unwrap();
+    assert_eq!(claimed_delivery.status(), WebhookDeliveryStatus::Pending);
+    assert!(claimed_delivery.next_attempt_at.unwrap() > Utc::now().naive_utc());
+    assert!(
+        WebhookDelivery::claim_due(100, connection)
+            .unwrap()
+            .iter()
+            .all(|d| d.id != delivery.id)
+    );
+}
+
+#[test]
+fn payload() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let subscription = create_subscription(&project, &organization, vec!["order.paid"]);
+    let domain_event = create_domain_event(DomainEventTypes::OrderPaid, &organization, connection);
+    WebhookDelivery::queue_pending(connection).unwrap();
+    let delivery = subscription.deliveries(100, connection).unwrap().remove(0);
+
+    let (event_type, payload) = delivery.payload(connection).unwrap();
+    assert_eq!(event_type, "order.paid");
+    assert_eq!(payload["id"], json!(domain_event.id));
+    assert_eq!(payload["type"], json!("order.paid"));
+    assert_eq!(payload["organization_id"], json!(organization.id));
+    assert_eq!(payload["data"], json!(domain_event.event_data));
+}
+
+#[test]
+fn mark_delivered() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let subscription = create_subscription(&project, &organization, vec!["order.paid"]);
+    create_domain_event(DomainEventTypes::OrderPaid, &organization, connection);
+    WebhookDelivery::queue_pending(connection).unwrap();
+    let delivery = subscription.deliveries(100, connection).unwrap().remove(0);
+
+    let delivery = delivery
+        .mark_delivered(200, Some("OK".to_string()), connection)
+        .unwrap();
+    assert_eq!(delivery.status(), WebhookDeliveryStatus::Delivered);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_status, Some(200));
+    assert_eq!(delivery.response_body, Some("OK".to_string()));
+    assert!(delivery.next_attempt_at.is_none());
+    assert!(delivery.last_attempt_at.is_some());
+    assert!(
+        WebhookDelivery::claim_due(100, connection)
+            .unwrap()
+            .iter()
+            .all(|d| d.id != delivery.id)
+    );
+}
+
+#[test]
+fn mark_attempt_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let subscription = create_subscription(&project, &organization, vec!["order.paid"]);
+    create_domain_event(DomainEventTypes::OrderPaid, &organization, connection);
+    WebhookDelivery::queue_pending(connection).unwrap();
+    let mut delivery = subscription.deliveries(100, connection).unwrap().remove(0);
+
+    delivery = delivery
+        .mark_attempt_failed(Some(500), Some("Error".to_string()), None, connection)
+        .unwrap();
+    assert_eq!(delivery.status(), WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_status, Some(500));
+    assert!(delivery.next_attempt_at.unwrap() > Utc::now().naive_utc() + Duration::seconds(50));
+    assert!(
+        WebhookDelivery::claim_due(100, connection)
+            .unwrap()
+            .iter()
+            .all(|d| d.id != delivery.id)
+    );
+
+    // Given up on after running out of attempts
+    for _ in 1..MAX_DELIVERY_ATTEMPTS {
+        delivery = delivery
+            .mark_attempt_failed(None, None, Some("Connection refused".to_string()), connection)
+            .unwrap();
+    }
+    assert_eq!(delivery.status(), WebhookDeliveryStatus::Failed);
+    assert_eq!(delivery.attempts, MAX_DELIVERY_ATTEMPTS);
+    assert_eq!(delivery.error, Some("Connection refused".to_string()));
+    assert!(delivery.next_attempt_at.is_none());
+}
+
+#[test]
+fn retry_delay() {
+    assert_eq!(WebhookDelivery::retry_delay(1), Duration::minutes(1));
+    assert_eq!(WebhookDelivery::retry_delay(2), Duration::minutes(2));
+    assert_eq!(WebhookDelivery::retry_delay(5), Duration::minutes(16));
+    assert_eq!(WebhookDelivery::retry_delay(20), Duration::hours(6));
+}
+
+#[test]
+fn redeliver() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let subscription = create_subscription(&project, &organization, vec!["order.paid"]);
+    create_domain_event(DomainEventTypes::OrderPaid, &organization, connection);
+    WebhookDelivery::queue_pending(connection).unwrap();
+    let delivery = subscription
+        .deliveries(100, connection)
+        .unwrap()
+        .remove(0)
+        .mark_delivered(200, None, connection)
+        .unwrap();
+
+    let redelivery = delivery.redeliver(connection).unwrap();
+    assert_eq!(redelivery.id, delivery.id);
+    assert_eq!(redelivery.status(), WebhookDeliveryStatus::Pending);
+    assert_eq!(redelivery.attempts, 0);
+    assert_eq!(redelivery.response_status, Some(200));
+    assert!(redelivery.next_attempt_at.unwrap() <= Utc::now().naive_utc());
+
+    // Events are delivered to each webhook once so no new delivery is queued
+    assert_eq!(WebhookDelivery::queue_pending(connection).unwrap(), 0);
+    assert_eq!(subscription.deliveries(100, connection).unwrap().len(), 1);
+}
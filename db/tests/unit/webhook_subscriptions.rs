@@ -0,0 +1,199 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+
+    let subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string(), "ticket.redeemed".to_string()],
+        user.id,
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(subscription.organization_id, organization.id);
+    assert_eq!(subscription.created_by, user.id);
+    assert_eq!(subscription.url, "https://example.com/webhooks");
+    assert_eq!(subscription.secret.len(), 32);
+    assert!(subscription.active);
+    assert_eq!(
+        subscription.domain_event_types(),
+        vec![
+            DomainEventTypes::OrderPaid.to_string(),
+            DomainEventTypes::TicketRedeemed.to_string(),
+        ]
+    );
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+
+    let result = WebhookSubscription::create(
+        organization.id,
+        "not a url".to_string(),
+        vec!["order.paid".to_string(), "order.created".to_string()],
+        user.id,
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("url"));
+                assert_eq!(errors["url"][0].code, "webhook_url");
+                assert!(errors.contains_key("event_types"));
+                assert_eq!(errors["event_types"][0].code, "webhook_event_types");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // At least one event type is required
+    let result = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![],
+        user.id,
+    ).commit(connection);
+    assert!(result.is_err());
+
+    // Payloads are only sent over https
+    let result = WebhookSubscription::create(
+        organization.id,
+        "http://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string()],
+        user.id,
+    ).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["url"][0].code, "webhook_url");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn event_type_name() {
+    assert_eq!(
+        WebhookSubscription::event_type_name(&DomainEventTypes::OrderPaid.to_string()),
+        Some("order.paid".to_string())
+    );
+    assert_eq!(
+        WebhookSubscription::event_type_name(&DomainEventTypes::PaymentCreated.to_string()),
+        None
+    );
+    assert_eq!(
+        WebhookSubscription::available_event_types(),
+        vec![
+            "event.published",
+            "order.paid",
+            "order.refunded",
+            "ticket.redeemed",
+            "ticket.transferred",
+        ]
+    );
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let organization2 = project.create_organization().with_owner(&user).finish();
+    let subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string()],
+        user.id,
+    ).commit(connection)
+    .unwrap();
+    WebhookSubscription::create(
+        organization2.id,
+        "https://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string()],
+        user.id,
+    ).commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        WebhookSubscription::find_for_organization(organization.id, connection).unwrap(),
+        vec![subscription.clone()]
+    );
+    assert_eq!(
+        WebhookSubscription::find(subscription.id, connection).unwrap(),
+        subscription
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string()],
+        user.id,
+    ).commit(connection)
+    .unwrap();
+
+    let subscription = subscription
+        .update(
+            WebhookSubscriptionEditableAttributes {
+                event_types: Some(vec!["event.published".to_string()]),
+                active: Some(false),
+                ..Default::default()
+            },
+            connection,
+        ).unwrap();
+    assert_eq!(subscription.event_types, vec!["event.published".to_string()]);
+    assert!(!subscription.active);
+    assert!(
+        WebhookSubscription::find_active(connection)
+            .unwrap()
+            .iter()
+            .all(|s| s.id != subscription.id)
+    );
+
+    let result = subscription.update(
+        WebhookSubscriptionEditableAttributes {
+            url: Some("not a url".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_owner(&user).finish();
+    let subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec!["order.paid".to_string()],
+        user.id,
+    ).commit(connection)
+    .unwrap();
+
+    subscription.destroy(connection).unwrap();
+    assert!(WebhookSubscription::find(subscription.id, connection).is_err());
+}